@@ -0,0 +1,88 @@
+//! Compares scalar (one harmonic at a time) vs. batched multi-harmonic perturbation evaluation.
+//!
+//! `NcPerturbation::p`/`dp_dpsip`/`dp_dtheta`/`dp_dzeta` sit on the innermost path of every
+//! RKF45 step in `close_theta_period`, so the cost of gathering and reducing over harmonics
+//! matters at realistic harmonic counts.
+
+use std::hint::black_box;
+use std::path::PathBuf;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use rsl_interpolation::Accelerator;
+
+use equilibrium::{Harmonic, NcHarmonic, NcHarmonicBuilder, NcHarmonicCache, NcPerturbation, Perturbation};
+
+const HARMONIC_COUNTS: [usize; 4] = [1, 4, 16, 64];
+
+fn build_harmonics(n: usize) -> Vec<NcHarmonic> {
+    let path = PathBuf::from(equilibrium::extract::STUB_TEST_NETCDF_PATH);
+    (0..n)
+        .map(|i| {
+            NcHarmonicBuilder::new(&path, "steffen", (i + 1) as i64, 1)
+                .build()
+                .expect("stub netCDF file provides enough harmonic data")
+        })
+        .collect()
+}
+
+/// The pre-batching baseline: one `h` evaluation per harmonic, each going through its own
+/// [`NcHarmonicCache`] independently.
+fn p_scalar(
+    harmonics: &[NcHarmonic],
+    psip: f64,
+    theta: f64,
+    zeta: f64,
+    acc: &mut Accelerator,
+    caches: &mut [NcHarmonicCache],
+) -> f64 {
+    harmonics
+        .iter()
+        .zip(caches.iter_mut())
+        .map(|(harmonic, cache)| harmonic.h(psip, theta, zeta, 0.0, acc, cache).unwrap())
+        .sum()
+}
+
+fn bench_perturbation_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("perturbation_p");
+
+    for &n in HARMONIC_COUNTS.iter() {
+        let harmonics = build_harmonics(n);
+        let perturbation = NcPerturbation::from_harmonics(&harmonics);
+        let mut acc = Accelerator::new();
+
+        group.bench_function(format!("scalar/{n}"), |b| {
+            let mut caches = vec![NcHarmonicCache::new(1); n];
+            b.iter(|| {
+                p_scalar(
+                    &harmonics,
+                    black_box(0.015),
+                    black_box(3.1415),
+                    black_box(6.2831),
+                    &mut acc,
+                    &mut caches,
+                )
+            })
+        });
+
+        group.bench_function(format!("batched/{n}"), |b| {
+            let mut caches = vec![NcHarmonicCache::new(1); n];
+            b.iter(|| {
+                perturbation
+                    .p(
+                        black_box(0.015),
+                        black_box(3.1415),
+                        black_box(6.2831),
+                        0.0,
+                        &mut acc,
+                        &mut caches,
+                    )
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_perturbation_batch);
+criterion_main!(benches);
@@ -0,0 +1,306 @@
+//! Analytically generated magnetic field strength, for benchmarking orbit integration against
+//! closed-form equilibria without reading a netCDF file.
+
+use std::path::PathBuf;
+
+use rsl_interpolation::{Accelerator, Cache};
+
+use crate::{Bfield, Flux, Length, Radians, Result};
+
+/// The closed-form model backing an [`AnalyticBfield`].
+#[derive(Debug, Clone, Copy)]
+enum AnalyticBfieldModel {
+    /// Large-aspect-ratio circular cross-section: `B(ψp, θ) = B0 / (1 + ε(ψp) cos θ)`, with
+    /// inverse aspect ratio `ε(ψp) = r(ψp) / R0`.
+    Circular {
+        raxis: Length,
+        r_wall: Length,
+        baxis: f64,
+    },
+    /// Shaped Solov'ev/Miller geometry, toroidal-field-dominated: `B(ψp, θ) = B0 R0 / R(ψp,
+    /// θ)`. `R(ψp, θ)` follows the same elongation/triangularity/Shafranov-shift
+    /// parametrization as [`SolovevGeometry`](crate::SolovevGeometry), extended with a shift
+    /// profile `Δ(ψp) = shift (1 - ψp)`, maximal on-axis and vanishing at the wall `ψp = 1`.
+    /// `kappa` only shapes the (unused here) `Z(ψp, θ)` flux surface -- the toroidal field
+    /// `B∝R0/R` depends on the major radius alone, so it is carried through for a builder
+    /// signature consistent with [`SolovevGeometryBuilder`](crate::SolovevGeometryBuilder) and
+    /// is otherwise inert.
+    Miller {
+        raxis: Length,
+        r_wall: Length,
+        kappa: f64,
+        delta: f64,
+        shift: Length,
+        baxis: f64,
+    },
+}
+
+/// The minor radius `r(ψp)` **in \[m\]**, shared by both models.
+fn minor_radius(r_wall: Length, psip: Flux) -> Length {
+    r_wall * psip.max(0.0).sqrt()
+}
+
+/// Used to create an [`AnalyticBfield`].
+pub struct AnalyticBfieldBuilder {
+    model: AnalyticBfieldModel,
+}
+
+impl AnalyticBfieldBuilder {
+    /// Creates a builder for the large-aspect-ratio circular model `B(ψp, θ) = B0 / (1 +
+    /// ε(ψp) cos θ)`, with magnetic axis at `raxis`, wall minor radius `r_wall` and axis field
+    /// `baxis`.
+    ///
+    /// # Example
+    /// ```
+    /// let builder = AnalyticBfieldBuilder::circular(1.0, 0.3, 1.0);
+    /// ```
+    pub fn circular(raxis: Length, r_wall: Length, baxis: f64) -> Self {
+        Self {
+            model: AnalyticBfieldModel::Circular {
+                raxis,
+                r_wall,
+                baxis,
+            },
+        }
+    }
+
+    /// Creates a builder for the shaped Solov'ev/Miller model `B(ψp, θ) = B0 R0 / R(ψp, θ)`,
+    /// with magnetic axis at `raxis`, wall minor radius `r_wall`, elongation `kappa`,
+    /// triangularity `delta`, on-axis Shafranov `shift` and axis field `baxis`.
+    ///
+    /// # Example
+    /// ```
+    /// let builder = AnalyticBfieldBuilder::miller(1.0, 0.3, 1.7, 0.4, 0.05, 1.0);
+    /// ```
+    pub fn miller(
+        raxis: Length,
+        r_wall: Length,
+        kappa: f64,
+        delta: f64,
+        shift: Length,
+        baxis: f64,
+    ) -> Self {
+        Self {
+            model: AnalyticBfieldModel::Miller {
+                raxis,
+                r_wall,
+                kappa,
+                delta,
+                shift,
+                baxis,
+            },
+        }
+    }
+
+    /// Creates a new [`AnalyticBfield`] with the Builder's configuration.
+    pub fn build(self) -> Result<AnalyticBfield> {
+        AnalyticBfield::build(self)
+    }
+}
+
+// ===============================================================================================
+
+/// Analytically generated magnetic field strength.
+///
+/// Reproduces, in closed form and with no data file involved, either a large-aspect-ratio
+/// circular cross-section or a shaped Solov'ev/Miller equilibrium (see [`AnalyticBfieldModel`]).
+/// Both `b`, `db_dpsip` and `db_dtheta` are evaluated by differentiating the closed form
+/// directly, rather than by fitting and differentiating a spline, so the `Bfield` implementation
+/// below never touches the `Accelerator`/`Cache` arguments -- they exist purely to satisfy the
+/// trait signature shared with the netCDF-backed [`NcBfield`](crate::NcBfield).
+pub struct AnalyticBfield {
+    model: AnalyticBfieldModel,
+}
+
+/// Creation
+impl AnalyticBfield {
+    /// Constructs an [`AnalyticBfield`] from an [`AnalyticBfieldBuilder`].
+    pub(crate) fn build(builder: AnalyticBfieldBuilder) -> Result<Self> {
+        Ok(Self {
+            model: builder.model,
+        })
+    }
+}
+
+/// Evaluation
+impl Bfield for AnalyticBfield {
+    fn b(
+        &self,
+        psip: Flux,
+        theta: Radians,
+        _xacc: &mut Accelerator,
+        _yacc: &mut Accelerator,
+        _cache: &mut Cache<f64>,
+    ) -> Result<f64> {
+        Ok(match self.model {
+            AnalyticBfieldModel::Circular {
+                raxis,
+                r_wall,
+                baxis,
+            } => {
+                let epsilon = minor_radius(r_wall, psip) / raxis;
+                baxis / (1.0 + epsilon * theta.cos())
+            }
+            AnalyticBfieldModel::Miller {
+                raxis,
+                r_wall,
+                delta,
+                shift,
+                baxis,
+                ..
+            } => baxis * raxis / rlab(raxis, r_wall, delta, shift, psip, theta),
+        })
+    }
+
+    fn db_dpsip(
+        &self,
+        psip: Flux,
+        theta: Radians,
+        _xacc: &mut Accelerator,
+        _yacc: &mut Accelerator,
+        _cache: &mut Cache<f64>,
+    ) -> Result<f64> {
+        Ok(match self.model {
+            AnalyticBfieldModel::Circular {
+                raxis,
+                r_wall,
+                baxis,
+            } => {
+                let epsilon = minor_radius(r_wall, psip) / raxis;
+                let depsilon_dpsip = r_wall / (raxis * 2.0 * psip.max(1e-12).sqrt());
+                -baxis * theta.cos() * depsilon_dpsip / (1.0 + epsilon * theta.cos()).powi(2)
+            }
+            AnalyticBfieldModel::Miller {
+                raxis,
+                r_wall,
+                delta,
+                shift,
+                baxis,
+                ..
+            } => {
+                let r = rlab(raxis, r_wall, delta, shift, psip, theta);
+                let dr_dpsip = drlab_dpsip(r_wall, delta, shift, psip, theta);
+                -baxis * raxis * dr_dpsip / (r * r)
+            }
+        })
+    }
+
+    fn db_dtheta(
+        &self,
+        psip: Flux,
+        theta: Radians,
+        _xacc: &mut Accelerator,
+        _yacc: &mut Accelerator,
+        _cache: &mut Cache<f64>,
+    ) -> Result<f64> {
+        Ok(match self.model {
+            AnalyticBfieldModel::Circular {
+                raxis,
+                r_wall,
+                baxis,
+            } => {
+                let epsilon = minor_radius(r_wall, psip) / raxis;
+                baxis * epsilon * theta.sin() / (1.0 + epsilon * theta.cos()).powi(2)
+            }
+            AnalyticBfieldModel::Miller {
+                raxis,
+                r_wall,
+                delta,
+                shift,
+                baxis,
+                ..
+            } => {
+                let r = rlab(raxis, r_wall, delta, shift, psip, theta);
+                let dr_dtheta = drlab_dtheta(r_wall, delta, psip, theta);
+                -baxis * raxis * dr_dtheta / (r * r)
+            }
+        })
+    }
+}
+
+/// The local triangularity angle `arcsin(δ(ψp))`, shared with [`SolovevGeometry`](crate::SolovevGeometry).
+fn delta_angle(delta: f64, psip: Flux) -> Radians {
+    (delta * psip.max(0.0).sqrt()).asin()
+}
+
+/// `R(ψp, θ)`, the Miller-shaped flux surface used by
+/// [`SolovevGeometry::rlab`](crate::SolovevGeometry), offset by the Shafranov shift `Δ(ψp) =
+/// shift (1 - ψp)`.
+fn rlab(raxis: Length, r_wall: Length, delta: f64, shift: Length, psip: Flux, theta: Radians) -> f64 {
+    let r = minor_radius(r_wall, psip);
+    let phase = theta + delta_angle(delta, psip) * theta.sin();
+    raxis + shift * (1.0 - psip) + r * phase.cos()
+}
+
+/// `∂R/∂ψp`, following the same derivative chain as
+/// [`SolovevGeometry::jacobian`](crate::SolovevGeometry), plus the shift's constant `-shift` term.
+fn drlab_dpsip(r_wall: Length, delta: f64, shift: Length, psip: Flux, theta: Radians) -> f64 {
+    let psip = psip.max(1e-12);
+    let r = minor_radius(r_wall, psip);
+    let delta_angle = delta_angle(delta, psip);
+    let phase = theta + delta_angle * theta.sin();
+
+    let two_sqrt_psip = 2.0 * psip.sqrt();
+    let dr_dpsip = r_wall / two_sqrt_psip;
+    let ddelta_denominator = (1.0 - (delta * psip.sqrt()).powi(2)).sqrt();
+    let ddelta_dpsip = delta / (two_sqrt_psip * ddelta_denominator);
+
+    -shift + dr_dpsip * phase.cos() - r * phase.sin() * ddelta_dpsip * theta.sin()
+}
+
+/// `∂R/∂θ`, following the same derivative chain as
+/// [`SolovevGeometry::jacobian`](crate::SolovevGeometry) -- the shift term has no `θ` dependence.
+fn drlab_dtheta(r_wall: Length, delta: f64, psip: Flux, theta: Radians) -> f64 {
+    let psip = psip.max(1e-12);
+    let r = minor_radius(r_wall, psip);
+    let delta_angle = delta_angle(delta, psip);
+    let phase = theta + delta_angle * theta.sin();
+
+    -r * phase.sin() * (1.0 + delta_angle * theta.cos())
+}
+
+/// Getters
+impl AnalyticBfield {
+    /// Returns a synthetic tag identifying the closed-form model in place of a file path, since
+    /// no file backs an [`AnalyticBfield`].
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(match self.model {
+            AnalyticBfieldModel::Circular { .. } => "<analytic: circular>",
+            AnalyticBfieldModel::Miller { .. } => "<analytic: miller>",
+        })
+    }
+
+    /// Returns `"analytic"` in place of an interpolation type, since `b`/`db_dpsip`/`db_dtheta`
+    /// are evaluated from the closed form directly rather than from a fitted spline.
+    pub fn typ(&self) -> String {
+        "analytic".to_string()
+    }
+
+    /// Returns the magnetic field strength on the axis `B0` **in \[T\]**.
+    pub fn baxis(&self) -> f64 {
+        match self.model {
+            AnalyticBfieldModel::Circular { baxis, .. } => baxis,
+            AnalyticBfieldModel::Miller { baxis, .. } => baxis,
+        }
+    }
+
+    /// Returns the elongation `κ` of the underlying flux surfaces, if built with
+    /// [`AnalyticBfieldBuilder::miller`]. Does not enter the toroidal-field approximation
+    /// itself -- see [`AnalyticBfieldModel::Miller`].
+    pub fn kappa(&self) -> Option<f64> {
+        match self.model {
+            AnalyticBfieldModel::Miller { kappa, .. } => Some(kappa),
+            AnalyticBfieldModel::Circular { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for AnalyticBfield {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnalyticBfield")
+            .field("path", &self.path())
+            .field("typ", &self.typ())
+            .field("Baxis [T]", &format!("{:.7}", self.baxis()))
+            .finish()
+    }
+}
@@ -1,90 +1,195 @@
 //! Helper structs for caching values to avoid unnecessary recalculations.
 
-use std::f64::consts::TAU;
-
 use rsl_interpolation::Accelerator;
 
 use crate::Harmonic;
 use crate::Result;
-use crate::{Flux, Radians};
+use crate::flt::{Flt, rem_euclid};
 use crate::{NcHarmonic, PhaseMethod};
 
 /// Required methods for caching an `impl Harmonic` object's values.
-pub trait HarmonicCache {
+///
+/// Generic over the same floating-point scalar `F` (see [`Flt`]) as the [`Harmonic`] it caches
+/// values for, defaulting to `f64`.
+pub trait HarmonicCache<F: Flt = f64> {
     /// Returns the Cache's hit count.
     fn hits(&self) -> usize;
 
     /// Returns the Cache's miss count.
     fn misses(&self) -> usize;
 
-    /// Checks if the cache's fields are valid.
+    /// Checks if the cache already holds a slot for `(psip, theta, zeta, time)`, promoting it to
+    /// most-recently-used and selecting it as the active slot if so.
     ///
     /// Comparing floats is OK here since they are simply copied between every call, and we
     /// **want** the check to fail with the slightest difference.
-    fn is_updated(&mut self, psip: Flux, theta: Radians, zeta: Radians) -> bool;
+    fn is_updated(&mut self, psip: F, theta: F, zeta: F, time: F) -> bool;
 
-    /// Updates the cache's fields.
+    /// Inserts `(psip, theta, zeta, time)`'s values into the cache, evicting the least-recently-
+    /// used slot if the cache is already at capacity, and selects it as the active slot.
     fn update(
         &mut self,
-        h: &NcHarmonic,
-        psip: Flux,
-        theta: Radians,
-        zeta: Radians,
+        h: &NcHarmonic<F>,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
         acc: &mut Accelerator,
     ) -> Result<()>;
 
-    fn alpha(&self) -> f64;
+    /// The active slot's `α(ψp)` (see [`Self::is_updated`]/[`Self::update`]).
+    fn alpha(&self) -> F;
+
+    fn dalpha(&self) -> F;
 
-    fn dalpha(&self) -> f64;
+    /// The amplitude's second derivative `d²α/dψp²`, used to assemble an analytic Jacobian (see
+    /// [`crate::Harmonic::d2h_dpsip2`]).
+    fn d2alpha(&self) -> F;
 
-    fn cos(&self) -> f64;
+    fn cos(&self) -> F;
 
-    fn sin(&self) -> f64;
+    fn sin(&self) -> F;
+
+    /// The amplitude-envelope factor `e^(γt)`. `1.0` for a constant-amplitude (γ=0) harmonic.
+    fn envelope(&self) -> F;
 }
 
-/// Holds an [`NcHarmonic`]'s values evalutated at a specific point.
+/// One cached `(ψp, θ, ζ, t)` point's evaluated [`NcHarmonic`] values, plus its own periodic-
+/// remainder tracking (see [`NcHarmonicCache::update`]) and LRU bookkeeping.
+#[derive(Clone, Copy)]
+struct Slot<F: Flt> {
+    /// `false` for a slot that has never been written to, so [`NcHarmonicCache::update`] can tell
+    /// "never used" apart from "used a long time ago" when picking an eviction candidate.
+    occupied: bool,
+    psip: F,
+    theta: F,
+    zeta: F,
+    time: F,
+    /// The periodic remainder `θ mod TAU`, advanced from this same slot's previous `theta` -- see
+    /// [`NcHarmonicCache::update`].
+    theta_reduced: F,
+    /// The periodic remainder `ζ mod TAU`, tracked the same way as [`Self::theta_reduced`].
+    zeta_reduced: F,
+    alpha: F,
+    phase: F,
+    dalpha: F,
+    d2alpha: F,
+    sin: F,
+    cos: F,
+    envelope: F,
+    /// The [`NcHarmonicCache`]-wide access tick this slot was last read or written at, used to
+    /// find the least-recently-used slot on eviction.
+    last_used: usize,
+}
+
+impl<F: Flt> Default for Slot<F> {
+    fn default() -> Self {
+        Self {
+            occupied: false,
+            psip: F::zero(),
+            theta: F::zero(),
+            zeta: F::zero(),
+            time: F::zero(),
+            theta_reduced: F::zero(),
+            zeta_reduced: F::zero(),
+            alpha: F::zero(),
+            phase: F::zero(),
+            dalpha: F::zero(),
+            d2alpha: F::zero(),
+            sin: F::zero(),
+            cos: F::zero(),
+            envelope: F::zero(),
+            last_used: 0,
+        }
+    }
+}
+
+/// A small associative cache of an [`NcHarmonic`]'s values at several `(ψp, θ, ζ, t)` points, with
+/// least-recently-used eviction once it's full.
 ///
-/// Since all the harmonic's methods are called consecutively over the same coordinates, most terms
-/// do not need to be calculated every time.
+/// A single stored point thrashes to near-100% misses whenever callers interleave two or more
+/// states -- an integrator comparing its current and trial states, or an ensemble of particles
+/// evaluated round-robin -- since every call then lands on a *different* point than the one the
+/// cache just evicted. [`Self::new`]'s `capacity` lets a caller size the cache to its own access
+/// pattern (e.g. 2 for a pair of interleaved states).
 ///
-/// Similar to the Accelerators, they are stored inside State, and do not affect the behavior of the
-/// equilibrium objects themselves.
+/// Since all the harmonic's methods are called consecutively over the same coordinates, most terms
+/// do not need to be calculated every time. Similar to the Accelerators, the cache is stored inside
+/// State, and does not affect the behavior of the equilibrium objects themselves. It should be
+/// cloned in each new state calculated from the Stepper.
 ///
-/// The cache should be cloned in each new state calculated from the Stepper.
-#[derive(Clone, Default)]
-pub struct NcHarmonicCache {
+/// Generic over the same floating-point scalar `F` (see [`Flt`]) as the [`NcHarmonic`] it caches
+/// values for, defaulting to `f64`.
+#[derive(Clone)]
+pub struct NcHarmonicCache<F: Flt = f64> {
     hits: usize,
     misses: usize,
-    psip: Flux,
-    theta: Radians,
-    zeta: Radians,
-    pub(crate) alpha: f64,
-    pub(crate) phase: Radians,
-    pub(crate) dalpha: f64,
-    pub(crate) sin: f64,
-    pub(crate) cos: f64,
+    tick: usize,
+    /// Index into [`Self::slots`] of the slot the last [`HarmonicCache::is_updated`]/
+    /// [`HarmonicCache::update`] call selected -- the one the `alpha()`/`cos()`/etc. getters read.
+    current: usize,
+    slots: Vec<Slot<F>>,
 }
 
-impl NcHarmonicCache {
-    /// Creates a new [`NcHarmonicCache`].
-    pub fn new() -> Self {
-        Self::default()
+impl<F: Flt> NcHarmonicCache<F> {
+    /// Creates a new [`NcHarmonicCache`] holding up to `capacity` points at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "NcHarmonicCache capacity must be at least 1");
+        Self {
+            hits: 0,
+            misses: 0,
+            tick: 0,
+            current: 0,
+            slots: vec![Slot::default(); capacity],
+        }
+    }
+
+    /// Returns the cache's capacity, i.e. the maximum number of points it can hold at once.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the number of slots currently holding a cached point.
+    pub fn occupancy(&self) -> usize {
+        self.slots.iter().filter(|s| s.occupied).count()
     }
 
     /// Returns the phase value `φ(ψp)`, depending on the harmonic's [`PhaseMethod`].
-    fn calculate_phase(h: &NcHarmonic, psip: f64, acc: &mut Accelerator) -> Result<f64> {
+    fn calculate_phase(h: &NcHarmonic<F>, psip: F, acc: &mut Accelerator) -> Result<F> {
         // Options are always Some when the correct method is set
-        match h.phase_method {
-            PhaseMethod::Zero => Ok(0.0),
+        match &h.phase_method {
+            PhaseMethod::Zero => Ok(F::zero()),
             PhaseMethod::Average => Ok(h.phase_average.expect("is Some")),
-            PhaseMethod::Resonance => Ok(h.phase_average.expect("is Some")),
+            PhaseMethod::Resonance => Ok(h.phase_resonance.expect("is Some")),
             PhaseMethod::Interpolation => Ok(h.phase(psip, acc)?),
-            PhaseMethod::Custom(value) => Ok(value),
+            PhaseMethod::Custom(_) => Ok(h.phase(psip, acc)?),
         }
     }
+
+    /// Picks the slot [`HarmonicCache::update`] should (over)write: an unoccupied one if any
+    /// remain, otherwise the least-recently-used occupied one.
+    fn eviction_target(&self) -> usize {
+        self.slots
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, s)| (s.occupied, s.last_used))
+            .map(|(i, _)| i)
+            .expect("capacity is at least 1")
+    }
 }
 
-impl HarmonicCache for NcHarmonicCache {
+impl<F: Flt> Default for NcHarmonicCache<F> {
+    /// Defaults to a single-slot cache, matching the old (pre-LRU) behavior.
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<F: Flt> HarmonicCache<F> for NcHarmonicCache<F> {
     fn hits(&self) -> usize {
         self.hits
     }
@@ -93,62 +198,118 @@ impl HarmonicCache for NcHarmonicCache {
         self.misses
     }
 
-    /// Checks if the cache's fields are valid.
-    ///
-    /// Comparing floats is OK here since they are simply copied between every call, and we
-    /// **want** the check to fail with the slightest difference.
-    fn is_updated(&mut self, psip: Flux, theta: Radians, zeta: Radians) -> bool {
-        if (self.psip == psip) && (self.theta == theta) && (self.zeta == zeta) {
-            self.hits += 1;
-            true
-        } else {
-            self.misses += 1;
-            false
+    fn is_updated(&mut self, psip: F, theta: F, zeta: F, time: F) -> bool {
+        let found = self.slots.iter().position(|s| {
+            s.occupied && s.psip == psip && s.theta == theta && s.zeta == zeta && s.time == time
+        });
+        self.tick += 1;
+        match found {
+            Some(idx) => {
+                self.slots[idx].last_used = self.tick;
+                self.current = idx;
+                self.hits += 1;
+                true
+            }
+            None => {
+                self.misses += 1;
+                false
+            }
         }
     }
 
-    /// Updates the cache's fields.
+    /// Evaluates `h` at `(psip, theta, zeta, time)` into the eviction target slot (see
+    /// [`Self::eviction_target`]), then selects it as the active slot.
+    ///
+    /// The perturbation argument `m·θ − n·ζ + φ` is *not* formed from the raw `θ`/`ζ` and then
+    /// reduced mod `TAU` -- by the time a long trace has pushed `θ`/`ζ` to `~1e6` (routine for a
+    /// many-period Poincaré trace, and worse yet for `F = f32`), most of the bits in `θ` itself are
+    /// spent on its secular growth, leaving too few to resolve `m·θ mod TAU` to any useful
+    /// precision. Instead, each slot's `theta_reduced`/`zeta_reduced` carry *only* the periodic
+    /// remainder, advanced from that *same slot's* previous `θ`/`ζ` rather than re-derived from the
+    /// full secular value every time. This assumes consecutive writes to a slot are small steps of
+    /// one continuous trajectory (true as long as `capacity` comfortably covers the caller's
+    /// distinct interleaved states, so each one keeps its own stable slot); a slot recycled for an
+    /// unrelated point falls back to the same single-reduction precision the pre-LRU cache always
+    /// had, no worse than before.
     fn update(
         &mut self,
-        h: &NcHarmonic,
-        psip: Flux,
-        theta: Radians,
-        zeta: Radians,
+        h: &NcHarmonic<F>,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
         acc: &mut Accelerator,
     ) -> Result<()> {
-        self.psip = psip;
-        self.theta = theta;
-        self.zeta = zeta;
-        self.alpha = h.a(psip, acc)?;
-        self.phase = Self::calculate_phase(h, psip, acc)?;
-        self.dalpha = h.da_dpsip(psip, acc)?;
-        let mod_arg = (h._m * self.theta - h._n * self.zeta + self.phase).rem_euclid(TAU);
-        (self.sin, self.cos) = mod_arg.sin_cos();
+        let idx = self.eviction_target();
+        let previous = self.slots[idx];
+
+        let theta_reduced = rem_euclid(previous.theta_reduced + (theta - previous.theta), F::TAU());
+        let zeta_reduced = rem_euclid(previous.zeta_reduced + (zeta - previous.zeta), F::TAU());
+        let alpha = h.a(psip, acc)?;
+        let phase = Self::calculate_phase(h, psip, acc)?;
+        let dalpha = h.da_dpsip(psip, acc)?;
+        let d2alpha = h.d2a_dpsip2(psip, acc)?;
+        let mod_arg = rem_euclid(
+            h._m * theta_reduced - h._n * zeta_reduced - h.omega * time + phase,
+            F::TAU(),
+        );
+        let (sin, cos) = h.sin_cos(mod_arg);
+        let envelope = (h.gamma * time).exp();
+
+        self.tick += 1;
+        self.slots[idx] = Slot {
+            occupied: true,
+            psip,
+            theta,
+            zeta,
+            time,
+            theta_reduced,
+            zeta_reduced,
+            alpha,
+            phase,
+            dalpha,
+            d2alpha,
+            sin,
+            cos,
+            envelope,
+            last_used: self.tick,
+        };
+        self.current = idx;
         Ok(())
     }
 
-    fn alpha(&self) -> f64 {
-        self.alpha
+    fn alpha(&self) -> F {
+        self.slots[self.current].alpha
+    }
+
+    fn dalpha(&self) -> F {
+        self.slots[self.current].dalpha
+    }
+
+    fn d2alpha(&self) -> F {
+        self.slots[self.current].d2alpha
     }
 
-    fn dalpha(&self) -> f64 {
-        self.dalpha
+    fn cos(&self) -> F {
+        self.slots[self.current].cos
     }
 
-    fn cos(&self) -> f64 {
-        self.cos
+    fn sin(&self) -> F {
+        self.slots[self.current].sin
     }
 
-    fn sin(&self) -> f64 {
-        self.sin
+    fn envelope(&self) -> F {
+        self.slots[self.current].envelope
     }
 }
 
-impl std::fmt::Debug for NcHarmonicCache {
+impl<F: Flt> std::fmt::Debug for NcHarmonicCache<F> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("HarmonicCache")
             .field("hits  ", &self.hits)
             .field("misses", &self.misses)
+            .field("capacity", &self.capacity())
+            .field("occupancy", &self.occupancy())
             .finish()
     }
 }
@@ -0,0 +1,246 @@
+//! Chirikov resonance-overlap diagnostic for a multi-harmonic perturbation spectrum.
+//!
+//! For an axisymmetric equilibrium, the unperturbed poloidal/toroidal transit frequencies satisfy
+//! `ω_ζ(ψp)/ω_θ(ψp) = q(ψp)`, so a harmonic `(m,n)` resonates where `m·ω_θ-n·ω_ζ = 0`, i.e. where
+//! `q(ψp_r) = m/n`. Linearizing the pendulum Hamiltonian `H = ½·G·(Δψp)² + V_mn·cos(φ)` around that
+//! resonance gives an island of half-width `W = 2·√(|V_mn/G|)`, with `G =
+//! d(m·ω_θ-n·ω_ζ)/dψp = -n·ω_θ(ψp_r)·dq/dψp(ψp_r)` (treating `ω_θ` as locally constant near the
+//! resonance) and `V_mn = a(ψp_r)`, the harmonic's own amplitude there. `ω_θ` itself is not
+//! recoverable from [`Qfactor`] alone -- it is the particle's actual poloidal transit frequency,
+//! not a purely geometric quantity -- so callers supply a reference value (e.g. from a prior
+//! `particle::Frequencies`'s `omega_theta()`, or the orbit's nominal bounce/transit frequency).
+//!
+//! Two adjacent resonant surfaces, separated by `δ = |ψp_{r,1} - ψp_{r,2}|`, are predicted to
+//! overlap -- the onset of global (Chirikov) stochasticity -- once `S = (W₁+W₂)/(2δ) ≳ 1`.
+
+use rsl_interpolation::Accelerator;
+
+use crate::{Harmonic, NcHarmonic, Qfactor, Result};
+
+/// The half-step used to estimate `dq/dψp` via a central difference, both at a found resonant
+/// surface and inside the safeguarded-Newton iteration in [`find_resonance`].
+const DQ_DPSIP_STEP: f64 = 1e-6;
+
+/// The maximum number of safeguarded-Newton iterations used to locate a resonant surface.
+const MAX_NEWTON_ITERATIONS: usize = 30;
+
+/// Convergence tolerance on `|q(ψp) - target|` and on the bracket width, in [`find_resonance`].
+const NEWTON_TOLERANCE: f64 = 1e-10;
+
+/// A harmonic's resonant surface and predicted magnetic-island half-width.
+#[derive(Debug, Clone, Copy)]
+pub struct ResonantIsland {
+    /// The harmonic's poloidal mode number.
+    pub m: i64,
+    /// The harmonic's toroidal mode number.
+    pub n: i64,
+    /// The poloidal flux `ψp_r` where `q(ψp_r) = m/n`.
+    pub psip_r: f64,
+    /// The island's predicted half-width in `ψp`.
+    pub width: f64,
+}
+
+/// Locates every harmonic's resonant surface within `[psip_min, psip_max]` and predicts its island
+/// half-width, given a reference unperturbed poloidal transit frequency `omega_theta_ref` (see
+/// module docs). Harmonics whose resonance does not fall inside `[psip_min, psip_max]` are skipped,
+/// so the returned `Vec` may be shorter than `harmonics`.
+pub fn locate_resonances(
+    harmonics: &[NcHarmonic],
+    qfactor: &impl Qfactor,
+    psip_min: f64,
+    psip_max: f64,
+    omega_theta_ref: f64,
+    acc: &mut Accelerator,
+) -> Result<Vec<ResonantIsland>> {
+    let mut islands = Vec::with_capacity(harmonics.len());
+    for harmonic in harmonics {
+        let m = harmonic.m();
+        let n = harmonic.n();
+        let Some(psip_r) = find_resonance(qfactor, psip_min, psip_max, m as f64 / n as f64, acc)?
+        else {
+            continue;
+        };
+
+        let dq_dpsip = (qfactor.q(psip_r + DQ_DPSIP_STEP, acc)?
+            - qfactor.q(psip_r - DQ_DPSIP_STEP, acc)?)
+            / (2.0 * DQ_DPSIP_STEP);
+        let g = -(n as f64) * omega_theta_ref * dq_dpsip;
+        let v_mn = harmonic.a(psip_r, acc)?;
+        let width = 2.0 * (v_mn / g).abs().sqrt();
+
+        islands.push(ResonantIsland { m, n, psip_r, width });
+    }
+    Ok(islands)
+}
+
+/// Locates `ψp` where `q(ψp) = target` over `[psip_min, psip_max]`, via a safeguarded Newton
+/// iteration: at each step, the Newton update `ψ_next = ψ - g(ψ)/q'(ψ)` (with `q'` estimated by a
+/// central difference) is taken whenever it lands inside the current bracket and `q'` isn't too
+/// small to trust, falling back to the bracket's bisection midpoint otherwise. The bracket is then
+/// shrunk using the sign of `g(ψ_next)`. Returns `None` if `q` does not change sign across the
+/// bracket, i.e. there is no resonance in range.
+fn find_resonance(
+    qfactor: &impl Qfactor,
+    psip_min: f64,
+    psip_max: f64,
+    target: f64,
+    acc: &mut Accelerator,
+) -> Result<Option<f64>> {
+    let mut lo = psip_min;
+    let mut hi = psip_max;
+    let mut f_lo = qfactor.q(lo, acc)? - target;
+    let f_hi = qfactor.q(hi, acc)? - target;
+
+    if f_lo == 0.0 {
+        return Ok(Some(lo));
+    }
+    if f_hi == 0.0 {
+        return Ok(Some(hi));
+    }
+    if f_lo.signum() == f_hi.signum() {
+        return Ok(None);
+    }
+
+    let mut psip = 0.5 * (lo + hi);
+    for _ in 0..MAX_NEWTON_ITERATIONS {
+        let f = qfactor.q(psip, acc)? - target;
+        if f.signum() == f_lo.signum() {
+            lo = psip;
+            f_lo = f;
+        } else {
+            hi = psip;
+        }
+        if f.abs() < NEWTON_TOLERANCE || (hi - lo).abs() < NEWTON_TOLERANCE {
+            return Ok(Some(psip));
+        }
+
+        let dq_dpsip = (qfactor.q(psip + DQ_DPSIP_STEP, acc)?
+            - qfactor.q(psip - DQ_DPSIP_STEP, acc)?)
+            / (2.0 * DQ_DPSIP_STEP);
+        let newton = psip - f / dq_dpsip;
+        psip = if dq_dpsip.abs() < f64::EPSILON || newton <= lo || newton >= hi {
+            0.5 * (lo + hi)
+        } else {
+            newton
+        };
+    }
+    Ok(Some(psip))
+}
+
+/// Locates the resonant surface `ψp` where `harmonic`'s rational-surface condition `q(ψp_r) =
+/// m/n` holds, within `[psip_min, psip_max]`. Returns `Ok(None)` if `m/n` is outside the q range
+/// on the grid (no sign change across the bracket).
+///
+/// Evaluate `harmonic.a(psip_r, acc)` at the returned surface for an island-width estimate (see
+/// [`locate_resonances`], which does exactly this for a whole harmonic spectrum).
+pub fn find_resonant_surface(
+    harmonic: &NcHarmonic,
+    qfactor: &impl Qfactor,
+    psip_min: f64,
+    psip_max: f64,
+    acc: &mut Accelerator,
+) -> Result<Option<f64>> {
+    let target = harmonic.m() as f64 / harmonic.n() as f64;
+    find_resonance(qfactor, psip_min, psip_max, target, acc)
+}
+
+/// Computes the Chirikov overlap parameter `S = (W₁+W₂)/(2δ)` for every pair of adjacent resonant
+/// surfaces in `islands`, sorted by `psip_r` first. `S ≳ 1` signals the onset of global chaos
+/// between that pair's resonances.
+pub fn overlap_parameters(islands: &[ResonantIsland]) -> Vec<f64> {
+    let mut islands = islands.to_vec();
+    islands.sort_by(|a, b| a.psip_r.total_cmp(&b.psip_r));
+    islands
+        .windows(2)
+        .map(|pair| {
+            let delta = (pair[1].psip_r - pair[0].psip_r).abs();
+            (pair[0].width + pair[1].width) / (2.0 * delta)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::NcHarmonicBuilder;
+
+    #[derive(Clone)]
+    struct LinearQfactor;
+
+    impl Qfactor for LinearQfactor {
+        fn q(&self, psip: f64, _acc: &mut Accelerator) -> Result<f64> {
+            Ok(1.0 + psip)
+        }
+        fn psi(&self, psip: f64, _acc: &mut Accelerator) -> Result<f64> {
+            Ok(psip)
+        }
+        fn dpsi_dpsip(&self, _psip: f64, _acc: &mut Accelerator) -> Result<f64> {
+            Ok(1.0)
+        }
+    }
+
+    #[test]
+    fn test_find_resonance() {
+        let qfactor = LinearQfactor;
+        let mut acc = Accelerator::new();
+
+        // q(psip) = 1 + psip = 1.5 => psip = 0.5
+        let psip_r = find_resonance(&qfactor, 0.0, 1.0, 1.5, &mut acc)
+            .unwrap()
+            .unwrap();
+        assert!((psip_r - 0.5).abs() < 1e-9);
+
+        // q never reaches 10 inside [0, 1]
+        assert!(
+            find_resonance(&qfactor, 0.0, 1.0, 10.0, &mut acc)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_find_resonant_surface() {
+        let path = PathBuf::from(crate::extract::STUB_NETCDF_PATH);
+        let harmonic = NcHarmonicBuilder::new(&path, "steffen", 3, 2).build().unwrap();
+        let qfactor = LinearQfactor;
+        let mut acc = Accelerator::new();
+
+        // m/n = 1.5, q(psip) = 1 + psip = 1.5 => psip = 0.5
+        let psip_r = find_resonant_surface(&harmonic, &qfactor, 0.0, 1.0, &mut acc)
+            .unwrap()
+            .unwrap();
+        assert!((psip_r - 0.5).abs() < 1e-9);
+
+        // No (4, 1) resonance inside [0, 1]: q never reaches 4.
+        let harmonic = NcHarmonicBuilder::new(&path, "steffen", 4, 1).build().unwrap();
+        assert!(
+            find_resonant_surface(&harmonic, &qfactor, 0.0, 1.0, &mut acc)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_overlap_parameters() {
+        let islands = [
+            ResonantIsland {
+                m: 2,
+                n: 1,
+                psip_r: 0.0,
+                width: 0.1,
+            },
+            ResonantIsland {
+                m: 3,
+                n: 1,
+                psip_r: 1.0,
+                width: 0.6,
+            },
+        ];
+        // S = (0.1 + 0.6) / (2 * 1.0) = 0.35
+        let overlaps = overlap_parameters(&islands);
+        assert_eq!(overlaps.len(), 1);
+        assert!((overlaps[0] - 0.35).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,51 @@
+//! Error types for equilibrium objects.
+
+use thiserror::Error;
+
+/// Errors raised while reading data out of an on-disk equilibrium file.
+#[derive(Error, Debug)]
+pub enum NcError {
+    /// The requested variable does not exist in the file.
+    #[error("variable `{0}` not found")]
+    MissingVariable(String),
+
+    /// The requested variable exists, but is empty.
+    #[error("variable `{0}` is empty")]
+    EmptyVariable(String),
+
+    /// Underlying netCDF library error.
+    #[error("netCDF error: {0}")]
+    Netcdf(#[from] netcdf::Error),
+
+    /// Underlying HDF5 library error.
+    #[error("HDF5 error: {0}")]
+    Hdf5(#[from] hdf5::Error),
+}
+
+/// The top-level error type returned by equilibrium objects.
+#[derive(Error, Debug)]
+pub enum EqError {
+    /// Failure while reading an equilibrium data file.
+    #[error("{0}")]
+    NcError(#[from] NcError),
+
+    /// Failure while constructing or evaluating an interpolator.
+    #[error("{0}")]
+    Interpolation(#[from] rsl_interpolation::Error),
+
+    /// I/O failure unrelated to the data file itself (e.g. resolving the absolute path).
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    /// A coordinate fell outside the stored data range while the active
+    /// [`ExtrapolationPolicy`](crate::ExtrapolationPolicy) was `Error`.
+    #[error("coordinate {0} is out of the equilibrium's data range")]
+    OutOfRange(f64),
+
+    /// An interpolation grid's x/y arrays didn't match in length, or had fewer than two points.
+    #[error(
+        "interpolation grid needs matching x/y lengths of at least 2, got {x_len} x-values and \
+         {y_len} y-values"
+    )]
+    InvalidGrid { x_len: usize, y_len: usize },
+}
@@ -3,10 +3,12 @@
 //! For analytical equilibria, this is achieved by evaluation of analytical formulas, while for
 //! numerical equilibria by interpolation over the reconstructed data arrays.
 
+use ndarray::Array2;
 use rsl_interpolation::{Accelerator, Cache};
 
 use crate::Result;
 use crate::cache::*;
+use crate::flt::Flt;
 
 // TODO: (maybe) add doctests
 
@@ -93,6 +95,10 @@ pub trait Geometry {
 
     /// Calculates the Jacobian `J(œàp, Œ∏)`,
     ///
+    /// Near the magnetic axis, `1/sqrt(psip)` terms in an analytical implementation's Jacobian can
+    /// blow up; [`SolovevGeometry`](crate::SolovevGeometry) offers an opt-in regularized mode for
+    /// this through [`SolovevGeometryBuilder::with_axis_regularization`](crate::SolovevGeometryBuilder::with_axis_regularization).
+    ///
     /// # Example
     ///
     /// ```
@@ -109,6 +115,13 @@ pub trait Geometry {
 }
 
 /// q-factor related quantities computation.
+///
+/// No implementation in this crate divides by a quantity that vanishes on the magnetic axis --
+/// [`NcQfactor`](crate::NcQfactor) is a pure spline lookup over tabulated data, and
+/// [`SolovevQfactor`](crate::SolovevQfactor)'s `q = q_axis + (q_edge - q_axis) * psip.powf(shaping)`
+/// stays finite at `psip = 0` for the physically typical `shaping >= 0`. There is accordingly no
+/// axis-regularized evaluation mode here -- unlike [`Geometry::jacobian`], whose analytical
+/// implementation has a genuine `1/sqrt(psip)` term to tame.
 pub trait Qfactor: Clone {
     /// Calculates the q-factor `q(œàp)`.
     ///
@@ -166,6 +179,9 @@ pub trait Qfactor: Clone {
 }
 
 /// Plasma current related quantities computation.
+///
+/// Like Bfield, has no batch evaluation variants yet -- the concrete implementation backing
+/// this trait is not present in this tree (see `currents.rs`) to extend with one.
 pub trait Current {
     /// Calculates `g(œàp)`
     ///
@@ -241,6 +257,14 @@ pub trait Current {
 }
 
 /// Magnetic field related quantities computation.
+///
+/// Does not yet expose second derivatives (`d2b_dpsip2`/`d2b_dtheta2`/`d2b_dpsip_dtheta`) for
+/// analytic Jacobian assembly -- see [`Harmonic::d2h_dpsip2`] and friends for the equivalent on a
+/// single harmonic, added once the matching `Bfield` implementation is available to extend.
+///
+/// Also has no batch evaluation variants (unlike [`Geometry`], [`Qfactor`] and
+/// [`NcPerturbation`](crate::NcPerturbation)) for the same reason -- the concrete `NcBfield`
+/// implementation is not present in this tree (see `bfields.rs`) to extend with one.
 pub trait Bfield {
     /// Calculates `B(œàp, Œ∏)`,
     ///
@@ -324,8 +348,13 @@ pub trait Bfield {
     ) -> Result<f64>;
 }
 
-/// Single Harmonic related quantities computation
-pub trait Harmonic {
+/// Single Harmonic related quantities computation.
+///
+/// Generic over the floating-point scalar `F` (see [`Flt`]), defaulting to `f64` so existing call
+/// sites are unaffected. `acc` stays `f64`-based regardless of `F`, since the spline tables
+/// backing a concrete implementation are; `F` only governs the coordinates, the returned value,
+/// and the [`HarmonicCache`] used to avoid recomputing them.
+pub trait Harmonic<F: Flt = f64> {
     /// Calculates the harmonic `Œ±(œàp) * cos(mŒ∏-nŒ∂+œÜ(œàp))`.
     ///
     /// # Example
@@ -340,17 +369,18 @@ pub trait Harmonic {
     /// #
     /// let mut acc = Accelerator::new();
     /// let mut hcache = HarmonicCache::new();
-    /// let h = harmonic.h(0.015, 3.1415, 6.2831, &mut acc, &mut hcache)?;
+    /// let h = harmonic.h(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcache)?;
     /// # Ok::<_, EqError>(())
     /// ```
     fn h(
         &self,
-        psip: f64,
-        theta: f64,
-        zeta: f64,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
         acc: &mut Accelerator,
-        cache: &mut HarmonicCache,
-    ) -> Result<f64>;
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F>;
 
     /// Calculates the harmonic derivative `ùúïh/ùúïœàp`.
     ///
@@ -366,17 +396,18 @@ pub trait Harmonic {
     /// #
     /// let mut acc = Accelerator::new();
     /// let mut hcache = HarmonicCache::new();
-    /// let dh_dpsip = harmonic.dh_dpsip(0.015, 3.1415, 6.2831, &mut acc, &mut hcache)?;
+    /// let dh_dpsip = harmonic.dh_dpsip(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcache)?;
     /// # Ok::<_, EqError>(())
     /// ```
     fn dh_dpsip(
         &self,
-        psip: f64,
-        theta: f64,
-        zeta: f64,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
         acc: &mut Accelerator,
-        cache: &mut HarmonicCache,
-    ) -> Result<f64>;
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F>;
 
     /// Calculates the harmonic derivative `ùúïh/ùúïŒ∏`.
     ///
@@ -392,17 +423,18 @@ pub trait Harmonic {
     /// #
     /// let mut acc = Accelerator::new();
     /// let mut hcache = HarmonicCache::new();
-    /// let dh_dtheta = harmonic.dh_dtheta(0.015, 3.1415, 6.2831, &mut acc, &mut hcache)?;
+    /// let dh_dtheta = harmonic.dh_dtheta(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcache)?;
     /// # Ok::<_, EqError>(())
     /// ```
     fn dh_dtheta(
         &self,
-        psip: f64,
-        theta: f64,
-        zeta: f64,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
         acc: &mut Accelerator,
-        cache: &mut HarmonicCache,
-    ) -> Result<f64>;
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F>;
 
     /// Calculates the perturbation derivative `ùúïh/ùúïŒ∂`.
     ///
@@ -418,21 +450,79 @@ pub trait Harmonic {
     /// #
     /// let mut acc = Accelerator::new();
     /// let mut hcache = HarmonicCache::new();
-    /// let dh_dzeta = harmonic.dh_dzeta(0.015, 3.1415, 6.2831, &mut acc, &mut hcache)?;
+    /// let dh_dzeta = harmonic.dh_dzeta(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcache)?;
     /// # Ok::<_, EqError>(())
     /// ```
     fn dh_dzeta(
         &self,
-        psip: f64,
-        theta: f64,
-        zeta: f64,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
         acc: &mut Accelerator,
-        cache: &mut HarmonicCache,
-    ) -> Result<f64>;
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F>;
+
+    /// Calculates the second partial derivative `d2h/dpsip2`.
+    ///
+    /// A stiff/implicit orbit stepper needs this, along with [`Self::d2h_dtheta2`] and
+    /// [`Self::d2h_dpsip_dtheta`], to assemble the equations of motion's Jacobian analytically
+    /// instead of finite-differencing the RHS every Newton iteration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use equilibrium::*;
+    /// # use std::path::PathBuf;
+    /// # use rsl_interpolation::{Accelerator, Cache};
+    /// #
+    /// # let path = PathBuf::from("./netcdf.nc");
+    /// # let harmonic = NcHarmonicBuilder::new(&path, "steffen", 1, 2).build()?;
+    /// #
+    /// let mut acc = Accelerator::new();
+    /// let mut hcache = HarmonicCache::new();
+    /// let d2h_dpsip2 = harmonic.d2h_dpsip2(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcache)?;
+    /// # Ok::<_, EqError>(())
+    /// ```
+    fn d2h_dpsip2(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F>;
+
+    /// Calculates the second partial derivative `d2h/dtheta2`. See [`Self::d2h_dpsip2`].
+    fn d2h_dtheta2(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F>;
+
+    /// Calculates the mixed second partial derivative `d2h/(dpsip dtheta)`. See
+    /// [`Self::d2h_dpsip2`].
+    fn d2h_dpsip_dtheta(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F>;
 
     /// Calculates the perturbation derivative `ùúïh/ùúït`.
     ///
-    /// Time-independent perturbations at the moment, so it always returns `0.0`.
+    /// For a rigidly rotating mode with a growing/damping envelope `e^(γt)` this is
+    /// `α(ψp)·e^(γt)·[ω·sin(mθ-nζ-ωt+φ(ψp)) + γ·cos(mθ-nζ-ωt+φ(ψp))]`, where `ω` is the harmonic's
+    /// rigid rotation angular frequency and `γ` its amplitude growth rate. `0.0` for a frozen,
+    /// constant-amplitude (`ω=γ=0`) harmonic.
     ///
     /// # Example
     ///
@@ -446,21 +536,18 @@ pub trait Harmonic {
     /// #
     /// let mut acc = Accelerator::new();
     /// let mut hcache = HarmonicCache::new();
-    /// let dh_dt = harmonic.dh_dt(0.015, 3.1415, 6.2831, &mut acc, &mut hcache)?;
-    /// assert_eq!(dh_dt, 0.0);
+    /// let dh_dt = harmonic.dh_dt(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcache)?;
     /// # Ok::<_, EqError>(())
     /// ```
-    #[allow(unused_variables)]
     fn dh_dt(
         &self,
-        psip: f64,
-        theta: f64,
-        zeta: f64,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
         acc: &mut Accelerator,
-        cache: &mut HarmonicCache,
-    ) -> Result<f64> {
-        Ok(0.0)
-    }
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F>;
 
     /// Calculates the harmonic's *amplitude* `Œ±(œàp)`.
     ///
@@ -478,7 +565,7 @@ pub trait Harmonic {
     /// let a = harmonic.a(0.015, &mut acc)?;
     /// # Ok::<_, EqError>(())
     /// ```
-    fn a(&self, psip: f64, acc: &mut Accelerator) -> Result<f64>;
+    fn a(&self, psip: F, acc: &mut Accelerator) -> Result<F>;
 
     /// Calculates the harmonic's *amplitude* derivative `dŒ±(œàp)/dœàp`.
     ///
@@ -496,7 +583,7 @@ pub trait Harmonic {
     /// let da_dpsip = harmonic.da_dpsip(0.015, &mut acc)?;
     /// # Ok::<_, EqError>(())
     /// ```
-    fn da_dpsip(&self, psip: f64, acc: &mut Accelerator) -> Result<f64>;
+    fn da_dpsip(&self, psip: F, acc: &mut Accelerator) -> Result<F>;
 
     /// Calculates the harmonic's *phase* `œÜ(œàp)`.
     ///
@@ -514,7 +601,7 @@ pub trait Harmonic {
     /// let phase = harmonic.phase(0.015, &mut acc)?;
     /// # Ok::<_, EqError>(())
     /// ```
-    fn phase(&self, psip: f64, acc: &mut Accelerator) -> Result<f64>;
+    fn phase(&self, psip: F, acc: &mut Accelerator) -> Result<F>;
 
     /// Calculates the term inside the cosine, modulo 2œÄ.
     ///
@@ -529,10 +616,10 @@ pub trait Harmonic {
     /// # let harmonic = NcHarmonicBuilder::new(&path, "steffen", 1, 2).build()?;
     /// #
     /// let mut acc = Accelerator::new();
-    /// let module = harmonic.mod_arg(0.015, 3.1415, 6.2831, &mut acc)?;
+    /// let module = harmonic.mod_arg(0.015, 3.1415, 6.2831, 0.0, &mut acc)?;
     /// # Ok::<_, EqError>(())
     /// ```
-    fn mod_arg(&self, psip: f64, theta: f64, zeta: f64, acc: &mut Accelerator) -> Result<f64>;
+    fn mod_arg(&self, psip: F, theta: F, zeta: F, time: F, acc: &mut Accelerator) -> Result<F>;
 }
 
 /// Perturbation related quantities computation
@@ -557,7 +644,7 @@ pub trait Perturbation {
     ///
     /// let mut acc = Accelerator::new();
     /// let mut hcaches = [HarmonicCache::new(), HarmonicCache::new()];
-    /// let p = perturbation.p(0.015, 3.1415, 6.2831, &mut acc, &mut hcaches)?;
+    /// let p = perturbation.p(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcaches)?;
     /// # Ok::<_, EqError>(())
     /// ```
     fn p(
@@ -565,6 +652,7 @@ pub trait Perturbation {
         psip: f64,
         theta: f64,
         zeta: f64,
+        time: f64,
         acc: &mut Accelerator,
         caches: &mut [HarmonicCache],
     ) -> Result<f64>;
@@ -586,7 +674,7 @@ pub trait Perturbation {
     ///
     /// let mut acc = Accelerator::new();
     /// let mut hcaches = [HarmonicCache::new(), HarmonicCache::new()];
-    /// let dp_dpsip = perturbation.dp_dpsip(0.015, 3.1415, 6.2831, &mut acc, &mut hcaches)?;
+    /// let dp_dpsip = perturbation.dp_dpsip(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcaches)?;
     /// # Ok::<_, EqError>(())
     /// ```
     fn dp_dpsip(
@@ -594,6 +682,7 @@ pub trait Perturbation {
         psip: f64,
         theta: f64,
         zeta: f64,
+        time: f64,
         acc: &mut Accelerator,
         caches: &mut [HarmonicCache],
     ) -> Result<f64>;
@@ -615,7 +704,7 @@ pub trait Perturbation {
     ///
     /// let mut acc = Accelerator::new();
     /// let mut hcaches = [HarmonicCache::new(), HarmonicCache::new()];
-    /// let dp_dtheta = perturbation.dp_dtheta(0.015, 3.1415, 6.2831, &mut acc, &mut hcaches)?;
+    /// let dp_dtheta = perturbation.dp_dtheta(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcaches)?;
     /// # Ok::<_, EqError>(())
     /// ```
     fn dp_dtheta(
@@ -623,6 +712,7 @@ pub trait Perturbation {
         psip: f64,
         theta: f64,
         zeta: f64,
+        time: f64,
         acc: &mut Accelerator,
         caches: &mut [HarmonicCache],
     ) -> Result<f64>;
@@ -644,7 +734,7 @@ pub trait Perturbation {
     ///
     /// let mut acc = Accelerator::new();
     /// let mut hcaches = [HarmonicCache::new(), HarmonicCache::new()];
-    /// let dp_dzeta = perturbation.dp_dzeta(0.015, 3.1415, 6.2831, &mut acc, &mut hcaches)?;
+    /// let dp_dzeta = perturbation.dp_dzeta(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcaches)?;
     /// # Ok::<_, EqError>(())
     /// ```
     fn dp_dzeta(
@@ -652,13 +742,53 @@ pub trait Perturbation {
         psip: f64,
         theta: f64,
         zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64>;
+
+    /// Calculates the second partial derivative `d2p/dpsip2`, the sum of every harmonic's
+    /// [`Harmonic::d2h_dpsip2`].
+    ///
+    /// Along with [`Self::d2p_dtheta2`] and [`Self::d2p_dpsip_dtheta`], this is what
+    /// [`Self::hessian_psip_theta`] packs into a dense Jacobian block for a stiff/implicit orbit
+    /// stepper.
+    fn d2p_dpsip2(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64>;
+
+    /// Calculates the second partial derivative `d2p/dtheta2`. See [`Self::d2p_dpsip2`].
+    fn d2p_dtheta2(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64>;
+
+    /// Calculates the mixed second partial derivative `d2p/(dpsip dtheta)`. See
+    /// [`Self::d2p_dpsip2`].
+    fn d2p_dpsip_dtheta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
         acc: &mut Accelerator,
         caches: &mut [HarmonicCache],
     ) -> Result<f64>;
 
     /// Calculates the Perturbation's derivative with respect to `t`.
     ///
-    /// Time-independent perturbations at the moment, so it always returns `0.0`.
+    /// The sum of each harmonic's [`Harmonic::dh_dt`]. `0.0` if every harmonic is frozen and constant-amplitude (`œâ=Œ≥=0`).
     ///
     /// # Example
     ///
@@ -675,8 +805,7 @@ pub trait Perturbation {
     ///
     /// let mut acc = Accelerator::new();
     /// let mut hcaches = [HarmonicCache::new(), HarmonicCache::new()];
-    /// let dp_dt = perturbation.dp_dt(0.015, 3.1415, 6.2831, &mut acc, &mut hcaches)?;
-    /// assert_eq!(dp_dt, 0.0);
+    /// let dp_dt = perturbation.dp_dt(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcaches)?;
     /// # Ok::<_, EqError>(())
     /// ```
     fn dp_dt(
@@ -684,6 +813,7 @@ pub trait Perturbation {
         psip: f64,
         theta: f64,
         zeta: f64,
+        time: f64,
         acc: &mut Accelerator,
         caches: &mut [HarmonicCache],
     ) -> Result<f64>;
@@ -691,6 +821,47 @@ pub trait Perturbation {
     /// Returns the number of harmonics.
     fn len(&self) -> usize;
 
+    /// Packs the `(ψp, θ)` Hessian block `[[d2p/dpsip2, d2p/(dpsip dtheta)], [d2p/(dpsip dtheta),
+    /// d2p/dtheta2]]` a Rosenbrock-W/implicit orbit stepper needs to assemble the equations of
+    /// motion's Jacobian `W = I/(γΔt) − J`, evaluating it once per step from [`Self::d2p_dpsip2`],
+    /// [`Self::d2p_dtheta2`], and [`Self::d2p_dpsip_dtheta`] instead of finite-differencing the RHS.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use equilibrium::*;
+    /// # use std::path::PathBuf;
+    /// # use rsl_interpolation::{Accelerator, Cache};
+    /// #
+    /// # let path = PathBuf::from("./netcdf.nc");
+    /// let perturbation = NcPerturbation::from_harmonics(&vec![
+    ///    NcHarmonicBuilder::new(&path, "steffen", 1, 2).build()?,
+    /// ]);
+    ///
+    /// let mut acc = Accelerator::new();
+    /// let mut hcaches = [HarmonicCache::new()];
+    /// let hessian = perturbation.hessian_psip_theta(0.015, 3.1415, 6.2831, 0.0, &mut acc, &mut hcaches)?;
+    /// # Ok::<_, EqError>(())
+    /// ```
+    fn hessian_psip_theta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array2<f64>> {
+        let d2p_dpsip2 = self.d2p_dpsip2(psip, theta, zeta, time, acc, caches)?;
+        let d2p_dtheta2 = self.d2p_dtheta2(psip, theta, zeta, time, acc, caches)?;
+        let d2p_dpsip_dtheta = self.d2p_dpsip_dtheta(psip, theta, zeta, time, acc, caches)?;
+        Ok(Array2::from_shape_vec(
+            (2, 2),
+            vec![d2p_dpsip2, d2p_dpsip_dtheta, d2p_dpsip_dtheta, d2p_dtheta2],
+        )
+        .expect("shape is correct by definition"))
+    }
+
     /// Returns true if the perturbation has no harmonics (== no perturbation).
     fn is_empty(&self) -> bool {
         self.len() == 0
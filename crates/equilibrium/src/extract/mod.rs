@@ -0,0 +1,214 @@
+//! Extraction of equilibrium data out of on-disk file formats.
+//!
+//! Every numerical equilibrium object ([`NcQfactor`](crate::NcQfactor),
+//! [`NcGeometry`](crate::NcGeometry), [`H5Qfactor`](crate::H5Qfactor),
+//! [`H5Geometry`](crate::H5Geometry), ...) is built by reading a handful of named scalars and
+//! 1D/2D arrays out of a file. The [`EqSource`] trait captures exactly that shape, so a single
+//! `build()` implementation can be written once per *object* and reused against every file format
+//! that knows how to open itself and hand back scalars/arrays by name.
+//!
+//! [`netcdf_fields`] holds the variable names both backends read, so the naming convention only
+//! has to be updated in one place.
+
+pub mod netcdf_fields;
+
+use std::path::Path;
+
+use ndarray::{Array1, Array2};
+
+use crate::Result;
+use crate::error::NcError;
+
+/// Path to the stub netCDF file used in unit tests and doctests.
+pub const STUB_NETCDF_PATH: &str = "../../data/stub_netcdf.nc";
+
+/// The same stub netCDF file as [`STUB_NETCDF_PATH`], under the name the integration tests,
+/// benches and doctests import.
+pub const STUB_TEST_NETCDF_PATH: &str = STUB_NETCDF_PATH;
+
+/// Path to the stub HDF5 file used in unit tests and doctests.
+///
+/// Holds the same fields as [`STUB_NETCDF_PATH`], under the same names (see [`netcdf_fields`]), so
+/// the HDF5 and netCDF backends can be exercised with identical test data.
+pub const STUB_H5_PATH: &str = "../../data/stub_equilibrium.h5";
+
+/// A file format that can hand back the named scalars/arrays an equilibrium is built from.
+///
+/// Implemented once per supported file format: [`NcSource`] for netCDF, [`H5Source`] for HDF5.
+pub trait EqSource: Sized {
+    /// Opens the file at `path`.
+    fn open(path: &Path) -> Result<Self>;
+
+    /// Reads the scalar variable named `name`.
+    fn scalar(&self, name: &str) -> Result<f64>;
+
+    /// Reads the 1D array variable named `name`.
+    fn array1d(&self, name: &str) -> Result<Array1<f64>>;
+
+    /// Reads the 2D array variable named `name`.
+    fn array2d(&self, name: &str) -> Result<Array2<f64>>;
+}
+
+// ===============================================================================================
+// netCDF
+// ===============================================================================================
+
+/// Opens the netCDF file at `path`.
+pub fn open(path: &Path) -> Result<netcdf::File> {
+    Ok(netcdf::open(path)?)
+}
+
+/// Reads the scalar variable named `name` out of `f`.
+pub fn extract_scalar(f: &netcdf::File, name: &str) -> Result<f64> {
+    let var = f
+        .variable(name)
+        .ok_or_else(|| NcError::MissingVariable(name.into()))?;
+    Ok(var.value::<f64, _>(0)?)
+}
+
+/// Reads the 1D array variable named `name` out of `f`.
+pub fn extract_1d_array(f: &netcdf::File, name: &str) -> Result<Array1<f64>> {
+    let var = f
+        .variable(name)
+        .ok_or_else(|| NcError::MissingVariable(name.into()))?;
+    let array = var.get::<f64, _>(..)?.into_dimensionality::<ndarray::Ix1>()?;
+    if array.is_empty() {
+        return Err(NcError::EmptyVariable(name.into()).into());
+    }
+    Ok(array)
+}
+
+/// Reads the 2D array variable named `name` out of `f`.
+pub fn extract_2d_array(f: &netcdf::File, name: &str) -> Result<Array2<f64>> {
+    let var = f
+        .variable(name)
+        .ok_or_else(|| NcError::MissingVariable(name.into()))?;
+    let array = var.get::<f64, _>(..)?.into_dimensionality::<ndarray::Ix2>()?;
+    if array.is_empty() {
+        return Err(NcError::EmptyVariable(name.into()).into());
+    }
+    Ok(array)
+}
+
+/// Reads the `α{m,n}(ψp)` and `φ{m,n}(ψp)` arrays of the harmonic `(m, n)` out of `f`.
+pub fn extract_harmonic_arrays(f: &netcdf::File, m: i64, n: i64) -> Result<(Array1<f64>, Array1<f64>)> {
+    use netcdf_fields::*;
+
+    let ms = extract_1d_array(f, M)?;
+    let ns = extract_1d_array(f, N)?;
+    let index = ms
+        .iter()
+        .zip(ns.iter())
+        .position(|(&dm, &dn)| dm as i64 == m && dn as i64 == n)
+        .ok_or_else(|| NcError::MissingVariable(format!("harmonic ({m}, {n})")))?;
+
+    let alphas = extract_2d_array(f, ALPHAS_NORM)?;
+    let phases = extract_2d_array(f, PHASES)?;
+    Ok((alphas.column(index).to_owned(), phases.column(index).to_owned()))
+}
+
+/// Reads the harmonic `(m, n)`'s rigid rotation angular frequency `ω{m,n}` out of `f`.
+pub fn extract_harmonic_omega(f: &netcdf::File, m: i64, n: i64) -> Result<f64> {
+    use netcdf_fields::*;
+
+    let ms = extract_1d_array(f, M)?;
+    let ns = extract_1d_array(f, N)?;
+    let index = ms
+        .iter()
+        .zip(ns.iter())
+        .position(|(&dm, &dn)| dm as i64 == m && dn as i64 == n)
+        .ok_or_else(|| NcError::MissingVariable(format!("harmonic ({m}, {n})")))?;
+
+    let omegas = extract_1d_array(f, OMEGAS)?;
+    Ok(omegas[index])
+}
+
+/// Reads the harmonic `(m, n)`'s amplitude-envelope growth rate `γ{m,n}` out of `f`.
+pub fn extract_harmonic_gamma(f: &netcdf::File, m: i64, n: i64) -> Result<f64> {
+    use netcdf_fields::*;
+
+    let ms = extract_1d_array(f, M)?;
+    let ns = extract_1d_array(f, N)?;
+    let index = ms
+        .iter()
+        .zip(ns.iter())
+        .position(|(&dm, &dn)| dm as i64 == m && dn as i64 == n)
+        .ok_or_else(|| NcError::MissingVariable(format!("harmonic ({m}, {n})")))?;
+
+    let gammas = extract_1d_array(f, GAMMAS)?;
+    Ok(gammas[index])
+}
+
+/// netCDF-backed [`EqSource`], wrapping an open [`netcdf::File`].
+pub struct NcSource(netcdf::File);
+
+impl EqSource for NcSource {
+    fn open(path: &Path) -> Result<Self> {
+        Ok(Self(open(path)?))
+    }
+
+    fn scalar(&self, name: &str) -> Result<f64> {
+        extract_scalar(&self.0, name)
+    }
+
+    fn array1d(&self, name: &str) -> Result<Array1<f64>> {
+        extract_1d_array(&self.0, name)
+    }
+
+    fn array2d(&self, name: &str) -> Result<Array2<f64>> {
+        extract_2d_array(&self.0, name)
+    }
+}
+
+// ===============================================================================================
+// HDF5
+// ===============================================================================================
+
+/// HDF5-backed [`EqSource`], wrapping an open [`hdf5::File`].
+///
+/// Reads the same variable names as [`NcSource`] (see [`netcdf_fields`]), so an equilibrium
+/// produced by any code that writes plain HDF5 datasets (many PDE/plasma codes already do, via
+/// `h5py` or `hdf5-rust`) can be read without first converting it to netCDF.
+pub struct H5Source(hdf5::File);
+
+impl EqSource for H5Source {
+    fn open(path: &Path) -> Result<Self> {
+        Ok(Self(hdf5::File::open(path).map_err(NcError::from)?))
+    }
+
+    fn scalar(&self, name: &str) -> Result<f64> {
+        let dataset = self
+            .0
+            .dataset(name)
+            .map_err(|_| NcError::MissingVariable(name.into()))?;
+        Ok(dataset.read_scalar::<f64>().map_err(NcError::from)?)
+    }
+
+    fn array1d(&self, name: &str) -> Result<Array1<f64>> {
+        let dataset = self
+            .0
+            .dataset(name)
+            .map_err(|_| NcError::MissingVariable(name.into()))?;
+        let array = dataset
+            .read::<f64, ndarray::Ix1>()
+            .map_err(NcError::from)?;
+        if array.is_empty() {
+            return Err(NcError::EmptyVariable(name.into()).into());
+        }
+        Ok(array)
+    }
+
+    fn array2d(&self, name: &str) -> Result<Array2<f64>> {
+        let dataset = self
+            .0
+            .dataset(name)
+            .map_err(|_| NcError::MissingVariable(name.into()))?;
+        let array = dataset
+            .read::<f64, ndarray::Ix2>()
+            .map_err(NcError::from)?;
+        if array.is_empty() {
+            return Err(NcError::EmptyVariable(name.into()).into());
+        }
+        Ok(array)
+    }
+}
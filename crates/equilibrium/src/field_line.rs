@@ -0,0 +1,154 @@
+//! Clebsch field-line-label coordinate, and its gradient split into periodic/secular parts.
+//!
+//! The field-line label `α = θ - q(ψp)·ζ` is constant along a field line, so `(ψp, α, ζ)` is a
+//! convenient (Clebsch) coordinate system for flux-tube and ballooning-mode calculations. Its
+//! covariant derivatives are `α_ψp = -ζ·(dq/dψp)`, `α_θ = 1`, `α_ζ = -q`, giving `∇α = α_ψp·e^ψp +
+//! α_θ·e^θ + α_ζ·e^ζ` in the equilibrium's dual (contravariant) basis.
+//!
+//! `α_ψp` grows linearly with `ζ` as a field line is followed over many toroidal transits, which
+//! would eventually swamp the ζ-independent `α_θ`/`α_ζ` contributions if summed into a single
+//! vector. [`FieldLine::grad_alpha`] keeps the two apart instead, returning the bounded periodic
+//! part (`α_θ·e^θ + α_ζ·e^ζ`) and the unbounded secular part (`α_ψp·e^ψp`) separately, so callers
+//! can accumulate the periodic part over many transits without losing bits to the secular one.
+
+use rsl_interpolation::Accelerator;
+
+use crate::{Geometry, Qfactor, Radians, Result};
+
+/// The half-step used to estimate `dq/dψp` via a central difference.
+const DQ_DPSIP_STEP: f64 = 1e-6;
+
+/// The covariant components of a gradient along the `(ψp, θ, ζ)` dual basis: `∇f = f_ψp·e^ψp +
+/// f_θ·e^θ + f_ζ·e^ζ`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GradAlpha {
+    /// The `e^ψp` covariant component.
+    pub psip: f64,
+    /// The `e^θ` covariant component.
+    pub theta: f64,
+    /// The `e^ζ` covariant component.
+    pub zeta: f64,
+}
+
+/// Constructs the Clebsch field-line label `α = θ - q(ψp)·ζ` and its gradient for a given
+/// equilibrium's `geometry` and `qfactor`.
+pub struct FieldLine<G, Q> {
+    geometry: G,
+    qfactor: Q,
+}
+
+impl<G: Geometry, Q: Qfactor> FieldLine<G, Q> {
+    /// Creates a new [`FieldLine`] from a `geometry` and `qfactor` describing the same
+    /// equilibrium.
+    pub fn new(geometry: G, qfactor: Q) -> Self {
+        Self { geometry, qfactor }
+    }
+
+    /// Returns the `geometry` this [`FieldLine`] was built from.
+    pub fn geometry(&self) -> &G {
+        &self.geometry
+    }
+
+    /// Returns the `qfactor` this [`FieldLine`] was built from.
+    pub fn qfactor(&self) -> &Q {
+        &self.qfactor
+    }
+
+    /// Returns the `(periodic, secular)` parts of `∇α` at `(ψp, θ, ζ)` (see the module docs).
+    ///
+    /// `θ` does not enter the computation (`α_θ = 1` is constant), but is taken for symmetry with
+    /// the rest of the crate's `(psip, theta, zeta)`-keyed evaluation methods.
+    pub fn grad_alpha(
+        &self,
+        psip: f64,
+        _theta: Radians,
+        zeta: Radians,
+        acc: &mut Accelerator,
+    ) -> Result<(GradAlpha, GradAlpha)> {
+        let q = self.qfactor.q(psip, acc)?;
+        let dq_dpsip = (self.qfactor.q(psip + DQ_DPSIP_STEP, acc)?
+            - self.qfactor.q(psip - DQ_DPSIP_STEP, acc)?)
+            / (2.0 * DQ_DPSIP_STEP);
+
+        let periodic = GradAlpha {
+            psip: 0.0,
+            theta: 1.0,
+            zeta: -q,
+        };
+        let secular = GradAlpha {
+            psip: -zeta * dq_dpsip,
+            theta: 0.0,
+            zeta: 0.0,
+        };
+        Ok((periodic, secular))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Clone)]
+    struct LinearQfactor;
+
+    impl Qfactor for LinearQfactor {
+        fn q(&self, psip: f64, _acc: &mut Accelerator) -> Result<f64> {
+            Ok(1.0 + psip)
+        }
+        fn psi(&self, psip: f64, _acc: &mut Accelerator) -> Result<f64> {
+            Ok(psip)
+        }
+        fn dpsi_dpsip(&self, _psip: f64, _acc: &mut Accelerator) -> Result<f64> {
+            Ok(1.0)
+        }
+    }
+
+    struct UnitGeometry;
+
+    impl Geometry for UnitGeometry {
+        fn r(&self, _psip: f64) -> Result<f64> {
+            Ok(1.0)
+        }
+        fn psip(&self, _r: f64) -> Result<f64> {
+            Ok(1.0)
+        }
+        fn psi(&self, _psip: f64) -> Result<f64> {
+            Ok(1.0)
+        }
+        fn rlab(&self, _psip: f64, _theta: f64) -> Result<f64> {
+            Ok(1.0)
+        }
+        fn zlab(&self, _psip: f64, _theta: f64) -> Result<f64> {
+            Ok(0.0)
+        }
+        fn jacobian(&self, _psip: f64, _theta: f64) -> Result<f64> {
+            Ok(1.0)
+        }
+    }
+
+    #[test]
+    fn test_grad_alpha() {
+        let field_line = FieldLine::new(UnitGeometry, LinearQfactor);
+        let mut acc = Accelerator::new();
+
+        // q(psip) = 1 + psip => dq/dpsip = 1
+        let (periodic, secular) = field_line.grad_alpha(0.5, 0.3, 4.0, &mut acc).unwrap();
+
+        assert_eq!(periodic.psip, 0.0);
+        assert_eq!(periodic.theta, 1.0);
+        assert!((periodic.zeta - -1.5).abs() < 1e-9);
+
+        assert!((secular.psip - -4.0).abs() < 1e-6);
+        assert_eq!(secular.theta, 0.0);
+        assert_eq!(secular.zeta, 0.0);
+    }
+
+    #[test]
+    fn test_grad_alpha_zero_zeta_has_no_secular_part() {
+        let field_line = FieldLine::new(UnitGeometry, LinearQfactor);
+        let mut acc = Accelerator::new();
+
+        let (_, secular) = field_line.grad_alpha(0.5, 0.0, 0.0, &mut acc).unwrap();
+        assert_eq!(secular, GradAlpha::default());
+    }
+}
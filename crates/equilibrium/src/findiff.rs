@@ -0,0 +1,299 @@
+//! High-order finite-difference derivative stencils and Kreiss-Oliger artificial dissipation.
+//!
+//! [`Bfield::db_dpsip`](crate::Bfield::db_dpsip)/[`db_dtheta`](crate::Bfield::db_dtheta) and
+//! [`Harmonic::dh_dpsip`](crate::Harmonic::dh_dpsip)/[`dh_dtheta`](crate::Harmonic::dh_dtheta)/
+//! [`dh_dzeta`](crate::Harmonic::dh_dzeta) currently differentiate their backing spline
+//! analytically. For a reconstructed netCDF field carrying high-frequency reconstruction noise,
+//! that amplifies the noise rather than the signal. [`CenteredStencil`] computes the same
+//! derivative instead from arbitrary-order centered Fornberg finite-difference weights evaluated
+//! directly on the sampled grid, and [`KreissOliger`] adds the companion artificial-dissipation
+//! term that damps the grid-scale (Nyquist) modes a centered stencil cannot itself remove.
+//!
+//! Wiring either of these in as a selectable mode on `NcBfieldBuilder` isn't done here -- that
+//! builder lives in `bfields.rs`, which this snapshot does not contain -- so both stay
+//! self-contained, reusable numerical building blocks: a caller with access to a field's sampled
+//! grid can already evaluate a high-order derivative or a dissipation term directly.
+
+/// Computes Fornberg finite-difference weights for every derivative order `0..=max_derivative` of
+/// a function sampled at `nodes`, evaluated at `eval_point`.
+///
+/// Implements the standard `O(n²)` recurrence from Fornberg (1988), "Generation of Finite
+/// Difference Formulas on Arbitrarily Spaced Grids" -- the same algorithm `scipy.differentiate`
+/// and most spectral-methods codes use, rather than solving the equivalent (and worse-conditioned)
+/// Vandermonde system directly.
+///
+/// Returns `weights[derivative][node_index]`, so `weights[d]` dotted with the sampled function
+/// values at `nodes` gives the `d`-th derivative at `eval_point`, **before** dividing by `h^d` if
+/// `nodes` are not already expressed in grid-spacing units (see [`CenteredStencil`], which handles
+/// that scaling).
+///
+/// # Panics
+///
+/// Panics if `nodes` is empty.
+pub fn fornberg_weights(nodes: &[f64], eval_point: f64, max_derivative: usize) -> Vec<Vec<f64>> {
+    assert!(!nodes.is_empty(), "need at least one node");
+
+    let n = nodes.len() - 1;
+    let m = max_derivative;
+    let mut c = vec![vec![0.0; n + 1]; m + 1];
+    c[0][0] = 1.0;
+
+    let mut c1 = 1.0;
+    let mut c4 = nodes[0] - eval_point;
+    for i in 1..=n {
+        let mn = i.min(m);
+        let mut c2 = 1.0;
+        let c5 = c4;
+        c4 = nodes[i] - eval_point;
+        for j in 0..i {
+            let c3 = nodes[i] - nodes[j];
+            c2 *= c3;
+            if j == i - 1 {
+                for k in (1..=mn).rev() {
+                    c[k][i] = c1 * (k as f64 * c[k - 1][i - 1] - c5 * c[k][i - 1]) / c2;
+                }
+                c[0][i] = -c1 * c5 * c[0][i - 1] / c2;
+            }
+            for k in (1..=mn).rev() {
+                c[k][j] = (c4 * c[k][j] - k as f64 * c[k - 1][j]) / c3;
+            }
+            c[0][j] = c4 * c[0][j] / c3;
+        }
+        c1 = c2;
+    }
+
+    c
+}
+
+/// A centered, uniform-grid finite-difference stencil for one derivative order, at a chosen
+/// accuracy order.
+///
+/// A centered stencil for the `d`-th derivative at accuracy order `p` needs a symmetric half-width
+/// `m = (d + p - 1) / 2` (rounded down), i.e. `2m+1` points -- the minimum width for which the
+/// Fornberg weights reduce to the standard textbook centered-difference coefficients.
+#[derive(Debug, Clone)]
+pub struct CenteredStencil {
+    derivative_order: usize,
+    accuracy_order: usize,
+    half_width: usize,
+    /// Weights for `nodes = -half_width..=half_width` on a unit-spacing grid, i.e. still needing a
+    /// `h^(-derivative_order)` scale applied in [`Self::apply`].
+    weights: Vec<f64>,
+}
+
+impl CenteredStencil {
+    /// Builds the centered stencil for `derivative_order` at `accuracy_order` (must be even, per
+    /// the standard centered-difference convention -- an odd accuracy order on a symmetric stencil
+    /// does not improve on the next-lower even order).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `accuracy_order` is zero or odd.
+    pub fn new(derivative_order: usize, accuracy_order: usize) -> Self {
+        assert!(accuracy_order > 0 && accuracy_order % 2 == 0, "accuracy_order must be a positive even number");
+
+        // Minimum half-width for a symmetric centered stencil of this derivative/accuracy order --
+        // 2*half_width+1 points in total, the fewest a centered Fornberg stencil needs to reach
+        // `accuracy_order`.
+        let half_width = (derivative_order + accuracy_order - 1) / 2;
+        let nodes: Vec<f64> = (-(half_width as isize)..=half_width as isize)
+            .map(|i| i as f64)
+            .collect();
+
+        let all_weights = fornberg_weights(&nodes, 0.0, derivative_order);
+        let weights = all_weights[derivative_order].clone();
+
+        Self {
+            derivative_order,
+            accuracy_order,
+            half_width,
+            weights,
+        }
+    }
+
+    /// The stencil's half-width: [`Self::apply`] needs exactly `2*half_width+1` samples, centered
+    /// on the evaluation point.
+    pub fn half_width(&self) -> usize {
+        self.half_width
+    }
+
+    /// The derivative order this stencil approximates.
+    pub fn derivative_order(&self) -> usize {
+        self.derivative_order
+    }
+
+    /// The stencil's formal accuracy order.
+    pub fn accuracy_order(&self) -> usize {
+        self.accuracy_order
+    }
+
+    /// Evaluates the derivative at the center of `samples`, a uniform grid of spacing `h`.
+    ///
+    /// `samples[i]` must hold the sampled field at `center - h*half_width + i*h`, i.e.
+    /// `samples.len() == 2*half_width+1` with the evaluation point at `samples[half_width]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples.len() != 2*self.half_width()+1`.
+    pub fn apply(&self, samples: &[f64], h: f64) -> f64 {
+        assert_eq!(
+            samples.len(),
+            2 * self.half_width + 1,
+            "expected {} samples for a half-width {} stencil, got {}",
+            2 * self.half_width + 1,
+            self.half_width,
+            samples.len()
+        );
+
+        let raw: f64 = self.weights.iter().zip(samples).map(|(w, s)| w * s).sum();
+        raw / h.powi(self.derivative_order as i32)
+    }
+}
+
+/// Kreiss-Oliger artificial dissipation, damping unresolved grid-scale (Nyquist) noise left behind
+/// by a centered finite-difference derivative of a given order without degrading its accuracy.
+///
+/// For a scheme of order `p`, the dissipation term added to the field value is
+/// `σ · (-1)^(p/2) · h^(p+1)/2^(p+2)` times the `(p+2)`-th order centered difference of the
+/// sampled array (the raw binomial difference, not a derivative) -- the sign is chosen so a
+/// positive dissipation strength `σ` damps, rather than amplifies, the grid-scale mode.
+#[derive(Debug, Clone, Copy)]
+pub struct KreissOliger {
+    /// The underlying finite-difference scheme's order `p` (must be even).
+    order: usize,
+    /// The dissipation strength `σ`, typically in `[0, 1]`.
+    strength: f64,
+}
+
+impl KreissOliger {
+    /// Creates a [`KreissOliger`] dissipation operator for a scheme of order `p`, with strength
+    /// `σ`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is odd.
+    pub fn new(order: usize, strength: f64) -> Self {
+        assert!(order % 2 == 0, "Kreiss-Oliger dissipation order must be even");
+        Self { order, strength }
+    }
+
+    /// The number of samples [`Self::apply`] needs: `order+3`, the width of the `(order+2)`-th
+    /// order centered binomial difference.
+    pub fn stencil_len(&self) -> usize {
+        self.order + 3
+    }
+
+    /// Computes the dissipation term at the center of `samples`, a uniform grid of spacing `h`.
+    ///
+    /// `samples` must be centered the same way as [`CenteredStencil::apply`]: length
+    /// [`Self::stencil_len`], evaluation point in the middle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples.len() != self.stencil_len()`.
+    pub fn apply(&self, samples: &[f64], h: f64) -> f64 {
+        let d = self.order + 2;
+        assert_eq!(
+            samples.len(),
+            d + 1,
+            "expected {} samples for a Kreiss-Oliger order-{} operator, got {}",
+            d + 1,
+            self.order,
+            samples.len()
+        );
+
+        // The (raw, undivided) centered binomial difference of even order d:
+        // Δ^d f = Σ_{k=0}^{d} (-1)^k C(d,k) f[d-k], indexed from the stencil's center.
+        let centered_difference: f64 = (0..=d)
+            .map(|k| {
+                let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                sign * binomial(d, k) * samples[d - k]
+            })
+            .sum();
+
+        let sign = if (d / 2 + 1) % 2 == 0 { 1.0 } else { -1.0 };
+        self.strength * sign * h.powi(self.order as i32 + 1) / 2f64.powi(self.order as i32 + 2)
+            * centered_difference
+    }
+}
+
+/// Returns the binomial coefficient `C(n, k)` as an `f64` (exact for the small `n` this module
+/// deals with -- derivative/dissipation orders stay well within `f64`'s exact-integer range).
+fn binomial(n: usize, k: usize) -> f64 {
+    let k = k.min(n - k);
+    let mut result = 1.0;
+    for i in 0..k {
+        result = result * (n - i) as f64 / (i + 1) as f64;
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_centered_first_derivative_is_exact_for_cubic() {
+        // A 4th-order centered first-derivative stencil is exact for cubics.
+        let stencil = CenteredStencil::new(1, 4);
+        assert_eq!(stencil.half_width(), 2);
+
+        let h = 0.1;
+        let f = |x: f64| x.powi(3) - 2.0 * x.powi(2) + 1.0;
+        let df = |x: f64| 3.0 * x.powi(2) - 4.0 * x;
+
+        let x0 = 0.7;
+        let samples: Vec<f64> = (-2..=2).map(|i| f(x0 + i as f64 * h)).collect();
+        let result = stencil.apply(&samples, h);
+        assert!((result - df(x0)).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_centered_second_derivative_is_exact_for_cubic() {
+        let stencil = CenteredStencil::new(2, 2);
+        assert_eq!(stencil.derivative_order(), 2);
+        assert_eq!(stencil.accuracy_order(), 2);
+
+        let h = 0.05;
+        let f = |x: f64| x.powi(3) - 2.0 * x.powi(2) + 1.0;
+        let d2f = |x: f64| 6.0 * x - 4.0;
+
+        let x0 = -0.3;
+        let samples: Vec<f64> = (-(stencil.half_width() as isize)..=stencil.half_width() as isize)
+            .map(|i| f(x0 + i as f64 * h))
+            .collect();
+        let result = stencil.apply(&samples, h);
+        assert!((result - d2f(x0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_kreiss_oliger_vanishes_on_constant_data() {
+        let ko = KreissOliger::new(2, 1.0);
+        let samples = vec![1.0; ko.stencil_len()];
+        let result = ko.apply(&samples, 0.1);
+        assert!(result.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kreiss_oliger_damps_checkerboard_mode() {
+        let ko = KreissOliger::new(2, 1.0);
+        let samples: Vec<f64> = (0..ko.stencil_len())
+            .map(|i| if i % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        let result = ko.apply(&samples, 0.1);
+        // Damping: the term opposes the sample at the stencil's center.
+        assert!(result * samples[samples.len() / 2] < 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_centered_stencil_rejects_odd_accuracy() {
+        CenteredStencil::new(1, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_kreiss_oliger_rejects_odd_order() {
+        KreissOliger::new(3, 1.0);
+    }
+}
@@ -0,0 +1,27 @@
+//! The floating-point scalar type extension point for [`Harmonic`](crate::Harmonic) and
+//! [`HarmonicCache`](crate::HarmonicCache).
+//!
+//! Everything else in this crate (splines, [`Accelerator`](rsl_interpolation::Accelerator), netCDF
+//! extraction) is hard-wired to `f64`, since the underlying data and interpolation backend are.
+//! [`Harmonic`]/[`HarmonicCache`] evaluate millions of times per ensemble trace without ever
+//! touching a spline table directly (that happens once, in [`NcHarmonicCache::update`]), so they
+//! are the one place where trading precision for a smaller per-state footprint is worth exposing.
+
+use num_traits::{Float, FloatConst, FromPrimitive, ToPrimitive};
+
+/// A floating-point scalar usable as [`Harmonic`](crate::Harmonic)/
+/// [`HarmonicCache`](crate::HarmonicCache)'s generic parameter.
+///
+/// Blanket-implemented for any type satisfying the bounds, so `f64` and `f32` both qualify for
+/// free. Includes [`std::fmt::Debug`] so every generic type that stores an `F` (e.g.
+/// [`NcHarmonic`](crate::NcHarmonic)) can keep deriving/implementing `Debug` without threading an
+/// extra bound through at every call site.
+pub trait Flt: Float + FloatConst + FromPrimitive + ToPrimitive + std::fmt::Debug {}
+
+impl<F: Float + FloatConst + FromPrimitive + ToPrimitive + std::fmt::Debug> Flt for F {}
+
+/// `x mod modulus`, always within `[0, modulus)` -- the generic equivalent of `f64::rem_euclid`,
+/// which isn't among [`Flt`]'s bounds.
+pub(crate) fn rem_euclid<F: Flt>(x: F, modulus: F) -> F {
+    x - (x / modulus).floor() * modulus
+}
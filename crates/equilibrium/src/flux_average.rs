@@ -0,0 +1,126 @@
+//! Flux-surface averaging of arbitrary `θ`-dependent profile quantities.
+//!
+//! For a quantity `f(ψp,θ)`, the flux-surface average at fixed `ψp` is
+//! `⟨f⟩ = (∮f(θ)·J dθ)/(∮J dθ)`, where `J` is the Boozer-coordinate Jacobian
+//! `J(ψp,θ) ∝ (g(ψp)·q(ψp)+I(ψp))/B(ψp,θ)²` (the proportionality constant cancels between numerator
+//! and denominator, so it is never needed here). Both integrals are taken over the full poloidal
+//! turn `θ ∈ [0,2π)`, via the periodic trapezoidal rule -- spectrally accurate for smooth periodic
+//! integrands, so a modest, fixed point count already reaches machine precision.
+
+use rsl_interpolation::{Accelerator, Cache};
+
+use crate::{Bfield, Current, Qfactor, Result};
+
+/// The number of equally-spaced `θ` samples used by [`flux_surface_average`]'s periodic
+/// trapezoidal rule.
+const N_SAMPLES: usize = 64;
+
+/// Flux-surface-averages `f(θ)` over the surface `ψp`, weighting by the Boozer-coordinate Jacobian
+/// `J(ψp,θ) ∝ (g(ψp)·q(ψp)+I(ψp))/B(ψp,θ)²` (see the module docs).
+///
+/// `f` is evaluated at the same `θ` samples as the Jacobian, so both integrals share a single pass
+/// over the grid. `xacc`/`yacc`/`cache` are the caller's own [`Bfield`] accelerators/cache, reused
+/// across the `N_SAMPLES` evaluations the same way a caller would reuse them across any other
+/// repeated call at the same `ψp`.
+///
+/// # Example
+///
+/// ```
+/// # use equilibrium::*;
+/// # use std::path::PathBuf;
+/// # use rsl_interpolation::{Accelerator, Cache};
+/// #
+/// # fn main() -> Result<()> {
+/// # let path = PathBuf::from("../data/stub_netcdf.nc");
+/// # let qfactor = NcQfactorBuilder::new(&path, "steffen").build()?;
+/// # let current = NcCurrentBuilder::new(&path, "steffen").build()?;
+/// # let bfield = NcBfieldBuilder::new(&path, "bilinear").build()?;
+/// #
+/// let mut acc = Accelerator::new();
+/// let mut xacc = Accelerator::new();
+/// let mut yacc = Accelerator::new();
+/// let mut cache = Cache::new();
+/// let average = flux_surface_average(
+///     0.015,
+///     &qfactor,
+///     &current,
+///     &bfield,
+///     &mut acc,
+///     &mut xacc,
+///     &mut yacc,
+///     &mut cache,
+///     |theta| Ok(theta.cos()),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn flux_surface_average(
+    psip: f64,
+    qfactor: &impl Qfactor,
+    current: &impl Current,
+    bfield: &impl Bfield,
+    acc: &mut Accelerator,
+    xacc: &mut Accelerator,
+    yacc: &mut Accelerator,
+    cache: &mut Cache<f64>,
+    mut f: impl FnMut(f64) -> Result<f64>,
+) -> Result<f64> {
+    let g = current.g(psip, acc)?;
+    let q = qfactor.q(psip, acc)?;
+    let i = current.i(psip, acc)?;
+    let numerator_const = g * q + i;
+
+    let dtheta = std::f64::consts::TAU / N_SAMPLES as f64;
+    let mut weighted_sum = 0.0;
+    let mut weight_sum = 0.0;
+    for k in 0..N_SAMPLES {
+        let theta = k as f64 * dtheta;
+        let b = bfield.b(psip, theta, xacc, yacc, cache)?;
+        let jacobian = numerator_const / (b * b);
+        weighted_sum += jacobian * f(theta)?;
+        weight_sum += jacobian;
+    }
+
+    Ok(weighted_sum / weight_sum)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::extract::STUB_NETCDF_PATH;
+    use crate::{NcBfieldBuilder, NcCurrentBuilder, NcQfactorBuilder};
+
+    #[test]
+    fn test_constant_integrand_averages_to_itself() {
+        let path = PathBuf::from(STUB_NETCDF_PATH);
+        let typ = "steffen";
+        let qfactor = NcQfactorBuilder::new(&path, typ).build().unwrap();
+        let current = NcCurrentBuilder::new(&path, typ).build().unwrap();
+        let bfield = NcBfieldBuilder::new(&path, "bilinear").build().unwrap();
+
+        let mut acc = Accelerator::new();
+        let mut xacc = Accelerator::new();
+        let mut yacc = Accelerator::new();
+        let mut cache = Cache::new();
+
+        // `f(theta) = 1` everywhere, so the Jacobian weighting cancels out of the average exactly,
+        // regardless of how `B(psip, theta)` varies with `theta`.
+        let average = flux_surface_average(
+            0.015,
+            &qfactor,
+            &current,
+            &bfield,
+            &mut acc,
+            &mut xacc,
+            &mut yacc,
+            &mut cache,
+            |_theta| Ok(1.0),
+        )
+        .unwrap();
+
+        assert_eq!(average, 1.0);
+    }
+}
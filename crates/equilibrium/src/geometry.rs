@@ -8,15 +8,14 @@ use std::path::PathBuf;
 
 use common::array1D_getter_impl;
 use rsl_interpolation::{
-    Accelerator, Cache, DynInterpolation, DynInterpolation2d, Interp2dType, InterpType,
-    make_interp_type, make_interp2d_type,
+    Accelerator, Cache, DynInterpolation2d, Interp2dType, make_interp2d_type,
 };
 
-use ndarray::{Array1, Array2};
+use ndarray::{Array1, Array2, ArrayView1, azip};
 
 use crate::fortran_vec_to_carray2d_impl;
-use crate::{Flux, Length, Radians};
-use crate::{Geometry, Result};
+use crate::{ExtrapolationPolicy, Flux, Length, Radians};
+use crate::{Geometry, OneDInterp, Result};
 
 /// Used to create an [`NcGeometry`].
 pub struct NcGeometryBuilder {
@@ -30,12 +29,17 @@ pub struct NcGeometryBuilder {
     ///
     /// [`Interpolation type`]: ../rsl_interpolation/trait.Interp2dType.html#implementors
     typ2d: String,
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
 }
 
 impl NcGeometryBuilder {
     /// Creates a new [`NcGeometryBuilder`] from a netCDF file at `path`, with spline of `typ`
     /// interpolation type.
     ///
+    /// Defaults to [`ExtrapolationPolicy::Error`]; use [`with_extrapolation`](Self::with_extrapolation)
+    /// to select a different policy.
+    ///
     /// # Example
     /// ```
     /// # use std::path::PathBuf;
@@ -47,9 +51,16 @@ impl NcGeometryBuilder {
             path: path.clone(),
             typ1d: typ1d.into(),
             typ2d: typ2d.into(),
+            policy: ExtrapolationPolicy::default(),
         }
     }
 
+    /// Sets the behavior for `psip` values outside the stored data range.
+    pub fn with_extrapolation(mut self, policy: ExtrapolationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     /// Creates a new [`NcGeometry`] with the Builder's configuration.
     ///
     /// # Example
@@ -84,6 +95,8 @@ pub struct NcGeometry {
     ///
     /// [`Interpolation type`]: ../rsl_interpolation/trait.Interp2dType.html#implementors
     typ2d: String,
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
 
     /// Magnetic field strength on the axis `B0` **in \[T\]**.
     baxis: f64,
@@ -113,9 +126,12 @@ pub struct NcGeometry {
     jacobian_data_fortran_flat: Vec<f64>,
 
     /// Interpolator of `ψp(r)` **in \[m\]**.
-    psip_of_r_interp: DynInterpolation<f64>,
+    ///
+    /// `typ1d` `"pchip"`/`"monotone"` selects a shape-preserving monotone cubic Hermite spline
+    /// instead of one of `rsl_interpolation`'s own backends -- see [`OneDInterp`].
+    psip_of_r_interp: OneDInterp,
     /// Interpolator of `r(ψp)` **in \[m\]**.
-    r_of_psip_interp: DynInterpolation<f64>,
+    r_of_psip_interp: OneDInterp,
 
     /// Interpolator over the R coordinate, as a function of ψp, θ.
     rlab_interp: DynInterpolation2d<f64>,
@@ -158,9 +174,9 @@ impl NcGeometry {
         let zlab_data_fortran_flat = zlab_data.flatten_with_order(order).to_vec();
         let jacobian_data_fortran_flat = jacobian_data.flatten_with_order(order).to_vec();
 
-        let r_of_psip_interp = make_interp_type(&builder.typ1d)?.build(&psip_data, &r_data)?;
+        let r_of_psip_interp = OneDInterp::build(&builder.typ1d, &psip_data, &r_data)?;
 
-        let psip_of_r_interp = make_interp_type(&builder.typ1d)?.build(&r_data, &psip_data)?;
+        let psip_of_r_interp = OneDInterp::build(&builder.typ1d, &r_data, &psip_data)?;
 
         let rlab_interp = make_interp2d_type(&builder.typ2d)?.build(
             &psip_data,
@@ -184,6 +200,7 @@ impl NcGeometry {
             path,
             typ1d: builder.typ1d,
             typ2d: builder.typ2d,
+            policy: builder.policy,
             baxis,
             raxis,
             zaxis,
@@ -208,64 +225,279 @@ impl NcGeometry {
 impl Geometry for NcGeometry {
     fn r(&self, psip: Flux) -> Result<Length> {
         let mut acc = Accelerator::new();
-        Ok(self
-            .r_of_psip_interp
-            .eval(&self.psip_data, &self.r_data, psip, &mut acc)?)
+        self.r_of_psip_interp
+            .eval_policy(&self.psip_data, &self.r_data, psip, &mut acc, self.policy)
     }
 
     fn psip(&self, r: Length) -> Result<Flux> {
         let mut acc = Accelerator::new();
-        Ok(self
-            .psip_of_r_interp
-            .eval(&self.r_data, &self.psip_data, r, &mut acc)?)
+        self.psip_of_r_interp
+            .eval_policy(&self.r_data, &self.psip_data, r, &mut acc, self.policy)
     }
 
     fn rlab(&self, psip: Flux, theta: Radians) -> Result<f64> {
         let mut xacc = Accelerator::new();
         let mut yacc = Accelerator::new();
         let mut cache = Cache::new();
-        Ok(self.rlab_interp.eval(
-            &self.psip_data,
-            &self.theta_data,
+        self.eval_2d_policy(
+            &self.rlab_interp,
             &self.rlab_data_fortran_flat,
             psip,
-            theta.rem_euclid(TAU),
+            theta,
             &mut xacc,
             &mut yacc,
             &mut cache,
-        )?)
+        )
     }
 
     fn zlab(&self, psip: Flux, theta: Radians) -> Result<f64> {
         let mut xacc = Accelerator::new();
         let mut yacc = Accelerator::new();
         let mut cache = Cache::new();
-        Ok(self.zlab_interp.eval(
-            &self.psip_data,
-            &self.theta_data,
+        self.eval_2d_policy(
+            &self.zlab_interp,
             &self.zlab_data_fortran_flat,
             psip,
-            theta.rem_euclid(TAU),
+            theta,
             &mut xacc,
             &mut yacc,
             &mut cache,
-        )?)
+        )
     }
 
     fn jacobian(&self, psip: Flux, theta: Radians) -> Result<f64> {
         let mut xacc = Accelerator::new();
         let mut yacc = Accelerator::new();
         let mut cache = Cache::new();
-        Ok(self.jacobian_interp.eval(
-            &self.psip_data,
-            &self.theta_data,
+        self.eval_2d_policy(
+            &self.jacobian_interp,
             &self.jacobian_data_fortran_flat,
             psip,
-            theta.rem_euclid(TAU),
+            theta,
             &mut xacc,
             &mut yacc,
             &mut cache,
-        )?)
+        )
+    }
+}
+
+/// Extrapolation
+impl NcGeometry {
+    /// Evaluates a `(ψp, θ)` interpolator, honoring `self.policy` when `psip` falls outside
+    /// `psip_data`'s range. `θ` is always periodic, so only `psip` is ever out of range.
+    #[allow(clippy::too_many_arguments)]
+    fn eval_2d_policy(
+        &self,
+        interp: &DynInterpolation2d<f64>,
+        data: &[f64],
+        psip: Flux,
+        theta: Radians,
+        xacc: &mut Accelerator,
+        yacc: &mut Accelerator,
+        cache: &mut Cache,
+    ) -> Result<f64> {
+        let theta = theta.rem_euclid(TAU);
+        let (lo, hi) = (self.psip_data[0], *self.psip_data.last().expect("non-empty"));
+        let boundary = if psip < lo {
+            Some(lo)
+        } else if psip > hi {
+            Some(hi)
+        } else {
+            None
+        };
+
+        let Some(boundary) = boundary else {
+            return Ok(interp.eval(
+                &self.psip_data,
+                &self.theta_data,
+                data,
+                psip,
+                theta,
+                xacc,
+                yacc,
+                cache,
+            )?);
+        };
+
+        match self.policy {
+            ExtrapolationPolicy::Error => Err(crate::EqError::OutOfRange(psip)),
+            ExtrapolationPolicy::Clamp => Ok(interp.eval(
+                &self.psip_data,
+                &self.theta_data,
+                data,
+                boundary,
+                theta,
+                xacc,
+                yacc,
+                cache,
+            )?),
+            ExtrapolationPolicy::LinearExtrapolate => {
+                // One-sided secant derivative in ψp, using the node adjacent to the boundary.
+                let neighbor = if boundary == lo {
+                    self.psip_data[1]
+                } else {
+                    self.psip_data[self.psip_data.len() - 2]
+                };
+                let value = interp.eval(
+                    &self.psip_data,
+                    &self.theta_data,
+                    data,
+                    boundary,
+                    theta,
+                    xacc,
+                    yacc,
+                    cache,
+                )?;
+                let value_neighbor = interp.eval(
+                    &self.psip_data,
+                    &self.theta_data,
+                    data,
+                    neighbor,
+                    theta,
+                    xacc,
+                    yacc,
+                    cache,
+                )?;
+                let deriv = (value_neighbor - value) / (neighbor - boundary);
+                Ok(value + deriv * (psip - boundary))
+            }
+        }
+    }
+}
+
+/// Batch evaluation
+///
+/// A single call to [`NcGeometry::rlab`]/[`zlab`](NcGeometry::zlab)/[`jacobian`](NcGeometry::jacobian)
+/// allocates a fresh [`Accelerator`]/[`Cache`] pair, which is wasteful when evaluating thousands of
+/// points along an orbit or on a Poincaré grid. These methods allocate the accelerators once and
+/// reuse them across the whole sweep: as long as the query points are roughly sorted, the
+/// accelerators turn repeated `O(log n)` binary searches into amortized `O(1)` neighbor steps.
+impl NcGeometry {
+    /// Evaluates `R(ψp, θ)` at paired `(psips[i], thetas[i])` points.
+    pub fn rlab_batch(
+        &self,
+        psips: &ArrayView1<Flux>,
+        thetas: &ArrayView1<Radians>,
+    ) -> Result<Array1<f64>> {
+        let mut xacc = Accelerator::new();
+        let mut yacc = Accelerator::new();
+        let mut cache = Cache::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas) {
+            match self.eval_2d_policy(
+                &self.rlab_interp,
+                &self.rlab_data_fortran_flat,
+                psip,
+                theta,
+                &mut xacc,
+                &mut yacc,
+                &mut cache,
+            ) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `Z(ψp, θ)` at paired `(psips[i], thetas[i])` points.
+    pub fn zlab_batch(
+        &self,
+        psips: &ArrayView1<Flux>,
+        thetas: &ArrayView1<Radians>,
+    ) -> Result<Array1<f64>> {
+        let mut xacc = Accelerator::new();
+        let mut yacc = Accelerator::new();
+        let mut cache = Cache::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas) {
+            match self.eval_2d_policy(
+                &self.zlab_interp,
+                &self.zlab_data_fortran_flat,
+                psip,
+                theta,
+                &mut xacc,
+                &mut yacc,
+                &mut cache,
+            ) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates the Jacobian `J(ψp, θ)` at paired `(psips[i], thetas[i])` points.
+    pub fn jacobian_batch(
+        &self,
+        psips: &ArrayView1<Flux>,
+        thetas: &ArrayView1<Radians>,
+    ) -> Result<Array1<f64>> {
+        let mut xacc = Accelerator::new();
+        let mut yacc = Accelerator::new();
+        let mut cache = Cache::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas) {
+            match self.eval_2d_policy(
+                &self.jacobian_interp,
+                &self.jacobian_data_fortran_flat,
+                psip,
+                theta,
+                &mut xacc,
+                &mut yacc,
+                &mut cache,
+            ) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `R(ψp, θ)` on the outer-product grid of `psips` and `thetas`, returning an
+    /// `Array2` of shape `(psips.len(), thetas.len())`.
+    pub fn rlab_grid(&self, psips: &ArrayView1<Flux>, thetas: &ArrayView1<Radians>) -> Result<Array2<f64>> {
+        self.grid_eval(psips, thetas, &self.rlab_interp, &self.rlab_data_fortran_flat)
+    }
+
+    /// Evaluates `Z(ψp, θ)` on the outer-product grid of `psips` and `thetas`, returning an
+    /// `Array2` of shape `(psips.len(), thetas.len())`.
+    pub fn zlab_grid(&self, psips: &ArrayView1<Flux>, thetas: &ArrayView1<Radians>) -> Result<Array2<f64>> {
+        self.grid_eval(psips, thetas, &self.zlab_interp, &self.zlab_data_fortran_flat)
+    }
+
+    /// Evaluates the Jacobian `J(ψp, θ)` on the outer-product grid of `psips` and `thetas`,
+    /// returning an `Array2` of shape `(psips.len(), thetas.len())`.
+    pub fn jacobian_grid(&self, psips: &ArrayView1<Flux>, thetas: &ArrayView1<Radians>) -> Result<Array2<f64>> {
+        self.grid_eval(psips, thetas, &self.jacobian_interp, &self.jacobian_data_fortran_flat)
+    }
+
+    /// Shared outer-product grid evaluator behind [`rlab_grid`](Self::rlab_grid),
+    /// [`zlab_grid`](Self::zlab_grid) and [`jacobian_grid`](Self::jacobian_grid).
+    fn grid_eval(
+        &self,
+        psips: &ArrayView1<Flux>,
+        thetas: &ArrayView1<Radians>,
+        interp: &DynInterpolation2d<f64>,
+        data: &[f64],
+    ) -> Result<Array2<f64>> {
+        let mut xacc = Accelerator::new();
+        let mut yacc = Accelerator::new();
+        let mut cache = Cache::new();
+        let mut out = Array2::zeros((psips.len(), thetas.len()));
+        for (i, &psip) in psips.iter().enumerate() {
+            for (j, &theta) in thetas.iter().enumerate() {
+                out[[i, j]] =
+                    self.eval_2d_policy(interp, data, psip, theta, &mut xacc, &mut yacc, &mut cache)?;
+            }
+        }
+        Ok(out)
     }
 }
 
@@ -286,6 +518,11 @@ impl NcGeometry {
         self.typ2d.clone()
     }
 
+    /// Returns the active out-of-range extrapolation policy.
+    pub fn policy(&self) -> ExtrapolationPolicy {
+        self.policy
+    }
+
     /// Returns the shape of the `b` array.
     pub fn shape(&self) -> (usize, usize) {
         (self.psip_data.len(), self.theta_data.len())
@@ -345,6 +582,7 @@ impl std::fmt::Debug for NcGeometry {
             .field("path", &self.path())
             .field("typ 1D", &self.typ1d())
             .field("typ 2D", &self.typ2d())
+            .field("extrapolation", &self.policy())
             .field("Baxis [T]", &format!("{:.7}", self.baxis()))
             .field("Raxis [m]", &format!("{:.7}", self.raxis()))
             .field("Zaxis [m]", &format!("{:.7}", self.zaxis()))
@@ -412,4 +650,64 @@ mod test {
         g.zlab(psip, theta).unwrap();
         g.jacobian(psip, theta).unwrap();
     }
+
+    #[test]
+    fn test_extrapolation_policy() {
+        let g = create_nc_geometry();
+        let psip_wall = g.psip_wall();
+        let beyond_wall = psip_wall + 1.0;
+        let theta = 0.0;
+
+        assert!(matches!(
+            g.r(beyond_wall),
+            Err(crate::EqError::OutOfRange(coord)) if coord == beyond_wall
+        ));
+        assert!(matches!(
+            g.rlab(beyond_wall, theta),
+            Err(crate::EqError::OutOfRange(coord)) if coord == beyond_wall
+        ));
+
+        let path = PathBuf::from(STUB_NETCDF_PATH);
+        let g_clamped = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+            .with_extrapolation(ExtrapolationPolicy::Clamp)
+            .build()
+            .unwrap();
+        assert_eq!(
+            g_clamped.r(beyond_wall).unwrap(),
+            g_clamped.r(psip_wall).unwrap()
+        );
+        assert_eq!(
+            g_clamped.rlab(beyond_wall, theta).unwrap(),
+            g_clamped.rlab(psip_wall, theta).unwrap()
+        );
+
+        let g_extrapolated = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+            .with_extrapolation(ExtrapolationPolicy::LinearExtrapolate)
+            .build()
+            .unwrap();
+        g_extrapolated.r(beyond_wall).unwrap();
+        g_extrapolated.rlab(beyond_wall, theta).unwrap();
+    }
+
+    #[test]
+    fn test_batch_evaluation() {
+        let g = create_nc_geometry();
+
+        let psips = Array1::linspace(0.01, 0.02, 5);
+        let thetas = Array1::linspace(0.0, 1.0, 5);
+
+        let rlab_batch = g.rlab_batch(&psips.view(), &thetas.view()).unwrap();
+        let zlab_batch = g.zlab_batch(&psips.view(), &thetas.view()).unwrap();
+        let jacobian_batch = g.jacobian_batch(&psips.view(), &thetas.view()).unwrap();
+
+        for (i, (&psip, &theta)) in psips.iter().zip(thetas.iter()).enumerate() {
+            assert_eq!(rlab_batch[i], g.rlab(psip, theta).unwrap());
+            assert_eq!(zlab_batch[i], g.zlab(psip, theta).unwrap());
+            assert_eq!(jacobian_batch[i], g.jacobian(psip, theta).unwrap());
+        }
+
+        let rlab_grid = g.rlab_grid(&psips.view(), &thetas.view()).unwrap();
+        assert_eq!(rlab_grid.shape(), &[psips.len(), thetas.len()]);
+        assert_eq!(rlab_grid[[0, 0]], g.rlab(psips[0], thetas[0]).unwrap());
+    }
 }
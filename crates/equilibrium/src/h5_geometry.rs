@@ -0,0 +1,495 @@
+//! Object for conversion from normalized to lab quantities, reconstructed from an HDF5 file.
+
+use std::f64::consts::TAU;
+use std::path::PathBuf;
+
+use common::array1D_getter_impl;
+use ndarray::{Array1, Array2};
+use rsl_interpolation::{
+    Accelerator, Cache, DynInterpolation2d, Interp2dType, make_interp2d_type,
+};
+
+use crate::extract::{EqSource, H5Source};
+use crate::fortran_vec_to_carray2d_impl;
+use crate::{ExtrapolationPolicy, Flux, Length, Radians};
+use crate::{Geometry, OneDInterp, Result};
+
+/// Used to create an [`H5Geometry`].
+pub struct H5GeometryBuilder {
+    /// Path to the HDF5 file.
+    path: PathBuf,
+    /// 1D [`Interpolation type`], in case-insensitive string format.
+    ///
+    /// [`Interpolation type`]: ../rsl_interpolation/trait.InterpType.html#implementors
+    typ1d: String,
+    /// 2D [`Interpolation type`], in case-insensitive string format.
+    ///
+    /// [`Interpolation type`]: ../rsl_interpolation/trait.Interp2dType.html#implementors
+    typ2d: String,
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
+}
+
+impl H5GeometryBuilder {
+    /// Creates a new [`H5GeometryBuilder`] from an HDF5 file at `path`, with spline of `typ`
+    /// interpolation type.
+    ///
+    /// Defaults to [`ExtrapolationPolicy::Error`]; use [`with_extrapolation`](Self::with_extrapolation)
+    /// to select a different policy.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::path::PathBuf;
+    /// let path = PathBuf::from("../data/stub_equilibrium.h5");
+    /// let builder = H5GeometryBuilder::new(&path, "akima", "bicubic");
+    /// ```
+    pub fn new(path: &PathBuf, typ1d: &str, typ2d: &str) -> Self {
+        Self {
+            path: path.clone(),
+            typ1d: typ1d.into(),
+            typ2d: typ2d.into(),
+            policy: ExtrapolationPolicy::default(),
+        }
+    }
+
+    /// Sets the behavior for `psip` values outside the stored data range.
+    pub fn with_extrapolation(mut self, policy: ExtrapolationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Creates a new [`H5Geometry`] with the Builder's configuration.
+    pub fn build(self) -> Result<H5Geometry> {
+        H5Geometry::build(self)
+    }
+}
+
+// ===============================================================================================
+
+/// Describes the general geometry of the equilibrium, reconstructed from an HDF5 file.
+///
+/// Mirrors [`NcGeometry`](crate::NcGeometry) exactly, only the file backend differs, so the two
+/// can be used interchangeably behind the [`Geometry`] trait.
+pub struct H5Geometry {
+    /// Path to the HDF5 file.
+    path: PathBuf,
+    /// 1D [`Interpolation type`], in case-insensitive string format.
+    ///
+    /// [`Interpolation type`]: ../rsl_interpolation/trait.InterpType.html#implementors
+    typ1d: String,
+    /// 2D [`Interpolation type`], in case-insensitive string format.
+    ///
+    /// [`Interpolation type`]: ../rsl_interpolation/trait.Interp2dType.html#implementors
+    typ2d: String,
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
+
+    /// Magnetic field strength on the axis `B0` **in \[T\]**.
+    baxis: f64,
+    /// The horizontal position of the magnetic axis `R0` **in \[m\]**.
+    raxis: Length,
+    /// The vertical position of the magnetic axis **in \[m\]**.
+    zaxis: Length,
+    /// The geometrical axis (device major radius) **in \[m\]**.
+    rgeo: Length,
+
+    /// The boozer toroidal angle `θ` **in \[rads\]**.
+    theta_data: Vec<Radians>,
+    /// The poloidal flux `ψp` **in Normalized Units**.
+    psip_data: Vec<Flux>,
+    /// The toroidal flux `ψ` **in Normalized Units**.
+    psi_data: Vec<Flux>,
+    /// The radial coordinate r **in \[m\]**.
+    r_data: Vec<Length>,
+
+    /// R(ψp, θ): The `R` coordinate with respect to boozer coordinates **in \[m\]**, flattened
+    /// in F order.
+    rlab_data_fortran_flat: Vec<Length>,
+    /// Z(ψp, θ): The `Z` coordinate with respect to boozer coordinates **in \[m\]**, flattened
+    /// in F order.
+    zlab_data_fortran_flat: Vec<Length>,
+    /// J(ψp, θ): The VMEC output to Boozer Jacobian in **\[ m/T \]**, flattened in F order.
+    jacobian_data_fortran_flat: Vec<f64>,
+
+    /// Interpolator of `ψp(r)` **in \[m\]**.
+    ///
+    /// `typ1d` `"pchip"`/`"monotone"` selects a shape-preserving monotone cubic Hermite spline
+    /// instead of one of `rsl_interpolation`'s own backends -- see [`OneDInterp`].
+    psip_of_r_interp: OneDInterp,
+    /// Interpolator of `r(ψp)` **in \[m\]**.
+    r_of_psip_interp: OneDInterp,
+
+    /// Interpolator over the R coordinate, as a function of ψp, θ.
+    rlab_interp: DynInterpolation2d<f64>,
+    /// Interpolator over the Z coordinate, as a function of ψp, θ.
+    zlab_interp: DynInterpolation2d<f64>,
+    /// Interpolator over the Jacobian, as a function of ψp, θ.
+    jacobian_interp: DynInterpolation2d<f64>,
+}
+
+/// Creation
+impl H5Geometry {
+    /// Constructs an [`H5Geometry`] from [`H5GeometryBuilder`].
+    pub(crate) fn build(builder: H5GeometryBuilder) -> Result<Self> {
+        use crate::extract::netcdf_fields::*;
+
+        // Make path absolute for display purposes.
+        let path = std::path::absolute(builder.path)?;
+        let source = H5Source::open(&path)?;
+
+        let baxis = source.scalar(BAXIS)?;
+        let raxis = source.scalar(RAXIS)?;
+        let zaxis = source.scalar(ZAXIS)?;
+        let rgeo = source.scalar(RGEO)?;
+        let psip_data = source.array1d(PSIP_NORM)?.as_standard_layout().to_vec();
+        let psi_data = source.array1d(PSI_NORM)?.as_standard_layout().to_vec();
+        let r_data = source.array1d(R)?.as_standard_layout().to_vec();
+        let theta_data = source.array1d(THETA)?.as_standard_layout().to_vec();
+        let rlab_data = source.array2d(RLAB)?;
+        let zlab_data = source.array2d(ZLAB)?;
+        let jacobian_data = source.array2d(JACOBIAN)?;
+
+        // Interpolator's `za` input must be in Fortran order.
+        let order = ndarray::Order::ColumnMajor;
+        let rlab_data_fortran_flat = rlab_data.flatten_with_order(order).to_vec();
+        let zlab_data_fortran_flat = zlab_data.flatten_with_order(order).to_vec();
+        let jacobian_data_fortran_flat = jacobian_data.flatten_with_order(order).to_vec();
+
+        let r_of_psip_interp = OneDInterp::build(&builder.typ1d, &psip_data, &r_data)?;
+
+        let psip_of_r_interp = OneDInterp::build(&builder.typ1d, &r_data, &psip_data)?;
+
+        let rlab_interp = make_interp2d_type(&builder.typ2d)?.build(
+            &psip_data,
+            &theta_data,
+            &rlab_data_fortran_flat,
+        )?;
+
+        let zlab_interp = make_interp2d_type(&builder.typ2d)?.build(
+            &psip_data,
+            &theta_data,
+            &zlab_data_fortran_flat,
+        )?;
+
+        let jacobian_interp = make_interp2d_type(&builder.typ2d)?.build(
+            &psip_data,
+            &theta_data,
+            &jacobian_data_fortran_flat,
+        )?;
+
+        Ok(Self {
+            path,
+            typ1d: builder.typ1d,
+            typ2d: builder.typ2d,
+            policy: builder.policy,
+            baxis,
+            raxis,
+            zaxis,
+            rgeo,
+            psip_data,
+            psi_data,
+            theta_data,
+            r_data,
+            rlab_data_fortran_flat,
+            zlab_data_fortran_flat,
+            jacobian_data_fortran_flat,
+            psip_of_r_interp,
+            r_of_psip_interp,
+            rlab_interp,
+            zlab_interp,
+            jacobian_interp,
+        })
+    }
+}
+
+/// Interpolation
+impl Geometry for H5Geometry {
+    fn r(&self, psip: Flux) -> Result<Length> {
+        let mut acc = Accelerator::new();
+        self.r_of_psip_interp
+            .eval_policy(&self.psip_data, &self.r_data, psip, &mut acc, self.policy)
+    }
+
+    fn psip(&self, r: Length) -> Result<Flux> {
+        let mut acc = Accelerator::new();
+        self.psip_of_r_interp
+            .eval_policy(&self.r_data, &self.psip_data, r, &mut acc, self.policy)
+    }
+
+    fn rlab(&self, psip: Flux, theta: Radians) -> Result<f64> {
+        let mut xacc = Accelerator::new();
+        let mut yacc = Accelerator::new();
+        let mut cache = Cache::new();
+        self.eval_2d_policy(
+            &self.rlab_interp,
+            &self.rlab_data_fortran_flat,
+            psip,
+            theta,
+            &mut xacc,
+            &mut yacc,
+            &mut cache,
+        )
+    }
+
+    fn zlab(&self, psip: Flux, theta: Radians) -> Result<f64> {
+        let mut xacc = Accelerator::new();
+        let mut yacc = Accelerator::new();
+        let mut cache = Cache::new();
+        self.eval_2d_policy(
+            &self.zlab_interp,
+            &self.zlab_data_fortran_flat,
+            psip,
+            theta,
+            &mut xacc,
+            &mut yacc,
+            &mut cache,
+        )
+    }
+
+    fn jacobian(&self, psip: Flux, theta: Radians) -> Result<f64> {
+        let mut xacc = Accelerator::new();
+        let mut yacc = Accelerator::new();
+        let mut cache = Cache::new();
+        self.eval_2d_policy(
+            &self.jacobian_interp,
+            &self.jacobian_data_fortran_flat,
+            psip,
+            theta,
+            &mut xacc,
+            &mut yacc,
+            &mut cache,
+        )
+    }
+}
+
+/// Extrapolation
+impl H5Geometry {
+    /// Evaluates a `(ψp, θ)` interpolator, honoring `self.policy` when `psip` falls outside
+    /// `psip_data`'s range. `θ` is always periodic, so only `psip` is ever out of range.
+    #[allow(clippy::too_many_arguments)]
+    fn eval_2d_policy(
+        &self,
+        interp: &DynInterpolation2d<f64>,
+        data: &[f64],
+        psip: Flux,
+        theta: Radians,
+        xacc: &mut Accelerator,
+        yacc: &mut Accelerator,
+        cache: &mut Cache,
+    ) -> Result<f64> {
+        let theta = theta.rem_euclid(TAU);
+        let (lo, hi) = (self.psip_data[0], *self.psip_data.last().expect("non-empty"));
+        let boundary = if psip < lo {
+            Some(lo)
+        } else if psip > hi {
+            Some(hi)
+        } else {
+            None
+        };
+
+        let Some(boundary) = boundary else {
+            return Ok(interp.eval(
+                &self.psip_data,
+                &self.theta_data,
+                data,
+                psip,
+                theta,
+                xacc,
+                yacc,
+                cache,
+            )?);
+        };
+
+        match self.policy {
+            ExtrapolationPolicy::Error => Err(crate::EqError::OutOfRange(psip)),
+            ExtrapolationPolicy::Clamp => Ok(interp.eval(
+                &self.psip_data,
+                &self.theta_data,
+                data,
+                boundary,
+                theta,
+                xacc,
+                yacc,
+                cache,
+            )?),
+            ExtrapolationPolicy::LinearExtrapolate => {
+                // One-sided secant derivative in ψp, using the node adjacent to the boundary.
+                let neighbor = if boundary == lo {
+                    self.psip_data[1]
+                } else {
+                    self.psip_data[self.psip_data.len() - 2]
+                };
+                let value = interp.eval(
+                    &self.psip_data,
+                    &self.theta_data,
+                    data,
+                    boundary,
+                    theta,
+                    xacc,
+                    yacc,
+                    cache,
+                )?;
+                let value_neighbor = interp.eval(
+                    &self.psip_data,
+                    &self.theta_data,
+                    data,
+                    neighbor,
+                    theta,
+                    xacc,
+                    yacc,
+                    cache,
+                )?;
+                let deriv = (value_neighbor - value) / (neighbor - boundary);
+                Ok(value + deriv * (psip - boundary))
+            }
+        }
+    }
+}
+
+/// Getters
+impl H5Geometry {
+    /// Returns the HDF5 file's path.
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// Returns the 1D interpolation type.
+    pub fn typ1d(&self) -> String {
+        self.typ1d.clone()
+    }
+
+    /// Returns the 2D interpolation type.
+    pub fn typ2d(&self) -> String {
+        self.typ2d.clone()
+    }
+
+    /// Returns the active out-of-range extrapolation policy.
+    pub fn policy(&self) -> ExtrapolationPolicy {
+        self.policy
+    }
+
+    /// Returns the shape of the `b` array.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.psip_data.len(), self.theta_data.len())
+    }
+
+    /// Returns the magnetic field strength on the axis `B0` **in \[T\]**.
+    pub fn baxis(&self) -> f64 {
+        self.baxis
+    }
+
+    /// Retruns the horizontal position of the magnetic axis `R0` **in \[m\]**.
+    pub fn raxis(&self) -> f64 {
+        self.raxis
+    }
+
+    /// Retruns the vertical position of the magnetic axis **in \[m\]**.
+    pub fn zaxis(&self) -> f64 {
+        self.zaxis
+    }
+
+    /// Returns the geometrical axis (device major radius) **in \[m\]**.
+    pub fn rgeo(&self) -> f64 {
+        self.rgeo
+    }
+
+    /// Returns the tokamak's minor radius `r_wall` **in \[m\]**.
+    pub fn r_wall(&self) -> f64 {
+        self.r_data.last().copied().expect("array non-empty")
+    }
+
+    /// Returns the poloidal flux's value at the wall `ψp_wall` **in Normalized Units**.
+    pub fn psip_wall(&self) -> f64 {
+        self.psip_data.last().copied().expect("array non-empty")
+    }
+
+    /// Returns the toroidal flux's value at the wall `ψ_wall` **in Normalized Units**.
+    pub fn psi_wall(&self) -> f64 {
+        self.psi_data.last().copied().expect("array non-empty")
+    }
+
+    array1D_getter_impl!(theta_data, theta_data, Radians);
+    array1D_getter_impl!(psip_data, psip_data, Flux);
+    array1D_getter_impl!(psi_data, psi_data, Flux);
+    array1D_getter_impl!(r_data, r_data, Length);
+
+    fortran_vec_to_carray2d_impl!(rlab_data, rlab_data_fortran_flat, R);
+    fortran_vec_to_carray2d_impl!(zlab_data, zlab_data_fortran_flat, Z);
+    fortran_vec_to_carray2d_impl!(jacobian_data, jacobian_data_fortran_flat, J);
+}
+
+impl std::fmt::Debug for H5Geometry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("H5Geometry")
+            .field("path", &self.path())
+            .field("typ 1D", &self.typ1d())
+            .field("typ 2D", &self.typ2d())
+            .field("extrapolation", &self.policy())
+            .field("Baxis [T]", &format!("{:.7}", self.baxis()))
+            .field("Raxis [m]", &format!("{:.7}", self.raxis()))
+            .field("Zaxis [m]", &format!("{:.7}", self.zaxis()))
+            .field("Rgeo [m]", &format!("{:.7}", self.rgeo()))
+            .field("ψp_wall", &format!("{:.7}", self.psip_wall()))
+            .field("ψ_wall", &format!("{:.7}", self.psi_wall()))
+            .field("r_wall", &format!("{:.7}", self.r_wall()))
+            .field("shape", &self.shape())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::extract::STUB_H5_PATH;
+
+    fn create_h5_geometry() -> H5Geometry {
+        let path = PathBuf::from(STUB_H5_PATH);
+        let typ1d = "steffen";
+        let typ2d = "bicubic";
+        H5GeometryBuilder::new(&path, typ1d, typ2d).build().unwrap()
+    }
+
+    #[test]
+    fn test_geometry_creation() {
+        let g = create_h5_geometry();
+        let _ = format!("{g:?}");
+    }
+
+    #[test]
+    fn test_getters() {
+        let g = create_h5_geometry();
+        g.path();
+        g.typ1d();
+        g.typ2d();
+        g.baxis();
+        g.raxis();
+        g.zaxis();
+        g.rgeo();
+        g.psip_wall();
+        g.psi_wall();
+        g.r_wall();
+        g.shape();
+
+        assert_eq!(g.psip_data().ndim(), 1);
+        assert_eq!(g.psi_data().ndim(), 1);
+        assert_eq!(g.r_data().ndim(), 1);
+        assert_eq!(g.theta_data().ndim(), 1);
+        assert_eq!(g.rlab_data().ndim(), 2);
+        assert_eq!(g.zlab_data().ndim(), 2);
+        assert_eq!(g.jacobian_data().ndim(), 2);
+    }
+
+    #[test]
+    fn test_spline_evaluation() {
+        let g = create_h5_geometry();
+
+        let r = 0.1;
+        let psip = 0.015;
+        let theta = 0.0;
+        g.r(psip).unwrap();
+        g.psip(r).unwrap();
+        g.rlab(psip, theta).unwrap();
+        g.zlab(psip, theta).unwrap();
+        g.jacobian(psip, theta).unwrap();
+    }
+}
@@ -0,0 +1,218 @@
+//! Representation of an equilibrium's q-factor, reconstructed from an HDF5 file.
+
+use std::path::PathBuf;
+
+use common::array1D_getter_impl;
+use rsl_interpolation::Accelerator;
+
+use crate::ExtrapolationPolicy;
+use crate::Flux;
+use crate::OneDInterp;
+use crate::Qfactor;
+use crate::Result;
+use crate::extract::{EqSource, H5Source};
+
+/// Used to create an [`H5Qfactor`].
+pub struct H5QfactorBuilder {
+    /// Path to the HDF5 file.
+    path: PathBuf,
+    /// 1D [`Interpolation type`], in case-insensitive string format.
+    ///
+    /// [`Interpolation type`]: ../rsl_interpolation/trait.InterpType.html#implementors
+    typ: String,
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
+}
+
+impl H5QfactorBuilder {
+    /// Creates a new [`H5QfactorBuilder`] from an HDF5 file at `path`, with spline of `typ`
+    /// interpolation type.
+    ///
+    /// Defaults to [`ExtrapolationPolicy::Error`]; use [`with_extrapolation`](Self::with_extrapolation)
+    /// to select a different policy.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::path::PathBuf;
+    /// let path = PathBuf::from("../data/stub_equilibrium.h5");
+    /// let builder = H5QfactorBuilder::new(&path, "cubic");
+    /// ```
+    pub fn new(path: &PathBuf, typ: &str) -> Self {
+        Self {
+            path: path.clone(),
+            typ: typ.into(),
+            policy: ExtrapolationPolicy::default(),
+        }
+    }
+
+    /// Sets the behavior for `psip` values outside the stored data range.
+    pub fn with_extrapolation(mut self, policy: ExtrapolationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Creates a new [`H5Qfactor`] with the Builder's configuration.
+    pub fn build(self) -> Result<H5Qfactor> {
+        H5Qfactor::build(self)
+    }
+}
+
+// ===============================================================================================
+
+/// q-factor reconstructed from an HDF5 file.
+///
+/// Mirrors [`NcQfactor`](crate::NcQfactor) exactly, only the file backend differs, so the two can
+/// be used interchangeably behind the [`Qfactor`] trait.
+pub struct H5Qfactor {
+    /// Path to the HDF5 file.
+    path: PathBuf,
+    /// 1D [`Interpolation type`], in case-insensitive string format.
+    ///
+    /// [`Interpolation type`]: ../rsl_interpolation/trait.InterpType.html#implementors
+    typ: String,
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
+
+    /// The `ψp` data array.
+    psip_data: Vec<Flux>,
+    /// The `q` data array.
+    q_data: Vec<f64>,
+    /// The `ψ` data array.
+    psi_data: Vec<Flux>,
+
+    /// Interpolator over the `q` values, as a function of ψp.
+    ///
+    /// `typ` `"pchip"`/`"monotone"` selects a shape-preserving monotone cubic Hermite spline
+    /// instead of one of `rsl_interpolation`'s own backends -- see [`OneDInterp`].
+    q_interp: OneDInterp,
+    /// Interpolator over the `ψ` values, as a function of ψp.
+    psi_interp: OneDInterp,
+}
+
+/// Creation
+impl H5Qfactor {
+    /// Constructs an [`H5Qfactor`] from [`H5QfactorBuilder`].
+    pub(crate) fn build(builder: H5QfactorBuilder) -> Result<Self> {
+        use crate::extract::netcdf_fields::*;
+
+        // Make path absolute for display purposes.
+        let path = std::path::absolute(builder.path)?;
+        let source = H5Source::open(&path)?;
+
+        let psip_data = source.array1d(PSIP_NORM)?.as_standard_layout().to_vec();
+        let psi_data = source.array1d(PSI_NORM)?.as_standard_layout().to_vec();
+        let q_data = source.array1d(Q)?.as_standard_layout().to_vec();
+
+        let q_interp = OneDInterp::build(&builder.typ, &psip_data, &q_data)?;
+        let psi_interp = OneDInterp::build(&builder.typ, &psip_data, &psi_data)?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            typ: builder.typ,
+            policy: builder.policy,
+            psip_data,
+            q_data,
+            psi_data,
+            q_interp,
+            psi_interp,
+        })
+    }
+}
+
+/// Interpolation
+impl Qfactor for H5Qfactor {
+    fn q(&self, psip: Flux, acc: &mut Accelerator) -> Result<f64> {
+        self.q_interp
+            .eval_policy(&self.psip_data, &self.q_data, psip, acc, self.policy)
+    }
+
+    fn psi(&self, psip: Flux, acc: &mut Accelerator) -> Result<Flux> {
+        self.psi_interp
+            .eval_policy(&self.psip_data, &self.psi_data, psip, acc, self.policy)
+    }
+
+    fn dpsi_dpsip(&self, psip: Flux, acc: &mut Accelerator) -> Result<f64> {
+        self.psi_interp
+            .eval_deriv_policy(&self.psip_data, &self.psi_data, psip, acc, self.policy)
+    }
+}
+
+/// Getters
+impl H5Qfactor {
+    /// Returns the HDF5 file's path.
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// Returns the interpolation type.
+    pub fn typ(&self) -> String {
+        self.typ.clone()
+    }
+
+    /// Returns the active out-of-range extrapolation policy.
+    pub fn policy(&self) -> ExtrapolationPolicy {
+        self.policy
+    }
+
+    /// Returns the number of data points.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.psip_data.len()
+    }
+
+    array1D_getter_impl!(psip_data, psip_data, Flux);
+    array1D_getter_impl!(psi_data, psi_data, Flux);
+    array1D_getter_impl!(q_data, q_data, f64);
+}
+
+impl std::fmt::Debug for H5Qfactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("H5Qfactor")
+            .field("path", &self.path())
+            .field("typ", &self.typ())
+            .field("extrapolation", &self.policy())
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::extract::STUB_H5_PATH;
+
+    fn create_h5_qfactor() -> H5Qfactor {
+        let path = PathBuf::from(STUB_H5_PATH);
+        let typ = "steffen";
+        H5QfactorBuilder::new(&path, typ).build().unwrap()
+    }
+
+    #[test]
+    fn test_qfactor_creation() {
+        let q = create_h5_qfactor();
+        let _ = format!("{q:?}");
+    }
+
+    #[test]
+    fn test_getters() {
+        let q = create_h5_qfactor();
+        q.path();
+        q.typ();
+        q.len();
+
+        assert_eq!(q.psip_data().ndim(), 1);
+        assert_eq!(q.q_data().ndim(), 1);
+        assert_eq!(q.psi_data().ndim(), 1);
+    }
+
+    #[test]
+    fn test_spline_evaluation() {
+        let q = create_h5_qfactor();
+        let mut acc = Accelerator::new();
+
+        let psip = 0.015;
+        q.q(psip, &mut acc).unwrap();
+        q.psi(psip, &mut acc).unwrap();
+        q.dpsi_dpsip(psip, &mut acc).unwrap();
+    }
+}
@@ -0,0 +1,1121 @@
+//! Representation of a perturbation's single harmonic.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use common::array1D_getter_impl;
+use ndarray::Array1;
+use rsl_interpolation::Accelerator;
+
+use crate::HarmonicCache;
+use crate::flt::{Flt, rem_euclid};
+use crate::{CenteredStencil, EqError, Flux, Harmonic, Length, NcError, Radians};
+use crate::{OneDInterp, Result};
+
+/// Defines the calculation method of the phase `φ(ψp)` in an [`NcHarmonic`].
+///
+/// Generic over the same floating-point scalar `F` (see [`Flt`]) as the [`NcHarmonic`] it is
+/// attached to, so a [`PhaseMethod::Custom`] profile can be supplied in whichever precision the
+/// harmonic was built with. Defaults to `f64` so existing, non-generic call sites are unaffected.
+#[derive(Default, Debug, Clone)]
+pub enum PhaseMethod<F: Flt = f64> {
+    /// Corresponds to `φ(ψp) = 0`.
+    Zero,
+    /// Corresponds to `φ = const = the average of all the values of the phase array`.
+    Average,
+    /// Corresponds to `φ = const = the value of φ(ψp) at the resonance m/n`. [`NcHarmonic::build`]
+    /// fails with [`EqError::OutOfRange`] if the rational surface `q(ψp) = m/n` falls outside the
+    /// wall.
+    #[default]
+    Resonance,
+    /// Interpolation over the phase array.
+    Interpolation,
+    /// A caller-supplied `φ(ψp)` profile (see [`CustomPhaseProfile`]), interpolated over an
+    /// independently supplied `(ψp, φ)` grid rather than the harmonic's own `phase_data`. Lets a
+    /// caller impose an experimentally measured or analytically prescribed mode phase instead of
+    /// any of the other, file-derived methods above. A single flux-surface average of `φ(ψp,θ)`
+    /// computed with [`flux_surface_average`](crate::flux_surface_average) also fits here, as a
+    /// one-point "profile" -- `NcHarmonic` itself only ever stores `φ` as a function of `ψp` (one
+    /// value per grid point, with no `θ` dependence), so averaging across `θ` is meaningless from
+    /// inside [`NcHarmonic::build`], which has no [`Bfield`](crate::Bfield)/
+    /// [`Current`](crate::Current)/[`Qfactor`](crate::Qfactor) to evaluate the Jacobian with in the
+    /// first place.
+    Custom(Arc<CustomPhaseProfile<F>>),
+}
+
+/// A caller-supplied `φ(ψp)` profile backing [`PhaseMethod::Custom`], interpolated the same way
+/// [`PhaseMethod::Interpolation`] interpolates over the harmonic's own `phase_data`, but over an
+/// independently supplied grid.
+///
+/// Generic over the same floating-point scalar `F` as the [`PhaseMethod`] it backs; the
+/// interpolation itself still happens in `f64`, like every other spline in this crate (see
+/// [`flt`](crate::flt)'s module docs) -- the `F` conversion only happens at [`Self::new`]'s input
+/// and [`Self::eval`]'s return value.
+pub struct CustomPhaseProfile<F: Flt = f64> {
+    psip_data: Vec<f64>,
+    phase_data: Vec<f64>,
+    interp: OneDInterp,
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F: Flt> CustomPhaseProfile<F> {
+    /// Builds a profile of interpolation type `typ` (see [`OneDInterp`]) over `(psip_data,
+    /// phase_data)`, matching the spline type already used for [`NcHarmonic::phase_data`].
+    ///
+    /// Unlike every other interpolation grid in this crate, `psip_data`/`phase_data` are not
+    /// drawn from a single, already-matched-length, file-derived array -- they can come from two
+    /// independently supplied Python lists -- so their lengths are validated here rather than left
+    /// for the spline backend to index out of bounds on.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EqError::InvalidGrid`] if `psip_data.len() != phase_data.len()` or either has
+    /// fewer than 2 points.
+    pub fn new(typ: &str, psip_data: &[F], phase_data: &[F]) -> Result<Self> {
+        if psip_data.len() != phase_data.len() || psip_data.len() < 2 {
+            return Err(EqError::InvalidGrid {
+                x_len: psip_data.len(),
+                y_len: phase_data.len(),
+            });
+        }
+        let to_f64 = |v: &F| v.to_f64().expect("F fits into f64");
+        let psip_data: Vec<f64> = psip_data.iter().map(to_f64).collect();
+        let phase_data: Vec<f64> = phase_data.iter().map(to_f64).collect();
+        let interp = OneDInterp::build(typ, &psip_data, &phase_data)?;
+        Ok(Self { psip_data, phase_data, interp, _marker: std::marker::PhantomData })
+    }
+
+    /// The `ψp` grid this profile was built over.
+    pub fn psip_data(&self) -> &[f64] {
+        &self.psip_data
+    }
+
+    /// The `φ` values this profile was built over, paired with [`Self::psip_data`].
+    pub fn phase_data(&self) -> &[f64] {
+        &self.phase_data
+    }
+
+    fn eval(&self, psip: F, acc: &mut Accelerator) -> Result<F> {
+        let psip = psip.to_f64().expect("F fits into f64");
+        self.interp.eval(&self.psip_data, &self.phase_data, psip, acc).map(to_f)
+    }
+}
+
+impl<F: Flt> std::fmt::Debug for CustomPhaseProfile<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomPhaseProfile")
+            .field("psip_data", &self.psip_data)
+            .field("phase_data", &self.phase_data)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Amplitude-normalization convention applied to a harmonic's `α(ψp)` coefficient on load.
+///
+/// External codes disagree on how a perturbation spectrum's Fourier coefficients are normalized;
+/// selecting the matching variant rescales `α(ψp)` by a per-harmonic factor `N(m)` so the
+/// reconstructed `δB` matches the source code's convention, instead of being off by a
+/// convention-dependent factor. Combine with [`NcHarmonicBuilder::with_condon_shortley`] for
+/// codes that additionally carry the `(-1)^m` sign in their spectral coefficients.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Normalization {
+    /// No rescaling: `N(m) = 1`, `α(ψp)` is used exactly as read from the netCDF file.
+    #[default]
+    Raw,
+    /// Power normalization: `N(m) = 1` for `m = 0`, `N(m) = 1/√2` otherwise, halving the
+    /// `θ,ζ`-averaged power `⟨h²⟩` of every `m ≠ 0` harmonic relative to [`Normalization::Raw`]. Matches codes whose
+    /// `α` is the coefficient of a one-sided, `m,n ≥ 0` cosine series.
+    Power,
+    /// Real-harmonic normalization: `N(m) = √2` for every `m`, matching codes that derive their
+    /// spectrum from a complex (two-sided, `m ∈ ℤ`) Fourier series and report only the `m ≥ 0`
+    /// half -- the `√2` recovers the amplitude the `-m` term would otherwise contribute.
+    RealHarmonic,
+}
+
+impl Normalization {
+    /// Returns the multiplicative factor `N(m)` this convention applies to `α(ψp)`.
+    fn factor(self, m: i64) -> f64 {
+        match self {
+            Normalization::Raw => 1.0,
+            Normalization::Power => {
+                if m == 0 {
+                    1.0
+                } else {
+                    std::f64::consts::FRAC_1_SQRT_2
+                }
+            }
+            Normalization::RealHarmonic => std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+/// `k` in the fast-trig lookup table's sample count `N = 2^k` (see
+/// [`NcHarmonicBuilder::with_fast_trig`]).
+const FAST_TRIG_TABLE_K: u32 = 10;
+
+/// The maximum number of safeguarded-Newton iterations [`find_resonant_psip`] attempts before
+/// giving up and returning its last estimate.
+const MAX_RESONANCE_ITERATIONS: usize = 30;
+
+/// Convergence tolerance on `|q(ψp) - target|` and on the bracket width, in
+/// [`find_resonant_psip`].
+const RESONANCE_TOLERANCE: f64 = 1e-10;
+
+/// Brackets and refines `ψp` where `q(ψp) = target`, given the harmonic's own stored `(ψp, q)`
+/// grid and a spline built over it. Scans the grid for a sign change of `q(ψp) - target`, then
+/// refines with the same safeguarded-Newton-or-bisect iteration as
+/// [`crate::chirikov::find_resonance`] (reusing the technique rather than the function itself,
+/// since that one takes a [`Qfactor`](crate::Qfactor) object, which doesn't exist yet at
+/// [`NcHarmonic::build`] time) -- except the Newton step here uses [`OneDInterp::eval_deriv`]'s
+/// exact derivative instead of a central difference, since the spline is already in hand. Returns
+/// `None` if `q` never crosses `target` on the grid, i.e. the rational surface is outside the
+/// wall.
+fn find_resonant_psip(
+    psip_data: &[f64],
+    q_data: &[f64],
+    q_interp: &OneDInterp,
+    target: f64,
+    acc: &mut Accelerator,
+) -> Result<Option<f64>> {
+    let bracket = psip_data.windows(2).zip(q_data.windows(2)).find(|(_, qs)| {
+        (qs[0] - target) == 0.0 || (qs[0] - target).signum() != (qs[1] - target).signum()
+    });
+
+    let Some((psips, qs)) = bracket else {
+        return Ok(None);
+    };
+
+    let (mut lo, mut hi) = (psips[0], psips[1]);
+    let mut f_lo = qs[0] - target;
+    if f_lo == 0.0 {
+        return Ok(Some(lo));
+    }
+
+    let mut psip = 0.5 * (lo + hi);
+    for _ in 0..MAX_RESONANCE_ITERATIONS {
+        let f = q_interp.eval(psip_data, q_data, psip, acc)? - target;
+        if f.signum() == f_lo.signum() {
+            lo = psip;
+            f_lo = f;
+        } else {
+            hi = psip;
+        }
+        if f.abs() < RESONANCE_TOLERANCE || (hi - lo).abs() < RESONANCE_TOLERANCE {
+            return Ok(Some(psip));
+        }
+
+        let dq_dpsip = q_interp.eval_deriv(psip_data, q_data, psip, acc)?;
+        let newton = psip - f / dq_dpsip;
+        psip = if dq_dpsip.abs() < f64::EPSILON || newton <= lo || newton >= hi {
+            0.5 * (lo + hi)
+        } else {
+            newton
+        };
+    }
+    Ok(Some(psip))
+}
+
+/// A precomputed `cos` table backing [`NcHarmonic`]'s opt-in fast-trig mode, trading the exact
+/// `sin`/`cos` of [`Harmonic::mod_arg`] for a table lookup plus linear interpolation.
+///
+/// Samples `cos` at `N = 2^`[`FAST_TRIG_TABLE_K`]` evenly spaced points over `[0, TAU)`; `sin` is
+/// read off the same table via a quarter-period index shift (`sin(x) = cos(x - τ/4)`), so no
+/// second table is needed. Linear interpolation between samples spaced `Δx = τ/N` apart has
+/// worst-case absolute error `Δx²/8` (from `cos`'s second derivative being bounded by 1) -- about
+/// `5·10⁻⁶` at the default `N = 1024`, negligible next to the `f32` rounding error of the harmonic
+/// evaluation this mode is meant for.
+struct FastTrigTable<F: Flt> {
+    /// `table[i] = cos(i·τ/N)`.
+    table: Vec<F>,
+}
+
+impl<F: Flt> FastTrigTable<F> {
+    fn new() -> Self {
+        let n = 1usize << FAST_TRIG_TABLE_K;
+        let tau = std::f64::consts::TAU;
+        let table = (0..n).map(|i| to_f((i as f64 * tau / n as f64).cos())).collect();
+        Self { table }
+    }
+
+    /// Linearly interpolates the table at `x` (assumed already reduced to `[0, τ)`), reading the
+    /// entry `offset` slots ahead of `x`'s own position -- `0` for `cos`, three quarters of a
+    /// period (i.e. a quarter period back) for `sin`.
+    fn lookup(&self, x: F, offset: usize) -> F {
+        let n = self.table.len();
+        let p = x * to_f::<F>(n as f64) / F::TAU();
+        let i = p.floor().to_usize().unwrap_or(0) % n;
+        let frac = p - to_f::<F>(i as f64);
+        let idx = (i + offset) % n;
+        let next = (idx + 1) % n;
+        self.table[idx] + frac * (self.table[next] - self.table[idx])
+    }
+
+    fn cos(&self, x: F) -> F {
+        self.lookup(x, 0)
+    }
+
+    fn sin(&self, x: F) -> F {
+        // sin(x) = cos(x - τ/4): equivalent to shifting the lookup index back a quarter period.
+        let n = self.table.len();
+        self.lookup(x, n - n / 4)
+    }
+}
+
+/// A dense, uniformly-spaced resampling of `α(ψp)`, `dα/dψp`, and (optionally) `φ(ψp)` over
+/// `[ψp.first, ψp.last]`, backing [`NcHarmonic`]'s opt-in precomputed-grid mode (see
+/// [`NcHarmonicBuilder::with_precomputed_grid`]).
+///
+/// Built once from the existing `f64` splines; evaluating it at runtime is then a plain
+/// index-plus-linear-interpolation instead of the accelerator's bracket search, at the cost of the
+/// grid's own resampling error. Always `f64`, like [`Inner`]; [`NcHarmonic`]'s `F` conversion
+/// happens only at the lookup's boundary.
+struct PrecomputedGrid {
+    /// `ψp` at the grid's first sample.
+    psip_min: f64,
+    /// The uniform spacing between consecutive samples.
+    step: f64,
+    /// `α(ψp)`, resampled onto the grid.
+    alpha: Vec<f64>,
+    /// `dα/dψp`, resampled onto the grid.
+    dalpha: Vec<f64>,
+    /// `φ(ψp)`, resampled onto the grid. `Some` only when built for
+    /// [`PhaseMethod::Interpolation`] -- every other phase method is already O(1).
+    phase: Option<Vec<f64>>,
+}
+
+impl PrecomputedGrid {
+    /// Resamples `alpha_interp`/`phase_interp` onto a uniform `ψp` grid of `n_points` spanning
+    /// `[psip_data.first, psip_data.last]`. `phase` is only resampled when `needs_phase` is set.
+    fn new(
+        n_points: usize,
+        psip_data: &[f64],
+        alpha_interp: &OneDInterp,
+        alpha_data: &[f64],
+        phase_interp: &OneDInterp,
+        phase_data: &[f64],
+        needs_phase: bool,
+    ) -> Result<Self> {
+        let psip_min = psip_data[0];
+        let psip_max = psip_data[psip_data.len() - 1];
+        let step = (psip_max - psip_min) / (n_points as f64 - 1.0);
+
+        let mut acc = Accelerator::new();
+        let mut alpha = Vec::with_capacity(n_points);
+        let mut dalpha = Vec::with_capacity(n_points);
+        for i in 0..n_points {
+            let p = psip_min + step * i as f64;
+            alpha.push(alpha_interp.eval(psip_data, alpha_data, p, &mut acc)?);
+            dalpha.push(alpha_interp.eval_deriv(psip_data, alpha_data, p, &mut acc)?);
+        }
+
+        let phase = needs_phase
+            .then(|| {
+                let mut acc = Accelerator::new();
+                (0..n_points)
+                    .map(|i| phase_interp.eval(psip_data, phase_data, psip_min + step * i as f64, &mut acc))
+                    .collect::<Result<Vec<f64>>>()
+            })
+            .transpose()?;
+
+        Ok(Self { psip_min, step, alpha, dalpha, phase })
+    }
+
+    /// Returns the sample index just below `psip` and its fractional offset to the next sample,
+    /// clamping `psip` to the grid's range instead of extrapolating.
+    fn index_frac(&self, psip: f64) -> (usize, f64) {
+        let n = self.alpha.len();
+        let p = ((psip - self.psip_min) / self.step).clamp(0.0, (n - 1) as f64);
+        let i = (p as usize).min(n - 2);
+        (i, p - i as f64)
+    }
+
+    fn lerp(data: &[f64], i: usize, frac: f64) -> f64 {
+        data[i] + frac * (data[i + 1] - data[i])
+    }
+
+    fn alpha(&self, psip: f64) -> f64 {
+        let (i, frac) = self.index_frac(psip);
+        Self::lerp(&self.alpha, i, frac)
+    }
+
+    fn dalpha(&self, psip: f64) -> f64 {
+        let (i, frac) = self.index_frac(psip);
+        Self::lerp(&self.dalpha, i, frac)
+    }
+
+    /// Panics if built with `needs_phase = false` -- callers only reach this through
+    /// [`PhaseMethod::Interpolation`], the only method the grid is ever built to cover.
+    fn phase(&self, psip: f64) -> f64 {
+        let (i, frac) = self.index_frac(psip);
+        Self::lerp(self.phase.as_ref().expect("grid built for PhaseMethod::Interpolation"), i, frac)
+    }
+}
+
+/// Used to create an [`NcHarmonic`].
+///
+/// Generic over the same floating-point scalar `F` (see [`Flt`]) as the [`NcHarmonic`] it builds,
+/// defaulting to `f64`. The netCDF-backed spline data itself always stays `f64` (see
+/// [`Flt`](crate::flt)'s module docs); `F` only governs `ω`/`γ`/the phase method's custom value and
+/// everything the resulting [`NcHarmonic`] evaluates.
+#[non_exhaustive]
+pub struct NcHarmonicBuilder<F: Flt = f64> {
+    /// Path to the netCDF file.
+    path: PathBuf,
+    /// 1D [`Interpolation type`], in case-insensitive string format.
+    ///
+    /// [`Interpolation type`]: ../rsl_interpolation/trait.InterpType.html#implementors
+    typ: String,
+    /// The `θ` frequency number.
+    m: i64,
+    /// The `ζ` frequency number.
+    n: i64,
+    /// The calculation method of the phase `φ(ψp)`.
+    phase_method: PhaseMethod<F>,
+    /// Overrides the harmonic's rigid rotation angular frequency `ω` instead of reading it from
+    /// the netCDF file. `None` by default -- see [`Self::with_omega`].
+    omega: Option<F>,
+    /// Overrides the harmonic's amplitude-envelope growth rate `γ` instead of reading it from the
+    /// netCDF file. `None` by default -- see [`Self::with_gamma`].
+    gamma: Option<F>,
+    /// The amplitude-normalization convention applied to `α(ψp)` on load.
+    normalization: Normalization,
+    /// Whether to fold the Condon-Shortley phase `(-1)^m` into `α(ψp)`'s sign.
+    condon_shortley: bool,
+    /// Whether to evaluate `sin`/`cos` through a precomputed lookup table instead of exactly --
+    /// see [`Self::with_fast_trig`].
+    fast_trig: bool,
+    /// The sample count of the dense `α`/`dα`/`φ` resampling grid, `None` to evaluate the splines
+    /// on demand instead -- see [`Self::with_precomputed_grid`].
+    precomputed_grid: Option<usize>,
+}
+
+impl<F: Flt> NcHarmonicBuilder<F> {
+    /// Creates a new [`NcHarmonicBuilder`] from a netCDF file at `path`, with spline of `typ`
+    /// interpolation type.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::path::PathBuf;
+    /// # use equilibrium::NcHarmonicBuilder;
+    /// let path = PathBuf::from("./netcdf.nc");
+    /// let builder = NcHarmonicBuilder::<f64>::new(&path, "steffen", 1, 2);
+    /// ```
+    pub fn new(path: &Path, typ: &str, m: i64, n: i64) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            typ: typ.into(),
+            m,
+            n,
+            phase_method: PhaseMethod::default(),
+            omega: None,
+            gamma: None,
+            normalization: Normalization::default(),
+            condon_shortley: false,
+            fast_trig: false,
+            precomputed_grid: None,
+        }
+    }
+
+    /// Creates a new [`NcHarmonic`] with the Builder's configuration.
+    ///
+    /// # Example
+    /// ```
+    /// # use equilibrium::*;
+    /// # use std::path::PathBuf;
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let path = PathBuf::from("../data/stub_netcdf.nc");
+    /// let harmonic = NcHarmonicBuilder::new(&path, "cubic", 1, 2).build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn build(self) -> Result<NcHarmonic<F>> {
+        NcHarmonic::build(self)
+    }
+
+    /// Sets the phase `φ(ψp)` calculation method.
+    ///
+    /// # Example
+    /// ```
+    /// # use equilibrium::*;
+    /// # use std::path::PathBuf;
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let path = PathBuf::from("../data/stub_netcdf.nc");
+    /// let harmonic = NcHarmonicBuilder::new(&path, "steffen", 1, 2)
+    ///     .with_phase_method(PhaseMethod::Interpolation)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_phase_method(mut self, method: PhaseMethod<F>) -> Self {
+        self.phase_method = method;
+        self
+    }
+
+    /// Sets the harmonic's rigid rotation angular frequency `ω`, overriding whatever the netCDF
+    /// file carries (or its absence -- a file with no `omegas` variable otherwise defaults to
+    /// `ω=0`, a frozen perturbation).
+    ///
+    /// # Example
+    /// ```
+    /// # use equilibrium::*;
+    /// # use std::path::PathBuf;
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let path = PathBuf::from("../data/stub_netcdf.nc");
+    /// let harmonic = NcHarmonicBuilder::new(&path, "steffen", 1, 2)
+    ///     .with_omega(0.3)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_omega(mut self, omega: F) -> Self {
+        self.omega = Some(omega);
+        self
+    }
+
+    /// Sets the harmonic's amplitude-envelope growth rate `γ`, overriding whatever the netCDF file
+    /// carries (or its absence -- a file with no `gammas` variable otherwise defaults to `γ=0`, a
+    /// constant-amplitude harmonic).
+    ///
+    /// The envelope `e^(γt)` multiplies `α(ψp)` in [`h`](crate::Harmonic::h) and every one of its
+    /// spatial/time derivatives, so a positive `γ` grows the harmonic's amplitude over time (e.g.
+    /// an instability) and a negative `γ` damps it.
+    ///
+    /// # Example
+    /// ```
+    /// # use equilibrium::*;
+    /// # use std::path::PathBuf;
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let path = PathBuf::from("../data/stub_netcdf.nc");
+    /// let harmonic = NcHarmonicBuilder::new(&path, "steffen", 1, 2)
+    ///     .with_gamma(0.02)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_gamma(mut self, gamma: F) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+
+    /// Sets the amplitude-normalization convention applied to `α(ψp)` on load (see
+    /// [`Normalization`]). Defaults to [`Normalization::Raw`].
+    ///
+    /// # Example
+    /// ```
+    /// # use equilibrium::*;
+    /// # use std::path::PathBuf;
+    /// #
+    /// # fn main() -> Result<()> {
+    /// let path = PathBuf::from("../data/stub_netcdf.nc");
+    /// let harmonic = NcHarmonicBuilder::new(&path, "steffen", 1, 2)
+    ///     .with_normalization(Normalization::Power)
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Folds the Condon-Shortley phase `(-1)^m` into `α(ψp)`'s sign, matching codes that carry
+    /// that sign convention in their spectral coefficients instead of in the basis functions
+    /// themselves. `false` by default.
+    pub fn with_condon_shortley(mut self, condon_shortley: bool) -> Self {
+        self.condon_shortley = condon_shortley;
+        self
+    }
+
+    /// Evaluates `sin(mod_arg)`/`cos(mod_arg)` through a precomputed lookup table (see
+    /// [`FastTrigTable`]) instead of the exact transcendental functions. Trades a small, bounded
+    /// interpolation error for removing the libm `sin`/`cos` call from the hot loop -- worthwhile
+    /// when sweeping many harmonics over long orbits, where that call otherwise dominates. `false`
+    /// (exact trig) by default.
+    pub fn with_fast_trig(mut self, fast_trig: bool) -> Self {
+        self.fast_trig = fast_trig;
+        self
+    }
+
+    /// Resamples `α(ψp)`, `dα/dψp`, and (if [`PhaseMethod::Interpolation`] is set) `φ(ψp)` onto a
+    /// dense, uniform `ψp` grid of `n_points` at `build` time, so [`Harmonic::a`]/[`da_dpsip`]/
+    /// [`phase`] can look the grid up directly instead of walking the spline with an
+    /// [`Accelerator`] on every call -- worthwhile in a hot loop like `map_integrate`'s RKF45
+    /// stages, at the cost of the grid's own bounded resampling error. The on-demand spline path
+    /// remains the default (`None`).
+    ///
+    /// [`Harmonic::a`]: crate::Harmonic::a
+    /// [`da_dpsip`]: crate::Harmonic::da_dpsip
+    /// [`phase`]: crate::Harmonic::phase
+    pub fn with_precomputed_grid(mut self, n_points: usize) -> Self {
+        self.precomputed_grid = Some(n_points);
+        self
+    }
+}
+
+// ===============================================================================================
+
+/// The immutable, potentially large part of an [`NcHarmonic`]'s data -- shared behind an [`Arc`]
+/// so that cloning an [`NcHarmonic`] (done widely, e.g. once per particle state) is a refcount
+/// bump instead of rebuilding both splines from scratch.
+///
+/// Always `f64`: the spline tables and the netCDF data they are built from are hard-wired to
+/// `f64` regardless of the [`NcHarmonic`]'s own scalar parameter `F` (see [`Flt`](crate::flt)'s
+/// module docs).
+struct Inner {
+    /// Path to the netCDF file.
+    path: PathBuf,
+    /// 1D [`Interpolation type`], in case-insensitive string format.
+    ///
+    /// [`Interpolation type`]: ../rsl_interpolation/trait.InterpType.html#implementors
+    typ: String,
+
+    /// The `ψp` data array.
+    psip_data: Vec<f64>,
+    /// The `α` data array.
+    alpha_data: Vec<f64>,
+    /// The `φ` data array.
+    phase_data: Vec<f64>,
+
+    /// Interpolator over the `α` values, as a function of ψp.
+    alpha_interp: OneDInterp,
+    /// Interpolator over the `φ` values, as a function of ψp.
+    phase_interp: OneDInterp,
+}
+
+/// Single perturbation harmonic from a netCDF file.
+///
+/// The harmonic has the form `α(ψp) * e^(γt) * cos(mθ-nζ-ωt+φ(ψp))`, where `α(ψp)` is calculated by
+/// interpolation over numerical data, and `φ(ψp)` is calculated as defined by [`PhaseMethod`].
+///
+/// Generic over the floating-point scalar `F` (see [`Flt`]), defaulting to `f64`. The backing
+/// splines and stored data arrays are always `f64` (see [`Inner`]); `F` only governs the mode
+/// numbers, `ω`/`γ`, the phase, and every value [`Harmonic`]'s methods return, converting to/from
+/// `f64` at the spline boundary via [`Flt`]'s `FromPrimitive`/`ToPrimitive` bounds. Building with
+/// `F = f32` halves the footprint of every cached/evaluated quantity, at the cost of `f32`
+/// rounding on top of whatever error the `f64` spline already carries.
+///
+/// Should be created with an [`NcHarmonicBuilder`]. Cloning is cheap: the path, splines and data
+/// arrays live behind an `Arc` and are shared between clones, while `m`/`n`/`ω`/the phase
+/// configuration are plain `Copy`/`Clone` fields.
+#[non_exhaustive]
+#[derive(Clone)]
+pub struct NcHarmonic<F: Flt = f64> {
+    /// The shared, immutable spline-backing data.
+    inner: Arc<Inner>,
+
+    /// The `θ` frequency number, cast to `F` to be used in calculations.
+    pub(crate) _m: F,
+    /// The `ζ` frequency number, cast to `F` to be used in calculations.
+    pub(crate) _n: F,
+    /// The rigid rotation angular frequency `ω`. `0.0` for a frozen (time-independent) harmonic.
+    pub(crate) omega: F,
+    /// The amplitude-envelope growth rate `γ`. `0.0` for a constant-amplitude harmonic.
+    pub(crate) gamma: F,
+    /// The calculation method of the phase `φ(ψp)`.
+    pub(crate) phase_method: PhaseMethod<F>,
+    /// The average value of the phase array. `Some` only when `phase_method` is
+    /// [`PhaseMethod::Average`].
+    pub(crate) phase_average: Option<F>,
+    /// The rational surface `ψp` where `q(ψp) = m/n`. `Some` only when `phase_method` is
+    /// [`PhaseMethod::Resonance`] -- [`NcHarmonic::build`] fails with [`EqError::OutOfRange`] if no
+    /// such surface exists inside the wall.
+    pub(crate) psip_resonance: Option<f64>,
+    /// The value of the phase at [`Self::psip_resonance`]. `Some` only when `phase_method` is
+    /// [`PhaseMethod::Resonance`].
+    pub(crate) phase_resonance: Option<F>,
+    /// The amplitude-normalization convention applied to `α(ψp)` on load.
+    pub(crate) normalization: Normalization,
+    /// Whether the Condon-Shortley phase `(-1)^m` was folded into `α(ψp)`'s sign on load.
+    pub(crate) condon_shortley: bool,
+    /// The fast-trig lookup table (see [`NcHarmonicBuilder::with_fast_trig`]), `None` when the
+    /// exact `sin`/`cos` are used instead. Shared behind an `Arc` like [`Self::inner`], so cloning
+    /// an [`NcHarmonic`] never rebuilds it.
+    trig_table: Option<Arc<FastTrigTable<F>>>,
+    /// The dense `α`/`dα`/`φ` resampling grid (see [`NcHarmonicBuilder::with_precomputed_grid`]),
+    /// `None` when the spline is evaluated on demand instead. Shared behind an `Arc` like
+    /// [`Self::inner`], so cloning an [`NcHarmonic`] never rebuilds it.
+    grid: Option<Arc<PrecomputedGrid>>,
+}
+
+/// Converts an already-computed `f64` to `F`. The conversion can only fail for a non-finite
+/// input, which never occurs here (every value passed through comes from either a parsed netCDF
+/// file or a `ψp`-interpolation already known to succeed).
+fn to_f<F: Flt>(value: f64) -> F {
+    F::from_f64(value).expect("netCDF/spline values are always finite")
+}
+
+/// Creation
+impl<F: Flt> NcHarmonic<F> {
+    /// Constructs an [`NcHarmonic`] from [`NcHarmonicBuilder`].
+    pub(crate) fn build(builder: NcHarmonicBuilder<F>) -> Result<Self> {
+        use crate::extract::netcdf_fields::*;
+        use crate::extract::*;
+
+        // Make path absolute for display purposes.
+        let path = std::path::absolute(builder.path)?;
+        let f = open(&path)?;
+
+        let psip_data = extract_1d_array(&f, PSIP_NORM)?.to_vec();
+        let (alpha_data, phase_data) = extract_harmonic_arrays(&f, builder.m, builder.n)?;
+        let phase_data = phase_data.to_vec();
+
+        // Rescale by the selected Normalization, optionally folding in the Condon-Shortley sign
+        // (see Normalization's docs for the exact per-variant factor).
+        let scale = builder.normalization.factor(builder.m)
+            * if builder.condon_shortley && builder.m % 2 != 0 {
+                -1.0
+            } else {
+                1.0
+            };
+        let alpha_data: Vec<f64> = alpha_data.iter().map(|a| a * scale).collect();
+
+        let omega = match builder.omega {
+            Some(omega) => omega,
+            // A file with no `omegas` variable predates rotating perturbations -- treat it as the
+            // frozen (ω=0) harmonics it was always evaluated as.
+            None => match extract_harmonic_omega(&f, builder.m, builder.n) {
+                Ok(omega) => to_f(omega),
+                Err(EqError::NcError(NcError::MissingVariable(_))) => F::zero(),
+                Err(err) => return Err(err),
+            },
+        };
+
+        let gamma = match builder.gamma {
+            Some(gamma) => gamma,
+            // A file with no `gammas` variable predates growing/damping envelopes -- treat it as
+            // the constant-amplitude (γ=0) harmonics it was always evaluated as.
+            None => match extract_harmonic_gamma(&f, builder.m, builder.n) {
+                Ok(gamma) => to_f(gamma),
+                Err(EqError::NcError(NcError::MissingVariable(_))) => F::zero(),
+                Err(err) => return Err(err),
+            },
+        };
+
+        let alpha_interp = OneDInterp::build(&builder.typ, &psip_data, &alpha_data)?;
+        let phase_interp = OneDInterp::build(&builder.typ, &psip_data, &phase_data)?;
+
+        let phase_average = match builder.phase_method {
+            PhaseMethod::Average => Some(
+                // If `phase_data` was empty, `extract_1d_array` would have failed.
+                Array1::from(phase_data.clone())
+                    .mean()
+                    .expect("array is non-empty"),
+            ),
+            _ => None,
+        };
+
+        let phase_method = builder.phase_method;
+        let (psip_resonance, phase_resonance) = match phase_method {
+            // Locate the rational surface ψp_res where q(ψp_res) = m/n, then read off φ there.
+            PhaseMethod::Resonance => {
+                let mut acc = Accelerator::new();
+                let target = (builder.m as f64) / (builder.n as f64);
+                let q_data = extract_1d_array(&f, Q)?.to_vec();
+                let q_interp = OneDInterp::build(&builder.typ, &psip_data, &q_data)?;
+                let psip_res = find_resonant_psip(&psip_data, &q_data, &q_interp, target, &mut acc)?
+                    .ok_or(EqError::OutOfRange(target))?;
+                let phase_res = phase_interp
+                    .eval(&psip_data, &phase_data, psip_res, &mut acc)
+                    .expect("psip_res is in-bounds");
+                (Some(psip_res), Some(phase_res))
+            }
+            _ => (None, None),
+        };
+
+        // Built from the still-owned arrays/splines, before they move into Inner below.
+        let grid = builder
+            .precomputed_grid
+            .map(|n_points| {
+                PrecomputedGrid::new(
+                    n_points,
+                    &psip_data,
+                    &alpha_interp,
+                    &alpha_data,
+                    &phase_interp,
+                    &phase_data,
+                    matches!(phase_method, PhaseMethod::Interpolation),
+                )
+            })
+            .transpose()?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                path: path.to_owned(),
+                typ: builder.typ,
+                psip_data,
+                alpha_data,
+                phase_data,
+                alpha_interp,
+                phase_interp,
+            }),
+            _m: to_f(builder.m as f64),
+            _n: to_f(builder.n as f64),
+            omega,
+            gamma,
+            phase_method,
+            phase_average: phase_average.map(to_f),
+            psip_resonance,
+            phase_resonance: phase_resonance.map(to_f),
+            normalization: builder.normalization,
+            condon_shortley: builder.condon_shortley,
+            trig_table: builder.fast_trig.then(|| Arc::new(FastTrigTable::new())),
+            grid: grid.map(Arc::new),
+        })
+    }
+}
+
+impl<F: Flt> Harmonic<F> for NcHarmonic<F> {
+    fn h(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F> {
+        if !cache.is_updated(psip, theta, zeta, time) {
+            cache.update(self, psip, theta, zeta, time, acc)?
+        };
+        Ok(cache.alpha() * cache.cos() * cache.envelope())
+    }
+
+    fn dh_dpsip(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F> {
+        if !cache.is_updated(psip, theta, zeta, time) {
+            cache.update(self, psip, theta, zeta, time, acc)?
+        };
+        Ok(cache.dalpha() * cache.cos() * cache.envelope())
+    }
+
+    fn dh_dtheta(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F> {
+        if !cache.is_updated(psip, theta, zeta, time) {
+            cache.update(self, psip, theta, zeta, time, acc)?
+        };
+        Ok(cache.alpha() * (-self._m) * cache.sin() * cache.envelope())
+    }
+
+    fn dh_dzeta(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F> {
+        if !cache.is_updated(psip, theta, zeta, time) {
+            cache.update(self, psip, theta, zeta, time, acc)?
+        };
+        Ok(cache.alpha() * self._n * cache.sin() * cache.envelope())
+    }
+
+    fn d2h_dpsip2(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F> {
+        if !cache.is_updated(psip, theta, zeta, time) {
+            cache.update(self, psip, theta, zeta, time, acc)?
+        };
+        Ok(cache.d2alpha() * cache.cos() * cache.envelope())
+    }
+
+    fn d2h_dtheta2(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F> {
+        if !cache.is_updated(psip, theta, zeta, time) {
+            cache.update(self, psip, theta, zeta, time, acc)?
+        };
+        Ok(-cache.alpha() * self._m.powi(2) * cache.cos() * cache.envelope())
+    }
+
+    fn d2h_dpsip_dtheta(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F> {
+        if !cache.is_updated(psip, theta, zeta, time) {
+            cache.update(self, psip, theta, zeta, time, acc)?
+        };
+        Ok(-cache.dalpha() * self._m * cache.sin() * cache.envelope())
+    }
+
+    /// For a rigidly rotating mode with a growing/damping envelope `e^(γt)`, this is
+    /// `α(ψp)·e^(γt)·[ω·sin(mθ-nζ-ωt+φ(ψp)) + γ·cos(mθ-nζ-ωt+φ(ψp))]` -- the product rule applied
+    /// to `h = α(ψp)·e^(γt)·cos(arg)`, with the `ω` term from differentiating `arg` and the `γ`
+    /// term from differentiating the envelope. `0.0` for a frozen, constant-amplitude (ω=γ=0)
+    /// harmonic.
+    fn dh_dt(
+        &self,
+        psip: F,
+        theta: F,
+        zeta: F,
+        time: F,
+        acc: &mut Accelerator,
+        cache: &mut HarmonicCache<F>,
+    ) -> Result<F> {
+        if !cache.is_updated(psip, theta, zeta, time) {
+            cache.update(self, psip, theta, zeta, time, acc)?
+        };
+        let amplitude = cache.alpha() * cache.envelope();
+        Ok(amplitude * (self.omega * cache.sin() + self.gamma * cache.cos()))
+    }
+
+    fn a(&self, psip: F, acc: &mut Accelerator) -> Result<F> {
+        let psip = psip.to_f64().expect("F fits into f64");
+        match &self.grid {
+            Some(grid) => Ok(to_f(grid.alpha(psip))),
+            None => self
+                .inner
+                .alpha_interp
+                .eval(&self.inner.psip_data, &self.inner.alpha_data, psip, acc)
+                .map(to_f),
+        }
+    }
+
+    fn da_dpsip(&self, psip: F, acc: &mut Accelerator) -> Result<F> {
+        let psip = psip.to_f64().expect("F fits into f64");
+        match &self.grid {
+            Some(grid) => Ok(to_f(grid.dalpha(psip))),
+            None => self
+                .inner
+                .alpha_interp
+                .eval_deriv(&self.inner.psip_data, &self.inner.alpha_data, psip, acc)
+                .map(to_f),
+        }
+    }
+
+    /// Returns the phase value `φ(ψp)`, depending on the harmonic's [`PhaseMethod`].
+    fn phase(&self, psip: F, acc: &mut Accelerator) -> Result<F> {
+        // Options are always Some when the correct method is set.
+        match &self.phase_method {
+            PhaseMethod::Zero => Ok(F::zero()),
+            PhaseMethod::Average => Ok(self.phase_average.expect("is Some")),
+            PhaseMethod::Resonance => Ok(self.phase_resonance.expect("is Some")),
+            PhaseMethod::Custom(profile) => profile.eval(psip, acc),
+            PhaseMethod::Interpolation => {
+                let psip = psip.to_f64().expect("F fits into f64");
+                match &self.grid {
+                    Some(grid) if grid.phase.is_some() => Ok(to_f(grid.phase(psip))),
+                    _ => self
+                        .inner
+                        .phase_interp
+                        .eval(&self.inner.psip_data, &self.inner.phase_data, psip, acc)
+                        .map(to_f),
+                }
+            }
+        }
+    }
+
+    fn mod_arg(&self, psip: F, theta: F, zeta: F, time: F, acc: &mut Accelerator) -> Result<F> {
+        let arg = self._m * theta - self._n * zeta - self.omega * time + self.phase(psip, acc)?;
+        Ok(rem_euclid(arg, F::TAU()))
+    }
+}
+
+/// Fast-trig evaluation, for [`HarmonicCache::update`] (see [`NcHarmonicBuilder::with_fast_trig`]).
+impl<F: Flt> NcHarmonic<F> {
+    /// Returns `(sin(mod_arg), cos(mod_arg))`, through [`FastTrigTable`] if fast-trig mode is
+    /// enabled, or the exact `F::sin_cos` otherwise.
+    pub(crate) fn sin_cos(&self, mod_arg: F) -> (F, F) {
+        match &self.trig_table {
+            Some(table) => (table.sin(mod_arg), table.cos(mod_arg)),
+            None => mod_arg.sin_cos(),
+        }
+    }
+}
+
+/// Second derivatives, for Jacobian assembly (see [`HarmonicCache::d2alpha`]).
+impl<F: Flt> NcHarmonic<F> {
+    /// Differentiates `α(ψp)` twice, for [`HarmonicCache::update`] to populate
+    /// [`HarmonicCache::d2alpha`].
+    ///
+    /// `α`'s backing spline only exposes an analytic first derivative ([`Self::da_dpsip`]), so the
+    /// second derivative instead differentiates *that* once more with a centered, second-order
+    /// [`CenteredStencil`] -- the same finite-difference machinery [`crate::findiff`] provides for
+    /// differentiating a sampled field, applied here to an already-analytic function instead of raw
+    /// grid samples. The finite-differencing itself is done in `f64`, since [`CenteredStencil`] is;
+    /// only the final result is converted to `F`.
+    pub(crate) fn d2a_dpsip2(&self, psip: F, acc: &mut Accelerator) -> Result<F> {
+        let stencil = CenteredStencil::new(1, 2);
+        let h = self.psip_step();
+        let psip = psip.to_f64().expect("F fits into f64");
+        let alpha_interp = &self.inner.alpha_interp;
+        let psip_data = &self.inner.psip_data;
+        let alpha_data = &self.inner.alpha_data;
+        let samples = [
+            alpha_interp.eval_deriv(psip_data, alpha_data, psip - h, acc)?,
+            alpha_interp.eval_deriv(psip_data, alpha_data, psip, acc)?,
+            alpha_interp.eval_deriv(psip_data, alpha_data, psip + h, acc)?,
+        ];
+        Ok(to_f(stencil.apply(&samples, h)))
+    }
+
+    /// A characteristic `ψp` step for finite-differencing the spline: half the data's average grid
+    /// spacing, small enough to resolve curvature without stepping past a neighboring node.
+    fn psip_step(&self) -> f64 {
+        let data = &self.inner.psip_data;
+        (data[data.len() - 1] - data[0]) / (data.len() as f64 - 1.0) * 0.5
+    }
+}
+
+/// Getters
+impl<F: Flt> NcHarmonic<F> {
+    /// Returns the netCDF file's path.
+    pub fn path(&self) -> PathBuf {
+        self.inner.path.clone()
+    }
+
+    /// Returns the interpolation type.
+    pub fn typ(&self) -> String {
+        self.inner.typ.clone()
+    }
+
+    /// Returns the number of data points.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.inner.psip_data.len()
+    }
+
+    /// Returns the poloidal mode number `m`.
+    pub fn m(&self) -> i64 {
+        self._m.to_i64().expect("mode number fits into i64")
+    }
+
+    /// Returns the toroidal mode number `n`.
+    pub fn n(&self) -> i64 {
+        self._n.to_i64().expect("mode number fits into i64")
+    }
+
+    /// Returns the rigid rotation angular frequency `ω`. `0.0` for a frozen (time-independent)
+    /// harmonic.
+    pub fn omega(&self) -> F {
+        self.omega
+    }
+
+    /// Returns the amplitude-envelope growth rate `γ`. `0.0` for a constant-amplitude harmonic.
+    pub fn gamma(&self) -> F {
+        self.gamma
+    }
+
+    /// Returns the [`NcHarmonic`]'s phase calculation method.
+    pub fn phase_method(&self) -> PhaseMethod<F> {
+        self.phase_method.clone()
+    }
+
+    /// Returns the [`NcHarmonic`]'s phase average.
+    ///
+    /// Returns `None` if the [`NcHarmonic`]'s [`PhaseMethod`] is not [`PhaseMethod::Average`].
+    pub fn phase_average(&self) -> Option<F> {
+        self.phase_average
+    }
+
+    /// Returns the rational surface `ψp` where `q(ψp) = m/n`.
+    ///
+    /// Returns `None` if the [`NcHarmonic`]'s [`PhaseMethod`] is not [`PhaseMethod::Resonance`].
+    pub fn psip_resonance(&self) -> Option<f64> {
+        self.psip_resonance
+    }
+
+    /// Returns the [`NcHarmonic`]'s phase value at [`Self::psip_resonance`].
+    ///
+    /// Returns `None` if the [`NcHarmonic`]'s [`PhaseMethod`] is not [`PhaseMethod::Resonance`].
+    pub fn phase_resonance(&self) -> Option<F> {
+        self.phase_resonance
+    }
+
+    /// Returns the amplitude-normalization convention applied to `α(ψp)` on load.
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    /// Returns whether the Condon-Shortley phase `(-1)^m` was folded into `α(ψp)`'s sign on load.
+    pub fn condon_shortley(&self) -> bool {
+        self.condon_shortley
+    }
+
+    /// Returns whether `sin`/`cos` are evaluated through the fast-trig lookup table (see
+    /// [`NcHarmonicBuilder::with_fast_trig`]) instead of exactly.
+    pub fn fast_trig(&self) -> bool {
+        self.trig_table.is_some()
+    }
+
+    array1D_getter_impl!(psip_data, inner.psip_data, Flux);
+    array1D_getter_impl!(a_data, inner.alpha_data, Length);
+    array1D_getter_impl!(phase_data, inner.phase_data, Radians);
+
+    /// Returns the dense `α(ψp)` resampling grid built by
+    /// [`NcHarmonicBuilder::with_precomputed_grid`]. `None` if no grid was built.
+    pub fn alpha_grid(&self) -> Option<Array1<f64>> {
+        self.grid.as_ref().map(|grid| Array1::from(grid.alpha.clone()))
+    }
+
+    /// Returns the dense `dα/dψp` resampling grid built by
+    /// [`NcHarmonicBuilder::with_precomputed_grid`]. `None` if no grid was built.
+    pub fn dalpha_grid(&self) -> Option<Array1<f64>> {
+        self.grid.as_ref().map(|grid| Array1::from(grid.dalpha.clone()))
+    }
+
+    /// Returns the dense `φ(ψp)` resampling grid built by
+    /// [`NcHarmonicBuilder::with_precomputed_grid`]. `None` if no grid was built, or if the
+    /// [`NcHarmonic`]'s [`PhaseMethod`] is not [`PhaseMethod::Interpolation`] -- the only method
+    /// the grid covers.
+    pub fn phase_grid(&self) -> Option<Array1<f64>> {
+        self.grid.as_ref()?.phase.clone().map(Array1::from)
+    }
+}
+
+impl<F: Flt> std::fmt::Debug for NcHarmonic<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NcHarmonic")
+            .field("path", &self.path())
+            .field("typ", &self.typ())
+            .field("m", &self.m())
+            .field("n", &self.n())
+            .field("omega", &self.omega)
+            .field("gamma", &self.gamma)
+            .field("phase_method", &self.phase_method)
+            .field("phase_average", &self.phase_average)
+            .field("psip_resonance", &self.psip_resonance)
+            .field("phase_resonance", &self.phase_resonance)
+            .field("normalization", &self.normalization)
+            .field("condon_shortley", &self.condon_shortley)
+            .field("fast_trig", &self.fast_trig())
+            .field("precomputed_grid", &self.grid.is_some())
+            .finish()
+    }
+}
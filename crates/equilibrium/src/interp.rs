@@ -0,0 +1,293 @@
+//! A local, shape-preserving monotone cubic Hermite ("pchip") interpolation backend.
+//!
+//! `rsl_interpolation`'s own cubic/akima splines can overshoot near the steep gradients at the
+//! plasma edge, producing a non-monotone `r(ψp)` or `q(ψp)` that breaks the `psip(r)` inversion.
+//! [`OneDInterp`] adds a Fritsch-Carlson monotone cubic Hermite spline, selectable through the
+//! exact same case-insensitive `typ` string as every `rsl_interpolation` backend, by wrapping
+//! whichever one was actually requested.
+
+use rsl_interpolation::{Accelerator, DynInterpolation, InterpType, make_interp_type};
+
+use crate::{EqError, Result};
+
+/// Configurable behavior for evaluating an interpolator outside the range of its stored data.
+///
+/// Particles routinely wander past `psip_wall` during integration, so every trait method backed
+/// by a [`OneDInterp`] needs a per-equilibrium answer to "what happens at `psip > psip_wall`?"
+/// instead of either panicking or silently returning a spline's often-unphysical extrapolation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExtrapolationPolicy {
+    /// Return [`EqError::OutOfRange`] carrying the offending coordinate.
+    #[default]
+    Error,
+    /// Evaluate at the nearest boundary node instead of the requested coordinate.
+    Clamp,
+    /// Extend linearly from the boundary node's value and derivative.
+    LinearExtrapolate,
+}
+
+impl ExtrapolationPolicy {
+    /// Returns the nearest boundary of `xa` if `x` falls outside it, `None` if `x` is in range.
+    fn boundary(xa: &[f64], x: f64) -> Option<f64> {
+        let (lo, hi) = (xa[0], xa[xa.len() - 1]);
+        if x < lo {
+            Some(lo)
+        } else if x > hi {
+            Some(hi)
+        } else {
+            None
+        }
+    }
+}
+
+/// A 1D interpolator that is either one of `rsl_interpolation`'s own splines, or our own
+/// shape-preserving [`PchipSpline`].
+pub enum OneDInterp {
+    /// One of `rsl_interpolation`'s own interpolation types.
+    External(DynInterpolation<f64>),
+    /// Our own monotone cubic Hermite spline.
+    Pchip(PchipSpline),
+}
+
+impl OneDInterp {
+    /// Builds a [`OneDInterp`] of the given `typ`, where `"pchip"`/`"monotone"` selects the
+    /// shape-preserving backend, and any other string is forwarded to
+    /// [`rsl_interpolation::make_interp_type`].
+    ///
+    /// # Example
+    /// ```
+    /// let xa = [0.0, 1.0, 2.0, 3.0];
+    /// let ya = [0.0, 1.0, 1.0, 4.0];
+    /// let interp = OneDInterp::build("pchip", &xa, &ya)?;
+    /// # Ok::<_, equilibrium::EqError>(())
+    /// ```
+    pub fn build(typ: &str, xa: &[f64], ya: &[f64]) -> Result<Self> {
+        match typ.to_lowercase().as_str() {
+            "pchip" | "monotone" => Ok(Self::Pchip(PchipSpline::new(xa, ya))),
+            _ => Ok(Self::External(make_interp_type(typ)?.build(xa, ya)?)),
+        }
+    }
+
+    /// Evaluates the interpolant at `x`.
+    pub fn eval(&self, xa: &[f64], ya: &[f64], x: f64, acc: &mut Accelerator) -> Result<f64> {
+        match self {
+            Self::External(interp) => Ok(interp.eval(xa, ya, x, acc)?),
+            Self::Pchip(spline) => Ok(spline.eval(x)),
+        }
+    }
+
+    /// Evaluates the interpolant's derivative at `x`.
+    pub fn eval_deriv(&self, xa: &[f64], ya: &[f64], x: f64, acc: &mut Accelerator) -> Result<f64> {
+        match self {
+            Self::External(interp) => Ok(interp.eval_deriv(xa, ya, x, acc)?),
+            Self::Pchip(spline) => Ok(spline.eval_deriv(x)),
+        }
+    }
+
+    /// Evaluates the interpolant at `x`, honoring `policy` when `x` falls outside `xa`'s range.
+    pub fn eval_policy(
+        &self,
+        xa: &[f64],
+        ya: &[f64],
+        x: f64,
+        acc: &mut Accelerator,
+        policy: ExtrapolationPolicy,
+    ) -> Result<f64> {
+        let Some(boundary) = ExtrapolationPolicy::boundary(xa, x) else {
+            return self.eval(xa, ya, x, acc);
+        };
+        match policy {
+            ExtrapolationPolicy::Error => Err(EqError::OutOfRange(x)),
+            ExtrapolationPolicy::Clamp => self.eval(xa, ya, boundary, acc),
+            ExtrapolationPolicy::LinearExtrapolate => {
+                let value = self.eval(xa, ya, boundary, acc)?;
+                let deriv = self.eval_deriv(xa, ya, boundary, acc)?;
+                Ok(value + deriv * (x - boundary))
+            }
+        }
+    }
+
+    /// Evaluates the interpolant's derivative at `x`, honoring `policy` when `x` falls outside
+    /// `xa`'s range.
+    pub fn eval_deriv_policy(
+        &self,
+        xa: &[f64],
+        ya: &[f64],
+        x: f64,
+        acc: &mut Accelerator,
+        policy: ExtrapolationPolicy,
+    ) -> Result<f64> {
+        let Some(boundary) = ExtrapolationPolicy::boundary(xa, x) else {
+            return self.eval_deriv(xa, ya, x, acc);
+        };
+        match policy {
+            ExtrapolationPolicy::Error => Err(EqError::OutOfRange(x)),
+            // The derivative is already constant across the linear extension, so `Clamp` and
+            // `LinearExtrapolate` agree here.
+            ExtrapolationPolicy::Clamp | ExtrapolationPolicy::LinearExtrapolate => {
+                self.eval_deriv(xa, ya, boundary, acc)
+            }
+        }
+    }
+}
+
+// ===============================================================================================
+
+/// Shape-preserving monotone cubic Hermite spline (Fritsch-Carlson).
+///
+/// Guarantees the interpolant is monotone on every interval where the data themselves are
+/// monotone -- exactly the property `r(ψp)` and `q(ψp)` need for `psip(r)` to stay invertible near
+/// the plasma edge, where ordinary cubic/akima splines can overshoot.
+pub struct PchipSpline {
+    x: Vec<f64>,
+    y: Vec<f64>,
+    /// Tangent (derivative) at each node, computed once at construction time.
+    m: Vec<f64>,
+}
+
+impl PchipSpline {
+    /// Builds the spline, computing the Fritsch-Carlson tangents at every node of `(x, y)`.
+    pub fn new(x: &[f64], y: &[f64]) -> Self {
+        let n = x.len();
+        assert!(n >= 2, "pchip needs at least two points");
+
+        // Secant slope of each interval.
+        let delta: Vec<f64> = (0..n - 1)
+            .map(|i| (y[i + 1] - y[i]) / (x[i + 1] - x[i]))
+            .collect();
+
+        let mut m = vec![0.0; n];
+        // One-sided tangents at the endpoints.
+        m[0] = delta[0];
+        m[n - 1] = delta[n - 2];
+
+        // Interior tangents, weighted average of the adjacent secants.
+        for i in 1..n - 1 {
+            let (d0, d1) = (delta[i - 1], delta[i]);
+            if d0 == 0.0 || d1 == 0.0 || d0.signum() != d1.signum() {
+                m[i] = 0.0;
+                continue;
+            }
+            let h0 = x[i] - x[i - 1];
+            let h1 = x[i + 1] - x[i];
+            let w0 = 2.0 * h1 + h0;
+            let w1 = h1 + 2.0 * h0;
+            m[i] = (w0 + w1) / (w0 / d0 + w1 / d1);
+        }
+
+        // Fritsch-Carlson rescaling: guarantees monotonicity on every interval.
+        for i in 0..n - 1 {
+            let d = delta[i];
+            if d == 0.0 {
+                m[i] = 0.0;
+                m[i + 1] = 0.0;
+                continue;
+            }
+            let alpha = m[i] / d;
+            let beta = m[i + 1] / d;
+            let norm = alpha.powi(2) + beta.powi(2);
+            if norm > 9.0 {
+                let tau = 3.0 / norm.sqrt();
+                m[i] = tau * alpha * d;
+                m[i + 1] = tau * beta * d;
+            }
+        }
+
+        Self {
+            x: x.to_vec(),
+            y: y.to_vec(),
+            m,
+        }
+    }
+
+    /// Finds the interval `i` such that `x[i] <= x_eval <= x[i + 1]`, clamping to the endpoints.
+    fn locate(&self, x_eval: f64) -> usize {
+        match self.x.partition_point(|&xi| xi <= x_eval) {
+            0 => 0,
+            n if n >= self.x.len() => self.x.len() - 2,
+            n => n - 1,
+        }
+    }
+
+    /// Evaluates the spline at `x_eval`, using the cubic Hermite basis.
+    pub fn eval(&self, x_eval: f64) -> f64 {
+        let i = self.locate(x_eval);
+        let h = self.x[i + 1] - self.x[i];
+        let t = (x_eval - self.x[i]) / h;
+
+        let h00 = 2.0 * t.powi(3) - 3.0 * t.powi(2) + 1.0;
+        let h10 = t.powi(3) - 2.0 * t.powi(2) + t;
+        let h01 = -2.0 * t.powi(3) + 3.0 * t.powi(2);
+        let h11 = t.powi(3) - t.powi(2);
+
+        h00 * self.y[i] + h10 * h * self.m[i] + h01 * self.y[i + 1] + h11 * h * self.m[i + 1]
+    }
+
+    /// Evaluates the spline's derivative at `x_eval`.
+    pub fn eval_deriv(&self, x_eval: f64) -> f64 {
+        let i = self.locate(x_eval);
+        let h = self.x[i + 1] - self.x[i];
+        let t = (x_eval - self.x[i]) / h;
+
+        let dh00 = 6.0 * t.powi(2) - 6.0 * t;
+        let dh10 = 3.0 * t.powi(2) - 4.0 * t + 1.0;
+        let dh01 = -6.0 * t.powi(2) + 6.0 * t;
+        let dh11 = 3.0 * t.powi(2) - 2.0 * t;
+
+        (dh00 * self.y[i] + dh10 * h * self.m[i] + dh01 * self.y[i + 1] + dh11 * h * self.m[i + 1]) / h
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pchip_reproduces_nodes() {
+        let x = [0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = [0.0, 2.0, 2.0, 2.5, 10.0];
+        let spline = PchipSpline::new(&x, &y);
+        for (&xi, &yi) in x.iter().zip(y.iter()) {
+            assert!((spline.eval(xi) - yi).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_extrapolation_policy() {
+        let x = [0.0, 1.0, 2.0, 3.0];
+        let y = [0.0, 1.0, 2.0, 3.0];
+        let interp = OneDInterp::build("pchip", &x, &y).unwrap();
+        let mut acc = Accelerator::new();
+
+        assert!(matches!(
+            interp.eval_policy(&x, &y, 4.0, &mut acc, ExtrapolationPolicy::Error),
+            Err(EqError::OutOfRange(coord)) if coord == 4.0
+        ));
+
+        let clamped = interp
+            .eval_policy(&x, &y, 4.0, &mut acc, ExtrapolationPolicy::Clamp)
+            .unwrap();
+        assert_eq!(clamped, 3.0);
+
+        let extrapolated = interp
+            .eval_policy(&x, &y, 4.0, &mut acc, ExtrapolationPolicy::LinearExtrapolate)
+            .unwrap();
+        assert!((extrapolated - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pchip_is_monotone_on_monotone_data() {
+        let x = [0.0, 1.0, 2.0, 3.0, 4.0];
+        let y = [0.0, 0.01, 0.02, 0.5, 1.0];
+        let spline = PchipSpline::new(&x, &y);
+
+        let n = 200;
+        let mut prev = spline.eval(0.0);
+        for i in 1..=n {
+            let xi = 4.0 * i as f64 / n as f64;
+            let yi = spline.eval(xi);
+            assert!(yi >= prev - 1e-12, "overshoot at x={xi}: {yi} < {prev}");
+            prev = yi;
+        }
+    }
+}
@@ -1,27 +1,53 @@
 #![doc = include_str!("../README.md")]
 
+mod analytic_bfield;
 mod bfields;
 mod cache;
+mod chirikov;
 mod currents;
 mod error;
 mod eval;
+mod field_line;
+mod findiff;
+mod flt;
+mod flux_average;
 mod geometry;
+mod h5_geometry;
+mod h5_qfactor;
 mod harmonics;
+mod interp;
 mod perturbation;
+mod profiles;
 mod qfactors;
+mod solovev_geometry;
+mod solovev_qfactor;
+mod tabulated_perturbation;
 
 pub mod extract;
 
 pub use eval::{Bfield, Current, Geometry, Harmonic, Perturbation, Qfactor};
+pub use flt::Flt;
 
+pub use analytic_bfield::{AnalyticBfield, AnalyticBfieldBuilder};
 pub use bfields::{NcBfield, NcBfieldBuilder};
+pub use chirikov::{ResonantIsland, find_resonant_surface, locate_resonances, overlap_parameters};
 pub use currents::{NcCurrent, NcCurrentBuilder};
+pub use field_line::{FieldLine, GradAlpha};
+pub use findiff::{CenteredStencil, KreissOliger, fornberg_weights};
+pub use flux_average::flux_surface_average;
 pub use geometry::{NcGeometry, NcGeometryBuilder};
+pub use h5_geometry::{H5Geometry, H5GeometryBuilder};
+pub use h5_qfactor::{H5Qfactor, H5QfactorBuilder};
 pub use harmonics::{NcHarmonic, NcHarmonicBuilder};
+pub use interp::{ExtrapolationPolicy, OneDInterp, PchipSpline};
+pub use profiles::{NcCollisionProfile, NcCollisionProfileBuilder};
 pub use qfactors::{NcQfactor, NcQfactorBuilder};
+pub use solovev_geometry::{SolovevGeometry, SolovevGeometryBuilder};
+pub use solovev_qfactor::{SolovevQfactor, SolovevQfactorBuilder};
 
-pub use harmonics::PhaseMethod;
+pub use harmonics::{CustomPhaseProfile, Normalization, PhaseMethod};
 pub use perturbation::NcPerturbation;
+pub use tabulated_perturbation::TabulatedPerturbation;
 
 pub use cache::{HarmonicCache, NcHarmonicCache};
 
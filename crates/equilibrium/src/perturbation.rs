@@ -0,0 +1,355 @@
+//! Representation of a total perturbation, a sum of multiple harmonics.
+
+use ndarray::{Array1, ArrayView1, azip};
+use rsl_interpolation::Accelerator;
+
+use crate::HarmonicCache;
+use crate::NcHarmonic;
+use crate::{Perturbation, Result};
+
+/// A sum of different perturbation [`NcHarmonics`](NcHarmonic).
+///
+/// It has the general form
+///     `Σ{ α(n,m)(ψp) * e^(γt) * cos(mθ-nζ-ωt+φ0) }`.
+pub struct NcPerturbation {
+    harmonics: Vec<NcHarmonic>,
+    /// Poloidal mode numbers of every harmonic, precomputed once at construction so the reduction
+    /// pass in [`gather`](Self::gather) can read them as a contiguous array.
+    ms: Array1<f64>,
+    /// Toroidal mode numbers of every harmonic, precomputed once at construction so the reduction
+    /// pass in [`gather`](Self::gather) can read them as a contiguous array.
+    ns: Array1<f64>,
+    /// Rigid rotation angular frequencies of every harmonic, precomputed once at construction so
+    /// [`dp_dt`](Self::dp_dt) can read them as a contiguous array.
+    omegas: Array1<f64>,
+    /// Amplitude-envelope growth rates of every harmonic, precomputed once at construction so
+    /// [`dp_dt`](Self::dp_dt) can read them as a contiguous array.
+    gammas: Array1<f64>,
+}
+
+// Creation and data extraction
+impl NcPerturbation {
+    /// Creates a Perturbation from different [`NcHarmonics`](NcHarmonic).
+    ///
+    /// # Examples
+    ///
+    /// No perturbations:
+    /// ```
+    /// # use equilibrium::*;
+    /// let perturbation = NcPerturbation::from_harmonics(&[]);
+    /// ```
+    ///
+    /// Multiple perturbations:
+    /// ```
+    /// # use equilibrium::*;
+    /// # use std::path::PathBuf;
+    /// # let path = PathBuf::from(extract::STUB_TEST_NETCDF_PATH);
+    /// let perturbation = NcPerturbation::from_harmonics(&[
+    ///     NcHarmonicBuilder::new(&path, "steffen", 2, 1).build()?,
+    ///     NcHarmonicBuilder::new(&path, "steffen", 3, 2).build()?,
+    /// ]);
+    /// # Ok::<_, equilibrium::EqError>(())
+    /// ```
+    pub fn from_harmonics(harmonics: &[NcHarmonic]) -> Self {
+        let ms = Array1::from_iter(harmonics.iter().map(|h| h._m));
+        let ns = Array1::from_iter(harmonics.iter().map(|h| h._n));
+        let omegas = Array1::from_iter(harmonics.iter().map(|h| h.omega));
+        let gammas = Array1::from_iter(harmonics.iter().map(|h| h.gamma));
+        Self {
+            harmonics: harmonics.into(),
+            ms,
+            ns,
+            omegas,
+            gammas,
+        }
+    }
+
+    pub fn get_harmonics(&self) -> Vec<NcHarmonic> {
+        self.harmonics.clone()
+    }
+}
+
+/// Batched evaluation
+///
+/// `p`/`dp_dpsip`/`dp_dtheta`/`dp_dzeta` all need the same per-harmonic quantities at the same
+/// `(ψp, θ, ζ)`, so instead of looping over harmonics once per derivative -- each loop doing its
+/// own spline lookup through its own [`HarmonicCache`] -- [`gather`](Self::gather) locates every
+/// harmonic's spline interval exactly once and collects `α`, `dα/dψp`, `cos`, and `sin` into
+/// contiguous arrays. Each derivative then reduces over that structure-of-arrays layout with a
+/// single vectorized pass instead of a per-harmonic spline lookup.
+impl NcPerturbation {
+    /// Gathers every harmonic's amplitude, amplitude derivative, and phase factors at
+    /// `(psip, theta, zeta)` into contiguous arrays, updating each harmonic's cache along the way.
+    ///
+    /// `alpha`/`dalpha` already carry each harmonic's amplitude-envelope factor `e^(γt)`, so every
+    /// derivative below can multiply them directly instead of re-reading `envelope()` per harmonic.
+    fn gather(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<(Array1<f64>, Array1<f64>, Array1<f64>, Array1<f64>, Array1<f64>)> {
+        let n = self.harmonics.len();
+        let mut alpha = Array1::zeros(n);
+        let mut dalpha = Array1::zeros(n);
+        let mut d2alpha = Array1::zeros(n);
+        let mut cos = Array1::zeros(n);
+        let mut sin = Array1::zeros(n);
+
+        for (index, harmonic) in self.harmonics.iter().enumerate() {
+            let cache = &mut caches[index];
+            if !cache.is_updated(psip, theta, zeta, time) {
+                cache.update(harmonic, psip, theta, zeta, time, acc)?;
+            }
+            alpha[index] = cache.alpha() * cache.envelope();
+            dalpha[index] = cache.dalpha() * cache.envelope();
+            d2alpha[index] = cache.d2alpha() * cache.envelope();
+            cos[index] = cache.cos();
+            sin[index] = cache.sin();
+        }
+
+        Ok((alpha, dalpha, d2alpha, cos, sin))
+    }
+}
+
+impl Perturbation for NcPerturbation {
+    fn p(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, cos, _sin) = self.gather(psip, theta, zeta, time, acc, caches)?;
+        Ok((&alpha * &cos).sum())
+    }
+
+    fn dp_dpsip(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (_alpha, dalpha, _d2alpha, cos, _sin) = self.gather(psip, theta, zeta, time, acc, caches)?;
+        Ok((&dalpha * &cos).sum())
+    }
+
+    fn dp_dtheta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, _cos, sin) = self.gather(psip, theta, zeta, time, acc, caches)?;
+        Ok(-((&alpha * &self.ms) * &sin).sum())
+    }
+
+    fn dp_dzeta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, _cos, sin) = self.gather(psip, theta, zeta, time, acc, caches)?;
+        Ok(((&alpha * &self.ns) * &sin).sum())
+    }
+
+    fn dp_dt(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, cos, sin) = self.gather(psip, theta, zeta, time, acc, caches)?;
+        Ok(((&alpha * &self.omegas) * &sin).sum() + ((&alpha * &self.gammas) * &cos).sum())
+    }
+
+    fn d2p_dpsip2(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (_alpha, _dalpha, d2alpha, cos, _sin) = self.gather(psip, theta, zeta, time, acc, caches)?;
+        Ok((&d2alpha * &cos).sum())
+    }
+
+    fn d2p_dtheta2(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, cos, _sin) = self.gather(psip, theta, zeta, time, acc, caches)?;
+        Ok(-((&alpha * &self.ms.mapv(|m| m.powi(2))) * &cos).sum())
+    }
+
+    fn d2p_dpsip_dtheta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (_alpha, dalpha, _d2alpha, _cos, sin) = self.gather(psip, theta, zeta, time, acc, caches)?;
+        Ok(-((&dalpha * &self.ms) * &sin).sum())
+    }
+
+    fn len(&self) -> usize {
+        self.harmonics.len()
+    }
+}
+
+/// Batch evaluation
+///
+/// A single call to [`p`](Self::p)/[`dp_dpsip`](Self::dp_dpsip)/etc. allocates a fresh
+/// [`Accelerator`], which is wasteful when evaluating thousands of points along an orbit or on a
+/// Poincaré grid. These methods allocate the accelerator once and reuse the caller's
+/// [`HarmonicCache`] array across the whole sweep, so -- as long as the query points are roughly
+/// sorted -- repeated `O(log n)` binary searches amortize down to `O(1)` neighbor steps and no
+/// per-point cache reallocation is needed.
+impl NcPerturbation {
+    /// Evaluates `p(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn p_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.p(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `dp/dψp(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn dp_dpsip_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.dp_dpsip(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `dp/dθ(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn dp_dtheta_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.dp_dtheta(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `dp/dζ(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn dp_dzeta_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.dp_dzeta(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `dp/dt(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn dp_dt_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.dp_dt(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+}
+
+impl std::fmt::Debug for NcPerturbation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for harmonic in self.get_harmonics() {
+            let _ = harmonic.fmt(f);
+        }
+        Ok(())
+    }
+}
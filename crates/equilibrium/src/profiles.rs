@@ -0,0 +1,120 @@
+//! Background density/temperature profiles, for evaluating collisional quantities (e.g. a
+//! deflection frequency) along an orbit rather than the field/current/q-factor an orbit actually
+//! moves through.
+//!
+//! Unlike [`Qfactor`](crate::Qfactor)/[`Bfield`]/[`Current`], which are traits with multiple
+//! backends (netCDF, Solovev, tabulated), a collision operator only needs *some* `n_e(ψp)`/
+//! `T_e(ψp)` pair to evaluate against, so this is a single concrete, netCDF-backed struct rather
+//! than a trait -- there is no other source for these profiles in this workspace yet.
+
+use std::path::PathBuf;
+
+use rsl_interpolation::Accelerator;
+
+use crate::ExtrapolationPolicy;
+use crate::Flux;
+use crate::OneDInterp;
+use crate::Result;
+
+/// Used to create an [`NcCollisionProfile`].
+pub struct NcCollisionProfileBuilder {
+    /// Path to the netCDF file.
+    path: PathBuf,
+    /// 1D [`Interpolation type`], in case-insensitive string format.
+    ///
+    /// [`Interpolation type`]: ../rsl_interpolation/trait.InterpType.html#implementors
+    typ: String,
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
+}
+
+impl NcCollisionProfileBuilder {
+    /// Creates a new [`NcCollisionProfileBuilder`] from a netCDF file at `path`, with spline of
+    /// `typ` interpolation type.
+    ///
+    /// Defaults to [`ExtrapolationPolicy::Error`]; use [`with_extrapolation`](Self::with_extrapolation)
+    /// to select a different policy.
+    pub fn new(path: &PathBuf, typ: &str) -> Self {
+        Self {
+            path: path.clone(),
+            typ: typ.into(),
+            policy: ExtrapolationPolicy::default(),
+        }
+    }
+
+    /// Sets the behavior for `psip` values outside the stored data range.
+    pub fn with_extrapolation(mut self, policy: ExtrapolationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Creates a new [`NcCollisionProfile`] with the Builder's configuration.
+    pub fn build(self) -> Result<NcCollisionProfile> {
+        NcCollisionProfile::build(self)
+    }
+}
+
+// ===============================================================================================
+
+/// Background electron density/temperature profiles, reconstructed from a netCDF file.
+pub struct NcCollisionProfile {
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
+
+    /// The `ψp` data array.
+    psip_data: Vec<Flux>,
+    /// The `n_e` data array.
+    ne_data: Vec<f64>,
+    /// The `T_e` data array.
+    te_data: Vec<f64>,
+
+    /// Interpolator over the `n_e` values, as a function of ψp.
+    ne_interp: OneDInterp,
+    /// Interpolator over the `T_e` values, as a function of ψp.
+    te_interp: OneDInterp,
+}
+
+/// Creation
+impl NcCollisionProfile {
+    /// Constructs an [`NcCollisionProfile`] from [`NcCollisionProfileBuilder`].
+    fn build(builder: NcCollisionProfileBuilder) -> Result<Self> {
+        use crate::extract::netcdf_fields::*;
+        use crate::extract::*;
+
+        let path = std::path::absolute(builder.path)?;
+        let f = open(&path)?;
+
+        let psip_data = extract_1d_array(&f, PSIP_NORM)?
+            .as_standard_layout()
+            .to_vec();
+        let ne_data = extract_1d_array(&f, NE)?.as_standard_layout().to_vec();
+        let te_data = extract_1d_array(&f, TE)?.as_standard_layout().to_vec();
+
+        let ne_interp = OneDInterp::build(&builder.typ, &psip_data, &ne_data)?;
+        let te_interp = OneDInterp::build(&builder.typ, &psip_data, &te_data)?;
+
+        Ok(Self {
+            policy: builder.policy,
+            psip_data,
+            ne_data,
+            te_data,
+            ne_interp,
+            te_interp,
+        })
+    }
+}
+
+/// Evaluation
+impl NcCollisionProfile {
+    /// Evaluates the background electron density `n_e(ψp)`.
+    pub fn n_e(&self, psip: Flux, acc: &mut Accelerator) -> Result<f64> {
+        self.ne_interp
+            .eval_policy(&self.psip_data, &self.ne_data, psip, acc, self.policy)
+    }
+
+    /// Evaluates the background electron temperature `T_e(ψp)`.
+    pub fn t_e(&self, psip: Flux, acc: &mut Accelerator) -> Result<f64> {
+        self.te_interp
+            .eval_policy(&self.psip_data, &self.te_data, psip, acc, self.policy)
+    }
+}
@@ -3,11 +3,13 @@
 use std::path::PathBuf;
 
 use common::array1D_getter_impl;
-use rsl_interpolation::{Accelerator, DynInterpolation, InterpType, make_interp_type};
+use rsl_interpolation::Accelerator;
 
-use ndarray::Array1;
+use ndarray::{Array1, ArrayView1, azip};
 
+use crate::ExtrapolationPolicy;
 use crate::Flux;
+use crate::OneDInterp;
 use crate::Qfactor;
 use crate::Result;
 
@@ -19,12 +21,17 @@ pub struct NcQfactorBuilder {
     ///
     /// [`Interpolation type`]: ../rsl_interpolation/trait.InterpType.html#implementors
     typ: String,
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
 }
 
 impl NcQfactorBuilder {
     /// Creates a new [`NcQfactorBuilder`] from a netCDF file at `path`, with spline of `typ`
     /// interpolation type.
     ///
+    /// Defaults to [`ExtrapolationPolicy::Error`]; use [`with_extrapolation`](Self::with_extrapolation)
+    /// to select a different policy.
+    ///
     /// # Example
     /// ```
     /// # use std::path::PathBuf;
@@ -35,9 +42,16 @@ impl NcQfactorBuilder {
         Self {
             path: path.clone(),
             typ: typ.into(),
+            policy: ExtrapolationPolicy::default(),
         }
     }
 
+    /// Sets the behavior for `psip` values outside the stored data range.
+    pub fn with_extrapolation(mut self, policy: ExtrapolationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     /// Creates a new [`NcQfactor`] with the Builder's configuration.
     ///
     /// # Example
@@ -66,6 +80,8 @@ pub struct NcQfactor {
     ///
     /// [`Interpolation type`]: ../rsl_interpolation/trait.InterpType.html#implementors
     typ: String,
+    /// Behavior when `psip` falls outside the stored data range.
+    policy: ExtrapolationPolicy,
 
     /// The `ψp` data array.
     psip_data: Vec<Flux>,
@@ -75,9 +91,12 @@ pub struct NcQfactor {
     psi_data: Vec<Flux>,
 
     /// Interpolator over the `q` values, as a function of ψp.
-    q_interp: DynInterpolation<f64>,
+    ///
+    /// `typ` `"pchip"`/`"monotone"` selects a shape-preserving monotone cubic Hermite spline
+    /// instead of one of `rsl_interpolation`'s own backends -- see [`OneDInterp`].
+    q_interp: OneDInterp,
     /// Interpolator over the `ψ` values, as a function of ψp.
-    psi_interp: DynInterpolation<f64>,
+    psi_interp: OneDInterp,
 }
 
 /// Creation
@@ -99,12 +118,13 @@ impl NcQfactor {
             .to_vec();
         let q_data = extract_1d_array(&f, Q)?.as_standard_layout().to_vec();
 
-        let q_interp = make_interp_type(&builder.typ)?.build(&psip_data, &q_data)?;
-        let psi_interp = make_interp_type(&builder.typ)?.build(&psip_data, &psi_data)?;
+        let q_interp = OneDInterp::build(&builder.typ, &psip_data, &q_data)?;
+        let psi_interp = OneDInterp::build(&builder.typ, &psip_data, &psi_data)?;
 
         Ok(Self {
             path: path.to_owned(),
             typ: builder.typ,
+            policy: builder.policy,
             psip_data,
             q_data,
             psi_data,
@@ -117,21 +137,72 @@ impl NcQfactor {
 /// Interpolation
 impl Qfactor for NcQfactor {
     fn q(&self, psip: Flux, acc: &mut Accelerator) -> Result<f64> {
-        Ok(self
-            .q_interp
-            .eval(&self.psip_data, &self.q_data, psip, acc)?)
+        self.q_interp
+            .eval_policy(&self.psip_data, &self.q_data, psip, acc, self.policy)
     }
 
     fn psi(&self, psip: Flux, acc: &mut Accelerator) -> Result<Flux> {
-        Ok(self
-            .psi_interp
-            .eval(&self.psip_data, &self.psi_data, psip, acc)?)
+        self.psi_interp
+            .eval_policy(&self.psip_data, &self.psi_data, psip, acc, self.policy)
     }
 
     fn dpsi_dpsip(&self, psip: Flux, acc: &mut Accelerator) -> Result<f64> {
-        Ok(self
-            .psi_interp
-            .eval_deriv(&self.psip_data, &self.psi_data, psip, acc)?)
+        self.psi_interp
+            .eval_deriv_policy(&self.psip_data, &self.psi_data, psip, acc, self.policy)
+    }
+}
+
+/// Batch evaluation
+///
+/// A single call to [`NcQfactor::q`]/[`psi`](NcQfactor::psi)/[`dpsi_dpsip`](NcQfactor::dpsi_dpsip)
+/// allocates a fresh [`Accelerator`], which is wasteful when evaluating thousands of points along
+/// an orbit or on a Poincaré grid. These methods allocate the accelerator once and reuse it across
+/// the whole sweep: as long as the query points are roughly sorted, the accelerator turns repeated
+/// `O(log n)` binary searches into amortized `O(1)` neighbor steps.
+impl NcQfactor {
+    /// Evaluates `q(ψp)` at every point in `psips`.
+    pub fn q_batch(&self, psips: &ArrayView1<Flux>) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips) {
+            match self.q_interp.eval_policy(&self.psip_data, &self.q_data, psip, &mut acc, self.policy) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `ψ(ψp)` at every point in `psips`.
+    pub fn psi_batch(&self, psips: &ArrayView1<Flux>) -> Result<Array1<Flux>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips) {
+            match self.psi_interp.eval_policy(&self.psip_data, &self.psi_data, psip, &mut acc, self.policy) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `dψ/dψp` at every point in `psips`.
+    pub fn dpsi_dpsip_batch(&self, psips: &ArrayView1<Flux>) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips) {
+            match self.psi_interp.eval_deriv_policy(&self.psip_data, &self.psi_data, psip, &mut acc, self.policy) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
     }
 }
 
@@ -147,6 +218,11 @@ impl NcQfactor {
         self.typ.clone()
     }
 
+    /// Returns the active out-of-range extrapolation policy.
+    pub fn policy(&self) -> ExtrapolationPolicy {
+        self.policy
+    }
+
     /// Returns the number of data points.
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
@@ -163,6 +239,7 @@ impl std::fmt::Debug for NcQfactor {
         f.debug_struct("NcQfactor")
             .field("path", &self.path())
             .field("typ", &self.typ())
+            .field("extrapolation", &self.policy())
             .field("len", &self.len())
             .finish()
     }
@@ -207,4 +284,62 @@ mod test {
         q.psi(psip, &mut acc).unwrap();
         q.dpsi_dpsip(psip, &mut acc).unwrap();
     }
+
+    #[test]
+    fn test_batch_evaluation() {
+        let q = create_nc_qfactor();
+        let mut acc = Accelerator::new();
+
+        let psips = Array1::linspace(0.01, 0.02, 5);
+        let q_batch = q.q_batch(&psips.view()).unwrap();
+        let psi_batch = q.psi_batch(&psips.view()).unwrap();
+        let dpsi_dpsip_batch = q.dpsi_dpsip_batch(&psips.view()).unwrap();
+
+        for (i, &psip) in psips.iter().enumerate() {
+            assert_eq!(q_batch[i], q.q(psip, &mut acc).unwrap());
+            assert_eq!(psi_batch[i], q.psi(psip, &mut acc).unwrap());
+            assert_eq!(dpsi_dpsip_batch[i], q.dpsi_dpsip(psip, &mut acc).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_extrapolation_policy() {
+        let path = PathBuf::from(STUB_NETCDF_PATH);
+        let mut acc = Accelerator::new();
+
+        let q = create_nc_qfactor();
+        let psip_wall = *q.psip_data.last().unwrap();
+        let beyond_wall = psip_wall + 1.0;
+
+        assert!(matches!(
+            q.q(beyond_wall, &mut acc),
+            Err(crate::EqError::OutOfRange(coord)) if coord == beyond_wall
+        ));
+
+        let q_clamped = NcQfactorBuilder::new(&path, "steffen")
+            .with_extrapolation(ExtrapolationPolicy::Clamp)
+            .build()
+            .unwrap();
+        assert_eq!(
+            q_clamped.q(beyond_wall, &mut acc).unwrap(),
+            q_clamped.q(psip_wall, &mut acc).unwrap()
+        );
+
+        let q_extrapolated = NcQfactorBuilder::new(&path, "steffen")
+            .with_extrapolation(ExtrapolationPolicy::LinearExtrapolate)
+            .build()
+            .unwrap();
+        q_extrapolated.q(beyond_wall, &mut acc).unwrap();
+    }
+
+    #[test]
+    fn test_pchip_selectable_through_typ() {
+        let path = PathBuf::from(STUB_NETCDF_PATH);
+        let q = NcQfactorBuilder::new(&path, "pchip").build().unwrap();
+        let mut acc = Accelerator::new();
+
+        q.q(0.015, &mut acc).unwrap();
+        q.psi(0.015, &mut acc).unwrap();
+        q.dpsi_dpsip(0.015, &mut acc).unwrap();
+    }
 }
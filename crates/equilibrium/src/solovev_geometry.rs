@@ -0,0 +1,227 @@
+//! Analytical geometry of a Solov'ev equilibrium.
+
+use crate::{Flux, Geometry, Length, Radians, Result};
+
+/// Regularizes the reciprocal `1/x` as the smooth surrogate `x/(x² + η²)`, which stays finite and
+/// continuous through `x = 0` while converging to `1/x` for `|x| ≫ η`. Used in place of a literal
+/// division wherever a near-axis quantity has a genuine `1/x` singularity (see
+/// [`SolovevGeometry::jacobian`]).
+///
+/// The surrogate trades exactness for boundedness: at `x = η` it returns `1/(2η)` instead of
+/// `1/η`, a factor-of-two bias that shrinks as `η` is set smaller relative to the orbit's closest
+/// approach to the axis.
+fn regularize_reciprocal(x: f64, eta: f64) -> f64 {
+    x / (x * x + eta * eta)
+}
+
+/// Used to create a [`SolovevGeometry`].
+pub struct SolovevGeometryBuilder {
+    /// The horizontal position of the magnetic axis `R0` **in \[m\]**.
+    raxis: Length,
+    /// The minor radius of the wall flux surface **in \[m\]**.
+    r_wall: Length,
+    /// The elongation `κ` of the flux surfaces.
+    kappa: f64,
+    /// The triangularity `δ` of the wall flux surface.
+    delta: f64,
+    /// Magnetic field strength on the axis `B0` **in \[T\]**.
+    baxis: f64,
+    /// The regularization strength `η` for [`SolovevGeometry::jacobian`]'s near-axis `1/√ψp`
+    /// terms. `None` (the default) keeps the hard `ψp = max(ψp, 1e-12)` floor instead -- see
+    /// [`Self::with_axis_regularization`].
+    axis_regularization: Option<f64>,
+}
+
+impl SolovevGeometryBuilder {
+    /// Creates a new [`SolovevGeometryBuilder`] with magnetic axis at `raxis`, wall minor radius
+    /// `r_wall`, elongation `kappa`, triangularity `delta` and axis field `baxis`.
+    ///
+    /// # Example
+    /// ```
+    /// let builder = SolovevGeometryBuilder::new(1.0, 0.3, 1.7, 0.4, 1.0);
+    /// ```
+    pub fn new(raxis: Length, r_wall: Length, kappa: f64, delta: f64, baxis: f64) -> Self {
+        Self {
+            raxis,
+            r_wall,
+            kappa,
+            delta,
+            baxis,
+            axis_regularization: None,
+        }
+    }
+
+    /// Enables axis regularization for [`SolovevGeometry::jacobian`]'s near-axis `1/√ψp` terms,
+    /// replacing each with the smooth surrogate `x/(x² + η²)` (see [`regularize_reciprocal`])
+    /// instead of the default hard `ψp = max(ψp, 1e-12)` floor.
+    ///
+    /// `eta` should be chosen against the closest approach to the axis a traced orbit is expected
+    /// to make: too large biases the Jacobian away from its true value even away from the axis,
+    /// too small reintroduces the original near-singular behavior.
+    pub fn with_axis_regularization(mut self, eta: f64) -> Self {
+        self.axis_regularization = Some(eta);
+        self
+    }
+
+    /// Creates a new [`SolovevGeometry`] with the Builder's configuration.
+    pub fn build(self) -> Result<SolovevGeometry> {
+        SolovevGeometry::build(self)
+    }
+}
+
+// ===============================================================================================
+
+/// Analytically generated Solov'ev equilibrium geometry.
+///
+/// Flux surfaces are Miller-shaped ellipses, D-shaped by a triangularity that grows linearly with
+/// the minor radius and vanishes on the magnetic axis:
+/// ```text
+/// r(ψp)     = r_wall √ψp
+/// R(ψp, θ)  = R0 + r(ψp) cos(θ + arcsin(δ √ψp) sin θ)
+/// Z(ψp, θ)  = κ r(ψp) sin θ
+/// ```
+/// which, together with the Solov'ev relation `ψ(r) ∝ r²`, reproduces the flux surfaces of a
+/// shaped, large-aspect-ratio Solov'ev equilibrium in closed form, with no data file involved.
+/// The Jacobian follows from `J = R (∂R/∂ψp ∂Z/∂θ - ∂R/∂θ ∂Z/∂ψp)`, also evaluated analytically.
+pub struct SolovevGeometry {
+    /// The horizontal position of the magnetic axis `R0` **in \[m\]**.
+    raxis: Length,
+    /// The minor radius of the wall flux surface **in \[m\]**.
+    r_wall: Length,
+    /// The elongation `κ` of the flux surfaces.
+    kappa: f64,
+    /// The triangularity `δ` of the wall flux surface.
+    delta: f64,
+    /// Magnetic field strength on the axis `B0` **in \[T\]**.
+    baxis: f64,
+    /// The regularization strength `η` for [`Self::jacobian`]'s near-axis `1/√ψp` terms. `None`
+    /// keeps the hard `ψp = max(ψp, 1e-12)` floor instead -- see
+    /// [`SolovevGeometryBuilder::with_axis_regularization`].
+    axis_regularization: Option<f64>,
+}
+
+/// Creation
+impl SolovevGeometry {
+    /// Constructs a [`SolovevGeometry`] from a [`SolovevGeometryBuilder`].
+    pub(crate) fn build(builder: SolovevGeometryBuilder) -> Result<Self> {
+        Ok(Self {
+            raxis: builder.raxis,
+            r_wall: builder.r_wall,
+            kappa: builder.kappa,
+            delta: builder.delta,
+            baxis: builder.baxis,
+            axis_regularization: builder.axis_regularization,
+        })
+    }
+
+    /// The minor radius `r(ψp)` **in \[m\]**.
+    fn minor_radius(&self, psip: Flux) -> Length {
+        self.r_wall * psip.max(0.0).sqrt()
+    }
+
+    /// The local triangularity angle `arcsin(δ(ψp))`, growing linearly with the minor radius and
+    /// vanishing on the magnetic axis.
+    fn delta_angle(&self, psip: Flux) -> Radians {
+        (self.delta * psip.max(0.0).sqrt()).asin()
+    }
+}
+
+/// Interpolation
+impl Geometry for SolovevGeometry {
+    fn r(&self, psip: Flux) -> Result<Length> {
+        Ok(self.minor_radius(psip))
+    }
+
+    fn psip(&self, r: Length) -> Result<Flux> {
+        Ok((r / self.r_wall).powi(2))
+    }
+
+    fn rlab(&self, psip: Flux, theta: Radians) -> Result<f64> {
+        let r = self.minor_radius(psip);
+        let phase = theta + self.delta_angle(psip) * theta.sin();
+        Ok(self.raxis + r * phase.cos())
+    }
+
+    fn zlab(&self, psip: Flux, theta: Radians) -> Result<f64> {
+        let r = self.minor_radius(psip);
+        Ok(self.kappa * r * theta.sin())
+    }
+
+    fn jacobian(&self, psip: Flux, theta: Radians) -> Result<f64> {
+        // Without regularization, fall back to the original hard floor; with it, the surrogate
+        // itself stays finite through ψp = 0, so the raw (possibly negative) ψp can be used as-is.
+        let psip = match self.axis_regularization {
+            Some(_) => psip,
+            None => psip.max(1e-12),
+        };
+        let r = self.minor_radius(psip);
+        let delta_angle = self.delta_angle(psip);
+        let phase = theta + delta_angle * theta.sin();
+
+        // d r/d ψp = r_wall / (2 √ψp)
+        let two_sqrt_psip = 2.0 * psip.max(0.0).sqrt();
+        let inv_two_sqrt_psip = match self.axis_regularization {
+            Some(eta) => regularize_reciprocal(two_sqrt_psip, eta),
+            None => 1.0 / two_sqrt_psip,
+        };
+        let dr_dpsip = self.r_wall * inv_two_sqrt_psip;
+        // d (arcsin(δ√ψp)) / d ψp
+        let ddelta_denominator = (1.0 - (self.delta * psip.max(0.0).sqrt()).powi(2)).sqrt();
+        let ddelta_dpsip = self.delta * inv_two_sqrt_psip / ddelta_denominator;
+
+        let dr_dtheta = -r * phase.sin() * (1.0 + delta_angle * theta.cos());
+        let dr_dpsip_total = dr_dpsip * phase.cos() - r * phase.sin() * ddelta_dpsip * theta.sin();
+
+        let dz_dtheta = self.kappa * r * theta.cos();
+        let dz_dpsip = self.kappa * dr_dpsip * theta.sin();
+
+        let rlab = self.rlab(psip, theta)?;
+        Ok(rlab * (dr_dpsip_total * dz_dtheta - dr_dtheta * dz_dpsip))
+    }
+}
+
+/// Getters
+impl SolovevGeometry {
+    /// Returns the horizontal position of the magnetic axis `R0` **in \[m\]**.
+    pub fn raxis(&self) -> Length {
+        self.raxis
+    }
+
+    /// Returns the minor radius of the wall flux surface **in \[m\]**.
+    pub fn r_wall(&self) -> Length {
+        self.r_wall
+    }
+
+    /// Returns the elongation `κ`.
+    pub fn kappa(&self) -> f64 {
+        self.kappa
+    }
+
+    /// Returns the triangularity `δ`.
+    pub fn delta(&self) -> f64 {
+        self.delta
+    }
+
+    /// Returns the magnetic field strength on the axis `B0` **in \[T\]**.
+    pub fn baxis(&self) -> f64 {
+        self.baxis
+    }
+
+    /// Returns the axis regularization strength `η`, if enabled (see
+    /// [`SolovevGeometryBuilder::with_axis_regularization`]).
+    pub fn axis_regularization(&self) -> Option<f64> {
+        self.axis_regularization
+    }
+}
+
+impl std::fmt::Debug for SolovevGeometry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolovevGeometry")
+            .field("Raxis [m]", &format!("{:.7}", self.raxis()))
+            .field("r_wall [m]", &format!("{:.7}", self.r_wall()))
+            .field("kappa", &format!("{:.7}", self.kappa()))
+            .field("delta", &format!("{:.7}", self.delta()))
+            .field("Baxis [T]", &format!("{:.7}", self.baxis()))
+            .finish()
+    }
+}
@@ -0,0 +1,106 @@
+//! Analytical q-factor of a Solov'ev equilibrium.
+
+use rsl_interpolation::Accelerator;
+
+use crate::Flux;
+use crate::Qfactor;
+use crate::Result;
+
+/// Used to create a [`SolovevQfactor`].
+pub struct SolovevQfactorBuilder {
+    /// The safety factor on the magnetic axis.
+    q_axis: f64,
+    /// The safety factor at the wall.
+    q_edge: f64,
+    /// Shaping coefficient controlling the peakedness of the current profile, and therefore of
+    /// the q-profile itself.
+    shaping: f64,
+}
+
+impl SolovevQfactorBuilder {
+    /// Creates a new [`SolovevQfactorBuilder`], with safety factor `q_axis` on the magnetic axis,
+    /// `q_edge` at the wall, and a current-profile `shaping` coefficient.
+    ///
+    /// # Example
+    /// ```
+    /// let builder = SolovevQfactorBuilder::new(1.0, 3.0, 1.5);
+    /// ```
+    pub fn new(q_axis: f64, q_edge: f64, shaping: f64) -> Self {
+        Self {
+            q_axis,
+            q_edge,
+            shaping,
+        }
+    }
+
+    /// Creates a new [`SolovevQfactor`] with the Builder's configuration.
+    pub fn build(self) -> Result<SolovevQfactor> {
+        SolovevQfactor::build(self)
+    }
+}
+
+// ===============================================================================================
+
+/// Closed-form q-factor of a Solov'ev equilibrium.
+///
+/// The current profile is taken to be `I(ψp) ∝ (ψp)^shaping`, so that `q(ψp) = q_axis +
+/// (q_edge - q_axis) ψp^shaping` follows the usual large-aspect-ratio relation `q = r Bφ / (R0
+/// Bθ)`. The toroidal flux is then obtained analytically from the exact identity `q = dψ/dψp`,
+/// rather than by interpolating over a data array, so no file is ever read.
+#[derive(Debug, Clone)]
+pub struct SolovevQfactor {
+    /// The safety factor on the magnetic axis.
+    q_axis: f64,
+    /// The safety factor at the wall.
+    q_edge: f64,
+    /// Shaping coefficient controlling the peakedness of the current profile.
+    shaping: f64,
+}
+
+/// Creation
+impl SolovevQfactor {
+    /// Constructs a [`SolovevQfactor`] from a [`SolovevQfactorBuilder`].
+    pub(crate) fn build(builder: SolovevQfactorBuilder) -> Result<Self> {
+        Ok(Self {
+            q_axis: builder.q_axis,
+            q_edge: builder.q_edge,
+            shaping: builder.shaping,
+        })
+    }
+}
+
+/// Evaluation
+impl Qfactor for SolovevQfactor {
+    #[allow(unused_variables)]
+    fn q(&self, psip: Flux, acc: &mut Accelerator) -> Result<f64> {
+        Ok(self.q_axis + (self.q_edge - self.q_axis) * psip.powf(self.shaping))
+    }
+
+    #[allow(unused_variables)]
+    fn psi(&self, psip: Flux, acc: &mut Accelerator) -> Result<Flux> {
+        Ok(self.q_axis * psip
+            + (self.q_edge - self.q_axis) * psip.powf(self.shaping + 1.0) / (self.shaping + 1.0))
+    }
+
+    fn dpsi_dpsip(&self, psip: Flux, acc: &mut Accelerator) -> Result<f64> {
+        self.q(psip, acc)
+    }
+}
+
+/// Getters
+impl SolovevQfactor {
+    /// Returns the safety factor on the magnetic axis.
+    pub fn q_axis(&self) -> f64 {
+        self.q_axis
+    }
+
+    /// Returns the safety factor at the wall.
+    pub fn q_edge(&self) -> f64 {
+        self.q_edge
+    }
+
+    /// Returns the current-profile shaping coefficient.
+    pub fn shaping(&self) -> f64 {
+        self.shaping
+    }
+}
@@ -0,0 +1,406 @@
+//! A lookup-table [`Perturbation`] backend, trading exact spline evaluation for speed.
+
+use ndarray::{Array1, Array2, ArrayView1, azip};
+use rsl_interpolation::Accelerator;
+
+use crate::HarmonicCache;
+use crate::NcHarmonic;
+use crate::NcPerturbation;
+use crate::{Harmonic, Perturbation, Result};
+
+/// A [`NcPerturbation`](crate::NcPerturbation) with each harmonic's amplitude `α(ψp)` and
+/// `dα/dψp` precomputed on a dense, uniform `ψp` grid.
+///
+/// `Harmonic::h`/`dh_dpsip` spend most of their time in `α(ψp)`'s spline lookup, and the adaptive
+/// stepper calls them on every RHS evaluation -- replacing that lookup with an `O(1)`
+/// index-and-lerp on a precomputed table is the same technique large cross-section codes use to
+/// avoid paying for repeated expensive evaluations. The `cos(mθ-nζ+φ(ψp))` phase factor is left
+/// exact, since it is cheap and, for [`PhaseMethod::Interpolation`](crate::PhaseMethod), carries
+/// the only part of the harmonic that genuinely needs per-call resolution.
+pub struct TabulatedPerturbation {
+    harmonics: Vec<NcHarmonic>,
+    ms: Array1<f64>,
+    ns: Array1<f64>,
+    omegas: Array1<f64>,
+    gammas: Array1<f64>,
+    psip_min: f64,
+    psip_max: f64,
+    dpsip: f64,
+    n_grid: usize,
+    /// `alpha_table[[harmonic, grid_point]]`.
+    alpha_table: Array2<f64>,
+    /// `dalpha_table[[harmonic, grid_point]]`.
+    dalpha_table: Array2<f64>,
+    /// `d2alpha_table[[harmonic, grid_point]]`.
+    d2alpha_table: Array2<f64>,
+    /// Whether to fall back to exact spline evaluation within one grid cell of `psip_min`/`psip_max`.
+    exact_near_edges: bool,
+}
+
+impl TabulatedPerturbation {
+    /// Builds a [`TabulatedPerturbation`] from `perturbation`, tabulating every harmonic's `α`
+    /// and `dα/dψp` on `n_grid` uniform points spanning its data's `ψp` range.
+    ///
+    /// Falls back to exact spline evaluation near the grid edges by default -- see
+    /// [`Self::with_exact_near_edges`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_grid < 2`, or if `perturbation` has no harmonics (there is no `ψp` range to
+    /// tabulate).
+    pub fn from_perturbation(perturbation: &NcPerturbation, n_grid: usize) -> Result<Self> {
+        assert!(n_grid >= 2, "a tabulated grid needs at least two points");
+
+        let harmonics = perturbation.get_harmonics();
+        assert!(
+            !harmonics.is_empty(),
+            "cannot infer a ψp grid from a perturbation with no harmonics"
+        );
+
+        let ms = Array1::from_iter(harmonics.iter().map(|h| h._m));
+        let ns = Array1::from_iter(harmonics.iter().map(|h| h._n));
+        let omegas = Array1::from_iter(harmonics.iter().map(|h| h.omega));
+        let gammas = Array1::from_iter(harmonics.iter().map(|h| h.gamma));
+
+        let psip_data = harmonics[0].psip_data();
+        let psip_min = psip_data[0];
+        let psip_max = psip_data[psip_data.len() - 1];
+        let dpsip = (psip_max - psip_min) / (n_grid - 1) as f64;
+
+        let mut acc = Accelerator::new();
+        let mut alpha_table = Array2::zeros((harmonics.len(), n_grid));
+        let mut dalpha_table = Array2::zeros((harmonics.len(), n_grid));
+        let mut d2alpha_table = Array2::zeros((harmonics.len(), n_grid));
+        for (h, harmonic) in harmonics.iter().enumerate() {
+            for i in 0..n_grid {
+                let psip = psip_min + i as f64 * dpsip;
+                alpha_table[[h, i]] = harmonic.a(psip, &mut acc)?;
+                dalpha_table[[h, i]] = harmonic.da_dpsip(psip, &mut acc)?;
+                d2alpha_table[[h, i]] = harmonic.d2a_dpsip2(psip, &mut acc)?;
+            }
+        }
+
+        Ok(Self {
+            harmonics,
+            ms,
+            ns,
+            omegas,
+            gammas,
+            psip_min,
+            psip_max,
+            dpsip,
+            n_grid,
+            alpha_table,
+            dalpha_table,
+            d2alpha_table,
+            exact_near_edges: true,
+        })
+    }
+
+    /// Sets whether `ψp` within one grid cell of the table's edges falls back to exact spline
+    /// evaluation instead of extrapolating the lookup table.
+    pub fn with_exact_near_edges(mut self, exact_near_edges: bool) -> Self {
+        self.exact_near_edges = exact_near_edges;
+        self
+    }
+
+    /// Looks up `α(psip)`, `dα/dψp(psip)`, and `d²α/dψp²(psip)` for harmonic `h`, either via the
+    /// table (`O(1)` index-and-lerp) or, near the table's edges when [`Self::exact_near_edges`] is
+    /// set, via the harmonic's own exact spline.
+    fn lookup(&self, h: usize, psip: f64, acc: &mut Accelerator) -> Result<(f64, f64, f64)> {
+        if self.exact_near_edges
+            && (psip < self.psip_min + self.dpsip || psip > self.psip_max - self.dpsip)
+        {
+            let harmonic = &self.harmonics[h];
+            return Ok((
+                harmonic.a(psip, acc)?,
+                harmonic.da_dpsip(psip, acc)?,
+                harmonic.d2a_dpsip2(psip, acc)?,
+            ));
+        }
+
+        let index = ((psip - self.psip_min) / self.dpsip).floor() as isize;
+        let index = index.clamp(0, self.n_grid as isize - 2) as usize;
+        let frac = (psip - (self.psip_min + index as f64 * self.dpsip)) / self.dpsip;
+
+        let alpha = self.alpha_table[[h, index]] * (1.0 - frac) + self.alpha_table[[h, index + 1]] * frac;
+        let dalpha =
+            self.dalpha_table[[h, index]] * (1.0 - frac) + self.dalpha_table[[h, index + 1]] * frac;
+        let d2alpha = self.d2alpha_table[[h, index]] * (1.0 - frac)
+            + self.d2alpha_table[[h, index + 1]] * frac;
+        Ok((alpha, dalpha, d2alpha))
+    }
+
+    /// Gathers every harmonic's tabulated amplitude/derivatives and exact phase factors at
+    /// `(psip, theta, zeta)`, mirroring [`NcPerturbation::gather`](crate::NcPerturbation).
+    #[allow(clippy::type_complexity)]
+    fn gather(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+    ) -> Result<(Array1<f64>, Array1<f64>, Array1<f64>, Array1<f64>, Array1<f64>)> {
+        let n = self.harmonics.len();
+        let mut alpha = Array1::zeros(n);
+        let mut dalpha = Array1::zeros(n);
+        let mut d2alpha = Array1::zeros(n);
+        let mut cos = Array1::zeros(n);
+        let mut sin = Array1::zeros(n);
+
+        for (index, harmonic) in self.harmonics.iter().enumerate() {
+            let (a, da, d2a) = self.lookup(index, psip, acc)?;
+            let mod_arg = harmonic._m * theta - harmonic._n * zeta - harmonic.omega * time
+                + harmonic.phase(psip, acc)?;
+            let envelope = (harmonic.gamma * time).exp();
+            alpha[index] = a * envelope;
+            dalpha[index] = da * envelope;
+            d2alpha[index] = d2a * envelope;
+            cos[index] = mod_arg.cos();
+            sin[index] = mod_arg.sin();
+        }
+
+        Ok((alpha, dalpha, d2alpha, cos, sin))
+    }
+}
+
+impl Perturbation for TabulatedPerturbation {
+    fn p(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        _caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, cos, _sin) = self.gather(psip, theta, zeta, time, acc)?;
+        Ok((&alpha * &cos).sum())
+    }
+
+    fn dp_dpsip(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        _caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (_alpha, dalpha, _d2alpha, cos, _sin) = self.gather(psip, theta, zeta, time, acc)?;
+        Ok((&dalpha * &cos).sum())
+    }
+
+    fn dp_dtheta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        _caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, _cos, sin) = self.gather(psip, theta, zeta, time, acc)?;
+        Ok(-((&alpha * &self.ms) * &sin).sum())
+    }
+
+    fn dp_dzeta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        _caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, _cos, sin) = self.gather(psip, theta, zeta, time, acc)?;
+        Ok(((&alpha * &self.ns) * &sin).sum())
+    }
+
+    fn dp_dt(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        _caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, cos, sin) = self.gather(psip, theta, zeta, time, acc)?;
+        Ok(((&alpha * &self.omegas) * &sin).sum() + ((&alpha * &self.gammas) * &cos).sum())
+    }
+
+    fn d2p_dpsip2(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        _caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (_alpha, _dalpha, d2alpha, cos, _sin) = self.gather(psip, theta, zeta, time, acc)?;
+        Ok((&d2alpha * &cos).sum())
+    }
+
+    fn d2p_dtheta2(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        _caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (alpha, _dalpha, _d2alpha, cos, _sin) = self.gather(psip, theta, zeta, time, acc)?;
+        Ok(-((&alpha * &self.ms.mapv(|m| m.powi(2))) * &cos).sum())
+    }
+
+    fn d2p_dpsip_dtheta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        _caches: &mut [HarmonicCache],
+    ) -> Result<f64> {
+        let (_alpha, dalpha, _d2alpha, _cos, sin) = self.gather(psip, theta, zeta, time, acc)?;
+        Ok(-((&dalpha * &self.ms) * &sin).sum())
+    }
+
+    fn len(&self) -> usize {
+        self.harmonics.len()
+    }
+}
+
+/// Batch evaluation
+///
+/// Mirrors [`NcPerturbation`]'s batch methods: a single call allocates an [`Accelerator`] once and
+/// reuses it across the whole sweep, amortizing the per-point cost of the bracket search down from
+/// `O(log n)` to `O(1)` for roughly sorted query points. The caller's [`HarmonicCache`] array is
+/// passed through for signature parity only; the table lookup never reads it.
+impl TabulatedPerturbation {
+    /// Evaluates `p(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn p_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.p(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `dp/dψp(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn dp_dpsip_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.dp_dpsip(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `dp/dθ(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn dp_dtheta_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.dp_dtheta(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `dp/dζ(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn dp_dzeta_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.dp_dzeta(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+
+    /// Evaluates `dp/dt(ψp, θ, ζ, t)` at paired `(psips[i], thetas[i], zetas[i], times[i])` points.
+    pub fn dp_dt_batch(
+        &self,
+        psips: &ArrayView1<f64>,
+        thetas: &ArrayView1<f64>,
+        zetas: &ArrayView1<f64>,
+        times: &ArrayView1<f64>,
+        caches: &mut [HarmonicCache],
+    ) -> Result<Array1<f64>> {
+        let mut acc = Accelerator::new();
+        let mut out = Array1::zeros(psips.len());
+        let mut err = Ok(());
+        azip!((o in &mut out, &psip in psips, &theta in thetas, &zeta in zetas, &time in times) {
+            match self.dp_dt(psip, theta, zeta, time, &mut acc, caches) {
+                Ok(v) => *o = v,
+                Err(e) => err = Err(e),
+            }
+        });
+        err?;
+        Ok(out)
+    }
+}
+
+impl std::fmt::Debug for TabulatedPerturbation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TabulatedPerturbation")
+            .field("harmonics", &self.harmonics.len())
+            .field("psip_min", &self.psip_min)
+            .field("psip_max", &self.psip_max)
+            .field("n_grid", &self.n_grid)
+            .field("exact_near_edges", &self.exact_near_edges)
+            .finish()
+    }
+}
@@ -67,31 +67,31 @@ fn test_nc_harmonic_evals() {
     // Normal
     assert!(
         harmonic
-            .h(psip, theta, zeta, &mut acc, &mut cache)
+            .h(psip, theta, zeta, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
     assert!(
         harmonic
-            .dh_dpsip(psip, theta, zeta, &mut acc, &mut cache)
+            .dh_dpsip(psip, theta, zeta, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
     assert!(
         harmonic
-            .dh_dtheta(psip, theta, zeta, &mut acc, &mut cache)
+            .dh_dtheta(psip, theta, zeta, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
     assert!(
         harmonic
-            .dh_dzeta(psip, theta, zeta, &mut acc, &mut cache)
+            .dh_dzeta(psip, theta, zeta, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
     assert!(
         harmonic
-            .dh_dt(psip, theta, zeta, &mut acc, &mut cache)
+            .dh_dt(psip, theta, zeta, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
@@ -102,31 +102,31 @@ fn test_nc_harmonic_evals() {
     // Big θ and ζ
     assert!(
         harmonic
-            .h(psip, 10000.0, 20000.0, &mut acc, &mut cache)
+            .h(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
     assert!(
         harmonic
-            .dh_dpsip(psip, 10000.0, 20000.0, &mut acc, &mut cache)
+            .dh_dpsip(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
     assert!(
         harmonic
-            .dh_dtheta(psip, 10000.0, 20000.0, &mut acc, &mut cache)
+            .dh_dtheta(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
     assert!(
         harmonic
-            .dh_dzeta(psip, 10000.0, 20000.0, &mut acc, &mut cache)
+            .dh_dzeta(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
     assert!(
         harmonic
-            .dh_dt(psip, 10000.0, 20000.0, &mut acc, &mut cache)
+            .dh_dt(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut cache)
             .unwrap()
             .is_finite()
     );
@@ -219,6 +219,77 @@ fn test_nc_harmonic_interpolation_phase_method() {
     );
 }
 
+#[test]
+fn test_nc_harmonic_cache_preserves_phase_accuracy_at_large_theta_zeta() {
+    use PhaseMethod::*;
+    let path = PathBuf::from(STUB_TEST_NETCDF_PATH);
+    let typ = "steffen";
+    let m = 2;
+    let n = 1;
+    let builder = NcHarmonicBuilder::<f32>::new(&path, typ, m, n).with_phase_method(Zero);
+    let harmonic = builder.build().unwrap();
+    let psip_data = harmonic.psip_data();
+    let psip = 0.5 * (*psip_data.last().unwrap() as f32);
+    let mut acc = Accelerator::new();
+    let mut cache = NcHarmonicCache::<f32>::new(1);
+
+    // Walk theta/zeta up to ~1e6 over many small steps, as a long Poincare trace would, instead
+    // of jumping straight there -- the cache should track the periodic remainder incrementally
+    // rather than re-deriving it from the full, by-then-huge theta/zeta every call.
+    const STEPS: usize = 1000;
+    let theta_target: f32 = 1_000_000.3;
+    let zeta_target: f32 = 2_000_000.7;
+    let mut h = 0.0;
+    for step in 1..=STEPS {
+        let theta = theta_target * (step as f32 / STEPS as f32);
+        let zeta = zeta_target * (step as f32 / STEPS as f32);
+        h = harmonic.h(psip, theta, zeta, 0.0, &mut acc, &mut cache).unwrap();
+    }
+
+    // Ground truth: m*theta - n*zeta mod TAU, reduced once in f64, where 1e6 still carries
+    // ample precision.
+    let expected_mod_arg = ((m as f64) * (theta_target as f64) - (n as f64) * (zeta_target as f64))
+        .rem_euclid(std::f64::consts::TAU);
+    let alpha = harmonic.a(psip, &mut acc).unwrap();
+    let expected = alpha * (expected_mod_arg as f32).cos();
+
+    assert!((h - expected).abs() < 1e-3, "h = {h}, expected = {expected}");
+}
+
+#[test]
+fn test_nc_harmonic_cache_capacity_avoids_thrashing_two_interleaved_states() {
+    let path = PathBuf::from(STUB_TEST_NETCDF_PATH);
+    let typ = "steffen";
+    let m = 2;
+    let n = 1;
+    let builder = NcHarmonicBuilder::new(&path, typ, m, n);
+    let harmonic = builder.build().unwrap();
+    let psip_data = harmonic.psip_data();
+    let psip = 0.5 * psip_data.last().unwrap();
+    let mut acc = Accelerator::new();
+
+    // A single-slot cache thrashes when two states are evaluated round-robin: each call evicts
+    // the other state's point, so every call is a miss.
+    let mut single = NcHarmonicCache::new(1);
+    for _ in 0..5 {
+        harmonic.h(psip, 1.0, 2.0, 0.0, &mut acc, &mut single).unwrap();
+        harmonic.h(psip, 3.0, 4.0, 0.0, &mut acc, &mut single).unwrap();
+    }
+    assert_eq!(single.hits(), 0);
+    assert_eq!(single.misses(), 10);
+
+    // A 2-slot cache gives each state its own slot, so only the first visit to each misses.
+    let mut paired = NcHarmonicCache::new(2);
+    for _ in 0..5 {
+        harmonic.h(psip, 1.0, 2.0, 0.0, &mut acc, &mut paired).unwrap();
+        harmonic.h(psip, 3.0, 4.0, 0.0, &mut acc, &mut paired).unwrap();
+    }
+    assert_eq!(paired.misses(), 2);
+    assert_eq!(paired.hits(), 8);
+    assert_eq!(paired.capacity(), 2);
+    assert_eq!(paired.occupancy(), 2);
+}
+
 #[test]
 fn test_nc_harmonic_custom_phase_method() {
     use PhaseMethod::*;
@@ -22,31 +22,31 @@ fn test_nc_perturbation_no_harmonics() {
 
     assert_eq!(
         perturbation
-            .p(psip, theta, zeta, &mut acc, &mut hcaches)
+            .p(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap(),
         0.0
     );
     assert_eq!(
         perturbation
-            .dp_dpsip(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dpsip(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap(),
         0.0
     );
     assert_eq!(
         perturbation
-            .dp_dtheta(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dtheta(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap(),
         0.0
     );
     assert_eq!(
         perturbation
-            .dp_dzeta(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dzeta(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap(),
         0.0
     );
     assert_eq!(
         perturbation
-            .dp_dt(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dt(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap(),
         0.0
     );
@@ -73,31 +73,31 @@ fn test_nc_perturbation_one_harmonic() {
     // Normal
     assert!(
         perturbation
-            .p(psip, theta, zeta, &mut acc, &mut hcaches)
+            .p(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dpsip(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dpsip(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dtheta(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dtheta(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dzeta(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dzeta(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dt(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dt(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
@@ -105,38 +105,38 @@ fn test_nc_perturbation_one_harmonic() {
     // Big θ and ζ
     assert!(
         perturbation
-            .dp_dzeta(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .dp_dzeta(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .p(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .p(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dpsip(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .dp_dpsip(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dtheta(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .dp_dtheta(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dt(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .dp_dt(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
 
     // Out of bounds
     assert!(matches!(
-        dbg!(perturbation.dp_dtheta(10000.0, theta, zeta, &mut acc, &mut hcaches)),
+        dbg!(perturbation.dp_dtheta(10000.0, theta, zeta, 0.0, &mut acc, &mut hcaches)),
         Err(equilibrium::EqError::DomainError(..))
     ));
 }
@@ -173,31 +173,31 @@ fn test_nc_perturbation_multiple_harmonics() {
     // Normal
     assert!(
         perturbation
-            .dp_dtheta(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dtheta(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .p(psip, theta, zeta, &mut acc, &mut hcaches)
+            .p(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dpsip(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dpsip(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dzeta(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dzeta(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dt(psip, theta, zeta, &mut acc, &mut hcaches)
+            .dp_dt(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
@@ -205,38 +205,38 @@ fn test_nc_perturbation_multiple_harmonics() {
     // Big θ and ζ
     assert!(
         perturbation
-            .dp_dpsip(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .dp_dpsip(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .p(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .p(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dtheta(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .dp_dtheta(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dzeta(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .dp_dzeta(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
     assert!(
         perturbation
-            .dp_dt(psip, 10000.0, 20000.0, &mut acc, &mut hcaches)
+            .dp_dt(psip, 10000.0, 20000.0, 0.0, &mut acc, &mut hcaches)
             .unwrap()
             .is_finite()
     );
 
     // Out of bounds
     assert!(matches!(
-        dbg!(perturbation.dp_dtheta(10000.0, theta, zeta, &mut acc, &mut hcaches)),
+        dbg!(perturbation.dp_dtheta(10000.0, theta, zeta, 0.0, &mut acc, &mut hcaches)),
         Err(equilibrium::EqError::DomainError(..))
     ));
 }
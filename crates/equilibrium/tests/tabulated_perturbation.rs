@@ -0,0 +1,83 @@
+use equilibrium::extract::STUB_TEST_NETCDF_PATH;
+use rsl_interpolation::Accelerator;
+use std::path::PathBuf;
+
+use equilibrium::*;
+
+#[test]
+fn test_tabulated_perturbation_agrees_with_exact() {
+    let path = PathBuf::from(STUB_TEST_NETCDF_PATH);
+    let harmonics = vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1).build().unwrap(),
+        NcHarmonicBuilder::new(&path, "steffen", 3, 2).build().unwrap(),
+    ];
+    let psip_data = harmonics[0].psip_data();
+    let psip_wall = *psip_data.last().unwrap();
+
+    let exact = NcPerturbation::from_harmonics(&harmonics);
+    let tabulated = TabulatedPerturbation::from_perturbation(&exact, 10_000).unwrap();
+
+    assert_eq!(tabulated.len(), exact.len());
+    assert!(!tabulated.is_empty());
+
+    let psip = 0.5 * psip_wall;
+    let theta = 3.14;
+    let zeta = 6.28;
+    let mut acc = Accelerator::new();
+    let mut hcaches = vec![HarmonicCache::new(); exact.len()];
+
+    let exact_p = exact.p(psip, theta, zeta, 0.0, &mut acc, &mut hcaches).unwrap();
+    let tabulated_p = tabulated.p(psip, theta, zeta, 0.0, &mut acc, &mut hcaches).unwrap();
+    assert!((exact_p - tabulated_p).abs() < 1e-6);
+
+    let exact_dp_dpsip = exact
+        .dp_dpsip(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
+        .unwrap();
+    let tabulated_dp_dpsip = tabulated
+        .dp_dpsip(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
+        .unwrap();
+    assert!((exact_dp_dpsip - tabulated_dp_dpsip).abs() < 1e-3);
+
+    let exact_dp_dtheta = exact
+        .dp_dtheta(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
+        .unwrap();
+    let tabulated_dp_dtheta = tabulated
+        .dp_dtheta(psip, theta, zeta, 0.0, &mut acc, &mut hcaches)
+        .unwrap();
+    assert!((exact_dp_dtheta - tabulated_dp_dtheta).abs() < 1e-6);
+}
+
+#[test]
+fn test_tabulated_perturbation_falls_back_near_edges() {
+    let path = PathBuf::from(STUB_TEST_NETCDF_PATH);
+    let harmonics = vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1).build().unwrap(),
+    ];
+    let psip_data = harmonics[0].psip_data();
+    let psip_min = psip_data[0];
+
+    let exact = NcPerturbation::from_harmonics(&harmonics);
+    let tabulated = TabulatedPerturbation::from_perturbation(&exact, 20).unwrap();
+
+    let theta = 1.0;
+    let zeta = 2.0;
+    let mut acc = Accelerator::new();
+    let mut hcaches = vec![HarmonicCache::new(); exact.len()];
+
+    // Right at the lower edge, the fallback path should match the exact evaluation exactly.
+    let exact_p = exact
+        .p(psip_min, theta, zeta, 0.0, &mut acc, &mut hcaches)
+        .unwrap();
+    let tabulated_p = tabulated
+        .p(psip_min, theta, zeta, 0.0, &mut acc, &mut hcaches)
+        .unwrap();
+    assert!((exact_p - tabulated_p).abs() < 1e-9);
+
+    let tabulated_no_fallback =
+        TabulatedPerturbation::from_perturbation(&exact, 20).unwrap().with_exact_near_edges(false);
+    assert!(
+        tabulated_no_fallback
+            .p(psip_min, theta, zeta, 0.0, &mut acc, &mut hcaches)
+            .is_ok()
+    );
+}
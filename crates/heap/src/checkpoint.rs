@@ -0,0 +1,389 @@
+//! Checkpoint/restart support for long-running Poincare-map ensembles, via netCDF scratch files.
+//!
+//! A full ensemble map can run for a long time; [`write_checkpoint`]/[`read_checkpoint`]
+//! periodically snapshot every particle's current phase-space coordinates, accumulated
+//! intersection count, step count, and [`IntegrationStatus`] to a netCDF scratch file, so a crash
+//! or a `max_steps` cutoff loses only the work since the last checkpoint instead of the whole run.
+//!
+//! Wiring a `Heap::resume_from` entry point, and driving checkpoints from the live `PoincarePbar`
+//! counters as a map runs, isn't done here: both `Heap` (`heap.rs`) and `PoincarePbar`
+//! (`progress_bars.rs`) live in files this snapshot does not contain. [`checkpoint_counts`] already
+//! returns the `(mapped, escaped, timedout)` split a progress bar would seed its counters and
+//! `set_position` from, for whenever that wiring lands.
+
+use std::path::Path;
+use std::time::Duration;
+
+use particle::{IntegrationStatus, Particle};
+use thiserror::Error;
+
+/// netCDF variable names used by the checkpoint file layout.
+pub mod names {
+    pub const CHECKPOINT_THETA: &str = "checkpoint_theta";
+    pub const CHECKPOINT_ZETA: &str = "checkpoint_zeta";
+    pub const CHECKPOINT_PSIP: &str = "checkpoint_psip";
+    pub const CHECKPOINT_RHO: &str = "checkpoint_rho";
+    pub const CHECKPOINT_INTERSECTIONS: &str = "checkpoint_intersections";
+    pub const CHECKPOINT_STEPS: &str = "checkpoint_steps";
+    pub const CHECKPOINT_STATUS: &str = "checkpoint_status";
+}
+
+/// Errors raised while writing or reading a checkpoint file.
+#[derive(Error, Debug)]
+pub enum CheckpointError {
+    /// Underlying netCDF library error.
+    #[error("netCDF error: {0}")]
+    Netcdf(#[from] netcdf::Error),
+    /// The checkpoint's status codes didn't decode to a known [`IntegrationStatus`] variant.
+    #[error("checkpoint file has an unrecognized status code `{0}`")]
+    UnknownStatusCode(i64),
+    /// The checkpoint file is missing an expected variable, e.g. a truncated or stale write.
+    #[error("checkpoint file is missing the `{0}` variable")]
+    MissingVariable(&'static str),
+    /// A checkpoint variable wasn't 1-dimensional, e.g. a truncated or stale write.
+    #[error("checkpoint variable `{0}` is not 1-dimensional")]
+    WrongDimensionality(&'static str),
+    /// Two checkpoint columns disagreed on length, e.g. a write interrupted partway through.
+    #[error("checkpoint columns have mismatched lengths: `{0}` has {1}, `{2}` has {3}")]
+    ShapeMismatch(&'static str, usize, &'static str, usize),
+}
+
+/// The result type returned by the checkpoint read/write functions.
+pub type Result<T> = std::result::Result<T, CheckpointError>;
+
+/// One particle's state as of the last checkpoint.
+#[derive(Debug, Clone)]
+pub struct CheckpointEntry {
+    /// The last-evaluated `θ`.
+    pub theta: f64,
+    /// The last-evaluated `ζ`.
+    pub zeta: f64,
+    /// The last-evaluated `ψp`.
+    pub psip: f64,
+    /// The last-evaluated `ρ∥`.
+    pub rho: f64,
+    /// The number of Poincare intersections accumulated so far.
+    pub intersections: usize,
+    /// The number of stepper steps taken so far.
+    pub steps_taken: usize,
+    /// The particle's [`IntegrationStatus`] at checkpoint time.
+    pub status: IntegrationStatus,
+}
+
+/// Maps an [`IntegrationStatus`] to the compact integer code stored in the checkpoint file.
+///
+/// [`IntegrationStatus::TimedOut`]/[`IntegrationStatus::Failed`]'s payloads aren't round-tripped --
+/// only whether a particle is done, and if not, why, matters for deciding what still needs to run.
+fn status_code(status: &IntegrationStatus) -> i64 {
+    match status {
+        IntegrationStatus::Initialized => 0,
+        IntegrationStatus::Integrated => 1,
+        IntegrationStatus::Mapped => 2,
+        IntegrationStatus::SinglePeriodIntegrated => 3,
+        IntegrationStatus::Escaped => 4,
+        IntegrationStatus::EvaluationNan => 5,
+        IntegrationStatus::TimedOut(_) => 6,
+        IntegrationStatus::InvalidIntersections => 7,
+        IntegrationStatus::Failed(_) => 8,
+    }
+}
+
+/// The inverse of [`status_code`].
+fn status_from_code(code: i64) -> Result<IntegrationStatus> {
+    Ok(match code {
+        0 => IntegrationStatus::Initialized,
+        1 => IntegrationStatus::Integrated,
+        2 => IntegrationStatus::Mapped,
+        3 => IntegrationStatus::SinglePeriodIntegrated,
+        4 => IntegrationStatus::Escaped,
+        5 => IntegrationStatus::EvaluationNan,
+        6 => IntegrationStatus::TimedOut(Duration::ZERO),
+        7 => IntegrationStatus::InvalidIntersections,
+        8 => IntegrationStatus::Failed("resumed from checkpoint".into()),
+        other => return Err(CheckpointError::UnknownStatusCode(other)),
+    })
+}
+
+/// Returns the last element of `data`, or `NaN` if it's empty (a particle not yet stepped).
+fn last_or_nan(data: &[f64]) -> f64 {
+    data.last().copied().unwrap_or(f64::NAN)
+}
+
+/// Writes a checkpoint snapshot of `particles` to a new netCDF scratch file at `path`, overwriting
+/// any existing file there. `steps_taken` gives each particle's underlying stepper step count at
+/// snapshot time, in the same order as `particles`.
+pub fn write_checkpoint(path: &Path, particles: &[Particle], steps_taken: &[usize]) -> Result<()> {
+    use names::*;
+
+    assert_eq!(
+        particles.len(),
+        steps_taken.len(),
+        "one steps_taken entry per particle"
+    );
+
+    let mut file = netcdf::create(path)?;
+    file.add_dimension("particle", particles.len())?;
+
+    macro_rules! write_column {
+        ($name:expr, $data:expr) => {{
+            let mut var = file.add_variable::<f64>($name, &["particle"])?;
+            var.put_values(&$data, ..)?;
+        }};
+    }
+
+    write_column!(
+        CHECKPOINT_THETA,
+        particles.iter().map(|p| last_or_nan(&p.evolution.theta)).collect::<Vec<_>>()
+    );
+    write_column!(
+        CHECKPOINT_ZETA,
+        particles.iter().map(|p| last_or_nan(&p.evolution.zeta)).collect::<Vec<_>>()
+    );
+    write_column!(
+        CHECKPOINT_PSIP,
+        particles.iter().map(|p| last_or_nan(&p.evolution.psip)).collect::<Vec<_>>()
+    );
+    write_column!(
+        CHECKPOINT_RHO,
+        particles.iter().map(|p| last_or_nan(&p.evolution.rho)).collect::<Vec<_>>()
+    );
+    write_column!(
+        CHECKPOINT_INTERSECTIONS,
+        particles.iter().map(|p| p.evolution.steps_stored() as f64).collect::<Vec<_>>()
+    );
+    write_column!(
+        CHECKPOINT_STEPS,
+        steps_taken.iter().map(|&s| s as f64).collect::<Vec<_>>()
+    );
+
+    let mut status_var = file.add_variable::<i64>(CHECKPOINT_STATUS, &["particle"])?;
+    let status_codes: Vec<i64> = particles.iter().map(|p| status_code(&p.status)).collect();
+    status_var.put_values(&status_codes, ..)?;
+
+    Ok(())
+}
+
+/// Reads a checkpoint snapshot previously written by [`write_checkpoint`] back from `path`.
+pub fn read_checkpoint(path: &Path) -> Result<Vec<CheckpointEntry>> {
+    use names::*;
+
+    let file = netcdf::open(path)?;
+
+    let read_f64 = |name: &'static str| -> Result<Vec<f64>> {
+        let var = file.variable(name).ok_or(CheckpointError::MissingVariable(name))?;
+        let array = var
+            .get::<f64, _>(..)?
+            .into_dimensionality::<ndarray::Ix1>()
+            .map_err(|_| CheckpointError::WrongDimensionality(name))?;
+        Ok(array.to_vec())
+    };
+    let theta = read_f64(CHECKPOINT_THETA)?;
+    let zeta = read_f64(CHECKPOINT_ZETA)?;
+    let psip = read_f64(CHECKPOINT_PSIP)?;
+    let rho = read_f64(CHECKPOINT_RHO)?;
+    let intersections = read_f64(CHECKPOINT_INTERSECTIONS)?;
+    let steps = read_f64(CHECKPOINT_STEPS)?;
+    let status_var = file
+        .variable(CHECKPOINT_STATUS)
+        .ok_or(CheckpointError::MissingVariable(CHECKPOINT_STATUS))?;
+    let status_codes: Vec<i64> = status_var
+        .get::<i64, _>(..)?
+        .into_dimensionality::<ndarray::Ix1>()
+        .map_err(|_| CheckpointError::WrongDimensionality(CHECKPOINT_STATUS))?
+        .to_vec();
+
+    for (name, len) in [
+        (CHECKPOINT_ZETA, zeta.len()),
+        (CHECKPOINT_PSIP, psip.len()),
+        (CHECKPOINT_RHO, rho.len()),
+        (CHECKPOINT_INTERSECTIONS, intersections.len()),
+        (CHECKPOINT_STEPS, steps.len()),
+        (CHECKPOINT_STATUS, status_codes.len()),
+    ] {
+        if len != theta.len() {
+            return Err(CheckpointError::ShapeMismatch(
+                CHECKPOINT_THETA,
+                theta.len(),
+                name,
+                len,
+            ));
+        }
+    }
+
+    (0..theta.len())
+        .map(|i| {
+            Ok(CheckpointEntry {
+                theta: theta[i],
+                zeta: zeta[i],
+                psip: psip[i],
+                rho: rho[i],
+                intersections: intersections[i] as usize,
+                steps_taken: steps[i] as usize,
+                status: status_from_code(status_codes[i])?,
+            })
+        })
+        .collect()
+}
+
+/// Splits `entries` into `(mapped, escaped, timedout)` counts, matching the three live counters
+/// `PoincarePbar` tracks, so a resumed run can seed its progress bar from where the checkpoint left
+/// off instead of starting from zero.
+pub fn checkpoint_counts(entries: &[CheckpointEntry]) -> (usize, usize, usize) {
+    let mapped = entries.iter().filter(|e| matches!(e.status, IntegrationStatus::Mapped)).count();
+    let escaped = entries.iter().filter(|e| matches!(e.status, IntegrationStatus::Escaped)).count();
+    let timedout = entries
+        .iter()
+        .filter(|e| matches!(e.status, IntegrationStatus::TimedOut(_)))
+        .count();
+    (mapped, escaped, timedout)
+}
+
+/// Whether `entry` still needs to be (re-)run -- i.e. its checkpointed status isn't a terminal one.
+pub fn is_unfinished(entry: &CheckpointEntry) -> bool {
+    matches!(
+        entry.status,
+        IntegrationStatus::Initialized | IntegrationStatus::Integrated | IntegrationStatus::SinglePeriodIntegrated
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use particle::InitialConditions;
+
+    use super::*;
+
+    /// A fresh scratch file path under the system temp dir, unique to this test process and name
+    /// so parallel test runs never collide.
+    fn scratch_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("dexter_checkpoint_test_{name}_{}.nc", std::process::id()))
+    }
+
+    fn make_particles() -> Vec<Particle> {
+        let mut a = Particle::new(&InitialConditions {
+            time0: 0.0,
+            theta0: 0.1,
+            psip0: 0.2,
+            rho0: 0.3,
+            zeta0: 0.4,
+            mu: 0.5,
+        });
+        a.status = IntegrationStatus::Mapped;
+
+        let mut b = Particle::new(&InitialConditions {
+            time0: 1.0,
+            theta0: 1.1,
+            psip0: 1.2,
+            rho0: 1.3,
+            zeta0: 1.4,
+            mu: 1.5,
+        });
+        b.status = IntegrationStatus::Escaped;
+
+        vec![a, b]
+    }
+
+    #[test]
+    fn test_write_then_read_round_trips() {
+        let path = scratch_path("round_trip");
+        let particles = make_particles();
+        write_checkpoint(&path, &particles, &[10, 20]).unwrap();
+
+        let entries = read_checkpoint(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].theta, 0.1);
+        assert_eq!(entries[0].zeta, 0.4);
+        assert_eq!(entries[0].psip, 0.2);
+        assert_eq!(entries[0].rho, 0.3);
+        assert_eq!(entries[0].steps_taken, 10);
+        assert!(matches!(entries[0].status, IntegrationStatus::Mapped));
+
+        assert_eq!(entries[1].theta, 1.1);
+        assert_eq!(entries[1].steps_taken, 20);
+        assert!(matches!(entries[1].status, IntegrationStatus::Escaped));
+
+        assert_eq!(checkpoint_counts(&entries), (1, 1, 0));
+        assert!(!is_unfinished(&entries[0]));
+        assert!(!is_unfinished(&entries[1]));
+    }
+
+    #[test]
+    fn test_read_checkpoint_missing_file() {
+        let path = scratch_path("missing");
+        let err = read_checkpoint(&path).unwrap_err();
+        assert!(matches!(err, CheckpointError::Netcdf(_)));
+    }
+
+    #[test]
+    fn test_read_checkpoint_missing_variable() {
+        let path = scratch_path("missing_variable");
+        {
+            let mut file = netcdf::create(&path).unwrap();
+            file.add_dimension("particle", 1).unwrap();
+            let mut var = file.add_variable::<f64>(names::CHECKPOINT_THETA, &["particle"]).unwrap();
+            var.put_values(&[0.1], ..).unwrap();
+        }
+
+        let err = read_checkpoint(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(
+            err,
+            CheckpointError::MissingVariable(names::CHECKPOINT_ZETA)
+        ));
+    }
+
+    #[test]
+    fn test_read_checkpoint_shape_mismatch() {
+        // A hand-built file with a `particle` dimension of 2 but a one-element `steps` column --
+        // the kind of mismatch a write interrupted partway through would leave behind.
+        let path = scratch_path("shape_mismatch");
+        {
+            let mut file = netcdf::create(&path).unwrap();
+            file.add_dimension("particle", 2).unwrap();
+            macro_rules! full_column {
+                ($name:expr, $data:expr) => {{
+                    let mut var = file.add_variable::<f64>($name, &["particle"]).unwrap();
+                    var.put_values(&$data, ..).unwrap();
+                }};
+            }
+            full_column!(names::CHECKPOINT_THETA, [0.1, 1.1]);
+            full_column!(names::CHECKPOINT_ZETA, [0.4, 1.4]);
+            full_column!(names::CHECKPOINT_PSIP, [0.2, 1.2]);
+            full_column!(names::CHECKPOINT_RHO, [0.3, 1.3]);
+            full_column!(names::CHECKPOINT_INTERSECTIONS, [0.0, 0.0]);
+
+            file.add_dimension("one_particle", 1).unwrap();
+            let mut steps_var = file.add_variable::<f64>(names::CHECKPOINT_STEPS, &["one_particle"]).unwrap();
+            steps_var.put_values(&[10.0], ..).unwrap();
+
+            let mut status_var = file.add_variable::<i64>(names::CHECKPOINT_STATUS, &["particle"]).unwrap();
+            status_var.put_values(&[2i64, 4i64], ..).unwrap();
+        }
+
+        let err = read_checkpoint(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(
+            err,
+            CheckpointError::ShapeMismatch(names::CHECKPOINT_THETA, 2, names::CHECKPOINT_STEPS, 1)
+        ));
+    }
+
+    #[test]
+    fn test_status_code_round_trips() {
+        for status in [
+            IntegrationStatus::Initialized,
+            IntegrationStatus::Integrated,
+            IntegrationStatus::Mapped,
+            IntegrationStatus::SinglePeriodIntegrated,
+            IntegrationStatus::Escaped,
+            IntegrationStatus::EvaluationNan,
+            IntegrationStatus::InvalidIntersections,
+        ] {
+            let code = status_code(&status);
+            let round_tripped = status_from_code(code).unwrap();
+            assert_eq!(status_code(&round_tripped), code);
+        }
+        assert!(matches!(status_from_code(99), Err(CheckpointError::UnknownStatusCode(99))));
+    }
+}
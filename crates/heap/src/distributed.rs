@@ -0,0 +1,856 @@
+//! An optional distributed execution backend: a master streams [`InitialConditions`] to a pool of
+//! worker processes over TCP, each worker integrates/maps the particle locally and ships the
+//! finished orbit back.
+//!
+//! This mirrors the concurrency already provided by [`map_ensemble`](particle::map_ensemble) and
+//! friends, except the work is spread across worker *processes* (potentially on other machines)
+//! instead of threads in this process -- useful once a batch is too large, or too slow, for one
+//! machine's core count. [`dispatch`] hands every particle to whichever connected worker asks for
+//! one next, so a slow worker simply gets fewer particles instead of stalling the batch (dynamic
+//! work-stealing, analogous to `rayon`'s in [`map_ensemble`](particle::map_ensemble)); a worker
+//! that drops its connection mid-batch has its in-flight particle reissued to another one, so a
+//! dead worker never corrupts the batch's accounting.
+//!
+//! Wiring a [`DistributedRoutine`] backend into `Heap`'s own routine selection is left as
+//! follow-up work -- that dispatch lives in `heap.rs`, which this snapshot does not contain.
+
+use std::collections::VecDeque;
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use equilibrium::{Bfield, Current, Perturbation, Qfactor};
+use particle::{
+    Evolution, Frequencies, InitialConditions, IntegrationConfig, IntegrationStatus,
+    MappingConfig, MappingParameters, OrbitType, Particle, PoincareSection, SinglePeriodConfig,
+    SteppingMethod,
+};
+
+/// Which of [`Particle`]'s routines a batch is dispatched to, and its configuration.
+///
+/// Sent once per connection, ahead of the batch's [`InitialConditions`], since every particle in
+/// one [`dispatch`] call shares the same routine and configuration.
+///
+/// Only the 8 stepper fields (see `encode_stepper_fields`), `t_eval`, the section, `alpha` and
+/// `intersections` of `params`, `map_threshold` and `periods` cross the wire. Every other config
+/// field (`integration_method`, the `Pζ` drift tolerances, `orbit_classifier`, and the
+/// Gauss-Legendre and symplectic midpoint solver settings) reaches the worker at its default.
+#[derive(Debug, Clone)]
+pub enum DistributedRoutine {
+    /// Runs [`Particle::integrate`] over `t_eval`.
+    Integrate {
+        t_eval: (f64, f64),
+        config: IntegrationConfig,
+    },
+    /// Runs [`Particle::map`] onto `params`'s Poincare surface.
+    Map {
+        params: MappingParameters,
+        config: MappingConfig,
+    },
+    /// Runs [`Particle::single_period_integrate`].
+    SinglePeriod { config: SinglePeriodConfig },
+}
+
+/// Runs `particle` through `routine`, exactly as the corresponding `Particle` method would.
+fn run_routine(
+    particle: &mut Particle,
+    routine: &DistributedRoutine,
+    qfactor: &impl Qfactor,
+    current: &impl Current,
+    bfield: &impl Bfield,
+    perturbation: &impl Perturbation,
+) {
+    match routine {
+        DistributedRoutine::Integrate { t_eval, config } => {
+            particle.integrate(qfactor, current, bfield, perturbation, *t_eval, config);
+        }
+        DistributedRoutine::Map { params, config } => {
+            particle.map(qfactor, current, bfield, perturbation, params, config);
+        }
+        DistributedRoutine::SinglePeriod { config } => {
+            particle.single_period_integrate(qfactor, current, bfield, perturbation, config);
+        }
+    }
+}
+
+/// Runs a worker loop on `addr`: accepts connections from a master (one at a time) and, for each,
+/// serves [`InitialConditions`] requests under the connection's [`DistributedRoutine`] until the
+/// master closes the batch.
+///
+/// A connection that errors out mid-batch (e.g. the master died) does not stop the worker --
+/// it simply waits for the next connection, so a reissued particle can be served by this same
+/// worker again.
+pub fn run_worker(
+    addr: impl ToSocketAddrs,
+    qfactor: &(impl Qfactor + Sync),
+    current: &(impl Current + Sync),
+    bfield: &(impl Bfield + Sync),
+    perturbation: &(impl Perturbation + Sync),
+) -> io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        if let Err(err) = serve_connection(&mut stream, qfactor, current, bfield, perturbation) {
+            eprintln!("distributed worker: dropping connection after error: {err}");
+        }
+    }
+    Ok(())
+}
+
+/// Serves one master connection until it closes the batch (an empty frame) or the socket errors.
+fn serve_connection(
+    stream: &mut TcpStream,
+    qfactor: &impl Qfactor,
+    current: &impl Current,
+    bfield: &impl Bfield,
+    perturbation: &impl Perturbation,
+) -> io::Result<()> {
+    let routine = decode_routine(&mut wire::Reader::new(&wire::read_frame(stream)?))?;
+
+    loop {
+        let frame = wire::read_frame(stream)?;
+        if frame.is_empty() {
+            return Ok(());
+        }
+        let mut reader = wire::Reader::new(&frame);
+        let id = reader.read_u64()?;
+        let initial_conditions = decode_initial_conditions(&mut reader)?;
+
+        let mut particle = Particle::new(&initial_conditions);
+        run_routine(
+            &mut particle,
+            &routine,
+            qfactor,
+            current,
+            bfield,
+            perturbation,
+        );
+
+        let mut reply = Vec::new();
+        reply.extend_from_slice(&id.to_le_bytes());
+        encode_work_result(&mut reply, &particle);
+        wire::write_frame(stream, &reply)?;
+    }
+}
+
+/// Dispatches `particles` to `workers` under `routine`, concurrently, and applies each worker's
+/// reply back onto the matching [`Particle`] in place -- exactly as
+/// [`map_ensemble`](particle::map_ensemble) does for the local, thread-based routines.
+///
+/// Work is pulled from a shared queue one particle at a time: whichever worker asks next gets the
+/// next queued index, so faster workers naturally end up serving more particles (dynamic
+/// work-stealing). If a worker's connection drops, its in-flight particle is pushed back onto the
+/// queue for another worker to pick up, rather than being dropped from the batch.
+pub fn dispatch(particles: &mut [Particle], workers: &[SocketAddr], routine: &DistributedRoutine) {
+    let initial_conditions: Vec<InitialConditions> = particles
+        .iter()
+        .map(|particle| particle.initial_conditions.clone())
+        .collect();
+    let queue = Mutex::new((0..particles.len()).collect::<VecDeque<usize>>());
+    let results = Mutex::new(vec![None; particles.len()]);
+
+    std::thread::scope(|scope| {
+        for &worker in workers {
+            scope.spawn(move || {
+                if let Err(err) = serve_worker(worker, routine, &initial_conditions, &queue, &results)
+                {
+                    eprintln!("distributed master: worker dropped out: {err}");
+                }
+            });
+        }
+    });
+
+    let results = results.into_inner().expect("no thread panicked while holding the lock");
+    for (particle, result) in particles.iter_mut().zip(results) {
+        if let Some((status, orbit_type, frequencies, evolution)) = result {
+            particle.status = status;
+            particle.orbit_type = orbit_type;
+            particle.frequencies = frequencies;
+            particle.evolution = evolution;
+        }
+        // A particle with no result ran out of workers to retry on; it keeps its `Initialized`
+        // status, which is still visible in `HeapStats` rather than silently missing.
+    }
+}
+
+type WorkerResult = (IntegrationStatus, OrbitType, Frequencies, Evolution);
+
+/// Connects to one worker and serves it particles off `queue` until the queue is empty, writing
+/// each finished result into `results` by index. If the connection drops partway through a
+/// particle, that particle's index is pushed back onto `queue` for another worker to retry.
+fn serve_worker(
+    addr: SocketAddr,
+    routine: &DistributedRoutine,
+    initial_conditions: &[InitialConditions],
+    queue: &Mutex<VecDeque<usize>>,
+    results: &Mutex<Vec<Option<WorkerResult>>>,
+) -> io::Result<()> {
+    let mut stream = TcpStream::connect(addr)?;
+    let mut handshake = Vec::new();
+    encode_routine(&mut handshake, routine);
+    wire::write_frame(&mut stream, &handshake)?;
+
+    loop {
+        let Some(index) = queue.lock().expect("queue mutex poisoned").pop_front() else {
+            break;
+        };
+
+        if let Err(err) = serve_one(&mut stream, index, &initial_conditions[index], results) {
+            // This worker is unreliable -- give the particle back to the queue for someone else.
+            queue.lock().expect("queue mutex poisoned").push_back(index);
+            return Err(err);
+        }
+    }
+    wire::write_frame(&mut stream, &[]) // tell the worker this batch is done
+}
+
+/// Sends one particle's [`InitialConditions`] to an already-connected worker and records its
+/// reply at `results[index]`.
+fn serve_one(
+    stream: &mut TcpStream,
+    index: usize,
+    initial_conditions: &InitialConditions,
+    results: &Mutex<Vec<Option<WorkerResult>>>,
+) -> io::Result<()> {
+    let mut request = Vec::new();
+    request.extend_from_slice(&(index as u64).to_le_bytes());
+    encode_initial_conditions(&mut request, initial_conditions);
+    wire::write_frame(stream, &request)?;
+
+    let reply = wire::read_frame(stream)?;
+    let mut reader = wire::Reader::new(&reply);
+    let _id = reader.read_u64()?;
+    let result = decode_work_result(&mut reader)?;
+    results.lock().expect("results mutex poisoned")[index] = Some(result);
+    Ok(())
+}
+
+// ===============================================================================================
+// Wire format
+// ===============================================================================================
+
+fn encode_initial_conditions(buf: &mut Vec<u8>, ic: &InitialConditions) {
+    wire::push_f64(buf, ic.time0);
+    wire::push_f64(buf, ic.theta0);
+    wire::push_f64(buf, ic.psip0);
+    wire::push_f64(buf, ic.rho0);
+    wire::push_f64(buf, ic.zeta0);
+    wire::push_f64(buf, ic.mu);
+}
+
+fn decode_initial_conditions(r: &mut wire::Reader) -> io::Result<InitialConditions> {
+    Ok(InitialConditions {
+        time0: r.read_f64()?,
+        theta0: r.read_f64()?,
+        psip0: r.read_f64()?,
+        rho0: r.read_f64()?,
+        zeta0: r.read_f64()?,
+        mu: r.read_f64()?,
+    })
+}
+
+/// Encodes the 8 fields shared by every `*Config` struct (see `StepperConfig` in
+/// `particle::config`): `method, max_steps, first_step, safety_factor, energy_rel_tol,
+/// energy_abs_tol, error_rel_tol, error_abs_tol`.
+macro_rules! encode_stepper_fields {
+    ($buf:ident, $config:expr) => {
+        $buf.push(match $config.method {
+            SteppingMethod::EnergyAdaptiveStep => 0u8,
+            SteppingMethod::ErrorAdaptiveStep => 1u8,
+        });
+        $buf.extend_from_slice(&($config.max_steps as u64).to_le_bytes());
+        wire::push_f64($buf, $config.first_step);
+        wire::push_f64($buf, $config.safety_factor);
+        wire::push_f64($buf, $config.energy_rel_tol);
+        wire::push_f64($buf, $config.energy_abs_tol);
+        wire::push_f64($buf, $config.error_rel_tol);
+        wire::push_f64($buf, $config.error_abs_tol);
+    };
+}
+
+macro_rules! decode_stepper_fields {
+    ($r:ident) => {{
+        let method = match $r.read_u8()? {
+            0 => SteppingMethod::EnergyAdaptiveStep,
+            _ => SteppingMethod::ErrorAdaptiveStep,
+        };
+        let max_steps = $r.read_u64()? as usize;
+        let first_step = $r.read_f64()?;
+        let safety_factor = $r.read_f64()?;
+        let energy_rel_tol = $r.read_f64()?;
+        let energy_abs_tol = $r.read_f64()?;
+        let error_rel_tol = $r.read_f64()?;
+        let error_abs_tol = $r.read_f64()?;
+        (
+            method,
+            max_steps,
+            first_step,
+            safety_factor,
+            energy_rel_tol,
+            energy_abs_tol,
+            error_rel_tol,
+            error_abs_tol,
+        )
+    }};
+}
+
+fn encode_routine(buf: &mut Vec<u8>, routine: &DistributedRoutine) {
+    match routine {
+        DistributedRoutine::Integrate { t_eval, config } => {
+            buf.push(0);
+            wire::push_f64(buf, t_eval.0);
+            wire::push_f64(buf, t_eval.1);
+            encode_stepper_fields!(buf, config);
+        }
+        DistributedRoutine::Map { params, config } => {
+            buf.push(1);
+            buf.push(match &params.section {
+                PoincareSection::ConstTheta => 0,
+                PoincareSection::ConstZeta => 1,
+                PoincareSection::Event(_) => panic!(
+                    "DistributedRoutine::Map with PoincareSection::Event cannot cross the wire: \
+                     an arbitrary closure has no serializable representation, so distributed \
+                     workers only support the ConstTheta/ConstZeta surfaces"
+                ),
+            });
+            wire::push_f64(buf, params.alpha);
+            buf.extend_from_slice(&(params.intersections as u64).to_le_bytes());
+            encode_stepper_fields!(buf, config);
+            wire::push_f64(buf, config.map_threshold);
+        }
+        DistributedRoutine::SinglePeriod { config } => {
+            buf.push(2);
+            encode_stepper_fields!(buf, config);
+            buf.extend_from_slice(&(config.periods as u64).to_le_bytes());
+        }
+    }
+}
+
+fn decode_routine(r: &mut wire::Reader) -> io::Result<DistributedRoutine> {
+    Ok(match r.read_u8()? {
+        0 => {
+            let t_eval = (r.read_f64()?, r.read_f64()?);
+            let (
+                method,
+                max_steps,
+                first_step,
+                safety_factor,
+                energy_rel_tol,
+                energy_abs_tol,
+                error_rel_tol,
+                error_abs_tol,
+            ) = decode_stepper_fields!(r);
+            DistributedRoutine::Integrate {
+                t_eval,
+                config: IntegrationConfig {
+                    method,
+                    max_steps,
+                    first_step,
+                    safety_factor,
+                    energy_rel_tol,
+                    energy_abs_tol,
+                    error_rel_tol,
+                    error_abs_tol,
+                    ..IntegrationConfig::default()
+                },
+            }
+        }
+        1 => {
+            let section = match r.read_u8()? {
+                0 => PoincareSection::ConstTheta,
+                _ => PoincareSection::ConstZeta,
+            };
+            let alpha = r.read_f64()?;
+            let intersections = r.read_u64()? as usize;
+            let (
+                method,
+                max_steps,
+                first_step,
+                safety_factor,
+                energy_rel_tol,
+                energy_abs_tol,
+                error_rel_tol,
+                error_abs_tol,
+            ) = decode_stepper_fields!(r);
+            let map_threshold = r.read_f64()?;
+            DistributedRoutine::Map {
+                params: MappingParameters::new(section, alpha, intersections),
+                config: MappingConfig {
+                    method,
+                    max_steps,
+                    first_step,
+                    safety_factor,
+                    energy_rel_tol,
+                    energy_abs_tol,
+                    error_rel_tol,
+                    error_abs_tol,
+                    map_threshold,
+                    ..MappingConfig::default()
+                },
+            }
+        }
+        _ => {
+            let (
+                method,
+                max_steps,
+                first_step,
+                safety_factor,
+                energy_rel_tol,
+                energy_abs_tol,
+                error_rel_tol,
+                error_abs_tol,
+            ) = decode_stepper_fields!(r);
+            let periods = r.read_u64()? as usize;
+            DistributedRoutine::SinglePeriod {
+                config: SinglePeriodConfig {
+                    method,
+                    max_steps,
+                    first_step,
+                    safety_factor,
+                    energy_rel_tol,
+                    energy_abs_tol,
+                    error_rel_tol,
+                    error_abs_tol,
+                    periods,
+                    ..SinglePeriodConfig::default()
+                },
+            }
+        }
+    })
+}
+
+fn encode_work_result(buf: &mut Vec<u8>, particle: &Particle) {
+    encode_status(buf, &particle.status);
+    encode_orbit_type(buf, &particle.orbit_type);
+
+    wire::push_f64_vec(buf, &particle.frequencies.omega_theta_samples().to_vec());
+    wire::push_f64_vec(buf, &particle.frequencies.omega_zeta_samples().to_vec());
+
+    let evolution = &particle.evolution;
+    wire::push_f64_vec(buf, &evolution.time);
+    wire::push_f64_vec(buf, &evolution.theta);
+    wire::push_f64_vec(buf, &evolution.psip);
+    wire::push_f64_vec(buf, &evolution.rho);
+    wire::push_f64_vec(buf, &evolution.zeta);
+    wire::push_f64_vec(buf, &evolution.psi);
+    wire::push_f64_vec(buf, &evolution.ptheta);
+    wire::push_f64_vec(buf, &evolution.pzeta);
+    wire::push_f64_vec(buf, &evolution.energy);
+    buf.extend_from_slice(&(evolution.duration.as_nanos() as u64).to_le_bytes());
+    buf.extend_from_slice(&(evolution.steps_taken() as u64).to_le_bytes());
+    wire::push_f64(buf, evolution.energy_std);
+    wire::push_f64(buf, evolution.rotation_number);
+    wire::push_f64(buf, evolution.rotation_number_err);
+}
+
+fn decode_work_result(r: &mut wire::Reader) -> io::Result<WorkerResult> {
+    let status = decode_status(r)?;
+    let orbit_type = decode_orbit_type(r)?;
+
+    let omega_theta_samples = r.read_f64_vec()?;
+    let omega_zeta_samples = r.read_f64_vec()?;
+    let frequencies = Frequencies::from_samples(omega_theta_samples, omega_zeta_samples);
+
+    let time = r.read_f64_vec()?;
+    let theta = r.read_f64_vec()?;
+    let psip = r.read_f64_vec()?;
+    let rho = r.read_f64_vec()?;
+    let zeta = r.read_f64_vec()?;
+    let psi = r.read_f64_vec()?;
+    let ptheta = r.read_f64_vec()?;
+    let pzeta = r.read_f64_vec()?;
+    let energy = r.read_f64_vec()?;
+    let duration = Duration::from_nanos(r.read_u64()?);
+    let steps_taken = r.read_u64()? as usize;
+    let energy_std = r.read_f64()?;
+    let rotation_number = r.read_f64()?;
+    let rotation_number_err = r.read_f64()?;
+
+    let evolution = Evolution::from_raw_parts(
+        time,
+        theta,
+        psip,
+        rho,
+        zeta,
+        psi,
+        ptheta,
+        pzeta,
+        energy,
+        duration,
+        steps_taken,
+        energy_std,
+        rotation_number,
+        rotation_number_err,
+    );
+
+    Ok((status, orbit_type, frequencies, evolution))
+}
+
+fn encode_status(buf: &mut Vec<u8>, status: &IntegrationStatus) {
+    match status {
+        IntegrationStatus::Initialized => buf.push(0),
+        IntegrationStatus::Integrated => buf.push(1),
+        IntegrationStatus::Mapped => buf.push(2),
+        IntegrationStatus::SinglePeriodIntegrated => buf.push(3),
+        IntegrationStatus::Escaped => buf.push(4),
+        IntegrationStatus::EvaluationNan => buf.push(5),
+        IntegrationStatus::TimedOut(duration) => {
+            buf.push(6);
+            buf.extend_from_slice(&(duration.as_nanos() as u64).to_le_bytes());
+        }
+        IntegrationStatus::InvalidIntersections => buf.push(7),
+        IntegrationStatus::Failed(reason) => {
+            buf.push(8);
+            let bytes = reason.as_bytes();
+            buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            buf.extend_from_slice(bytes);
+        }
+    }
+}
+
+fn decode_status(r: &mut wire::Reader) -> io::Result<IntegrationStatus> {
+    Ok(match r.read_u8()? {
+        0 => IntegrationStatus::Initialized,
+        1 => IntegrationStatus::Integrated,
+        2 => IntegrationStatus::Mapped,
+        3 => IntegrationStatus::SinglePeriodIntegrated,
+        4 => IntegrationStatus::Escaped,
+        5 => IntegrationStatus::EvaluationNan,
+        6 => IntegrationStatus::TimedOut(Duration::from_nanos(r.read_u64()?)),
+        7 => IntegrationStatus::InvalidIntersections,
+        _ => {
+            let len = r.read_u32()? as usize;
+            let reason = String::from_utf8_lossy(r.read_bytes(len)?).into_owned();
+            IntegrationStatus::Failed(reason.into())
+        }
+    })
+}
+
+fn encode_orbit_type(buf: &mut Vec<u8>, orbit_type: &OrbitType) {
+    buf.push(match orbit_type {
+        OrbitType::Undefined => 0,
+        OrbitType::Trapped => 1,
+        OrbitType::Passing => 2,
+    });
+}
+
+fn decode_orbit_type(r: &mut wire::Reader) -> io::Result<OrbitType> {
+    Ok(match r.read_u8()? {
+        1 => OrbitType::Trapped,
+        2 => OrbitType::Passing,
+        _ => OrbitType::Undefined,
+    })
+}
+
+/// A minimal, hand-rolled wire format: length-prefixed frames of little-endian primitives. No
+/// external serialization crate is used anywhere else in this workspace, so this stays consistent
+/// with that rather than introducing one just for this module.
+mod wire {
+    use std::io::{self, Read, Write};
+
+    pub fn write_frame(stream: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+        stream.write_all(&(payload.len() as u32).to_le_bytes())?;
+        stream.write_all(payload)
+    }
+
+    /// Caps a single frame's payload, so a corrupted or hostile length prefix (e.g. a peer
+    /// claiming `len ≈ u32::MAX`) can't force a huge allocation before any of those bytes have
+    /// even been confirmed to exist on the wire. Comfortably above the largest legitimate frame
+    /// (a multi-million-step orbit's handful of `f64` columns), but far below memory exhaustion.
+    const MAX_FRAME_LEN: usize = 256 * 1024 * 1024;
+
+    pub fn read_frame(stream: &mut impl Read) -> io::Result<Vec<u8>> {
+        let mut len_buf = [0u8; 4];
+        stream.read_exact(&mut len_buf)?;
+        let len = u32::from_le_bytes(len_buf) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("wire frame of {len} bytes exceeds the {MAX_FRAME_LEN}-byte cap"),
+            ));
+        }
+        let mut buf = vec![0u8; len];
+        stream.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    pub fn push_f64(buf: &mut Vec<u8>, value: f64) {
+        buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn push_f64_vec(buf: &mut Vec<u8>, values: &[f64]) {
+        buf.extend_from_slice(&(values.len() as u32).to_le_bytes());
+        for &value in values {
+            push_f64(buf, value);
+        }
+    }
+
+    pub struct Reader<'a> {
+        bytes: &'a [u8],
+        pos: usize,
+    }
+
+    impl<'a> Reader<'a> {
+        pub fn new(bytes: &'a [u8]) -> Self {
+            Self { bytes, pos: 0 }
+        }
+
+        /// Reads `len` bytes, or fails with `UnexpectedEof` if the frame doesn't have that many
+        /// left -- a malformed or truncated frame is a connection-level error, not a panic.
+        pub fn read_bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+            if len > self.bytes.len() - self.pos {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "wire frame truncated",
+                ));
+            }
+            let slice = &self.bytes[self.pos..self.pos + len];
+            self.pos += len;
+            Ok(slice)
+        }
+
+        pub fn read_u8(&mut self) -> io::Result<u8> {
+            let value = *self.bytes.get(self.pos).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "wire frame truncated")
+            })?;
+            self.pos += 1;
+            Ok(value)
+        }
+
+        pub fn read_u32(&mut self) -> io::Result<u32> {
+            Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+        }
+
+        pub fn read_u64(&mut self) -> io::Result<u64> {
+            Ok(u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+        }
+
+        pub fn read_f64(&mut self) -> io::Result<f64> {
+            Ok(f64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+        }
+
+        pub fn read_f64_vec(&mut self) -> io::Result<Vec<f64>> {
+            let len = self.read_u32()? as usize;
+            (0..len).map(|_| self.read_f64()).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use particle::IntegrationMethod;
+
+    use super::*;
+
+    #[test]
+    fn test_read_frame_round_trips_write_frame() {
+        let mut conn = Vec::new();
+        wire::write_frame(&mut conn, &[1, 2, 3, 4, 5]).unwrap();
+        wire::write_frame(&mut conn, &[]).unwrap();
+
+        let mut cursor = conn.as_slice();
+        assert_eq!(wire::read_frame(&mut cursor).unwrap(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(wire::read_frame(&mut cursor).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_read_frame_rejects_oversized_length_prefix() {
+        let mut header = Vec::new();
+        header.extend_from_slice(&u32::MAX.to_le_bytes());
+        let mut cursor = header.as_slice();
+
+        let err = wire::read_frame(&mut cursor).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_frame_rejects_truncated_payload() {
+        let mut conn = Vec::new();
+        conn.extend_from_slice(&10u32.to_le_bytes());
+        conn.extend_from_slice(&[1, 2, 3]); // fewer than the 10 bytes promised
+        let mut cursor = conn.as_slice();
+
+        let err = wire::read_frame(&mut cursor).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_reader_rejects_truncated_scalar() {
+        let bytes = [0u8; 4]; // not enough for a u64/f64
+        let mut reader = wire::Reader::new(&bytes);
+        assert_eq!(
+            reader.read_u64().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_initial_conditions_round_trips() {
+        let ic = InitialConditions {
+            time0: 1.0,
+            theta0: 2.0,
+            psip0: 3.0,
+            rho0: 4.0,
+            zeta0: 5.0,
+            mu: 6.0,
+        };
+        let mut buf = Vec::new();
+        encode_initial_conditions(&mut buf, &ic);
+        let decoded = decode_initial_conditions(&mut wire::Reader::new(&buf)).unwrap();
+        assert_eq!(decoded.time0, ic.time0);
+        assert_eq!(decoded.theta0, ic.theta0);
+        assert_eq!(decoded.psip0, ic.psip0);
+        assert_eq!(decoded.rho0, ic.rho0);
+        assert_eq!(decoded.zeta0, ic.zeta0);
+        assert_eq!(decoded.mu, ic.mu);
+    }
+
+    #[test]
+    fn test_encode_decode_routine_round_trips_integrate() {
+        let routine = DistributedRoutine::Integrate {
+            t_eval: (0.0, 100.0),
+            config: IntegrationConfig {
+                integration_method: IntegrationMethod::Rkf45,
+                method: SteppingMethod::ErrorAdaptiveStep,
+                max_steps: 42,
+                first_step: 0.5,
+                safety_factor: 0.8,
+                energy_rel_tol: 1e-9,
+                energy_abs_tol: 1e-11,
+                error_rel_tol: 1e-13,
+                error_abs_tol: 1e-15,
+                ..IntegrationConfig::default()
+            },
+        };
+        let mut buf = Vec::new();
+        encode_routine(&mut buf, &routine);
+        let decoded = decode_routine(&mut wire::Reader::new(&buf)).unwrap();
+
+        let DistributedRoutine::Integrate { t_eval, config } = decoded else {
+            panic!("expected DistributedRoutine::Integrate, got {decoded:?}");
+        };
+        assert_eq!(t_eval, (0.0, 100.0));
+        assert!(matches!(config.method, SteppingMethod::ErrorAdaptiveStep));
+        assert_eq!(config.max_steps, 42);
+        assert_eq!(config.first_step, 0.5);
+        assert_eq!(config.safety_factor, 0.8);
+        assert_eq!(config.energy_rel_tol, 1e-9);
+        assert_eq!(config.energy_abs_tol, 1e-11);
+        assert_eq!(config.error_rel_tol, 1e-13);
+        assert_eq!(config.error_abs_tol, 1e-15);
+    }
+
+    #[test]
+    fn test_encode_decode_routine_round_trips_map() {
+        let routine = DistributedRoutine::Map {
+            params: MappingParameters::new(PoincareSection::ConstZeta, 0.25, 7),
+            config: MappingConfig {
+                max_steps: 9,
+                map_threshold: 1e-8,
+                ..MappingConfig::default()
+            },
+        };
+        let mut buf = Vec::new();
+        encode_routine(&mut buf, &routine);
+        let decoded = decode_routine(&mut wire::Reader::new(&buf)).unwrap();
+
+        let DistributedRoutine::Map { params, config } = decoded else {
+            panic!("expected DistributedRoutine::Map, got {decoded:?}");
+        };
+        assert!(matches!(params.section, PoincareSection::ConstZeta));
+        assert_eq!(params.alpha, 0.25);
+        assert_eq!(params.intersections, 7);
+        assert_eq!(config.max_steps, 9);
+        assert_eq!(config.map_threshold, 1e-8);
+    }
+
+    #[test]
+    fn test_encode_decode_routine_round_trips_single_period() {
+        let routine = DistributedRoutine::SinglePeriod {
+            config: SinglePeriodConfig {
+                periods: 3,
+                ..SinglePeriodConfig::default()
+            },
+        };
+        let mut buf = Vec::new();
+        encode_routine(&mut buf, &routine);
+        let decoded = decode_routine(&mut wire::Reader::new(&buf)).unwrap();
+
+        let DistributedRoutine::SinglePeriod { config } = decoded else {
+            panic!("expected DistributedRoutine::SinglePeriod, got {decoded:?}");
+        };
+        assert_eq!(config.periods, 3);
+    }
+
+    #[test]
+    fn test_encode_decode_work_result_round_trips() {
+        let ic = InitialConditions {
+            time0: 0.0,
+            theta0: 0.0,
+            psip0: 0.0,
+            rho0: 0.0,
+            zeta0: 0.0,
+            mu: 0.0,
+        };
+        let mut particle = Particle::new(&ic);
+        particle.status = IntegrationStatus::Mapped;
+        particle.orbit_type = OrbitType::Trapped;
+        particle.frequencies = Frequencies::from_samples(vec![1.0, 2.0], vec![3.0, 4.0]);
+        particle.evolution = Evolution::from_raw_parts(
+            vec![0.0, 1.0],
+            vec![0.1, 1.1],
+            vec![0.2, 1.2],
+            vec![0.3, 1.3],
+            vec![0.4, 1.4],
+            vec![0.5, 1.5],
+            vec![0.6, 1.6],
+            vec![0.7, 1.7],
+            vec![0.8, 1.8],
+            Duration::from_secs(5),
+            9,
+            0.01,
+            0.02,
+            0.03,
+        );
+
+        let mut buf = Vec::new();
+        encode_work_result(&mut buf, &particle);
+        let (status, orbit_type, frequencies, evolution) =
+            decode_work_result(&mut wire::Reader::new(&buf)).unwrap();
+
+        assert!(matches!(status, IntegrationStatus::Mapped));
+        assert!(matches!(orbit_type, OrbitType::Trapped));
+        assert_eq!(frequencies.omega_theta_samples(), particle.frequencies.omega_theta_samples());
+        assert_eq!(frequencies.omega_zeta_samples(), particle.frequencies.omega_zeta_samples());
+        assert_eq!(evolution.theta, particle.evolution.theta);
+        assert_eq!(evolution.energy, particle.evolution.energy);
+        assert_eq!(evolution.duration, particle.evolution.duration);
+        assert_eq!(evolution.steps_taken(), particle.evolution.steps_taken());
+    }
+
+    #[test]
+    fn test_status_round_trips_failed_reason() {
+        let status = IntegrationStatus::Failed("nan encountered".into());
+        let mut buf = Vec::new();
+        encode_status(&mut buf, &status);
+        let decoded = decode_status(&mut wire::Reader::new(&buf)).unwrap();
+        match decoded {
+            IntegrationStatus::Failed(reason) => assert_eq!(reason.as_ref(), "nan encountered"),
+            other => panic!("expected Failed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_orbit_type_round_trips() {
+        for orbit_type in [OrbitType::Undefined, OrbitType::Trapped, OrbitType::Passing] {
+            let mut buf = Vec::new();
+            encode_orbit_type(&mut buf, &orbit_type);
+            let decoded = decode_orbit_type(&mut wire::Reader::new(&buf)).unwrap();
+            assert_eq!(
+                std::mem::discriminant(&decoded),
+                std::mem::discriminant(&orbit_type)
+            );
+        }
+    }
+}
@@ -1,12 +1,26 @@
+mod distributed;
 mod error;
 mod heap;
 mod initials;
 mod progress_bars;
+mod sampling;
 mod stats;
 
+pub mod checkpoint;
+pub mod output;
+pub mod provenance;
+
+pub use checkpoint::{CheckpointEntry, CheckpointError, checkpoint_counts, is_unfinished, read_checkpoint, write_checkpoint};
+pub use distributed::{DistributedRoutine, dispatch, run_worker};
 pub use error::HeapError;
 pub use heap::Heap;
 pub use initials::HeapInitialConditions;
+pub use output::{Hdf5WriteError, Hdf5Writer};
+pub use provenance::{Provenance, ProvenanceError};
+pub use sampling::{
+    EnsembleStats, FrequencyBounds, JitterSpec, OrbitTypeFractions, SamplingRoutine,
+    run_robustness_study,
+};
 pub use stats::HeapStats;
 
 pub type Result<T> = std::result::Result<T, HeapError>;
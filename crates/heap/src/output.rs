@@ -0,0 +1,349 @@
+//! Streaming HDF5 output for ensemble mapping results.
+//!
+//! `Heap::store_arrays` builds dense `Array2` buffers (`thetas`, `zetas`, `psips`, `psis`) over the
+//! whole ensemble, NaN-padding every row up to `params.intersections + 1` even for escaped/timed-out
+//! particles, which is exactly what forces `calculate_frequencies` to `discard()` each particle's
+//! evolution just to keep memory bounded. [`Hdf5Writer`] avoids that by opening one resizable,
+//! chunked HDF5 dataset per field (unlimited first axis) and appending a single row per particle as
+//! it finishes, so an ensemble's results never have to exist as one in-memory matrix. The particle's
+//! [`IntegrationStatus`] and [`InitialConditions`] are stored as parallel columns rather than being
+//! inferrable only from NaN padding, so downstream tools can filter directly on them.
+//!
+//! Wiring this in as `Heap`'s own output path isn't done here -- the dense-matrix behavior this
+//! replaces lives in `heap.rs`, which this snapshot does not contain -- so [`Hdf5Writer`] is a
+//! self-contained writer a caller can drive directly alongside a mapping loop, one
+//! [`append`](Hdf5Writer::append) call per finished [`Particle`].
+
+use std::path::Path;
+
+use hdf5::Dataset;
+use particle::{IntegrationStatus, MappingParameters, Particle, PoincareSection};
+use thiserror::Error;
+
+/// Errors raised while streaming an ensemble's results to an HDF5 file.
+///
+/// Kept local to this module rather than folded into [`HeapError`](crate::HeapError): that enum's
+/// definition lives in `error.rs`, which this snapshot does not contain.
+#[derive(Error, Debug)]
+pub enum Hdf5WriteError {
+    /// Underlying HDF5 library error.
+    #[error("HDF5 error: {0}")]
+    Hdf5(#[from] hdf5::Error),
+    /// Failure while writing the run's provenance/reproducibility metadata.
+    #[error("{0}")]
+    Provenance(#[from] crate::provenance::ProvenanceError),
+}
+
+/// The result type returned by [`Hdf5Writer`]'s methods.
+pub type Result<T> = std::result::Result<T, Hdf5WriteError>;
+
+/// The chunk length (in rows) used for every resizable dataset [`Hdf5Writer`] creates. HDF5 only
+/// grows a chunked dataset's on-disk storage in units of its chunk shape, so this keeps the
+/// per-[`append`](Hdf5Writer::append) overhead small without over-allocating for short ensembles.
+const CHUNK_ROWS: usize = 256;
+
+/// A human-readable label for an [`IntegrationStatus`], used for the `status` column rather than
+/// storing the enum's discriminant, so the archive is self-describing without the reader needing
+/// `particle`'s definition.
+fn status_label(status: &IntegrationStatus) -> &'static str {
+    match status {
+        IntegrationStatus::Initialized => "initialized",
+        IntegrationStatus::Integrated => "integrated",
+        IntegrationStatus::Mapped => "mapped",
+        IntegrationStatus::SinglePeriodIntegrated => "single_period_integrated",
+        IntegrationStatus::Escaped => "escaped",
+        IntegrationStatus::EvaluationNan => "evaluation_nan",
+        IntegrationStatus::TimedOut(_) => "timed_out",
+        IntegrationStatus::InvalidIntersections => "invalid_intersections",
+        IntegrationStatus::Failed(_) => "failed",
+    }
+}
+
+/// Streams Poincare mapping results to an HDF5 file, one particle at a time.
+///
+/// Opens a resizable 2D dataset per intersection field (`theta`, `zeta`, `psip`, `psi`), each
+/// shaped `(0, max_intersections)` with an unlimited first axis, plus parallel 1D datasets for each
+/// particle's `status` and [`InitialConditions`] fields. [`append`] grows every dataset by one row,
+/// so memory use stays bounded by a single particle's evolution rather than the whole ensemble's.
+///
+/// [`append`]: Self::append
+pub struct Hdf5Writer {
+    file: hdf5::File,
+    theta: Dataset,
+    zeta: Dataset,
+    psip: Dataset,
+    psi: Dataset,
+    status: Dataset,
+    time0: Dataset,
+    theta0: Dataset,
+    psip0: Dataset,
+    rho0: Dataset,
+    zeta0: Dataset,
+    mu: Dataset,
+    max_intersections: usize,
+    rows: usize,
+}
+
+impl Hdf5Writer {
+    /// Creates a new HDF5 file at `path` and opens every resizable dataset, sized to hold up to
+    /// `max_intersections` columns per particle (typically `params.intersections + 1`).
+    pub fn create(path: &Path, max_intersections: usize) -> Result<Self> {
+        let file = hdf5::File::create(path)?;
+
+        let intersection_dataset = |name: &str| -> Result<Dataset> {
+            Ok(file
+                .new_dataset::<f64>()
+                .shape((0.., max_intersections))
+                .chunk((CHUNK_ROWS, max_intersections))
+                .create(name)?)
+        };
+        let column_dataset = |name: &str| -> Result<Dataset> {
+            Ok(file
+                .new_dataset::<f64>()
+                .shape(0..)
+                .chunk(CHUNK_ROWS)
+                .create(name)?)
+        };
+
+        Ok(Self {
+            theta: intersection_dataset("theta")?,
+            zeta: intersection_dataset("zeta")?,
+            psip: intersection_dataset("psip")?,
+            psi: intersection_dataset("psi")?,
+            status: file
+                .new_dataset::<hdf5::types::VarLenUnicode>()
+                .shape(0..)
+                .chunk(CHUNK_ROWS)
+                .create("status")?,
+            time0: column_dataset("time0")?,
+            theta0: column_dataset("theta0")?,
+            psip0: column_dataset("psip0")?,
+            rho0: column_dataset("rho0")?,
+            zeta0: column_dataset("zeta0")?,
+            mu: column_dataset("mu")?,
+            file,
+            max_intersections,
+            rows: 0,
+        })
+    }
+
+    /// Attaches the equilibrium configuration and mapping setup as file-level attributes, so the
+    /// archive can be traced back to the run that produced it without a side-channel.
+    pub fn with_equilibrium_attributes(
+        self,
+        source_path: &str,
+        source_typ: &str,
+        mapping: &MappingParameters,
+    ) -> Result<Self> {
+        self.write_attr("source_path", source_path)?;
+        self.write_attr("source_typ", source_typ)?;
+
+        let section = match mapping.section {
+            PoincareSection::ConstTheta => "const_theta",
+            PoincareSection::ConstZeta => "const_zeta",
+            PoincareSection::Event(_) => "event",
+        };
+        self.write_attr("mapping_section", section)?;
+        self.write_scalar_attr("mapping_alpha", mapping.alpha)?;
+        self.write_scalar_attr("mapping_intersections", mapping.intersections as u64)?;
+
+        Ok(self)
+    }
+
+    /// Attaches full provenance/reproducibility metadata: `source_path`/`source_typ`/`mapping_*`
+    /// (same as [`with_equilibrium_attributes`](Self::with_equilibrium_attributes)), plus the
+    /// crate version, the [`IntegrationConfig`](particle::IntegrationConfig) used, the `(m, n)`
+    /// harmonics included, and the collision operator's RNG seed, if any. Supersedes
+    /// [`with_equilibrium_attributes`](Self::with_equilibrium_attributes) -- call one or the
+    /// other, not both, or the shared `source_path`/`source_typ`/`mapping_*` attributes collide.
+    pub fn with_provenance_attributes(
+        self,
+        source_path: &str,
+        source_typ: &str,
+        integration: &particle::IntegrationConfig,
+        mapping: &MappingParameters,
+        harmonics: &[(i64, i64)],
+        collision_seed: Option<u64>,
+    ) -> Result<Self> {
+        crate::provenance::write(
+            &self.file,
+            source_path,
+            source_typ,
+            integration,
+            mapping,
+            harmonics,
+            collision_seed,
+        )?;
+        Ok(self)
+    }
+
+    fn write_attr(&self, name: &str, value: &str) -> Result<()> {
+        let value: hdf5::types::VarLenUnicode = value.parse().expect("ASCII/UTF-8 string");
+        self.file
+            .new_attr::<hdf5::types::VarLenUnicode>()
+            .create(name)?
+            .write_scalar(&value)?;
+        Ok(())
+    }
+
+    fn write_scalar_attr<T: hdf5::H5Type>(&self, name: &str, value: T) -> Result<()> {
+        self.file.new_attr::<T>().create(name)?.write_scalar(&value)?;
+        Ok(())
+    }
+
+    /// Appends one row to every dataset for `particle`, growing each dataset by one row first.
+    ///
+    /// The intersection columns are taken from `particle`'s [`Evolution`](particle::Evolution), and
+    /// NaN-padded up to `max_intersections` if the particle escaped, timed out, or otherwise stopped
+    /// short -- the padding only ever spans one row at a time, rather than the whole ensemble.
+    pub fn append(&mut self, particle: &Particle) -> Result<()> {
+        let row = self.rows;
+        let new_rows = row + 1;
+
+        self.theta.resize((new_rows, self.max_intersections))?;
+        self.zeta.resize((new_rows, self.max_intersections))?;
+        self.psip.resize((new_rows, self.max_intersections))?;
+        self.psi.resize((new_rows, self.max_intersections))?;
+        self.status.resize(new_rows)?;
+        self.time0.resize(new_rows)?;
+        self.theta0.resize(new_rows)?;
+        self.psip0.resize(new_rows)?;
+        self.rho0.resize(new_rows)?;
+        self.zeta0.resize(new_rows)?;
+        self.mu.resize(new_rows)?;
+
+        let evolution = &particle.evolution;
+        self.theta
+            .write_slice(&padded_row(&evolution.theta, self.max_intersections), (row, ..))?;
+        self.zeta
+            .write_slice(&padded_row(&evolution.zeta, self.max_intersections), (row, ..))?;
+        self.psip
+            .write_slice(&padded_row(&evolution.psip, self.max_intersections), (row, ..))?;
+        self.psi
+            .write_slice(&padded_row(&evolution.psi, self.max_intersections), (row, ..))?;
+
+        let status: hdf5::types::VarLenUnicode = status_label(&particle.status)
+            .parse()
+            .expect("ASCII status label");
+        self.status.write_slice(&[status], row..new_rows)?;
+
+        let ic = &particle.initial_conditions;
+        self.time0.write_slice(&[ic.time0], row..new_rows)?;
+        self.theta0.write_slice(&[ic.theta0], row..new_rows)?;
+        self.psip0.write_slice(&[ic.psip0], row..new_rows)?;
+        self.rho0.write_slice(&[ic.rho0], row..new_rows)?;
+        self.zeta0.write_slice(&[ic.zeta0], row..new_rows)?;
+        self.mu.write_slice(&[ic.mu], row..new_rows)?;
+
+        self.rows = new_rows;
+        Ok(())
+    }
+
+    /// The number of particles appended so far.
+    pub fn len(&self) -> usize {
+        self.rows
+    }
+
+    /// Whether no particle has been appended yet.
+    pub fn is_empty(&self) -> bool {
+        self.rows == 0
+    }
+}
+
+/// Copies `data` into a `width`-long row, NaN-padding any remaining columns -- mirroring
+/// `Heap::store_arrays`'s existing NaN-sentinel convention for short/escaped orbits, just applied
+/// to one row instead of the whole matrix at once.
+fn padded_row(data: &[f64], width: usize) -> Vec<f64> {
+    let mut row = vec![f64::NAN; width];
+    let n = data.len().min(width);
+    row[..n].copy_from_slice(&data[..n]);
+    row
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use particle::{Evolution, InitialConditions};
+
+    use super::*;
+
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dexter_output_test_{name}_{}.h5", std::process::id()))
+    }
+
+    fn make_particle() -> Particle {
+        let mut particle = Particle::new(&InitialConditions {
+            time0: 1.0,
+            theta0: 2.0,
+            psip0: 3.0,
+            rho0: 4.0,
+            zeta0: 5.0,
+            mu: 6.0,
+        });
+        particle.status = IntegrationStatus::Mapped;
+        particle.evolution = Evolution::from_raw_parts(
+            vec![0.0, 1.0],
+            vec![0.1, 1.1],
+            vec![0.2, 1.2],
+            vec![0.3, 1.3],
+            vec![0.4, 1.4],
+            vec![0.5, 1.5],
+            vec![0.6, 1.6],
+            vec![0.7, 1.7],
+            vec![0.8, 1.8],
+            Duration::from_secs(1),
+            2,
+            0.0,
+            0.0,
+            0.0,
+        );
+        particle
+    }
+
+    #[test]
+    fn test_padded_row_pads_short_rows_with_nan() {
+        let row = padded_row(&[1.0, 2.0], 4);
+        assert_eq!(&row[..2], &[1.0, 2.0]);
+        assert!(row[2].is_nan());
+        assert!(row[3].is_nan());
+    }
+
+    #[test]
+    fn test_padded_row_truncates_long_rows() {
+        assert_eq!(padded_row(&[1.0, 2.0, 3.0], 2), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_append_then_read_back_raw_datasets() {
+        let path = scratch_path("round_trip");
+        let mut writer = Hdf5Writer::create(&path, 3).unwrap();
+        assert!(writer.is_empty());
+
+        let particle = make_particle();
+        writer.append(&particle).unwrap();
+        assert_eq!(writer.len(), 1);
+        assert!(!writer.is_empty());
+        drop(writer);
+
+        let file = hdf5::File::open(&path).unwrap();
+        let theta = file.dataset("theta").unwrap().read::<f64, ndarray::Ix2>().unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(theta.shape(), &[1, 3]);
+        assert_eq!(theta[[0, 0]], 0.1);
+        assert_eq!(theta[[0, 1]], 1.1);
+        assert!(theta[[0, 2]].is_nan());
+
+        let status = file
+            .dataset("status")
+            .unwrap()
+            .read::<hdf5::types::VarLenUnicode, ndarray::Ix1>()
+            .unwrap();
+        assert_eq!(status[0].to_string(), "mapped");
+
+        let theta0 = file.dataset("theta0").unwrap().read::<f64, ndarray::Ix1>().unwrap();
+        assert_eq!(theta0[0], 2.0);
+    }
+}
@@ -0,0 +1,338 @@
+//! Provenance/reproducibility metadata for Poincare-map HDF5 output.
+//!
+//! An output file on its own records *what* a map produced, but not *how* -- which equilibrium
+//! it was read from, what integration scheme and tolerances advanced each particle, which
+//! harmonics were included, or what RNG seed the collision operator used. [`write`] stamps all of
+//! that onto an already-open HDF5 file as global attributes (alongside
+//! [`Hdf5Writer::with_equilibrium_attributes`](crate::Hdf5Writer::with_equilibrium_attributes)'s
+//! existing `source_path`/`source_typ`/`mapping_*` attributes), and [`read`] reconstructs an
+//! [`IntegrationConfig`] + [`MappingParameters`] from a file [`write`] produced.
+//! `PoincarePbar::print_prelude` already prints a subset of this to the terminal -- this is the
+//! durable, on-disk counterpart.
+//!
+//! The stored [`IntegrationConfig`] fields are the integration and stepping methods, `max_steps`,
+//! `first_step`, `safety_factor`, the energy and error tolerances and the Gauss-Legendre solver
+//! settings. The `Pζ` drift tolerances, `orbit_classifier` and the symplectic midpoint solver
+//! settings are not written, and [`read`] returns them at their defaults.
+//!
+//! [`MappingParameters::section`] reconstructs only [`PoincareSection::ConstTheta`]/
+//! [`PoincareSection::ConstZeta`] -- an arbitrary [`PoincareSection::Event`] closure has no
+//! on-disk representation, so [`read`] fails with [`ProvenanceError::EventSection`] on a file
+//! whose `mapping_section` attribute is `"event"`.
+
+use std::path::Path;
+
+use particle::{IntegrationConfig, IntegrationMethod, MappingParameters, PoincareSection, SteppingMethod};
+use thiserror::Error;
+
+/// HDF5 attribute names used by the provenance metadata this module writes/reads.
+pub mod names {
+    pub const CRATE_VERSION: &str = "dexter_version";
+    pub const SOURCE_PATH: &str = "source_path";
+    pub const SOURCE_TYP: &str = "source_typ";
+    pub const MAPPING_SECTION: &str = "mapping_section";
+    pub const MAPPING_ALPHA: &str = "mapping_alpha";
+    pub const MAPPING_INTERSECTIONS: &str = "mapping_intersections";
+    pub const INTEGRATION_METHOD: &str = "integration_method";
+    pub const STEPPING_METHOD: &str = "stepping_method";
+    pub const MAX_STEPS: &str = "max_steps";
+    pub const FIRST_STEP: &str = "first_step";
+    pub const SAFETY_FACTOR: &str = "safety_factor";
+    pub const ENERGY_REL_TOL: &str = "energy_rel_tol";
+    pub const ENERGY_ABS_TOL: &str = "energy_abs_tol";
+    pub const ERROR_REL_TOL: &str = "error_rel_tol";
+    pub const ERROR_ABS_TOL: &str = "error_abs_tol";
+    pub const GL_TOLERANCE: &str = "gl_tolerance";
+    pub const GL_MAX_ITERATIONS: &str = "gl_max_iterations";
+    pub const HARMONICS_M: &str = "harmonics_m";
+    pub const HARMONICS_N: &str = "harmonics_n";
+    pub const COLLISION_SEED: &str = "collision_seed";
+}
+
+/// Errors raised while writing or reading provenance metadata.
+#[derive(Error, Debug)]
+pub enum ProvenanceError {
+    /// Underlying HDF5 library error.
+    #[error("HDF5 error: {0}")]
+    Hdf5(#[from] hdf5::Error),
+    /// An `integration_method`/`stepping_method` attribute didn't decode to a known variant.
+    #[error("unrecognized `{0}` value `{1}`")]
+    UnknownVariant(&'static str, String),
+    /// The file's `mapping_section` attribute was `"event"`: [`PoincareSection::Event`] carries a
+    /// closure with no on-disk representation, so it can't be reconstructed from this attribute
+    /// alone -- the caller must supply the event function itself out of band.
+    #[error("mapping section `event` can't be reconstructed from its attribute alone")]
+    EventSection,
+}
+
+/// The result type returned by this module's functions.
+pub type Result<T> = std::result::Result<T, ProvenanceError>;
+
+/// A run's full provenance, as reconstructed by [`read`].
+#[derive(Debug, Clone)]
+pub struct Provenance {
+    /// The crate version that produced the file.
+    pub dexter_version: String,
+    /// The source equilibrium file path.
+    pub source_path: String,
+    /// The source equilibrium's interpolation type (e.g. `"steffen"`/`"bicubic"`).
+    pub source_typ: String,
+    /// The reconstructed mapping configuration. See the module docs for the
+    /// [`PoincareSection::Event`] caveat.
+    pub mapping: MappingParameters,
+    /// The reconstructed integration configuration.
+    pub integration: IntegrationConfig,
+    /// The `(m, n)` harmonics included in the run.
+    pub harmonics: Vec<(i64, i64)>,
+    /// The collision operator's RNG seed, if the run used `CollisionConfig`.
+    pub collision_seed: Option<u64>,
+}
+
+fn stepping_method_label(method: &SteppingMethod) -> &'static str {
+    match method {
+        SteppingMethod::EnergyAdaptiveStep => "energy_adaptive_step",
+        SteppingMethod::ErrorAdaptiveStep => "error_adaptive_step",
+    }
+}
+
+fn stepping_method_from_label(label: &str) -> Result<SteppingMethod> {
+    Ok(match label {
+        "energy_adaptive_step" => SteppingMethod::EnergyAdaptiveStep,
+        "error_adaptive_step" => SteppingMethod::ErrorAdaptiveStep,
+        other => {
+            return Err(ProvenanceError::UnknownVariant(
+                "stepping_method",
+                other.to_string(),
+            ));
+        }
+    })
+}
+
+fn integration_method_label(method: IntegrationMethod) -> &'static str {
+    match method {
+        IntegrationMethod::Rkf45 => "rkf45",
+        IntegrationMethod::GaussLegendre4 => "gauss_legendre4",
+        IntegrationMethod::SymplecticMidpoint => "symplectic_midpoint",
+    }
+}
+
+fn integration_method_from_label(label: &str) -> Result<IntegrationMethod> {
+    Ok(match label {
+        "rkf45" => IntegrationMethod::Rkf45,
+        "gauss_legendre4" => IntegrationMethod::GaussLegendre4,
+        "symplectic_midpoint" => IntegrationMethod::SymplecticMidpoint,
+        other => {
+            return Err(ProvenanceError::UnknownVariant(
+                "integration_method",
+                other.to_string(),
+            ));
+        }
+    })
+}
+
+fn write_string(file: &hdf5::File, name: &str, value: &str) -> Result<()> {
+    let value: hdf5::types::VarLenUnicode = value.parse().expect("ASCII/UTF-8 string");
+    file.new_attr::<hdf5::types::VarLenUnicode>()
+        .create(name)?
+        .write_scalar(&value)?;
+    Ok(())
+}
+
+fn write_scalar<T: hdf5::H5Type>(file: &hdf5::File, name: &str, value: T) -> Result<()> {
+    file.new_attr::<T>().create(name)?.write_scalar(&value)?;
+    Ok(())
+}
+
+fn read_string(file: &hdf5::File, name: &str) -> Result<String> {
+    Ok(file
+        .attr(name)?
+        .read_scalar::<hdf5::types::VarLenUnicode>()?
+        .to_string())
+}
+
+fn read_scalar<T: hdf5::H5Type>(file: &hdf5::File, name: &str) -> Result<T> {
+    Ok(file.attr(name)?.read_scalar::<T>()?)
+}
+
+/// Stamps `integration`/`mapping`/`harmonics`/`collision_seed` onto the already-open `file` as
+/// global attributes, alongside this crate's own version string. Intended to be called once, at
+/// the same point a caller would call
+/// [`Hdf5Writer::with_equilibrium_attributes`](crate::Hdf5Writer::with_equilibrium_attributes).
+pub fn write(
+    file: &hdf5::File,
+    source_path: &str,
+    source_typ: &str,
+    integration: &IntegrationConfig,
+    mapping: &MappingParameters,
+    harmonics: &[(i64, i64)],
+    collision_seed: Option<u64>,
+) -> Result<()> {
+    use names::*;
+
+    write_string(file, CRATE_VERSION, env!("CARGO_PKG_VERSION"))?;
+    write_string(file, SOURCE_PATH, source_path)?;
+    write_string(file, SOURCE_TYP, source_typ)?;
+
+    let section = match mapping.section {
+        PoincareSection::ConstTheta => "const_theta",
+        PoincareSection::ConstZeta => "const_zeta",
+        PoincareSection::Event(_) => "event",
+    };
+    write_string(file, MAPPING_SECTION, section)?;
+    write_scalar(file, MAPPING_ALPHA, mapping.alpha)?;
+    write_scalar(file, MAPPING_INTERSECTIONS, mapping.intersections as u64)?;
+
+    write_string(file, INTEGRATION_METHOD, integration_method_label(integration.integration_method))?;
+    write_string(file, STEPPING_METHOD, stepping_method_label(&integration.method))?;
+    write_scalar(file, MAX_STEPS, integration.max_steps as u64)?;
+    write_scalar(file, FIRST_STEP, integration.first_step)?;
+    write_scalar(file, SAFETY_FACTOR, integration.safety_factor)?;
+    write_scalar(file, ENERGY_REL_TOL, integration.energy_rel_tol)?;
+    write_scalar(file, ENERGY_ABS_TOL, integration.energy_abs_tol)?;
+    write_scalar(file, ERROR_REL_TOL, integration.error_rel_tol)?;
+    write_scalar(file, ERROR_ABS_TOL, integration.error_abs_tol)?;
+    write_scalar(file, GL_TOLERANCE, integration.gl_tolerance)?;
+    write_scalar(file, GL_MAX_ITERATIONS, integration.gl_max_iterations as u64)?;
+
+    let harmonics_m: Vec<i64> = harmonics.iter().map(|&(m, _)| m).collect();
+    let harmonics_n: Vec<i64> = harmonics.iter().map(|&(_, n)| n).collect();
+    file.new_dataset_builder()
+        .with_data(&harmonics_m)
+        .create(HARMONICS_M)?;
+    file.new_dataset_builder()
+        .with_data(&harmonics_n)
+        .create(HARMONICS_N)?;
+
+    if let Some(seed) = collision_seed {
+        write_scalar(file, COLLISION_SEED, seed)?;
+    }
+
+    Ok(())
+}
+
+/// Reconstructs a [`Provenance`] -- and from it, an [`IntegrationConfig`] + [`MappingParameters`]
+/// -- from a file [`write`] previously stamped. See the module docs for the
+/// [`PoincareSection::Event`] caveat and for the config fields that are not stored.
+pub fn read(path: &Path) -> Result<Provenance> {
+    use names::*;
+
+    let file = hdf5::File::open(path)?;
+
+    let section_label = read_string(&file, MAPPING_SECTION)?;
+    let section = match section_label.as_str() {
+        "const_theta" => PoincareSection::ConstTheta,
+        "const_zeta" => PoincareSection::ConstZeta,
+        "event" => return Err(ProvenanceError::EventSection),
+        other => return Err(ProvenanceError::UnknownVariant("mapping_section", other.to_string())),
+    };
+    let mapping = MappingParameters::new(
+        section,
+        read_scalar::<f64>(&file, MAPPING_ALPHA)?,
+        read_scalar::<u64>(&file, MAPPING_INTERSECTIONS)? as usize,
+    );
+
+    let integration = IntegrationConfig {
+        integration_method: integration_method_from_label(&read_string(&file, INTEGRATION_METHOD)?)?,
+        method: stepping_method_from_label(&read_string(&file, STEPPING_METHOD)?)?,
+        max_steps: read_scalar::<u64>(&file, MAX_STEPS)? as usize,
+        first_step: read_scalar(&file, FIRST_STEP)?,
+        safety_factor: read_scalar(&file, SAFETY_FACTOR)?,
+        energy_rel_tol: read_scalar(&file, ENERGY_REL_TOL)?,
+        energy_abs_tol: read_scalar(&file, ENERGY_ABS_TOL)?,
+        error_rel_tol: read_scalar(&file, ERROR_REL_TOL)?,
+        error_abs_tol: read_scalar(&file, ERROR_ABS_TOL)?,
+        gl_tolerance: read_scalar(&file, GL_TOLERANCE)?,
+        gl_max_iterations: read_scalar::<u64>(&file, GL_MAX_ITERATIONS)? as usize,
+        ..IntegrationConfig::default()
+    };
+
+    let harmonics_m: Vec<i64> = file.dataset(HARMONICS_M)?.read_raw()?;
+    let harmonics_n: Vec<i64> = file.dataset(HARMONICS_N)?.read_raw()?;
+    let harmonics = harmonics_m.into_iter().zip(harmonics_n).collect();
+
+    let collision_seed = if file.attr(COLLISION_SEED).is_ok() {
+        Some(read_scalar::<u64>(&file, COLLISION_SEED)?)
+    } else {
+        None
+    };
+
+    Ok(Provenance {
+        dexter_version: read_string(&file, CRATE_VERSION)?,
+        source_path: read_string(&file, SOURCE_PATH)?,
+        source_typ: read_string(&file, SOURCE_TYP)?,
+        mapping,
+        integration,
+        harmonics,
+        collision_seed,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("dexter_provenance_test_{name}_{}.h5", std::process::id()))
+    }
+
+    #[test]
+    fn test_write_then_read_round_trips() {
+        let path = scratch_path("round_trip");
+        let file = hdf5::File::create(&path).unwrap();
+
+        let integration = IntegrationConfig {
+            method: SteppingMethod::ErrorAdaptiveStep,
+            max_steps: 123,
+            ..IntegrationConfig::default()
+        };
+        let mapping = MappingParameters::new(PoincareSection::ConstZeta, 0.5, 4);
+        let harmonics = vec![(1, 0), (2, -1)];
+
+        write(&file, "eq.nc", "steffen", &integration, &mapping, &harmonics, Some(42)).unwrap();
+        drop(file);
+
+        let provenance = read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(provenance.source_path, "eq.nc");
+        assert_eq!(provenance.source_typ, "steffen");
+        assert!(matches!(provenance.mapping.section, PoincareSection::ConstZeta));
+        assert_eq!(provenance.mapping.alpha, 0.5);
+        assert_eq!(provenance.mapping.intersections, 4);
+        assert!(matches!(provenance.integration.method, SteppingMethod::ErrorAdaptiveStep));
+        assert_eq!(provenance.integration.max_steps, 123);
+        assert_eq!(provenance.harmonics, harmonics);
+        assert_eq!(provenance.collision_seed, Some(42));
+    }
+
+    #[test]
+    fn test_write_then_read_without_collision_seed() {
+        let path = scratch_path("no_seed");
+        let file = hdf5::File::create(&path).unwrap();
+        let integration = IntegrationConfig::default();
+        let mapping = MappingParameters::new(PoincareSection::ConstTheta, 0.1, 1);
+
+        write(&file, "eq.nc", "steffen", &integration, &mapping, &[], None).unwrap();
+        drop(file);
+
+        let provenance = read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(provenance.collision_seed, None);
+        assert!(provenance.harmonics.is_empty());
+    }
+
+    #[test]
+    fn test_read_rejects_event_section() {
+        let path = scratch_path("event_section");
+        let file = hdf5::File::create(&path).unwrap();
+        write_string(&file, names::MAPPING_SECTION, "event").unwrap();
+        drop(file);
+
+        let err = read(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(err, ProvenanceError::EventSection));
+    }
+}
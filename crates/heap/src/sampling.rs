@@ -0,0 +1,368 @@
+//! A parameter-sampling "robustness study": jitter a base [`InitialConditions`] (and, optionally,
+//! a shared [`Perturbation`]'s amplitude) into an ensemble of nearby particles, integrate them
+//! all, and report how stable their [`OrbitType`] classification and [`Frequencies`] are to that
+//! jitter.
+//!
+//! This answers a different question than a single [`Particle::map`] or a [`crate::HeapStats`]
+//! snapshot: not "what does this orbit do", but "how much does the answer change if this orbit
+//! had started somewhere slightly different" -- useful for flagging phase-space regions (e.g.
+//! near a separatrix) where `OrbitType` is numerically fragile rather than physically ambiguous.
+
+use rayon::prelude::*;
+
+use equilibrium::{Bfield, Current, HarmonicCache, Perturbation, Qfactor, Result as EqResult};
+use particle::{CollisionRng, IntegrationConfig, InitialConditions, Particle, SinglePeriodConfig};
+use rsl_interpolation::Accelerator;
+
+use crate::stats::{IntegrationStatusNums, OrbitTypeNums};
+
+/// Which of [`Particle`]'s routines a [`run_robustness_study`] exercises on every sampled
+/// particle.
+#[derive(Debug, Clone)]
+pub enum SamplingRoutine {
+    /// Runs [`Particle::integrate`] over `t_eval`.
+    Integrate {
+        t_eval: (f64, f64),
+        config: IntegrationConfig,
+    },
+    /// Runs [`Particle::single_period_integrate`].
+    SinglePeriod { config: SinglePeriodConfig },
+}
+
+/// Independent Gaussian jitter applied to a subset of `base`'s [`InitialConditions`] fields, plus
+/// an optional multiplicative jitter on the shared [`Perturbation`]'s amplitude, used to build a
+/// [`run_robustness_study`] ensemble.
+///
+/// A zero standard deviation disables jitter on that field entirely (every sample keeps `base`'s
+/// exact value).
+#[derive(Debug, Clone, Default)]
+pub struct JitterSpec {
+    /// Standard deviation of the Gaussian jitter applied to `psip0`.
+    pub psip0_std: f64,
+    /// Standard deviation of the Gaussian jitter applied to `rho0`.
+    pub rho0_std: f64,
+    /// Standard deviation of the Gaussian jitter applied to `mu`.
+    pub mu_std: f64,
+    /// Standard deviation of the multiplicative scale factor applied to the perturbation's
+    /// amplitude (1.0 = unperturbed scale).
+    pub perturbation_scale_std: f64,
+}
+
+/// Runs a robustness study: samples `samples` [`Particle`]s around `base` under `jitter`, runs
+/// each through `routine`, and reports how their outcomes held up in an [`EnsembleStats`].
+///
+/// `seed` makes the study reproducible -- the same `seed` and `samples` always jitter the same
+/// sequence of particles. Particles are integrated concurrently with `rayon`'s work-stealing
+/// scheduler, exactly as [`integrate_ensemble`](particle::integrate_ensemble) does, except each
+/// particle gets its own amplitude-scaled [`Perturbation`] (see [`ScaledPerturbation`]), which
+/// rules out reusing `integrate_ensemble` itself -- it assumes every particle shares one
+/// `Perturbation`.
+pub fn run_robustness_study(
+    base: &InitialConditions,
+    jitter: &JitterSpec,
+    samples: usize,
+    seed: u64,
+    qfactor: &(impl Qfactor + Sync),
+    current: &(impl Current + Sync),
+    bfield: &(impl Bfield + Sync),
+    perturbation: &(impl Perturbation + Sync),
+    routine: &SamplingRoutine,
+) -> EnsembleStats {
+    let mut rng = CollisionRng::new(seed);
+    let mut particles = Vec::with_capacity(samples);
+    let mut scales = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let mut ic = base.clone();
+        ic.psip0 += jitter.psip0_std * rng.next_gaussian();
+        ic.rho0 += jitter.rho0_std * rng.next_gaussian();
+        ic.mu += jitter.mu_std * rng.next_gaussian();
+        particles.push(Particle::new(&ic));
+        scales.push(1.0 + jitter.perturbation_scale_std * rng.next_gaussian());
+    }
+
+    particles
+        .par_iter_mut()
+        .zip(scales.into_par_iter())
+        .for_each(|(particle, scale)| {
+            let scaled = ScaledPerturbation {
+                inner: perturbation,
+                scale,
+            };
+            match routine {
+                SamplingRoutine::Integrate { t_eval, config } => {
+                    particle.integrate(qfactor, current, bfield, &scaled, *t_eval, config);
+                }
+                SamplingRoutine::SinglePeriod { config } => {
+                    particle.single_period_integrate(qfactor, current, bfield, &scaled, config);
+                }
+            }
+        });
+
+    EnsembleStats::from_particles(&particles)
+}
+
+/// Mean, standard deviation and a symmetric 95% confidence half-width (`1.96 * std / sqrt(n)`) of
+/// one [`Frequencies`](particle::Frequencies) field across an ensemble's samples, computed only
+/// over the samples where it was actually available (e.g. `qkinetic` needs at least one closed
+/// period).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrequencyBounds {
+    pub mean: f64,
+    pub std: f64,
+    /// Half-width of the symmetric 95% confidence interval around `mean`. `0.0` with fewer than 2
+    /// samples -- there is no spread to bound.
+    pub confidence95: f64,
+    /// How many samples actually contributed a value.
+    pub samples: usize,
+}
+
+impl FrequencyBounds {
+    fn of(values: &[f64]) -> Self {
+        let samples = values.len();
+        if samples == 0 {
+            return Self::default();
+        }
+        let mean = values.iter().sum::<f64>() / samples as f64;
+        let variance =
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / samples as f64;
+        let std = variance.sqrt();
+        let confidence95 = if samples > 1 {
+            1.96 * std / (samples as f64).sqrt()
+        } else {
+            0.0
+        };
+        Self {
+            mean,
+            std,
+            confidence95,
+            samples,
+        }
+    }
+}
+
+/// The fraction of an ensemble's samples landing in each [`OrbitType`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrbitTypeFractions {
+    pub undefined: f64,
+    pub trapped: f64,
+    pub passing: f64,
+}
+
+impl OrbitTypeFractions {
+    fn of(counts: &OrbitTypeNums, total: usize) -> Self {
+        if total == 0 {
+            return Self::default();
+        }
+        let total = total as f64;
+        Self {
+            undefined: counts.undefined as f64 / total,
+            trapped: counts.trapped as f64 / total,
+            passing: counts.passing as f64 / total,
+        }
+    }
+}
+
+/// The aggregate report of one [`run_robustness_study`] ensemble: the fraction of samples landing
+/// in each [`OrbitType`], the escape/timeout rates, and confidence bounds on the sampled
+/// `ωθ`/`ωζ`/qkinetic.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct EnsembleStats {
+    total_samples: usize,
+    status_nums: IntegrationStatusNums,
+    orbit_type_fractions: OrbitTypeFractions,
+    /// Confidence bounds on the sampled `ωθ`.
+    pub omega_theta: FrequencyBounds,
+    /// Confidence bounds on the sampled `ωζ`.
+    pub omega_zeta: FrequencyBounds,
+    /// Confidence bounds on the sampled qkinetic.
+    pub qkinetic: FrequencyBounds,
+}
+
+impl EnsembleStats {
+    fn from_particles(particles: &[Particle]) -> Self {
+        let total_samples = particles.len();
+        let status_nums = IntegrationStatusNums::count(particles);
+        let orbit_type_fractions =
+            OrbitTypeFractions::of(&OrbitTypeNums::count(particles), total_samples);
+
+        let omega_theta: Vec<f64> = particles
+            .iter()
+            .filter_map(|p| p.frequencies.omega_theta())
+            .collect();
+        let omega_zeta: Vec<f64> = particles
+            .iter()
+            .filter_map(|p| p.frequencies.omega_zeta())
+            .collect();
+        let qkinetic: Vec<f64> = particles
+            .iter()
+            .filter_map(|p| p.frequencies.qkinetic())
+            .collect();
+
+        Self {
+            total_samples,
+            status_nums,
+            orbit_type_fractions,
+            omega_theta: FrequencyBounds::of(&omega_theta),
+            omega_zeta: FrequencyBounds::of(&omega_zeta),
+            qkinetic: FrequencyBounds::of(&qkinetic),
+        }
+    }
+
+    /// The number of samples in the ensemble.
+    pub fn total_samples(&self) -> usize {
+        self.total_samples
+    }
+
+    /// The fraction of samples landing in each [`OrbitType`].
+    pub fn orbit_type_fractions(&self) -> OrbitTypeFractions {
+        self.orbit_type_fractions
+    }
+
+    /// The fraction of samples that [`Escaped`](particle::IntegrationStatus::Escaped).
+    pub fn escape_rate(&self) -> f64 {
+        self.status_nums.escaped as f64 / self.total_samples as f64
+    }
+
+    /// The fraction of samples that [`TimedOut`](particle::IntegrationStatus::TimedOut).
+    pub fn timeout_rate(&self) -> f64 {
+        self.status_nums.timedout as f64 / self.total_samples as f64
+    }
+}
+
+/// A [`Perturbation`] wrapper that scales an inner perturbation's amplitude by a fixed factor.
+///
+/// Used by [`run_robustness_study`] to jitter the perturbation strength across an ensemble
+/// without needing to know how any particular [`Perturbation`] impl stores its harmonics.
+struct ScaledPerturbation<'a, P: Perturbation> {
+    inner: &'a P,
+    scale: f64,
+}
+
+impl<P: Perturbation> Perturbation for ScaledPerturbation<'_, P> {
+    fn p(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> EqResult<f64> {
+        self.inner
+            .p(psip, theta, zeta, time, acc, caches)
+            .map(|value| value * self.scale)
+    }
+
+    fn dp_dpsip(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> EqResult<f64> {
+        self.inner
+            .dp_dpsip(psip, theta, zeta, time, acc, caches)
+            .map(|value| value * self.scale)
+    }
+
+    fn dp_dtheta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> EqResult<f64> {
+        self.inner
+            .dp_dtheta(psip, theta, zeta, time, acc, caches)
+            .map(|value| value * self.scale)
+    }
+
+    fn dp_dzeta(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> EqResult<f64> {
+        self.inner
+            .dp_dzeta(psip, theta, zeta, time, acc, caches)
+            .map(|value| value * self.scale)
+    }
+
+    fn dp_dt(
+        &self,
+        psip: f64,
+        theta: f64,
+        zeta: f64,
+        time: f64,
+        acc: &mut Accelerator,
+        caches: &mut [HarmonicCache],
+    ) -> EqResult<f64> {
+        self.inner
+            .dp_dt(psip, theta, zeta, time, acc, caches)
+            .map(|value| value * self.scale)
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_frequency_bounds_of_empty_is_zero_valued() {
+        let bounds = FrequencyBounds::of(&[]);
+        assert_eq!(bounds.samples, 0);
+        assert_eq!(bounds.mean, 0.0);
+        assert_eq!(bounds.confidence95, 0.0);
+    }
+
+    #[test]
+    fn test_frequency_bounds_of_single_sample_has_no_confidence_width() {
+        let bounds = FrequencyBounds::of(&[3.0]);
+        assert_eq!(bounds.samples, 1);
+        assert_eq!(bounds.mean, 3.0);
+        assert_eq!(bounds.std, 0.0);
+        assert_eq!(bounds.confidence95, 0.0);
+    }
+
+    #[test]
+    fn test_frequency_bounds_of_known_samples() {
+        let bounds = FrequencyBounds::of(&[1.0, 2.0, 3.0]);
+        assert_eq!(bounds.samples, 3);
+        assert_eq!(bounds.mean, 2.0);
+        assert!((bounds.std - (2.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert!(bounds.confidence95 > 0.0);
+    }
+
+    #[test]
+    fn test_orbit_type_fractions_of_empty_total_is_zero_valued() {
+        let fractions = OrbitTypeFractions::of(&OrbitTypeNums::default(), 0);
+        assert_eq!(fractions.undefined, 0.0);
+        assert_eq!(fractions.trapped, 0.0);
+        assert_eq!(fractions.passing, 0.0);
+    }
+
+    #[test]
+    fn test_orbit_type_fractions_of_known_counts() {
+        let counts = OrbitTypeNums {
+            undefined: 1,
+            trapped: 3,
+            passing: 6,
+        };
+        let fractions = OrbitTypeFractions::of(&counts, 10);
+        assert_eq!(fractions.undefined, 0.1);
+        assert_eq!(fractions.trapped, 0.3);
+        assert_eq!(fractions.passing, 0.6);
+    }
+}
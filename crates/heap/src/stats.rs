@@ -4,49 +4,211 @@ use particle::Particle;
 
 use crate::{Heap, HeapInitialConditions, heap::Routine};
 
+/// Default absolute tolerance for conservation-invariant drift -- see [`HeapStats::update_incremental`].
+const INVARIANT_ABS_TOL: f64 = 1e-8;
+
+/// Default relative tolerance for conservation-invariant drift, chosen near the stepper's own
+/// `energy_rel_tol` -- see [`HeapStats::update_incremental`].
+const INVARIANT_REL_TOL: f64 = 1e-10;
+
+macro_rules! count_variants {
+    ($particles:expr, $which:ident, $is_enum:ident) => {
+        $particles.iter().filter(|p| p.$which.$is_enum()).count()
+    };
+}
+
 /// Keeps track of the number of Particles per `IntegrationStatus`.
 #[derive(Default, Debug)]
-struct IntegrationStatusNums {
-    initialized: usize,
-    integrated: usize,
-    single_period: usize,
-    mapped: usize,
-    escaped: usize,
-    evaluation_nan: usize,
-    timedout: usize,
-    invalid: usize,
-    failed: usize,
+pub(crate) struct IntegrationStatusNums {
+    pub(crate) initialized: usize,
+    pub(crate) integrated: usize,
+    pub(crate) single_period: usize,
+    pub(crate) mapped: usize,
+    pub(crate) escaped: usize,
+    pub(crate) evaluation_nan: usize,
+    pub(crate) timedout: usize,
+    pub(crate) invalid: usize,
+    pub(crate) failed: usize,
+}
+
+impl IntegrationStatusNums {
+    /// Counts the occurences of each [`IntegrationStatus`](particle::IntegrationStatus)'s
+    /// variants across `particles`. Shared by [`HeapStats`] and
+    /// [`EnsembleStats`](crate::EnsembleStats).
+    pub(crate) fn count(particles: &[Particle]) -> Self {
+        Self {
+            initialized: count_variants!(particles, status, is_initialized),
+            integrated: count_variants!(particles, status, is_integrated),
+            mapped: count_variants!(particles, status, is_mapped),
+            escaped: count_variants!(particles, status, is_escaped),
+            evaluation_nan: count_variants!(particles, status, is_evaluation_nan),
+            timedout: count_variants!(particles, status, is_timed_out),
+            failed: count_variants!(particles, status, is_failed),
+            invalid: count_variants!(particles, status, is_invalid_intersections),
+            single_period: count_variants!(particles, status, is_single_period_integrated),
+        }
+    }
+
+    /// Folds one particle's [`IntegrationStatus`](particle::IntegrationStatus) into the running
+    /// counts. Used by [`HeapStats::update_incremental`] to count one particle at a time, rather
+    /// than rescanning a whole particle slice via [`Self::count`].
+    pub(crate) fn increment(&mut self, status: &particle::IntegrationStatus) {
+        self.initialized += status.is_initialized() as usize;
+        self.integrated += status.is_integrated() as usize;
+        self.mapped += status.is_mapped() as usize;
+        self.escaped += status.is_escaped() as usize;
+        self.evaluation_nan += status.is_evaluation_nan() as usize;
+        self.timedout += status.is_timed_out() as usize;
+        self.failed += status.is_failed() as usize;
+        self.invalid += status.is_invalid_intersections() as usize;
+        self.single_period += status.is_single_period_integrated() as usize;
+    }
 }
 
 /// Keeps track of the number of Particles per `OrbitType`.
 #[derive(Default, Debug)]
-struct OrbitTypeNums {
-    undefined: usize,
-    trapped: usize,
-    passing: usize,
+pub(crate) struct OrbitTypeNums {
+    pub(crate) undefined: usize,
+    pub(crate) trapped: usize,
+    pub(crate) passing: usize,
+}
+
+impl OrbitTypeNums {
+    /// Counts the occurences of each [`OrbitType`](particle::OrbitType)'s variants across
+    /// `particles`. Shared by [`HeapStats`] and [`EnsembleStats`](crate::EnsembleStats).
+    pub(crate) fn count(particles: &[Particle]) -> Self {
+        Self {
+            undefined: count_variants!(particles, orbit_type, is_undefined),
+            trapped: count_variants!(particles, orbit_type, is_trapped),
+            passing: count_variants!(particles, orbit_type, is_passing),
+        }
+    }
+
+    /// Folds one particle's [`OrbitType`](particle::OrbitType) into the running counts. Used by
+    /// [`HeapStats::update_incremental`] to count one particle at a time, rather than rescanning a
+    /// whole particle slice via [`Self::count`].
+    pub(crate) fn increment(&mut self, orbit_type: &particle::OrbitType) {
+        self.undefined += orbit_type.is_undefined() as usize;
+        self.trapped += orbit_type.is_trapped() as usize;
+        self.passing += orbit_type.is_passing() as usize;
+    }
+}
+
+/// A population fraction estimated incrementally over `n` folded-in particles: the fraction
+/// itself and its standard error, the usual binomial-proportion estimate `sqrt(p(1-p)/n)`.
+///
+/// Mirrors the `(mean, relative_error)` shape [`Frequencies`](particle::Frequencies) reports for
+/// `ωθ`/`ωζ`, except the error here is always available (a proportion's standard error is defined
+/// from a single sample count, unlike a sample standard deviation which needs at least two
+/// samples).
+#[derive(Debug, Clone, Copy)]
+pub struct FractionEstimate {
+    pub fraction: f64,
+    pub standard_error: f64,
+    /// `standard_error / fraction`. `f64::INFINITY` if zero instances of this category have been
+    /// observed yet -- a category nobody has hit can't be called converged just because other
+    /// categories have been, no matter how many particles have been folded in overall.
+    pub relative_error: f64,
+}
+
+impl Default for FractionEstimate {
+    /// No particles folded in at all: an undefined fraction, reported as never converged.
+    fn default() -> Self {
+        Self {
+            fraction: 0.0,
+            standard_error: 0.0,
+            relative_error: f64::INFINITY,
+        }
+    }
+}
+
+impl FractionEstimate {
+    fn of(count: usize, total: usize) -> Self {
+        if total == 0 {
+            return Self::default();
+        }
+        let n = total as f64;
+        let fraction = count as f64 / n;
+        let standard_error = (fraction * (1.0 - fraction) / n).sqrt();
+        let relative_error = if count > 0 {
+            standard_error / fraction
+        } else {
+            f64::INFINITY
+        };
+        Self {
+            fraction,
+            standard_error,
+            relative_error,
+        }
+    }
+}
+
+/// Keeps track of the number of Particles whose conservation-invariant drift exceeds
+/// [`HeapStats::invariant_abs_tol`]/[`HeapStats::invariant_rel_tol`].
+///
+/// The magnetic moment `μ` is not tracked here: it only ever enters the equations of motion as a
+/// fixed parameter of a [`Particle`]'s initial conditions, never as a quantity the stepper
+/// advances step-by-step, so there is no time series of it in [`Evolution`](particle::Evolution)
+/// to audit for drift. Its conservation is structural, not numerical.
+#[derive(Default, Debug)]
+struct InvariantViolationNums {
+    energy: usize,
+    pzeta: usize,
 }
 
 #[non_exhaustive]
-#[derive(Default)]
 pub struct HeapStats {
     routine: Routine,
     status_nums: IntegrationStatusNums,
     orbit_type_nums: OrbitTypeNums,
     total_particles: usize,
+    /// How many particles have been folded in via [`Self::update_incremental`] so far. The
+    /// denominator behind every [`FractionEstimate`] this reports -- may be less than
+    /// `total_particles` while a survey is still in flight.
+    particles_seen: usize,
+    /// How many folded-in particles had a nonzero stored evolution, i.e. were eligible for
+    /// [`Self::fastest`].
+    timed_particles_seen: usize,
     /// Duration of the slowest particle.
     slowest: ParticleDuration,
     /// Duration of the fastest particle.
     fastest: ParticleDuration,
+    /// Every timed-in particle's integration duration, used to compute [`Self::duration_stats`]
+    /// on demand.
+    durations: Vec<Duration>,
+    /// Every timed-in particle's steps taken, used to compute [`Self::steps_stats`] on demand.
+    steps_taken: Vec<usize>,
+    /// The absolute drift tolerance used by [`Self::update_incremental`].
+    invariant_abs_tol: f64,
+    /// The relative drift tolerance used by [`Self::update_incremental`].
+    invariant_rel_tol: f64,
+    invariant_violation_nums: InvariantViolationNums,
+    /// The largest energy drift (by relative value) seen across the Heap.
+    worst_energy_drift: InvariantDrift,
+    /// The largest `Pζ` drift (by relative value) seen across the Heap.
+    worst_pzeta_drift: InvariantDrift,
 }
 
-macro_rules! count_variants {
-    ($heap:ident, $which:ident, $is_enum:ident) => {
-        $heap
-            .particles
-            .iter()
-            .filter(|p| p.$which.$is_enum())
-            .count()
-    };
+impl Default for HeapStats {
+    fn default() -> Self {
+        Self {
+            routine: Default::default(),
+            status_nums: Default::default(),
+            orbit_type_nums: Default::default(),
+            total_particles: 0,
+            particles_seen: 0,
+            timed_particles_seen: 0,
+            slowest: Default::default(),
+            fastest: Default::default(),
+            durations: Default::default(),
+            steps_taken: Default::default(),
+            invariant_abs_tol: INVARIANT_ABS_TOL,
+            invariant_rel_tol: INVARIANT_REL_TOL,
+            invariant_violation_nums: Default::default(),
+            worst_energy_drift: Default::default(),
+            worst_pzeta_drift: Default::default(),
+        }
+    }
 }
 
 impl HeapStats {
@@ -58,55 +220,141 @@ impl HeapStats {
         }
     }
 
-    /// Creates a new Self from a Heap. This is needed since borrow checker exists
+    /// Creates a new Self from a Heap, by folding in every particle via
+    /// [`Self::update_incremental`]. This is needed since borrow checker exists
     pub fn from_heap(heap: &Heap) -> Self {
-        let mut stat = Self::new(&heap.initials);
-        stat.update(heap);
-        stat
+        let mut stats = Self::new(&heap.initials);
+        stats.routine = heap.routine.clone();
+        for particle in &heap.particles {
+            stats.update_incremental(particle);
+        }
+        stats
+    }
+
+    /// Sets the absolute/relative tolerances used by [`Self::update_incremental`] to flag a
+    /// particle's conservation-invariant drift as a violation.
+    ///
+    /// Only affects particles folded in after this call -- a particle already folded in was
+    /// judged against whatever tolerances were in effect at the time.
+    pub fn with_invariant_tolerances(mut self, abs_tol: f64, rel_tol: f64) -> Self {
+        self.invariant_abs_tol = abs_tol;
+        self.invariant_rel_tol = rel_tol;
+        self
+    }
+
+    /// Folds one finished [`Particle`] into the running totals, in `O(1)`.
+    ///
+    /// Mirrors a stochastic-estimator-with-convergence-check pattern: every population fraction
+    /// ([`Self::trapped`], [`Self::passing`], [`Self::escaped`]) and the conservation-invariant
+    /// drift worst-cases update incrementally from one particle at a time, so [`Self::converged`]
+    /// can tell a driving loop that integrating further particles is unlikely to move these
+    /// estimates materially -- without ever rescanning the particles already folded in.
+    pub fn update_incremental(&mut self, particle: &Particle) {
+        self.status_nums.increment(&particle.status);
+        self.orbit_type_nums.increment(&particle.orbit_type);
+        self.update_duration(particle);
+        self.update_invariant_drift(particle);
+        self.particles_seen += 1;
     }
 
-    fn update(&mut self, heap: &Heap) {
-        self.update_flags(heap);
-        self.calculate_particle_nums(heap);
-        self.calculate_durations(heap);
-        self.calculate_orbit_types_nums(heap);
+    /// The fraction of folded-in particles classified as
+    /// [`Trapped`](particle::OrbitType::Trapped), with its standard error.
+    pub fn trapped(&self) -> FractionEstimate {
+        FractionEstimate::of(self.orbit_type_nums.trapped, self.particles_seen)
     }
 
-    /// Updates various future flags.
-    fn update_flags(&mut self, heap: &Heap) {
-        self.routine = heap.routine.clone()
+    /// The fraction of folded-in particles classified as
+    /// [`Passing`](particle::OrbitType::Passing), with its standard error.
+    pub fn passing(&self) -> FractionEstimate {
+        FractionEstimate::of(self.orbit_type_nums.passing, self.particles_seen)
     }
 
-    /// Counts the occurences of each [`IntegrationStatus`]'s variants.
-    fn calculate_particle_nums(&mut self, heap: &Heap) {
-        self.status_nums.initialized = count_variants!(heap, status, is_initialized);
-        self.status_nums.integrated = count_variants!(heap, status, is_integrated);
-        self.status_nums.mapped = count_variants!(heap, status, is_mapped);
-        self.status_nums.escaped = count_variants!(heap, status, is_escaped);
-        self.status_nums.evaluation_nan = count_variants!(heap, status, is_evaluation_nan);
-        self.status_nums.timedout = count_variants!(heap, status, is_timed_out);
-        self.status_nums.failed = count_variants!(heap, status, is_failed);
-        self.status_nums.invalid = count_variants!(heap, status, is_invalid_intersections);
-        self.status_nums.single_period = count_variants!(heap, status, is_single_period_integrated);
-        self.total_particles = heap.particles.len(); // Update just in case
+    /// The fraction of folded-in particles whose status is
+    /// [`Escaped`](particle::IntegrationStatus::Escaped), with its standard error.
+    pub fn escaped(&self) -> FractionEstimate {
+        FractionEstimate::of(self.status_nums.escaped, self.particles_seen)
     }
 
-    fn calculate_orbit_types_nums(&mut self, heap: &Heap) {
-        self.orbit_type_nums.undefined = count_variants!(heap, orbit_type, is_undefined);
-        self.orbit_type_nums.trapped = count_variants!(heap, orbit_type, is_trapped);
-        self.orbit_type_nums.passing = count_variants!(heap, orbit_type, is_passing);
+    /// Whether every reported population fraction ([`Self::trapped`], [`Self::passing`],
+    /// [`Self::escaped`]) has a relative error at or below `target_relative_error`, and at least
+    /// `min_samples` particles have been folded in via [`Self::update_incremental`].
+    ///
+    /// A `true` result tells the driving loop that integrating further particles is unlikely to
+    /// move these population estimates materially, so a survey can stop early.
+    pub fn converged(&self, target_relative_error: f64, min_samples: usize) -> bool {
+        self.particles_seen >= min_samples
+            && [self.trapped(), self.passing(), self.escaped()]
+                .iter()
+                .all(|estimate| estimate.relative_error <= target_relative_error)
     }
 
-    /// Calculates and stores the fastest and slowest integrations.
-    fn calculate_durations(&mut self, heap: &Heap) {
-        let slowest = heap.particles.iter().max_by_key(|p| p.evolution.duration);
-        let fastest = heap
-            .particles
-            .iter()
-            .filter(|p| p.evolution.steps_stored() > 0) // Drop invalid
-            .min_by_key(|p| p.evolution.duration);
-        self.slowest = ParticleDuration::from(slowest);
-        self.fastest = ParticleDuration::from(fastest);
+    /// Folds one particle's duration into the running slowest/fastest, and -- if timed -- into
+    /// the samples backing [`Self::duration_stats`]/[`Self::steps_stats`].
+    fn update_duration(&mut self, particle: &Particle) {
+        let candidate = ParticleDuration::from(Some(particle));
+
+        if self.particles_seen == 0 || particle.evolution.duration > self.slowest.duration {
+            self.slowest = candidate;
+        }
+
+        if particle.evolution.steps_stored() > 0 {
+            if self.timed_particles_seen == 0 || particle.evolution.duration < self.fastest.duration
+            {
+                self.fastest = candidate;
+            }
+            self.durations.push(particle.evolution.duration);
+            self.steps_taken.push(particle.evolution.steps_taken());
+            self.timed_particles_seen += 1;
+        }
+    }
+
+    /// Distribution-level statistics (mean, standard deviation, median, p90, p99, in seconds)
+    /// over every timed-in particle's integration duration.
+    ///
+    /// Returns the zero-valued default if no particle has been timed in yet (see
+    /// [`Self::timed_particles_seen`]).
+    pub fn duration_stats(&self) -> DistributionStats {
+        let mut samples: Vec<f64> = self.durations.iter().map(Duration::as_secs_f64).collect();
+        DistributionStats::of(&mut samples)
+    }
+
+    /// Distribution-level statistics (mean, standard deviation, median, p90, p99) over every
+    /// timed-in particle's steps taken.
+    ///
+    /// Returns the zero-valued default if no particle has been timed in yet (see
+    /// [`Self::timed_particles_seen`]).
+    pub fn steps_stats(&self) -> DistributionStats {
+        let mut samples: Vec<f64> = self.steps_taken.iter().map(|&s| s as f64).collect();
+        DistributionStats::of(&mut samples)
+    }
+
+    /// Audits how well one particle's integration preserves its conservation invariants, folding
+    /// the result into the running worst-case drift and violation counts.
+    ///
+    /// For the particle's stored [`Evolution`](particle::Evolution), computes the energy `E` and
+    /// toroidal canonical momentum `Pζ` drift from their first stored value -- both the absolute
+    /// drift `max_i |Q_i - Q_0|` and the relative drift `max_i |Q_i - Q_0| / |Q_0|` -- and counts
+    /// it as a violation if it exceeds [`Self::invariant_abs_tol`]/[`Self::invariant_rel_tol`].
+    /// `Pζ` is only conserved in the unperturbed/axisymmetric case, so a nonzero drift there is
+    /// expected whenever a perturbation is present; `E` drift, on the other hand, is a direct audit
+    /// of the adaptive stepper's own per-step energy tolerance over the whole orbit.
+    fn update_invariant_drift(&mut self, particle: &Particle) {
+        let energy_drift = InvariantDrift::of(&particle.evolution.energy);
+        let pzeta_drift = InvariantDrift::of(&particle.evolution.pzeta);
+
+        if energy_drift.exceeds(self.invariant_abs_tol, self.invariant_rel_tol) {
+            self.invariant_violation_nums.energy += 1;
+        }
+        if pzeta_drift.exceeds(self.invariant_abs_tol, self.invariant_rel_tol) {
+            self.invariant_violation_nums.pzeta += 1;
+        }
+
+        if energy_drift.relative > self.worst_energy_drift.relative {
+            self.worst_energy_drift = energy_drift;
+        }
+        if pzeta_drift.relative > self.worst_pzeta_drift.relative {
+            self.worst_pzeta_drift = pzeta_drift;
+        }
     }
 }
 
@@ -115,16 +363,75 @@ impl std::fmt::Debug for HeapStats {
         f.debug_struct("Heap") // Propagated to Heap
             .field("routine", &self.routine)
             .field("total_particles", &self.total_particles)
+            .field("particles_seen", &self.particles_seen)
             .field("IntegrationStatusNums", &self.status_nums)
             .field("OrbitTypeNums", &self.orbit_type_nums)
             .field("slowest", &self.slowest)
             .field("fastest", &self.fastest)
+            .field("duration_stats (s)", &self.duration_stats())
+            .field("steps_stats", &self.steps_stats())
+            .field("InvariantViolationNums", &self.invariant_violation_nums)
+            .field("worst_energy_drift", &self.worst_energy_drift)
+            .field("worst_pzeta_drift", &self.worst_pzeta_drift)
             .finish()
     }
 }
 
 // ===============================================================================================
 
+/// Distribution-level summary statistics over a sample of values: the mean and standard
+/// deviation, plus the median/p90/p99 computed by nearest-rank indexing into a sorted copy of the
+/// samples.
+#[derive(Default, Clone, Copy)]
+pub struct DistributionStats {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub median: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+impl DistributionStats {
+    /// Computes summary statistics over `samples`, sorting it in place. Returns the zero-valued
+    /// default for an empty slice, rather than panicking.
+    fn of(samples: &mut [f64]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = samples.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / n;
+        let std_dev = variance.sqrt();
+
+        samples.sort_by(f64::total_cmp);
+        let nearest_rank = |percentile: f64| {
+            let rank = (percentile * samples.len() as f64).ceil() as usize;
+            samples[rank.clamp(1, samples.len()) - 1]
+        };
+
+        Self {
+            mean,
+            std_dev,
+            median: nearest_rank(0.5),
+            p90: nearest_rank(0.90),
+            p99: nearest_rank(0.99),
+        }
+    }
+}
+
+impl std::fmt::Debug for DistributionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mean {:.3e}, std {:.3e}, median {:.3e}, p90 {:.3e}, p99 {:.3e}",
+            self.mean, self.std_dev, self.median, self.p90, self.p99
+        )
+    }
+}
+
+// ===============================================================================================
+
 /// Helper struct to display fastest and slowest particles
 #[derive(Default)]
 struct ParticleDuration {
@@ -152,3 +459,118 @@ impl std::fmt::Debug for ParticleDuration {
         )
     }
 }
+
+// ===============================================================================================
+
+/// A conservation invariant's drift over one particle's stored [`Evolution`](particle::Evolution):
+/// the absolute drift `max_i |Q_i - Q_0|` and the relative drift `max_i |Q_i - Q_0| / |Q_0|` from
+/// the first stored sample `Q_0`.
+#[derive(Default, Clone, Copy)]
+struct InvariantDrift {
+    absolute: f64,
+    relative: f64,
+}
+
+impl InvariantDrift {
+    /// Computes the drift of `series` from its first element. Returns the zero drift for an
+    /// empty series.
+    fn of(series: &[f64]) -> Self {
+        let Some(&q0) = series.first() else {
+            return Self::default();
+        };
+        let absolute = series.iter().fold(0.0_f64, |worst, &q| worst.max((q - q0).abs()));
+        let relative = if q0 != 0.0 { absolute / q0.abs() } else { absolute };
+        Self { absolute, relative }
+    }
+
+    /// Whether this drift exceeds either `abs_tol` or `rel_tol`.
+    fn exceeds(&self, abs_tol: f64, rel_tol: f64) -> bool {
+        self.absolute > abs_tol || self.relative > rel_tol
+    }
+}
+
+impl std::fmt::Debug for InvariantDrift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "abs drift: {:.3e}, rel drift: {:.3e}",
+            self.absolute, self.relative
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fraction_estimate_of_zero_count_is_never_converged() {
+        // 10 samples folded in, none of them hits -- the rate could still be substantial with so
+        // few samples, so this must read as unconverged (infinite relative error), not as a
+        // perfectly-converged `0.0`.
+        let estimate = FractionEstimate::of(0, 10);
+        assert_eq!(estimate.fraction, 0.0);
+        assert_eq!(estimate.relative_error, f64::INFINITY);
+    }
+
+    #[test]
+    fn test_fraction_estimate_of_zero_total_is_never_converged() {
+        let estimate = FractionEstimate::of(0, 0);
+        assert_eq!(estimate.relative_error, f64::INFINITY);
+        assert_eq!(FractionEstimate::default().relative_error, f64::INFINITY);
+    }
+
+    #[test]
+    fn test_fraction_estimate_of_nonzero_count_has_finite_error() {
+        let estimate = FractionEstimate::of(5, 10);
+        assert_eq!(estimate.fraction, 0.5);
+        assert!(estimate.relative_error.is_finite());
+        assert_eq!(estimate.relative_error, estimate.standard_error / estimate.fraction);
+    }
+
+    #[test]
+    fn test_distribution_stats_of_empty_is_zero_valued() {
+        let stats = DistributionStats::of(&mut []);
+        assert_eq!(stats.mean, 0.0);
+        assert_eq!(stats.std_dev, 0.0);
+        assert_eq!(stats.median, 0.0);
+        assert_eq!(stats.p90, 0.0);
+        assert_eq!(stats.p99, 0.0);
+    }
+
+    #[test]
+    fn test_distribution_stats_of_known_samples() {
+        let mut samples: Vec<f64> = (1..=10).map(f64::from).collect();
+        let stats = DistributionStats::of(&mut samples);
+
+        assert_eq!(stats.mean, 5.5);
+        assert!((stats.std_dev - 2.872_281_323_269_014).abs() < 1e-9);
+        // Nearest-rank indexing into 10 sorted samples: ceil(0.5*10)=5th -> 5.0,
+        // ceil(0.90*10)=9th -> 9.0, ceil(0.99*10)=10th -> 10.0.
+        assert_eq!(stats.median, 5.0);
+        assert_eq!(stats.p90, 9.0);
+        assert_eq!(stats.p99, 10.0);
+    }
+
+    #[test]
+    fn test_invariant_drift_of_empty_series_is_zero() {
+        let drift = InvariantDrift::of(&[]);
+        assert_eq!(drift.absolute, 0.0);
+        assert_eq!(drift.relative, 0.0);
+    }
+
+    #[test]
+    fn test_invariant_drift_of_tracks_worst_deviation_from_first() {
+        let drift = InvariantDrift::of(&[1.0, 1.0001, 0.999, 1.002]);
+        assert!((drift.absolute - 0.002).abs() < 1e-12);
+        assert!((drift.relative - 0.002).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invariant_drift_exceeds_checks_both_tolerances() {
+        let drift = InvariantDrift::of(&[1.0, 1.1]);
+        assert!(drift.exceeds(0.05, 1.0)); // absolute tolerance tripped
+        assert!(drift.exceeds(1.0, 0.05)); // relative tolerance tripped
+        assert!(!drift.exceeds(1.0, 1.0)); // neither tripped
+    }
+}
@@ -0,0 +1,155 @@
+//! Monte Carlo Lorentz pitch-angle scattering, for collisional integration under
+//! [`CollisionConfig`](crate::CollisionConfig).
+//!
+//! Per step of length `Δt`, [`pitch_angle_kick`] rotates the pitch `ξ = v∥/v` by
+//! `ξ_new = ξ(1 − νΔt) + R·√((1 − ξ²)·νΔt)`, where `R` is a zero-mean, unit-variance random
+//! variate and `ν` is the local deflection frequency (see [`deflection_frequency`]). This
+//! conserves kinetic energy exactly -- only the velocity direction is rotated -- matching the
+//! standard reduced (pitch-angle-only) Monte Carlo collision operator.
+//!
+//! Because ensembles are mapped under rayon, two runs of the same ensemble must scatter every
+//! particle identically regardless of which worker thread happens to process it. [`CollisionRng`]
+//! seeds a fresh stream per particle from `(global seed, particle index)` rather than sharing one
+//! generator across the pool, so the result is reproducible independent of thread scheduling --
+//! the same approach WHIZARD uses for consistent random sequencing during parallel event
+//! generation. [`CollisionRng`] (SplitMix64 + Box-Muller) is also reused directly by
+//! `heap::sampling`'s robustness-study jitter via [`CollisionRng::new`], rather than each crate
+//! keeping its own copy -- no external RNG crate is used anywhere in this workspace, so this stays
+//! the one place that PRNG lives.
+//!
+//! Wiring [`pitch_angle_kick`] into the stepper itself -- recomputing `ρ`/`μ`-consistent
+//! components from the rotated `ξ` after each step -- additionally requires `rkf45::Stepper` and
+//! `state::State`, which this checkout's `rkf45`/`state` modules do not contain.
+//!
+//! A later request asked for the rest of that wiring: a stochastic stepping routine, as a sibling
+//! of [`routines::integrate`](crate::routines)/[`routines::map_integrate`](crate::routines),
+//! that advances the deterministic step and then applies [`pitch_angle_kick`] using
+//! [`CollisionConfig`](crate::CollisionConfig)'s `collision_freq`/`seed`, plus an ensemble entry
+//! point returning the per-step state history for diffusion/transport statistics.
+//! [`CollisionConfig`](crate::CollisionConfig) (implementing
+//! [`StepperConfig`](crate::config::StepperConfig) like the crate's other configs) is addable on
+//! its own, and now exists; the routine itself is still blocked on the same `rkf45::Stepper`/
+//! `state::State` gap above, since recomputing `ρ`/`μ` from the rotated `ξ` after a step needs
+//! those types' internals to do the recomputation in.
+
+/// The Coulomb logarithm `ln Λ`, held fixed rather than evaluated self-consistently from the
+/// local density/temperature -- it varies only logarithmically across typical tokamak parameters,
+/// so a constant is a standard simplifying approximation for a reduced collision operator.
+const COULOMB_LOGARITHM: f64 = 17.0;
+
+/// Evaluates a Spitzer-like electron deflection frequency `ν ∝ n_e / T_e^{3/2}`, in the same
+/// normalized units as the orbit's own time coordinate.
+///
+/// This is a deliberate simplification, not a self-consistent evaluation of the Coulomb
+/// logarithm or the full Lorentz collision integral -- it only captures the standard scaling with
+/// background density `n_e` and temperature `T_e`, holding [`COULOMB_LOGARITHM`] fixed.
+pub fn deflection_frequency(n_e: f64, t_e: f64) -> f64 {
+    const SPITZER_PREFACTOR: f64 = 1e-11;
+    SPITZER_PREFACTOR * COULOMB_LOGARITHM * n_e / t_e.powf(1.5)
+}
+
+/// Rotates the pitch `ξ = v∥/v` by one Monte Carlo pitch-angle scattering kick of step `dt`,
+/// given the local deflection frequency `nu` and a zero-mean, unit-variance random variate `r`
+/// (see [`CollisionRng::next_gaussian`]).
+///
+/// The result is clamped to `[-1, 1]`, since the update is only first-order accurate in `nu * dt`
+/// and can otherwise overshoot the physical range for a large step or a rare large `r`.
+pub fn pitch_angle_kick(xi: f64, nu: f64, dt: f64, r: f64) -> f64 {
+    let nu_dt = nu * dt;
+    let kicked = xi * (1.0 - nu_dt) + r * ((1.0 - xi * xi) * nu_dt).max(0.0).sqrt();
+    kicked.clamp(-1.0, 1.0)
+}
+
+/// A minimal, self-seeded PRNG (SplitMix64) plus a Box-Muller transform for Gaussian samples,
+/// seeded deterministically per particle so an ensemble mapped under rayon scatters identically
+/// regardless of thread scheduling.
+///
+/// The sole SplitMix64 + Box-Muller generator in this workspace -- other crates needing a PRNG
+/// stream reuse this type (via [`CollisionRng::new`]) rather than keeping their own copy.
+pub struct CollisionRng(u64);
+
+impl CollisionRng {
+    /// Seeds a fresh stream for `particle_index`, deterministically derived from `global_seed` so
+    /// distinct particles never share a stream and a given particle always scatters identically
+    /// across repeated runs.
+    pub fn for_particle(global_seed: u64, particle_index: u64) -> Self {
+        // Mix the index in via one SplitMix64 round before seeding, rather than e.g. adding it
+        // directly, so nearby indices don't produce near-identical initial states.
+        let mut seed = global_seed ^ particle_index.wrapping_mul(0x9E3779B97F4A7C15);
+        seed = (seed ^ (seed >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        Self(seed)
+    }
+
+    /// Seeds a stream directly from `seed`, with no particle-index mixing. For callers that
+    /// already have one unique stream to draw an entire sequence from (e.g.
+    /// `heap::sampling::run_robustness_study`'s ensemble jitter), rather than one stream per
+    /// particle.
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform sample in `(0, 1]`, never exactly `0.0` so [`Self::next_gaussian`]'s `ln()` stays
+    /// finite. Also used directly by [`crate::sampling::sample_ensemble`] callers that need a
+    /// uniform (rather than Gaussian) distribution.
+    pub(crate) fn next_unit(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 1.0) / (1u64 << 53) as f64
+    }
+
+    /// A standard-normal sample, via the Box-Muller transform.
+    pub fn next_gaussian(&mut self) -> f64 {
+        let u1 = self.next_unit();
+        let u2 = self.next_unit();
+        (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pitch_angle_kick_conserves_range() {
+        for &xi in &[-1.0, -0.5, 0.0, 0.5, 1.0] {
+            for &r in &[-5.0, 0.0, 5.0] {
+                let kicked = pitch_angle_kick(xi, 1.0, 0.1, r);
+                assert!((-1.0..=1.0).contains(&kicked));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pitch_angle_kick_zero_rate_is_identity() {
+        assert_eq!(pitch_angle_kick(0.42, 0.0, 0.1, 3.0), 0.42);
+    }
+
+    #[test]
+    fn test_collision_rng_is_deterministic_per_particle() {
+        let mut a = CollisionRng::for_particle(7, 3);
+        let mut b = CollisionRng::for_particle(7, 3);
+        assert_eq!(a.next_gaussian(), b.next_gaussian());
+    }
+
+    #[test]
+    fn test_collision_rng_differs_across_particles() {
+        let mut a = CollisionRng::for_particle(7, 3);
+        let mut b = CollisionRng::for_particle(7, 4);
+        assert_ne!(a.next_gaussian(), b.next_gaussian());
+    }
+
+    #[test]
+    fn test_collision_rng_new_is_deterministic() {
+        let mut a = CollisionRng::new(42);
+        let mut b = CollisionRng::new(42);
+        for _ in 0..5 {
+            assert_eq!(a.next_gaussian(), b.next_gaussian());
+        }
+    }
+}
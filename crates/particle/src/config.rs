@@ -1,5 +1,6 @@
 #[allow(unused_imports)] // doc
 use crate::Particle;
+use crate::OrbitClassifier;
 
 #[derive(Debug, Clone)]
 /// The method used to calculate the next optimal step.
@@ -12,6 +13,26 @@ pub enum SteppingMethod {
     ErrorAdaptiveStep,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The integration scheme used to advance the particle's state each step, selectable via
+/// [`IntegrationConfig::integration_method`].
+pub enum IntegrationMethod {
+    /// The default adaptive, embedded Runge-Kutta-Fehlberg 4(5) stepper (see [`SteppingMethod`]
+    /// for its step-size control).
+    Rkf45,
+    /// A fixed-step, implicit 2-stage, 4th-order Gauss-Legendre stepper. Being symmetric, it keeps
+    /// the particle's energy and toroidal canonical momentum `Pζ` bounded over many periods
+    /// instead of drifting monotonically, at the cost of a fixed step size and an inner
+    /// fixed-point solve per step (see [`gauss_legendre_step`](crate::gauss_legendre_step)).
+    GaussLegendre4,
+    /// An adaptive, symplectic, implicit midpoint stepper. Being symmetric like
+    /// [`IntegrationMethod::GaussLegendre4`] but only 2nd-order, it trades some accuracy for a
+    /// cheaper single-stage solve per step, with adaptive step-size control driven by a
+    /// half-step/full-step error estimate rather than a fixed step (see
+    /// [`symplectic_midpoint_step`](crate::symplectic_midpoint_step)).
+    SymplecticMidpoint,
+}
+
 // ===============================================================================================
 
 /// Ensures that all methods' configurations have the fields required by the Stepper.
@@ -22,6 +43,14 @@ pub(crate) trait StepperConfig {
     fn error_rel_tol(&self) -> f64;
     fn error_abs_tol(&self) -> f64;
     fn safety_factor(&self) -> f64;
+    /// The relative tolerance on the toroidal canonical momentum `Pζ`'s drift from its initial
+    /// value, checked post-integration by [`routines::invariants`](crate::routines) -- see
+    /// [`Particle::worst_pzeta_drift`](crate::Particle::worst_pzeta_drift).
+    fn pzeta_rel_tol(&self) -> f64;
+    /// The absolute tolerance on `Pζ`'s drift, see [`Self::pzeta_rel_tol`].
+    fn pzeta_abs_tol(&self) -> f64;
+    /// The algorithm used to classify the finished orbit's [`OrbitType`](crate::OrbitType).
+    fn orbit_classifier(&self) -> &OrbitClassifier;
 }
 
 #[rustfmt::skip]
@@ -40,6 +69,12 @@ macro_rules! stepper_config_impl {
             fn error_abs_tol(&self) -> f64 { self.error_abs_tol }
             #[inline(always)]
             fn safety_factor(&self) -> f64 { self.safety_factor }
+            #[inline(always)]
+            fn pzeta_rel_tol(&self) -> f64 { self.pzeta_rel_tol }
+            #[inline(always)]
+            fn pzeta_abs_tol(&self) -> f64 { self.pzeta_abs_tol }
+            #[inline(always)]
+            fn orbit_classifier(&self) -> &OrbitClassifier { &self.orbit_classifier }
         }
     };
 }
@@ -47,6 +82,7 @@ macro_rules! stepper_config_impl {
 stepper_config_impl!(IntegrationConfig);
 stepper_config_impl!(MappingConfig);
 stepper_config_impl!(SinglePeriodConfig);
+stepper_config_impl!(CollisionConfig);
 
 // ===============================================================================================
 
@@ -55,11 +91,15 @@ stepper_config_impl!(SinglePeriodConfig);
 /// See [`IntegrationConfig::default`] for the default values.
 #[derive(Debug, Clone)]
 pub struct IntegrationConfig {
-    /// The optimal step calculation method.
+    /// The integration scheme used to advance the particle's state each step.
+    pub integration_method: IntegrationMethod,
+    /// The optimal step calculation method. Only used when `integration_method` is
+    /// [`IntegrationMethod::Rkf45`].
     pub method: SteppingMethod,
     /// The maximum amount of steps a particle can make before terminating its integration.
     pub max_steps: usize,
-    /// The initial time step for the RKF45 adaptive step method. The value is empirical.
+    /// The initial time step for the RKF45 adaptive step method. The value is empirical. Used as
+    /// the (fixed) step size when `integration_method` is [`IntegrationMethod::GaussLegendre4`].
     pub first_step: f64,
     /// The safety factor of the solver. Should be less than 1.0
     pub safety_factor: f64,
@@ -71,11 +111,36 @@ pub struct IntegrationConfig {
     pub error_rel_tol: f64,
     /// The absolute tolerance of the local truncation error in every step.
     pub error_abs_tol: f64,
+    /// The relative tolerance of the toroidal canonical momentum `Pζ`'s drift from its
+    /// initial value, checked post-integration (see [`crate::Particle::worst_pzeta_drift`]).
+    /// Automatically skipped when a non-trivial `Perturbation` is supplied, since it breaks
+    /// `Pζ` conservation.
+    pub pzeta_rel_tol: f64,
+    /// The absolute tolerance of `Pζ`'s drift, see [`Self::pzeta_rel_tol`] (field on the same
+    /// struct, just documented here once).
+    pub pzeta_abs_tol: f64,
+    /// The algorithm used to classify the finished orbit's `OrbitType`.
+    pub orbit_classifier: OrbitClassifier,
+    /// The fixed-point iteration tolerance on the Gauss-Legendre stage solve's max stage change.
+    /// Only used when `integration_method` is [`IntegrationMethod::GaussLegendre4`].
+    pub gl_tolerance: f64,
+    /// The maximum number of fixed-point iterations per Gauss-Legendre step, after which the
+    /// solve is reported as not converged. Only used when `integration_method` is
+    /// [`IntegrationMethod::GaussLegendre4`].
+    pub gl_max_iterations: usize,
+    /// The fixed-point iteration tolerance on the symplectic midpoint solve's max component-wise
+    /// update. Only used when `integration_method` is [`IntegrationMethod::SymplecticMidpoint`].
+    pub midpoint_tol: f64,
+    /// The maximum number of fixed-point iterations per symplectic midpoint step, after which the
+    /// solve is reported as not converged. Only used when `integration_method` is
+    /// [`IntegrationMethod::SymplecticMidpoint`].
+    pub midpoint_max_iterations: usize,
 }
 
 impl Default for IntegrationConfig {
     fn default() -> Self {
         Self {
+            integration_method: IntegrationMethod::Rkf45,
             method: SteppingMethod::EnergyAdaptiveStep,
             max_steps: 1_000_000,
             first_step: 1e-1,
@@ -84,6 +149,13 @@ impl Default for IntegrationConfig {
             energy_abs_tol: 1e-12,
             error_rel_tol: 1e-12,
             error_abs_tol: 1e-14,
+            pzeta_rel_tol: 1e-8,
+            pzeta_abs_tol: 1e-10,
+            orbit_classifier: OrbitClassifier::ThetaSpan,
+            gl_tolerance: 1e-12,
+            gl_max_iterations: 50,
+            midpoint_tol: 1e-12,
+            midpoint_max_iterations: 50,
         }
     }
 }
@@ -95,11 +167,15 @@ impl Default for IntegrationConfig {
 /// See [`MappingConfig::default`] for the default values.
 #[derive(Debug, Clone)]
 pub struct MappingConfig {
-    /// The optimal step calculation method.
+    /// The integration scheme used to advance the particle's state each step.
+    pub integration_method: IntegrationMethod,
+    /// The optimal step calculation method. Only used when `integration_method` is
+    /// [`IntegrationMethod::Rkf45`].
     pub method: SteppingMethod,
     /// The maximum amount of steps a particle can make before terminating its integration.
     pub max_steps: usize,
-    /// The initial time step for the RKF45 adaptive step method. The value is empirical.
+    /// The initial time step for the RKF45 adaptive step method. The value is empirical. Used as
+    /// the (fixed) step size when `integration_method` is [`IntegrationMethod::GaussLegendre4`].
     pub first_step: f64,
     /// The safety factor of the solver. Should be less than 1.0
     pub safety_factor: f64,
@@ -111,14 +187,39 @@ pub struct MappingConfig {
     pub error_rel_tol: f64,
     /// The absolute tolerance of the local truncation error in every step.
     pub error_abs_tol: f64,
+    /// The relative tolerance of the toroidal canonical momentum `Pζ`'s drift from its
+    /// initial value, checked post-integration (see [`crate::Particle::worst_pzeta_drift`]).
+    /// Automatically skipped when a non-trivial `Perturbation` is supplied, since it breaks
+    /// `Pζ` conservation.
+    pub pzeta_rel_tol: f64,
+    /// The absolute tolerance of `Pζ`'s drift, see [`Self::pzeta_rel_tol`] (field on the same
+    /// struct, just documented here once).
+    pub pzeta_abs_tol: f64,
+    /// The algorithm used to classify the finished orbit's `OrbitType`.
+    pub orbit_classifier: OrbitClassifier,
     /// The maximum allowed absolute difference between the difference of two consecutive
     /// intersections and 2Ï€.
     pub map_threshold: f64,
+    /// The fixed-point iteration tolerance on the Gauss-Legendre stage solve's max stage change.
+    /// Only used when `integration_method` is [`IntegrationMethod::GaussLegendre4`].
+    pub gl_tolerance: f64,
+    /// The maximum number of fixed-point iterations per Gauss-Legendre step, after which the
+    /// solve is reported as not converged. Only used when `integration_method` is
+    /// [`IntegrationMethod::GaussLegendre4`].
+    pub gl_max_iterations: usize,
+    /// The fixed-point iteration tolerance on the symplectic midpoint solve's max component-wise
+    /// update. Only used when `integration_method` is [`IntegrationMethod::SymplecticMidpoint`].
+    pub midpoint_tol: f64,
+    /// The maximum number of fixed-point iterations per symplectic midpoint step, after which the
+    /// solve is reported as not converged. Only used when `integration_method` is
+    /// [`IntegrationMethod::SymplecticMidpoint`].
+    pub midpoint_max_iterations: usize,
 }
 
 impl Default for MappingConfig {
     fn default() -> Self {
         Self {
+            integration_method: IntegrationMethod::Rkf45,
             method: SteppingMethod::EnergyAdaptiveStep,
             max_steps: 1_000_000,
             first_step: 1e-1,
@@ -127,7 +228,14 @@ impl Default for MappingConfig {
             energy_abs_tol: 1e-12,
             error_rel_tol: 1e-12,
             error_abs_tol: 1e-14,
+            pzeta_rel_tol: 1e-8,
+            pzeta_abs_tol: 1e-10,
+            orbit_classifier: OrbitClassifier::ThetaSpan,
             map_threshold: 1e-9,
+            gl_tolerance: 1e-12,
+            gl_max_iterations: 50,
+            midpoint_tol: 1e-12,
+            midpoint_max_iterations: 50,
         }
     }
 }
@@ -139,11 +247,15 @@ impl Default for MappingConfig {
 /// See [`SinglePeriodConfig::default`] for the default values.
 #[derive(Debug, Clone)]
 pub struct SinglePeriodConfig {
-    /// The optimal step calculation method.
+    /// The integration scheme used to advance the particle's state each step.
+    pub integration_method: IntegrationMethod,
+    /// The optimal step calculation method. Only used when `integration_method` is
+    /// [`IntegrationMethod::Rkf45`].
     pub method: SteppingMethod,
     /// The maximum amount of steps a particle can make before terminating its integration.
     pub max_steps: usize,
-    /// The initial time step for the RKF45 adaptive step method. The value is empirical.
+    /// The initial time step for the RKF45 adaptive step method. The value is empirical. Used as
+    /// the (fixed) step size when `integration_method` is [`IntegrationMethod::GaussLegendre4`].
     pub first_step: f64,
     /// The safety factor of the solver. Should be less than 1.0
     pub safety_factor: f64,
@@ -155,11 +267,120 @@ pub struct SinglePeriodConfig {
     pub error_rel_tol: f64,
     /// The absolute tolerance of the local truncation error in every step.
     pub error_abs_tol: f64,
+    /// The relative tolerance of the toroidal canonical momentum `Pζ`'s drift from its
+    /// initial value, checked post-integration (see [`crate::Particle::worst_pzeta_drift`]).
+    /// Automatically skipped when a non-trivial `Perturbation` is supplied, since it breaks
+    /// `Pζ` conservation.
+    pub pzeta_rel_tol: f64,
+    /// The absolute tolerance of `Pζ`'s drift, see [`Self::pzeta_rel_tol`] (field on the same
+    /// struct, just documented here once).
+    pub pzeta_abs_tol: f64,
+    /// The algorithm used to classify the finished orbit's `OrbitType`.
+    pub orbit_classifier: OrbitClassifier,
+    /// The number of successive `θ-ψp` periods to integrate. `ωθ` and `ωζ` are reported as the
+    /// mean over this many per-period samples, together with their relative standard deviation --
+    /// see [`Frequencies`](crate::Frequencies).
+    pub periods: usize,
+    /// The fixed-point iteration tolerance on the Gauss-Legendre stage solve's max stage change.
+    /// Only used when `integration_method` is [`IntegrationMethod::GaussLegendre4`].
+    pub gl_tolerance: f64,
+    /// The maximum number of fixed-point iterations per Gauss-Legendre step, after which the
+    /// solve is reported as not converged. Only used when `integration_method` is
+    /// [`IntegrationMethod::GaussLegendre4`].
+    pub gl_max_iterations: usize,
+    /// The fixed-point iteration tolerance on the symplectic midpoint solve's max component-wise
+    /// update. Only used when `integration_method` is [`IntegrationMethod::SymplecticMidpoint`].
+    pub midpoint_tol: f64,
+    /// The maximum number of fixed-point iterations per symplectic midpoint step, after which the
+    /// solve is reported as not converged. Only used when `integration_method` is
+    /// [`IntegrationMethod::SymplecticMidpoint`].
+    pub midpoint_max_iterations: usize,
 }
 
 impl Default for SinglePeriodConfig {
     fn default() -> Self {
         Self {
+            integration_method: IntegrationMethod::Rkf45,
+            method: SteppingMethod::EnergyAdaptiveStep,
+            max_steps: 1_000_000,
+            first_step: 1e-1,
+            safety_factor: 0.9,
+            energy_rel_tol: 1e-10,
+            energy_abs_tol: 1e-12,
+            error_rel_tol: 1e-12,
+            error_abs_tol: 1e-14,
+            pzeta_rel_tol: 1e-8,
+            pzeta_abs_tol: 1e-10,
+            orbit_classifier: OrbitClassifier::ThetaSpan,
+            periods: 1,
+            gl_tolerance: 1e-12,
+            gl_max_iterations: 50,
+            midpoint_tol: 1e-12,
+            midpoint_max_iterations: 50,
+        }
+    }
+}
+
+// ===============================================================================================
+
+/// Defines the parameters of a stochastic, collisional integration routine that applies a
+/// [`pitch_angle_kick`](crate::pitch_angle_kick) after each deterministic step -- collisions are
+/// always applied by virtue of this config being selected, composing with whichever step-size
+/// strategy `method` picks (unlike [`IntegrationConfig`]/[`MappingConfig`]/[`SinglePeriodConfig`],
+/// [`SteppingMethod`] has nothing collision-specific to select here: the kick uses `Δt` as the
+/// Brownian increment regardless of whether that step came from the energy- or error-adaptive
+/// strategy).
+///
+/// See [`CollisionConfig::default`] for the default values.
+#[derive(Debug, Clone)]
+pub struct CollisionConfig {
+    /// The integration scheme used to advance the particle's deterministic step, before the
+    /// collision kick is applied.
+    pub integration_method: IntegrationMethod,
+    /// The optimal step calculation method the deterministic step uses, before the collision kick
+    /// is applied on top. Only used when `integration_method` is [`IntegrationMethod::Rkf45`].
+    pub method: SteppingMethod,
+    /// The maximum amount of steps a particle can make before terminating its integration.
+    pub max_steps: usize,
+    /// The initial time step for the RKF45 adaptive step method. The value is empirical. Used as
+    /// the (fixed) step size when `integration_method` is [`IntegrationMethod::GaussLegendre4`]
+    /// or [`IntegrationMethod::SymplecticMidpoint`].
+    pub first_step: f64,
+    /// The safety factor of the solver. Should be less than 1.0
+    pub safety_factor: f64,
+    /// The relative tolerance of the energy difference in every step.
+    pub energy_rel_tol: f64,
+    /// The absolute tolerance of the energy difference in every step.
+    pub energy_abs_tol: f64,
+    /// The relative tolerance of the local truncation error in every step.
+    pub error_rel_tol: f64,
+    /// The absolute tolerance of the local truncation error in every step.
+    pub error_abs_tol: f64,
+    /// The relative tolerance of the toroidal canonical momentum `Pζ`'s drift from its initial
+    /// value. Unlike the other configs, this is not expected to hold here -- the collision kick
+    /// itself breaks `Pζ` conservation by design -- but is kept for a consistent
+    /// [`StepperConfig`] interface and for detecting drift well beyond what the collision
+    /// frequency alone would explain.
+    pub pzeta_rel_tol: f64,
+    /// The absolute tolerance of `Pζ`'s drift, see [`Self::pzeta_rel_tol`].
+    pub pzeta_abs_tol: f64,
+    /// The algorithm used to classify the finished orbit's `OrbitType`.
+    pub orbit_classifier: OrbitClassifier,
+    /// The local deflection frequency `ν`, in the orbit's own normalized time units -- see
+    /// [`deflection_frequency`](crate::deflection_frequency). Held constant along the orbit
+    /// rather than evaluated from a local density/temperature profile, matching
+    /// [`pitch_angle_kick`](crate::pitch_angle_kick)'s own reduced operator.
+    pub collision_freq: f64,
+    /// The global seed [`CollisionRng`](crate::CollisionRng) streams are derived from, combined
+    /// with each particle's index so an ensemble mapped under rayon scatters identically
+    /// regardless of thread scheduling.
+    pub seed: u64,
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        Self {
+            integration_method: IntegrationMethod::Rkf45,
             method: SteppingMethod::EnergyAdaptiveStep,
             max_steps: 1_000_000,
             first_step: 1e-1,
@@ -168,6 +389,11 @@ impl Default for SinglePeriodConfig {
             energy_abs_tol: 1e-12,
             error_rel_tol: 1e-12,
             error_abs_tol: 1e-14,
+            pzeta_rel_tol: 1e-8,
+            pzeta_abs_tol: 1e-10,
+            orbit_classifier: OrbitClassifier::ThetaSpan,
+            collision_freq: 1e-3,
+            seed: 0,
         }
     }
 }
@@ -0,0 +1,71 @@
+//! Cubic Hermite dense-output interpolant for reconstructing a state anywhere inside an accepted
+//! Runge-Kutta step, given only its endpoint values and derivatives.
+//!
+//! Given `y0 = y(t_n)`, `f0 = ẏ(t_n)`, `y1 = y(t_n+h)`, `f1 = ẏ(t_n+h)`, the cubic Hermite
+//! polynomial `H(θ)` for `θ = (t-t_n)/h ∈ [0,1]` matches `y0`, `y1`, and both derivatives exactly,
+//! giving third-order accuracy anywhere inside the step -- strictly better than the secant-based
+//! linear interpolation a root-finder would otherwise fall back to between whole steps.
+//!
+//! This is the numerical core for locating Poincare-section crossings to integrator accuracy
+//! instead of re-integrating a fresh sub-step per root-finding iteration; wiring it into
+//! `rkf45::Stepper` additionally requires that stepper to retain its accepted step's stage
+//! derivatives, which this checkout's `rkf45` module does not currently expose.
+//!
+//! A later request asked for exactly that wiring: have the stepper store each accepted step's
+//! `(t_n, y_n, f_n, h_n)` in [`Evolution`](crate::Evolution) and have `state_at` binary-search the
+//! bracketing step and evaluate [`hermite_dense_output`] directly, reusing the step's endpoint
+//! derivatives instead of [`routines::resample`](crate::routines::resample)'s
+//! [`PchipSpline`](equilibrium::PchipSpline) refit. The blocker is unchanged: both `y_n`'s stage
+//! derivative `f_n` and the step size `h_n` are internal to `rkf45::Stepper`, which still isn't
+//! part of this checkout, so there is nothing here yet to store them in or bracket over.
+//! [`routines::resample`](crate::routines::resample) remains the only `state_at` implementation
+//! this checkout can provide.
+
+/// Evaluates the cubic Hermite dense-output interpolant at `theta = (t - t_n)/h ∈ [0, 1]`, given
+/// the step's endpoint states `y0`/`y1` and derivatives `f0`/`f1`, and the step size `h`.
+pub fn hermite_dense_output(y0: &[f64], f0: &[f64], y1: &[f64], f1: &[f64], h: f64, theta: f64) -> Vec<f64> {
+    let t2 = theta * theta;
+    let t3 = t2 * theta;
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + theta;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+
+    (0..y0.len())
+        .map(|i| h00 * y0[i] + h10 * h * f0[i] + h01 * y1[i] + h11 * h * f1[i])
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hermite_matches_endpoints() {
+        let y0 = [0.0];
+        let y1 = [1.0];
+        let f0 = [1.0];
+        let f1 = [1.0];
+        let h = 1.0;
+
+        let at0 = hermite_dense_output(&y0, &f0, &y1, &f1, h, 0.0);
+        let at1 = hermite_dense_output(&y0, &f0, &y1, &f1, h, 1.0);
+        assert!((at0[0] - y0[0]).abs() < 1e-12);
+        assert!((at1[0] - y1[0]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_hermite_reproduces_linear_motion() {
+        // y(t) = t over [0,1], y'=1 everywhere => the interpolant is exact at any theta.
+        let y0 = [0.0];
+        let y1 = [1.0];
+        let f0 = [1.0];
+        let f1 = [1.0];
+        let h = 1.0;
+
+        for &theta in &[0.0, 0.25, 0.5, 0.75, 1.0] {
+            let y = hermite_dense_output(&y0, &f0, &y1, &f1, h, theta);
+            assert!((y[0] - theta).abs() < 1e-12);
+        }
+    }
+}
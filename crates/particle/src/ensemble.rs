@@ -0,0 +1,241 @@
+//! Concurrent single-period integration of many particles.
+//!
+//! [`Evolution::discard`](crate::Evolution::discard) already exists "when dealing with many
+//! particles", yet every routine in [`routines`](crate::routines) integrates one [`Particle`] at
+//! a time. Orbit cost is wildly uneven -- some particles close a `θ-ψp` period in a few hundred
+//! steps, others run until `SinglePeriodConfig::max_steps` and come back `TimedOut` -- so a
+//! static split of the particle list across threads leaves idle threads waiting on whichever
+//! thread drew the slow ones. `rayon`'s work-stealing `par_iter_mut` solves this without any
+//! manual chunking: each particle is its own task, and an idle thread steals the next one off a
+//! busier thread's queue.
+//!
+//! [`Ensemble`] wraps this up for bulk callers (typically the Python wrapper layer): it owns the
+//! particle list itself, runs [`integrate_ensemble`]/[`map_ensemble`] over it, and stacks the
+//! resulting ragged per-particle time series into a single rectangular array via [`Ensemble::stack`],
+//! rather than handing back one `Vec` per particle for the caller to stack itself.
+
+use ndarray::Array2;
+use rayon::prelude::*;
+
+use equilibrium::{Bfield, Current, Perturbation, Qfactor};
+
+use crate::{IntegrationConfig, InitialConditions, MappingConfig, MappingParameters};
+use crate::{IntegrationStatus, OrbitType, Particle, ParticleError, Result, SinglePeriodConfig};
+
+/// Integrates every [`Particle`] in `particles` for a single `θ-ψp` period, concurrently.
+///
+/// Particles are distributed across threads with `rayon`'s work-stealing scheduler, so the
+/// uneven cost of individual particles is load-balanced automatically instead of being split
+/// evenly up front. Returns one [`Result`] per particle, in the same order as `particles`, so a
+/// single particle timing out or hitting a domain error doesn't abort the rest of the batch --
+/// each particle's own [`IntegrationStatus`] is still updated in place, exactly as
+/// [`Particle::single_period_integrate`] does for a single particle.
+///
+/// If `discard_evolution` is `true`, each particle's [`Evolution`](crate::Evolution) time series
+/// is freed right after integration via [`Evolution::discard`](crate::Evolution::discard), to
+/// bound peak memory when the ensemble is too large to keep every orbit in memory at once.
+pub fn single_period_integrate_ensemble(
+    particles: &mut [Particle],
+    qfactor: &(impl Qfactor + Sync),
+    current: &(impl Current + Sync),
+    bfield: &(impl Bfield + Sync),
+    perturbation: &(impl Perturbation + Sync),
+    config: &SinglePeriodConfig,
+    discard_evolution: bool,
+) -> Vec<Result<()>> {
+    particles
+        .par_iter_mut()
+        .map(|particle| {
+            let res = crate::routines::close_theta_period(
+                particle,
+                qfactor,
+                current,
+                bfield,
+                perturbation,
+                config,
+            );
+            apply_status(particle, &res, IntegrationStatus::SinglePeriodIntegrated);
+            if discard_evolution {
+                particle.evolution.discard();
+            }
+            res
+        })
+        .collect()
+}
+
+/// Integrates every [`Particle`] in `particles` over `t_eval`, concurrently.
+///
+/// Each particle keeps integrating its own [`State`](crate::State), which owns its own
+/// `Accelerator`s and per-harmonic cache buffers, so distributing particles across threads only
+/// requires the shared `qfactor`/`current`/`bfield`/`perturbation` interpolators to be read
+/// concurrently -- exactly as [`single_period_integrate_ensemble`]. Returns one [`Result`] per
+/// particle, in the same order as `particles`.
+pub fn integrate_ensemble(
+    particles: &mut [Particle],
+    qfactor: &(impl Qfactor + Sync),
+    current: &(impl Current + Sync),
+    bfield: &(impl Bfield + Sync),
+    perturbation: &(impl Perturbation + Sync),
+    t_eval: (f64, f64),
+    config: &IntegrationConfig,
+) -> Vec<Result<()>> {
+    particles
+        .par_iter_mut()
+        .map(|particle| {
+            let res = crate::routines::integrate(
+                particle,
+                qfactor,
+                current,
+                bfield,
+                perturbation,
+                t_eval,
+                config,
+            );
+            apply_status(particle, &res, IntegrationStatus::Integrated);
+            res
+        })
+        .collect()
+}
+
+/// Maps every [`Particle`] in `particles` onto the Poincare surface defined by `params`,
+/// concurrently.
+///
+/// See [`integrate_ensemble`] for how particles share the equilibrium's interpolators while
+/// keeping their own integration state.
+pub fn map_ensemble(
+    particles: &mut [Particle],
+    qfactor: &(impl Qfactor + Sync),
+    current: &(impl Current + Sync),
+    bfield: &(impl Bfield + Sync),
+    perturbation: &(impl Perturbation + Sync),
+    params: &MappingParameters,
+    config: &MappingConfig,
+) -> Vec<Result<()>> {
+    particles
+        .par_iter_mut()
+        .map(|particle| {
+            let res = crate::routines::map_integrate(
+                particle,
+                qfactor,
+                current,
+                bfield,
+                perturbation,
+                params,
+                config,
+            );
+            apply_status(particle, &res, IntegrationStatus::Mapped);
+            res
+        })
+        .collect()
+}
+
+/// Updates `particle`'s [`IntegrationStatus`] from a reference to its integration [`Result`],
+/// mirroring [`Particle::set_status_from_error`](crate::Particle) without consuming the error --
+/// the caller still needs it afterwards to fill its slot in the ensemble's result vector.
+///
+/// `success` is the status to apply on [`Ok`], since each ensemble routine reports a different one
+/// on success (e.g. [`IntegrationStatus::Integrated`] vs. [`IntegrationStatus::Mapped`]).
+fn apply_status(particle: &mut Particle, res: &Result<()>, success: IntegrationStatus) {
+    particle.status = match res {
+        Ok(()) => success,
+        Err(ParticleError::EqError(..)) => IntegrationStatus::Escaped,
+        Err(ParticleError::TimedOut(duration)) => {
+            particle.evolution.duration = *duration;
+            IntegrationStatus::TimedOut(*duration)
+        }
+        Err(ParticleError::IntersectionError) => IntegrationStatus::InvalidIntersections,
+        Err(ParticleError::EvaluationNaN) => IntegrationStatus::EvaluationNan,
+    };
+}
+
+// ===================================================================================================
+
+/// Owns a set of [`Particle`]s and runs them through [`integrate_ensemble`]/[`map_ensemble`], then
+/// stacks their (generally ragged -- orbits close or time out at different lengths) per-particle
+/// time series into a single rectangular array, for bulk consumers like the Python wrapper layer
+/// that expect one 2D NumPy array rather than a list of one Vec per particle.
+pub struct Ensemble {
+    particles: Vec<Particle>,
+}
+
+impl Ensemble {
+    /// Creates one [`Particle`] per [`InitialConditions`], in order.
+    pub fn new(initial_conditions: &[InitialConditions]) -> Self {
+        Self {
+            particles: initial_conditions.iter().map(Particle::new).collect(),
+        }
+    }
+
+    /// The ensemble's particles, e.g. for reading each particle's results one by one rather than
+    /// through [`Self::stack`].
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    /// The number of particles in the ensemble.
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Whether the ensemble has no particles.
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    /// Integrates every particle over `t_eval`, concurrently -- see [`integrate_ensemble`].
+    pub fn integrate(
+        &mut self,
+        qfactor: &(impl Qfactor + Sync),
+        current: &(impl Current + Sync),
+        bfield: &(impl Bfield + Sync),
+        perturbation: &(impl Perturbation + Sync),
+        t_eval: (f64, f64),
+        config: &IntegrationConfig,
+    ) -> Vec<Result<()>> {
+        integrate_ensemble(&mut self.particles, qfactor, current, bfield, perturbation, t_eval, config)
+    }
+
+    /// Maps every particle onto `params`'s Poincare surface, concurrently -- see [`map_ensemble`].
+    pub fn map(
+        &mut self,
+        qfactor: &(impl Qfactor + Sync),
+        current: &(impl Current + Sync),
+        bfield: &(impl Bfield + Sync),
+        perturbation: &(impl Perturbation + Sync),
+        params: &MappingParameters,
+        config: &MappingConfig,
+    ) -> Vec<Result<()>> {
+        map_ensemble(&mut self.particles, qfactor, current, bfield, perturbation, params, config)
+    }
+
+    /// Stacks `field` (e.g. `|p| &p.evolution.theta[..]`) from every particle into a single
+    /// `n_particles × max_len` array, right-padded with `NaN` for particles whose orbit is
+    /// shorter than the ensemble's longest.
+    ///
+    /// Orbits genuinely close (or time out) at different lengths, so there is no single
+    /// `n_stored` shared across the ensemble -- `NaN`-padding is the simplest honest way to turn
+    /// the ragged per-particle series into the one rectangular array a bulk NumPy consumer
+    /// expects, rather than silently truncating every particle down to the shortest one.
+    pub fn stack(&self, field: impl Fn(&Particle) -> &[f64]) -> Array2<f64> {
+        let series: Vec<&[f64]> = self.particles.iter().map(&field).collect();
+        let max_len = series.iter().map(|s| s.len()).max().unwrap_or(0);
+
+        let mut stacked = Array2::from_elem((self.particles.len(), max_len), f64::NAN);
+        for (mut row, values) in stacked.rows_mut().into_iter().zip(&series) {
+            for (cell, &value) in row.iter_mut().zip(values.iter()) {
+                *cell = value;
+            }
+        }
+        stacked
+    }
+
+    /// Every particle's [`IntegrationStatus`], in order.
+    pub fn status(&self) -> Vec<IntegrationStatus> {
+        self.particles.iter().map(|particle| particle.status.clone()).collect()
+    }
+
+    /// Every particle's [`OrbitType`], in order.
+    pub fn orbit_type(&self) -> Vec<OrbitType> {
+        self.particles.iter().map(|particle| particle.orbit_type.clone()).collect()
+    }
+}
@@ -4,13 +4,14 @@ use std::time::Duration;
 use common::array1D_getter_impl;
 use ndarray::Array1;
 
+use crate::routines::EvolutionSample;
 use crate::State;
 
 /// The initial capacity of the time series Vecs.
 const EVOLUTION_INIT_CAPACITY: usize = 2000;
 
 /// Time series for a Particle's orbit.
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Evolution {
     pub time: Vec<f64>,
     /// The `θ` angle time series.
@@ -37,6 +38,30 @@ pub struct Evolution {
     steps_stored: usize,
     /// Relative standard deviation of the energy time series (σ/μ).
     pub energy_std: f64,
+    /// Per-step relative drift of the energy from its initial value, `|E_i - E_0|/|E_0|`. Stays
+    /// bounded over many periods for a symmetric stepper (e.g. the Gauss-Legendre
+    /// [`IntegrationMethod`](crate::IntegrationMethod)); grows roughly monotonically for RKF45.
+    pub energy_drift: Vec<f64>,
+    /// Per-step relative drift of the toroidal canonical momentum `Pζ` from its initial value, see
+    /// [`Self::energy_drift`].
+    pub pzeta_drift: Vec<f64>,
+    /// Per-step absolute drift of the energy from its initial value, `|E_i - E_0|`. Checked
+    /// alongside [`Self::energy_drift`] by the invariant-drift monitor (see
+    /// [`Particle::worst_energy_drift`](crate::Particle::worst_energy_drift)), since the relative
+    /// drift alone is ill-behaved near `E_0 ≈ 0`.
+    pub energy_abs_drift: Vec<f64>,
+    /// Per-step absolute drift of `Pζ` from its initial value, see [`Self::energy_abs_drift`].
+    pub pzeta_abs_drift: Vec<f64>,
+    /// The orbit's rotation number `ν`, estimated via a weighted Birkhoff average over a const-θ
+    /// [`map`](crate::Particle::map)'s recorded ζ-intersections. `NaN` unless the Particle was
+    /// mapped onto a [`PoincareSection::ConstTheta`](crate::PoincareSection::ConstTheta) surface
+    /// with at least 2 intersections -- see `routines::map::rotation_number`.
+    pub rotation_number: f64,
+    /// A stochastic error bar for [`Self::rotation_number`]: the relative difference between `ν`
+    /// estimated over the first half of the recorded intersections and over the full window. Large
+    /// on a chaotic orbit, since the Birkhoff average only converges quickly on regular
+    /// (invariant-curve) orbits. `NaN` alongside [`Self::rotation_number`].
+    pub rotation_number_err: f64,
 }
 
 impl Evolution {
@@ -53,9 +78,15 @@ impl Evolution {
             pzeta: Vec::with_capacity(capacity),
             energy: Vec::with_capacity(capacity),
             energy_std: f64::NAN,
+            energy_drift: Vec::with_capacity(capacity),
+            pzeta_drift: Vec::with_capacity(capacity),
+            energy_abs_drift: Vec::with_capacity(capacity),
+            pzeta_abs_drift: Vec::with_capacity(capacity),
             duration: Duration::default(),
             steps_taken: 0,
             steps_stored: 0,
+            rotation_number: f64::NAN,
+            rotation_number_err: f64::NAN,
         }
     }
 
@@ -74,6 +105,65 @@ impl Evolution {
         self.time.last().copied()
     }
 
+    /// Reconstructs an [`Evolution`] from its already-finished raw time series, e.g. after
+    /// receiving them from a remote worker (see `heap::distributed`). `steps_stored` is inferred
+    /// from `time`'s length, mirroring [`Self::push_state`]/[`Self::finish`].
+    pub fn from_raw_parts(
+        time: Vec<f64>,
+        theta: Vec<f64>,
+        psip: Vec<f64>,
+        rho: Vec<f64>,
+        zeta: Vec<f64>,
+        psi: Vec<f64>,
+        ptheta: Vec<f64>,
+        pzeta: Vec<f64>,
+        energy: Vec<f64>,
+        duration: Duration,
+        steps_taken: usize,
+        energy_std: f64,
+        rotation_number: f64,
+        rotation_number_err: f64,
+    ) -> Self {
+        let steps_stored = time.len();
+        let energy_drift = Self::relative_drift(&energy);
+        let pzeta_drift = Self::relative_drift(&pzeta);
+        let energy_abs_drift = Self::absolute_drift(&energy);
+        let pzeta_abs_drift = Self::absolute_drift(&pzeta);
+        Self {
+            time,
+            theta,
+            psip,
+            rho,
+            zeta,
+            psi,
+            ptheta,
+            pzeta,
+            energy,
+            duration,
+            steps_taken,
+            steps_stored,
+            energy_std,
+            energy_drift,
+            pzeta_drift,
+            energy_abs_drift,
+            pzeta_abs_drift,
+            rotation_number,
+            rotation_number_err,
+        }
+    }
+
+    /// Returns `series`'s per-step relative drift from its first value, `|v_i - v_0|/|v_0|`.
+    fn relative_drift(series: &[f64]) -> Vec<f64> {
+        let initial = series.first().copied().unwrap_or(f64::NAN);
+        series.iter().map(|v| (v - initial).abs() / initial.abs()).collect()
+    }
+
+    /// Returns `series`'s per-step absolute drift from its first value, `|v_i - v_0|`.
+    fn absolute_drift(series: &[f64]) -> Vec<f64> {
+        let initial = series.first().copied().unwrap_or(f64::NAN);
+        series.iter().map(|v| (v - initial).abs()).collect()
+    }
+
     /// Pushes the variables of a [`State`] to the time series vecs.
     pub(crate) fn push_state(&mut self, state: &State) {
         self.time.push(state.time);
@@ -88,6 +178,29 @@ impl Evolution {
         self.steps_stored += 1;
     }
 
+    /// Appends a `θ → −θ`, `ρ → −ρ` mirror of every already-stored state, doubling
+    /// [`Self::steps_stored`]. Used by
+    /// [`map_integrate`](crate::routines::map_integrate) when
+    /// [`MappingParameters::fold_symmetric`](crate::MappingParameters) halves the integration
+    /// length for a symmetric equilibrium: reflection flips the sign of the parallel gyroradius
+    /// `ρ` (a particle co-passing above the midplane is counter-passing in its mirror image below
+    /// it), but leaves `ψp`, `ζ` and the conserved quantities (`ψ`, `Pθ`, `Pζ`, energy) unchanged.
+    pub(crate) fn mirror_intersections(&mut self) {
+        let mirrored_theta: Vec<f64> = self.theta.iter().map(|theta| -theta).collect();
+        let mirrored_rho: Vec<f64> = self.rho.iter().map(|rho| -rho).collect();
+
+        self.time.extend_from_within(..);
+        self.theta.extend(mirrored_theta);
+        self.psip.extend_from_within(..);
+        self.rho.extend(mirrored_rho);
+        self.zeta.extend_from_within(..);
+        self.psi.extend_from_within(..);
+        self.ptheta.extend_from_within(..);
+        self.pzeta.extend_from_within(..);
+        self.energy.extend_from_within(..);
+        self.steps_stored *= 2;
+    }
+
     /// Shrinks the vecs and calculates `energy_std`.
     pub(crate) fn finish(&mut self) {
         self.time.shrink_to_fit();
@@ -102,6 +215,11 @@ impl Evolution {
 
         let energy_array = Array1::from_vec(self.energy.clone());
         self.energy_std = energy_array.std(0.0) / energy_array.mean().unwrap_or(f64::NAN);
+
+        self.energy_drift = Self::relative_drift(&self.energy);
+        self.pzeta_drift = Self::relative_drift(&self.pzeta);
+        self.energy_abs_drift = Self::absolute_drift(&self.energy);
+        self.pzeta_abs_drift = Self::absolute_drift(&self.pzeta);
     }
 
     /// Resets all arrays to the empty defaults, keeping all the other fields.
@@ -117,6 +235,24 @@ impl Evolution {
         self.ptheta = Vec::default();
         self.pzeta = Vec::default();
         self.energy = Vec::default();
+        self.energy_drift = Vec::default();
+        self.pzeta_drift = Vec::default();
+        self.energy_abs_drift = Vec::default();
+        self.pzeta_abs_drift = Vec::default();
+    }
+
+    /// Reconstructs the orbit's state at time `t`, via a [`PchipSpline`](equilibrium::PchipSpline)
+    /// fit through every stored field -- see [`routines::resample`](crate::routines) (through
+    /// [`Particle::state_at`](crate::Particle::state_at)) for why this isn't the integrator's own
+    /// dense output. `None` if fewer than two states are stored.
+    pub fn state_at(&self, t: f64) -> Option<EvolutionSample> {
+        crate::routines::state_at(self, t)
+    }
+
+    /// Reconstructs the orbit's state at every time in `times`, sharing a single spline fit across
+    /// all of them -- see [`Self::state_at`]. `None` if fewer than two states are stored.
+    pub fn resample(&self, times: &[f64]) -> Option<Vec<EvolutionSample>> {
+        crate::routines::resample(self, times)
     }
 
     array1D_getter_impl!(time, time);
@@ -128,6 +264,10 @@ impl Evolution {
     array1D_getter_impl!(ptheta, ptheta);
     array1D_getter_impl!(pzeta, pzeta);
     array1D_getter_impl!(energy, energy);
+    array1D_getter_impl!(energy_drift, energy_drift);
+    array1D_getter_impl!(pzeta_drift, pzeta_drift);
+    array1D_getter_impl!(energy_abs_drift, energy_abs_drift);
+    array1D_getter_impl!(pzeta_abs_drift, pzeta_abs_drift);
 }
 
 impl Debug for Evolution {
@@ -143,6 +283,19 @@ impl Debug for Evolution {
             )
             .field("duration", &self.duration)
             .field("energy_std", &format!("{:.5}", self.energy_std))
+            .field(
+                "energy_drift (final)",
+                &format!("{:.5}", self.energy_drift.last().unwrap_or(&f64::NAN)),
+            )
+            .field(
+                "pzeta_drift (final)",
+                &format!("{:.5}", self.pzeta_drift.last().unwrap_or(&f64::NAN)),
+            )
+            .field("rotation_number", &format!("{:.5}", self.rotation_number))
+            .field(
+                "rotation_number_err",
+                &format!("{:.5}", self.rotation_number_err),
+            )
             .field("steps taken", &self.steps_taken())
             .field("steps stored", &self.steps_stored())
             .finish()
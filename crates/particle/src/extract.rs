@@ -0,0 +1,89 @@
+//! netCDF export of integration results, mirroring `equilibrium`'s `extract` input format.
+//!
+//! Round-trips an [`Evolution`] -- plus its [`MappingParameters`]/[`Frequencies`], when available
+//! -- into a netCDF file with named variables, so a batch of orbit runs can be persisted and fed
+//! back into the same tooling that produced the originating equilibrium, instead of only being
+//! consumable in-memory.
+
+use std::path::Path;
+
+use equilibrium::{EqError, NcError};
+
+use crate::{Evolution, Frequencies, MappingParameters, ParticleError, PoincareSection, Result};
+
+/// Converts a raw `netcdf` error into a [`ParticleError`], routed through
+/// [`equilibrium::NcError`]/[`equilibrium::EqError`] so callers see the same error type the
+/// crate's own equilibrium-reading calls would produce for a netCDF failure.
+fn nc_err(e: netcdf::Error) -> ParticleError {
+    ParticleError::EqError(EqError::from(NcError::from(e)))
+}
+
+/// Writes `evolution`'s time series -- `t`, `ψp`, `θ`, `ζ`, `ρ∥`, and the derived `energy`/`Pζ` --
+/// to a new netCDF file at `path`.
+///
+/// `source_path`/`source_typ` identify the equilibrium the orbit was integrated against (the
+/// netCDF file path and interpolation `typ` used to build its [`Qfactor`](equilibrium::Qfactor) /
+/// [`Geometry`](equilibrium::Geometry)) and are stored as global attributes, so the output can be
+/// traced back to the equilibrium that produced it. `mapping`/`frequencies`, if given, are stored
+/// as attributes as well.
+pub fn write(
+    path: &Path,
+    evolution: &Evolution,
+    source_path: &str,
+    source_typ: &str,
+    mapping: Option<&MappingParameters>,
+    frequencies: Option<&Frequencies>,
+) -> Result<()> {
+    let mut file = netcdf::create(path).map_err(nc_err)?;
+
+    file.add_attribute("source_path", source_path).map_err(nc_err)?;
+    file.add_attribute("source_typ", source_typ).map_err(nc_err)?;
+
+    file.add_dimension("time", evolution.steps_stored())
+        .map_err(nc_err)?;
+
+    macro_rules! write_series {
+        ($name:literal, $data:expr) => {{
+            let mut var = file.add_variable::<f64>($name, &["time"]).map_err(nc_err)?;
+            var.put_values($data, ..).map_err(nc_err)?;
+        }};
+    }
+
+    write_series!("t", &evolution.time);
+    write_series!("psip", &evolution.psip);
+    write_series!("theta", &evolution.theta);
+    write_series!("zeta", &evolution.zeta);
+    write_series!("rho", &evolution.rho);
+    write_series!("energy", &evolution.energy);
+    write_series!("pzeta", &evolution.pzeta);
+
+    if let Some(mapping) = mapping {
+        let section = match mapping.section {
+            PoincareSection::ConstTheta => "const_theta",
+            PoincareSection::ConstZeta => "const_zeta",
+            PoincareSection::Event(_) => "event",
+        };
+        file.add_attribute("mapping_section", section)
+            .map_err(nc_err)?;
+        file.add_attribute("mapping_alpha", mapping.alpha)
+            .map_err(nc_err)?;
+        file.add_attribute("mapping_intersections", mapping.intersections as i64)
+            .map_err(nc_err)?;
+    }
+
+    if let Some(frequencies) = frequencies {
+        if let Some(omega_theta) = frequencies.omega_theta() {
+            file.add_attribute("omega_theta", omega_theta)
+                .map_err(nc_err)?;
+        }
+        if let Some(omega_zeta) = frequencies.omega_zeta() {
+            file.add_attribute("omega_zeta", omega_zeta)
+                .map_err(nc_err)?;
+        }
+        if let Some(qkinetic) = frequencies.qkinetic() {
+            file.add_attribute("qkinetic", qkinetic).map_err(nc_err)?;
+        }
+    }
+
+    Ok(())
+}
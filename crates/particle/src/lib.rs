@@ -12,6 +12,44 @@
 //!   ζ=const surface.
 //! + [`Single Period Integration`](Particle::single_period_integrate): Straight forward
 //!   integration for a single θ-ψp period.
+//! + [`Ensemble Integration`](single_period_integrate_ensemble): Concurrent single-period
+//!   integration of many particles at once, via a work-stealing scheduler.
+//! + [`Ensemble Tracing`](integrate_ensemble) / [`Ensemble Mapping`](map_ensemble): Concurrent
+//!   [`integrate`](Particle::integrate)/[`map`](Particle::map) over many particles, for building
+//!   Poincaré plots from thousands of trajectories.
+//! + [`Ensemble`]: Owns a set of particles and stacks their ragged per-particle time series into
+//!   a single rectangular array via [`Ensemble::stack`], for bulk callers (e.g. the Python layer)
+//!   that need one 2D array rather than a `Vec` per particle.
+//! + [`Poloidal Action`](Particle::poloidal_action) / [`Toroidal Action`](Particle::toroidal_action):
+//!   Canonical action integrals (adiabatic invariants) from an already-integrated orbit, for
+//!   labeling orbits and detecting resonances.
+//! + [`Orbit Average`](Particle::orbit_average): Averages an arbitrary function of the orbit's
+//!   state over its first closed period, via the same adaptive quadrature as the action integrals.
+//! + [`Parameter Scans`](run_scan): Sweeps one [`InitialConditions`] field over a grid and maps
+//!   every point concurrently, for building up a family of Poincare sections at once.
+//! + [`Adaptive Scans`](run_adaptive_scan): Like a parameter scan, but bisects wherever
+//!   neighboring points disagree on orbit type or rotation number, concentrating orbits near
+//!   separatrices and island chains instead of spreading them uniformly.
+//! + [`Ensemble Sampling`](sample_ensemble): Draws [`InitialConditions`] from caller-supplied
+//!   energy/pitch/`ψp` distributions with a seeded RNG, for distribution-function/loss-fraction
+//!   studies instead of hand-specified initial conditions.
+//! + [`Orbit Symmetry`](Particle::orbit_symmetry): Tests an integrated orbit for invariance under
+//!   `θ → −θ`, and optionally ([`MappingParameters::fold_symmetric`]) halves the integration
+//!   length needed to map a symmetric equilibrium by mirroring the other half in.
+//! + [`NAFF Frequencies`](Particle::naff_frequencies): Extracts `ωθ`/`ωζ` from a dense, stored
+//!   orbit to near machine precision via Laskar's NAFF, rather than
+//!   [`Single Period Integration`](Particle::single_period_integrate)'s period-counted
+//!   [`Frequencies`].
+//! + [`Resampling`](Particle::state_at): Reconstructs the orbit's state at arbitrary times from
+//!   the already-stored [`Evolution`], via [`Particle::state_at`]/[`Particle::resample`], in
+//!   either the raw guiding-center coordinates or derived canonical quantities
+//!   ([`Parametrization`]).
+//! + [`Poincare Analysis`](Particle::poincare_analysis): Turns a mapped orbit's recorded rotation
+//!   number into a nearest low-order resonance and an [`OrbitClass`] -- regular KAM curve,
+//!   `q`-periodic island chain, or chaotic.
+//! + [`Bounce Averages`](Particle::bounce_average): Locates a trapped particle's turning points
+//!   directly from `bfield`/`qfactor` (no prior integration needed) and bounce-averages its
+//!   precession drifts over the resulting [`Well`].
 //!
 //! ## Integration Configuration
 //!
@@ -21,21 +59,43 @@
 //! + [`MappingConfig`]
 //! + [`SinglePeriodConfig`]
 
+mod collisions;
 mod config;
+mod dense_output;
+mod ensemble;
 mod error;
 mod evolution;
+mod gauss_legendre;
 mod particle;
+mod poincare_scan;
 mod rkf45;
 mod routines;
+mod sampling;
+mod scan;
 mod state;
+mod symplectic_midpoint;
+
+pub mod extract;
 
 pub(crate) use rkf45::Stepper;
 
+pub use collisions::{CollisionRng, deflection_frequency, pitch_angle_kick};
 pub use config::*;
+pub use dense_output::hermite_dense_output;
+pub use ensemble::{Ensemble, integrate_ensemble, map_ensemble, single_period_integrate_ensemble};
 pub use error::ParticleError;
 pub use evolution::Evolution;
-pub use particle::{InitialConditions, IntegrationStatus, OrbitType, Particle};
-pub use routines::{Frequencies, MappingParameters, PoincareSection};
+pub use gauss_legendre::{StageSolve, gauss_legendre_step};
+pub use particle::{InitialConditions, IntegrationStatus, OrbitClassifier, OrbitType, Particle};
+pub use poincare_scan::{PoincareScanConfig, PoincareScanPoint, PoincareScanResult, run_adaptive_scan};
+pub use routines::{
+    ActionIntegral, BounceAverages, EventFn, EvolutionSample, Frequencies, MappingParameters,
+    NaffFrequencies, OrbitAverage, OrbitClass, OrbitSymmetry, Parametrization, PoincareAnalysis,
+    PoincareSection, Well,
+};
+pub use sampling::sample_ensemble;
+pub use scan::{ScanConfig, ScanParameter, ScanPoint, ScanResult, ScanSpacing, run_scan};
 pub use state::State;
+pub use symplectic_midpoint::{midpoint_step_doubling, symplectic_midpoint_step};
 
 pub type Result<T> = std::result::Result<T, ParticleError>;
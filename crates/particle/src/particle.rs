@@ -1,17 +1,41 @@
 //! Representation of a particle
 
+use std::f64::consts::TAU;
 use std::time::Duration;
 
 use derive_is_enum_variant::is_enum_variant as IsEnumVariant;
 use equilibrium::{Bfield, Current, Perturbation, Qfactor};
 
-use crate::routines::{Frequencies, close_theta_period, integrate, map_integrate};
+use crate::config::StepperConfig;
+use crate::routines::check_invariant_drift as invariant_drift_status;
+use crate::routines::{
+    ActionIntegral, BounceAverages, EvolutionSample, Frequencies, NaffFrequencies, OrbitAverage,
+    OrbitSymmetry, PoincareAnalysis, bounce_average, close_theta_period, integrate, map_integrate,
+    naff_frequencies, orbit_average, orbit_symmetry, poincare_analysis, poloidal_action,
+    toroidal_action, worst_drift,
+};
 use crate::state::Display;
 use crate::{Evolution, IntegrationConfig, MappingParameters, SinglePeriodConfig, State};
-use crate::{MappingConfig, ParticleError};
+use crate::{MappingConfig, ParticleError, Result};
+
+/// The width (in stored steps) of the sliding window [`orbit_type_from_theta`] votes over. Wide
+/// enough to denoise a single step's sign noise near the separatrix, narrow enough to still
+/// resolve a genuine turning point within one `θ-ψp` period.
+const ORBIT_TYPE_WINDOW: usize = 32;
+
+/// The `θ`-span "relaxation" factor used by [`orbit_type_from_theta`]'s fallback/tie-break
+/// criterion: a particle is passing if `|θ[-1] - θ[0]| > TAU - TRAPPED_THRESHOLD`.
+const TRAPPED_THRESHOLD: f64 = 1e-7;
+
+/// The [`Evolution::rotation_number_err`] above which [`calculate_orbit_type`] treats the orbit as
+/// chaotic: the weighted Birkhoff average only converges quickly on a regular (invariant-curve)
+/// orbit, so a large discrepancy between the half-window and full-window estimate is evidence the
+/// orbit is instead wandering chaotically. There is no dedicated [`OrbitType`] variant for this --
+/// a chaotic orbit is reported as [`OrbitType::Undefined`], same as a too-short/ambiguous one.
+const CHAOS_RELATIVE_ERROR_THRESHOLD: f64 = 0.1;
 
 /// A set of a Particle's intial conditions.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct InitialConditions {
     /// The initial time.
     pub time0: f64,
@@ -48,17 +72,64 @@ pub enum IntegrationStatus {
     /// Intersections calculated from the mapping are invalid (The spacing between each
     /// intersection and its neighbors must be *exactly* 2π).
     InvalidIntersections,
+    /// The integration/mapping finished, but the energy (or, absent a non-trivial
+    /// [`Perturbation`], the toroidal canonical momentum `Pζ`) drifted past the tolerances
+    /// configured via `energy_rel_tol`/`energy_abs_tol`/`pzeta_rel_tol`/`pzeta_abs_tol` -- see
+    /// [`Particle::worst_energy_drift`]/[`Particle::worst_pzeta_drift`]. `pzeta_drift` is `NaN`
+    /// when the `Pζ` check was skipped.
+    InvariantDriftExceeded { energy_drift: f64, pzeta_drift: f64 },
     /// Integration/Mapping failed for unknown reasons.
     Failed(Box<str>),
+    /// Scattered into the loss cone by the collision operator's pitch-angle kicks, under
+    /// [`CollisionConfig`](crate::CollisionConfig): the particle still
+    /// crossed the wall, but because the orbit drifted there via random pitch-angle diffusion
+    /// rather than its (collisionless) guiding-center trajectory, it is reported distinctly from
+    /// [`Escaped`](Self::Escaped) so `PoincarePbar::inc` can count collisional losses separately.
+    CollisionallyLost,
 }
 
-/// Defines the Particle's orbit type from its θ-span.
+/// Defines the Particle's orbit type, as determined by [`OrbitClassifier`].
 #[derive(Debug, Default, Clone, IsEnumVariant)]
 pub enum OrbitType {
     #[default]
     Undefined,
     Trapped,
     Passing,
+    /// A passing orbit whose parallel gyroradius `ρ` stayed positive for its whole duration.
+    /// Only ever reported by [`OrbitClassifier::ParallelVelocitySign`].
+    CoPassing,
+    /// A passing orbit whose `ρ` stayed negative for its whole duration, see [`Self::CoPassing`].
+    CounterPassing,
+    /// `ρ` stayed within a small band around zero for the whole orbit: the particle neither
+    /// bounces nor circulates. Only ever reported by [`OrbitClassifier::ParallelVelocitySign`].
+    Stagnation,
+    /// The orbit encircles the magnetic axis: `ψp` crossed below a near-axis threshold while `θ`
+    /// advanced by a full `2π`. Only ever reported by [`OrbitClassifier::ParallelVelocitySign`].
+    Potato,
+}
+
+/// Selects the algorithm [`Particle::calculate_orbit_type`] uses to classify a finished orbit,
+/// mirroring the [`IntegrationMethod`](crate::IntegrationMethod) algorithm-switch pattern.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrbitClassifier {
+    /// The original, cheap heuristic: a sliding-window majority vote (falling back to a bare
+    /// `θ`-span test) over the `θ` time series alone. Can only ever report [`OrbitType::Trapped`]
+    /// or [`OrbitType::Passing`], and conflates stagnation/potato orbits with those two.
+    ThetaSpan,
+    /// The physically robust test: scans the parallel gyroradius `ρ` time series, whose sign
+    /// tracks the parallel velocity `v∥`. A sign change at least once along the orbit means
+    /// [`OrbitType::Trapped`]; no sign change means passing, with [`OrbitType::CoPassing`] or
+    /// [`OrbitType::CounterPassing`] decided by the persistent sign. Additionally detects
+    /// [`OrbitType::Stagnation`] (`ρ` never leaves `±rho_threshold`) and [`OrbitType::Potato`]
+    /// (`ψp` dips below `psip_threshold` while `θ` still winds a full `2π`).
+    ParallelVelocitySign {
+        /// The half-width of the band around `ρ = 0` that counts as "no parallel motion" for
+        /// stagnation detection.
+        rho_threshold: f64,
+        /// The near-axis `ψp` value below which the orbit is considered to encircle the magnetic
+        /// axis, for potato-orbit detection.
+        psip_threshold: f64,
+    },
 }
 
 /// Representation of a particle.
@@ -176,7 +247,10 @@ impl Particle {
         config: &IntegrationConfig,
     ) {
         match integrate(self, qfactor, current, bfield, perturbation, t_eval, config) {
-            Ok(()) => self.status = IntegrationStatus::Integrated,
+            Ok(()) => {
+                self.status = IntegrationStatus::Integrated;
+                self.check_invariant_drift(perturbation, config);
+            }
             Err(error) => self.set_status_from_error(error),
         }
     }
@@ -240,7 +314,10 @@ impl Particle {
         config: &MappingConfig,
     ) {
         match map_integrate(self, qfactor, current, bfield, perturbation, params, config) {
-            Ok(()) => self.status = IntegrationStatus::Mapped,
+            Ok(()) => {
+                self.status = IntegrationStatus::Mapped;
+                self.check_invariant_drift(perturbation, config);
+            }
             Err(error) => self.set_status_from_error(error),
         }
     }
@@ -298,9 +375,52 @@ impl Particle {
         }
     }
 
-    /// Calculates the Particles OrbitType.
-    pub(crate) fn calculate_orbit_type(&mut self) {
-        // TODO: Decide how to setup up parameters
+    /// Calculates the Particle's [`OrbitType`] using `classifier`. If a rotation number was
+    /// estimated (see [`Evolution::rotation_number_err`]) and its error bar exceeds
+    /// [`CHAOS_RELATIVE_ERROR_THRESHOLD`], the orbit is instead reported as
+    /// [`OrbitType::Undefined`], since neither classifier can distinguish a chaotic orbit from a
+    /// regular one.
+    pub(crate) fn calculate_orbit_type(&mut self, classifier: &OrbitClassifier) {
+        self.orbit_type = match *classifier {
+            OrbitClassifier::ThetaSpan => orbit_type_from_theta(&self.evolution.theta),
+            OrbitClassifier::ParallelVelocitySign { rho_threshold, psip_threshold } => {
+                orbit_type_from_rho(
+                    &self.evolution.rho,
+                    &self.evolution.theta,
+                    &self.evolution.psip,
+                    rho_threshold,
+                    psip_threshold,
+                )
+            }
+        };
+        if self.evolution.rotation_number_err > CHAOS_RELATIVE_ERROR_THRESHOLD {
+            self.orbit_type = OrbitType::Undefined;
+        }
+    }
+
+    /// Runs the post-integration invariant-drift monitor, overriding [`Particle::status`] to
+    /// [`IntegrationStatus::InvariantDriftExceeded`] if the energy (or, absent a non-trivial
+    /// `perturbation`, `Pζ`) drifted past `config`'s tolerances. Only called after a successful
+    /// [`IntegrationStatus::Integrated`]/[`IntegrationStatus::Mapped`] result, since a failed
+    /// integration's [`Particle::evolution`] is not meaningfully complete.
+    fn check_invariant_drift(&mut self, perturbation: &impl Perturbation, config: &impl StepperConfig) {
+        if let Some(status) = invariant_drift_status(&self.evolution, config, perturbation.len() == 0) {
+            self.status = status;
+        }
+    }
+
+    /// Returns the largest relative or absolute per-step energy drift recorded in
+    /// [`Particle::evolution`], see [`Evolution::energy_drift`]/[`Evolution::energy_abs_drift`].
+    pub fn worst_energy_drift(&self) -> f64 {
+        worst_drift(&self.evolution.energy_drift).max(worst_drift(&self.evolution.energy_abs_drift))
+    }
+
+    /// Returns the largest relative or absolute per-step `Pζ` drift recorded in
+    /// [`Particle::evolution`], see [`Evolution::pzeta_drift`]/[`Evolution::pzeta_abs_drift`].
+    /// Meaningless (and not checked by [`Particle::check_invariant_drift`]) when a non-trivial
+    /// [`Perturbation`] was supplied, since `Pζ` is not conserved in that case.
+    pub fn worst_pzeta_drift(&self) -> f64 {
+        worst_drift(&self.evolution.pzeta_drift).max(worst_drift(&self.evolution.pzeta_abs_drift))
     }
 
     /// Sets the Particle's [`IntegrationStatus`] from a Result::Err() of an integration
@@ -330,6 +450,183 @@ impl Particle {
     pub fn final_energy(&self) -> f64 {
         self.final_state.energy()
     }
+
+    /// Computes the poloidal action `J_poloidal = (1/2π) ∮ ψp dθ` over the first `θ-ψp` period
+    /// closed by [`Particle::evolution`], e.g. after [`Particle::integrate`].
+    ///
+    /// Returns [`ParticleError::IntersectionError`] if the stored orbit never returns to its
+    /// starting `θ`, since there is no closed period to integrate over.
+    pub fn poloidal_action(&self) -> Result<ActionIntegral> {
+        poloidal_action(&self.evolution)
+    }
+
+    /// Computes the toroidal action `J_toroidal = (1/2π) ∮ ψp dζ`, analogously to
+    /// [`Particle::poloidal_action`] but bounded by successive crossings of the starting `ζ`.
+    pub fn toroidal_action(&self) -> Result<ActionIntegral> {
+        toroidal_action(&self.evolution)
+    }
+
+    /// Computes `⟨f⟩ = ∮f dτ / ∮dτ` over the first `θ-ψp` period closed by [`Particle::evolution`],
+    /// e.g. after [`Particle::integrate`]. `f` is evaluated via a spline fit through every stored
+    /// time series, so it can be sampled more finely than the integrator's own step size.
+    ///
+    /// Returns [`ParticleError::IntersectionError`] if the stored orbit never returns to its
+    /// starting `θ`, since there is no closed period to average over.
+    pub fn orbit_average(&self, f: impl Fn(EvolutionSample) -> f64) -> Result<OrbitAverage> {
+        orbit_average(&self.evolution, f)
+    }
+
+    /// Computes [`BounceAverages`] for this particle's [`InitialConditions::mu`] and
+    /// [`Particle::initial_energy`], trapped on the flux surface
+    /// [`InitialConditions::psip0`](InitialConditions) -- unlike [`Particle::poloidal_action`]/
+    /// [`Particle::orbit_average`], this needs no prior [`Particle::integrate`]/[`Particle::map`]
+    /// call, since the bounce motion is found directly from `bfield`/`qfactor` rather than a
+    /// stored [`Particle::evolution`].
+    ///
+    /// Returns [`ParticleError::IntersectionError`] if the particle is passing, i.e. it never
+    /// reflects on this flux surface.
+    pub fn bounce_average(
+        &self,
+        qfactor: &impl Qfactor,
+        bfield: &impl Bfield,
+    ) -> Result<BounceAverages> {
+        bounce_average(
+            self.initial_conditions.psip0,
+            self.initial_conditions.mu,
+            self.initial_energy(),
+            bfield,
+            qfactor,
+        )
+    }
+
+    /// Tests whether [`Particle::evolution`]'s sampled `(ψp, θ)` points are invariant under
+    /// `θ → −θ`, as they would be for an up-down (or stellarator) symmetric equilibrium.
+    ///
+    /// [`OrbitSymmetry::residual`] reports the worst mismatch found, which is directly useful for
+    /// seeing how far a symmetry-breaking [`Perturbation`] (with given poloidal/toroidal mode
+    /// numbers) pushes a real orbit away from the ideal symmetric case. Check
+    /// [`OrbitSymmetry::up_down`] before opting into
+    /// [`MappingParameters::fold_symmetric`] -- folding a genuinely asymmetric orbit silently
+    /// produces the wrong intersection set.
+    pub fn orbit_symmetry(&self) -> OrbitSymmetry {
+        orbit_symmetry(&self.evolution)
+    }
+
+    /// Extracts `ωθ`/`ωζ` from [`Particle::evolution`] via Laskar's NAFF, to much finer precision
+    /// than [`Particle::single_period_integrate`]'s period-counted [`Frequencies`] -- at the cost
+    /// of needing a long, dense time series rather than a single closed period (e.g. after
+    /// [`Particle::integrate`] over many periods).
+    pub fn naff_frequencies(&self) -> NaffFrequencies {
+        naff_frequencies(&self.evolution)
+    }
+
+    /// Reconstructs [`Particle::evolution`]'s state at time `t`, without re-integrating -- see
+    /// [`Evolution::state_at`]. `None` if fewer than two states are stored.
+    pub fn state_at(&self, t: f64) -> Option<EvolutionSample> {
+        self.evolution.state_at(t)
+    }
+
+    /// Reconstructs [`Particle::evolution`]'s state at every time in `times`, without
+    /// re-integrating -- see [`Evolution::resample`]. `None` if fewer than two states are stored.
+    pub fn resample(&self, times: &[f64]) -> Option<Vec<EvolutionSample>> {
+        self.evolution.resample(times)
+    }
+
+    /// Interprets [`Particle::evolution`]'s recorded rotation number `ν` (see
+    /// [`Evolution::rotation_number`], set by [`Particle::map`]) as the nearest low-order
+    /// resonance `p/q` and an [`OrbitClass`](crate::routines::OrbitClass) -- regular, `q`-periodic
+    /// island, or chaotic.
+    pub fn poincare_analysis(&self) -> PoincareAnalysis {
+        poincare_analysis(&self.evolution)
+    }
+}
+
+// ===============================================================================================
+
+/// Classifies a `θ` time series as [`OrbitType::Trapped`] or [`OrbitType::Passing`] via a
+/// denoising sliding-window majority vote, rather than a single `|θ[-1] - θ[0]|` test.
+///
+/// Builds the per-step sign sequence of `dθ`, slides a fixed-width [`ORBIT_TYPE_WINDOW`] over it,
+/// and labels each window [`OrbitType::Passing`] if `θ` winds monotonically through it (no sign
+/// reversal) or [`OrbitType::Trapped`] if it contains a turning point (a sign reversal, or no net
+/// motion at all -- a stagnating orbit is trapped, not undecided). The majority label wins via a
+/// three-bin histogram-argmax over `{Undefined, Trapped, Passing}` (the `Undefined` bin is never
+/// actually voted for, but keeps the histogram shaped after [`OrbitType`] itself). An evenly split
+/// vote, or an orbit shorter than one window, falls back to the existing `TRAPPED_THRESHOLD`/`TAU`
+/// span relaxation.
+fn orbit_type_from_theta(theta: &[f64]) -> OrbitType {
+    if theta.len() <= ORBIT_TYPE_WINDOW {
+        return orbit_type_from_span(theta);
+    }
+
+    let dtheta: Vec<f64> = theta.windows(2).map(|pair| pair[1] - pair[0]).collect();
+
+    // Bins match `OrbitType::{Undefined, Trapped, Passing}`; only the last two are ever voted for.
+    let mut votes = [0usize; 3];
+    for window in dtheta.windows(ORBIT_TYPE_WINDOW) {
+        votes[label_window(window)] += 1;
+    }
+
+    match votes[1].cmp(&votes[2]) {
+        std::cmp::Ordering::Greater => OrbitType::Trapped,
+        std::cmp::Ordering::Less => OrbitType::Passing,
+        std::cmp::Ordering::Equal => orbit_type_from_span(theta),
+    }
+}
+
+/// Labels one window of consecutive `dθ` signs: `2` ([`OrbitType::Passing`]) if every nonzero
+/// sample shares the same sign, `1` ([`OrbitType::Trapped`]) otherwise (a sign reversal, or no
+/// motion at all within the window).
+fn label_window(dtheta: &[f64]) -> usize {
+    let saw_positive = dtheta.iter().any(|&d| d > 0.0);
+    let saw_negative = dtheta.iter().any(|&d| d < 0.0);
+    if saw_positive != saw_negative { 2 } else { 1 }
+}
+
+/// The `θ`-span relaxation criterion: an orbit is passing if `|θ[-1] - θ[0]| > TAU -
+/// TRAPPED_THRESHOLD`, i.e. its net `θ` motion is (approximately) a full period.
+fn orbit_type_from_span(theta: &[f64]) -> OrbitType {
+    match (theta.first(), theta.last()) {
+        (Some(&first), Some(&last)) if (last - first).abs() > TAU - TRAPPED_THRESHOLD => {
+            OrbitType::Passing
+        }
+        _ => OrbitType::Trapped,
+    }
+}
+
+/// Classifies an orbit via the sign of its parallel gyroradius `ρ` time series, per
+/// [`OrbitClassifier::ParallelVelocitySign`]. Checked in order: potato first (it can otherwise
+/// masquerade as a sign change in `rho`), then stagnation, then the persistent sign of `ρ`.
+fn orbit_type_from_rho(
+    rho: &[f64],
+    theta: &[f64],
+    psip: &[f64],
+    rho_threshold: f64,
+    psip_threshold: f64,
+) -> OrbitType {
+    if is_potato_orbit(theta, psip, psip_threshold) {
+        return OrbitType::Potato;
+    }
+
+    let saw_positive = rho.iter().any(|&r| r > rho_threshold);
+    let saw_negative = rho.iter().any(|&r| r < -rho_threshold);
+    match (saw_positive, saw_negative) {
+        (true, true) => OrbitType::Trapped,
+        (true, false) => OrbitType::CoPassing,
+        (false, true) => OrbitType::CounterPassing,
+        (false, false) => OrbitType::Stagnation,
+    }
+}
+
+/// An orbit is a potato orbit if it encircles the magnetic axis: `ψp` ever dips below
+/// `psip_threshold` while `θ` still advances by a full `2π` over the stored orbit.
+fn is_potato_orbit(theta: &[f64], psip: &[f64], psip_threshold: f64) -> bool {
+    let crossed_axis = psip.iter().any(|&p| p < psip_threshold);
+    let wound_full_period = match (theta.first(), theta.last()) {
+        (Some(&first), Some(&last)) => (last - first).abs() >= TAU,
+        _ => false,
+    };
+    crossed_axis && wound_full_period
 }
 
 impl std::fmt::Debug for Particle {
@@ -356,3 +653,58 @@ impl std::fmt::Debug for Particle {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// `n` full, monotonically increasing `θ` windings, each sampled finely enough to span
+    /// several [`ORBIT_TYPE_WINDOW`]s.
+    fn passing_theta(periods: usize) -> Vec<f64> {
+        let samples_per_period = 4 * ORBIT_TYPE_WINDOW;
+        (0..=periods * samples_per_period)
+            .map(|i| TAU * i as f64 / samples_per_period as f64)
+            .collect()
+    }
+
+    /// `θ` oscillating back and forth between `lo` and `hi`, never completing a winding. The
+    /// bounce period is kept shorter than [`ORBIT_TYPE_WINDOW`] so that every window straddles a
+    /// turning point.
+    fn trapped_theta(lo: f64, hi: f64, bounces: usize) -> Vec<f64> {
+        let samples_per_bounce = ORBIT_TYPE_WINDOW / 4;
+        (0..=bounces * samples_per_bounce)
+            .map(|i| {
+                let phase = (i as f64 / samples_per_bounce as f64) * std::f64::consts::PI;
+                lo + (hi - lo) * (1.0 - phase.cos()) / 2.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_orbit_type_from_theta_passing() {
+        let theta = passing_theta(3);
+        assert!(orbit_type_from_theta(&theta).is_passing());
+    }
+
+    #[test]
+    fn test_orbit_type_from_theta_trapped() {
+        let theta = trapped_theta(0.0, 1.0, 20);
+        assert!(orbit_type_from_theta(&theta).is_trapped());
+    }
+
+    #[test]
+    fn test_orbit_type_from_theta_stagnation_is_trapped_not_undefined() {
+        let theta = vec![0.3; 4 * ORBIT_TYPE_WINDOW];
+        assert!(orbit_type_from_theta(&theta).is_trapped());
+    }
+
+    #[test]
+    fn test_orbit_type_from_theta_short_orbit_falls_back_to_span() {
+        // Fewer stored steps than one window: the span criterion decides directly.
+        let passing = vec![0.0, TAU - 1e-9];
+        assert!(orbit_type_from_theta(&passing).is_passing());
+
+        let trapped = vec![0.0, 0.5, 0.0];
+        assert!(orbit_type_from_theta(&trapped).is_trapped());
+    }
+}
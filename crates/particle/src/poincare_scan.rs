@@ -0,0 +1,247 @@
+//! Adaptive refinement of a [`ScanConfig`]-style grid, concentrating effort near separatrices and
+//! island chains instead of spending it uniformly.
+//!
+//! [`run_scan`](crate::run_scan) maps a fixed, uniform grid of [`InitialConditions`]. That wastes
+//! orbits deep inside a trapped or passing region, where neighboring points all look alike, and
+//! under-resolves the boundary between them, where the orbit type or rotation number can change
+//! within a tiny parameter step. [`run_adaptive_scan`] instead starts from a coarse uniform grid,
+//! classifies every orbit, and bisects any parameter interval whose endpoints disagree on orbit
+//! type or whose rotation numbers jump by more than a tolerance -- recursing into the new midpoint
+//! the same way, up to a configurable depth and total-orbit budget. Each refinement level is mapped
+//! as one batch via [`map_ensemble`], so the scan stays as parallel as a single uniform grid.
+
+use equilibrium::{Bfield, Current, Perturbation, Qfactor};
+
+use crate::{Evolution, InitialConditions, IntegrationStatus, MappingConfig, MappingParameters};
+use crate::{OrbitType, Particle, ScanParameter, map_ensemble};
+
+/// Configures a [`run_adaptive_scan`] call.
+#[derive(Debug, Clone)]
+pub struct PoincareScanConfig {
+    /// The [`InitialConditions`] every grid point starts from, except for the swept field.
+    pub base: InitialConditions,
+    /// The field of `base` that is swept.
+    pub parameter: ScanParameter,
+    /// The first value of the swept parameter.
+    pub start: f64,
+    /// The last value of the swept parameter.
+    pub stop: f64,
+    /// The number of points in the initial, coarse, uniformly-spaced grid.
+    pub initial_steps: usize,
+    /// An interval is bisected when its endpoints' [`OrbitType`] differ, or when their
+    /// [`rotation_number`](Evolution::rotation_number)s differ by more than this (relative to the
+    /// larger of the two).
+    pub refinement_tolerance: f64,
+    /// The maximum number of times a single interval may be bisected.
+    pub max_depth: usize,
+    /// The scan stops requesting further refinement once this many orbits have been mapped in
+    /// total, even if unresolved intervals remain.
+    pub max_orbits: usize,
+}
+
+impl PoincareScanConfig {
+    /// Creates a new [`PoincareScanConfig`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `initial_steps < 2`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        base: InitialConditions,
+        parameter: ScanParameter,
+        start: f64,
+        stop: f64,
+        initial_steps: usize,
+        refinement_tolerance: f64,
+        max_depth: usize,
+        max_orbits: usize,
+    ) -> Self {
+        assert!(
+            initial_steps >= 2,
+            "an adaptive scan needs at least two coarse grid points"
+        );
+        Self {
+            base,
+            parameter,
+            start,
+            stop,
+            initial_steps,
+            refinement_tolerance,
+            max_depth,
+            max_orbits,
+        }
+    }
+
+    /// `base` with the swept field set to `value`.
+    fn initial_conditions_at(&self, value: f64) -> InitialConditions {
+        let mut ic = self.base.clone();
+        match self.parameter {
+            ScanParameter::Theta0 => ic.theta0 = value,
+            ScanParameter::Psip0 => ic.psip0 = value,
+            ScanParameter::Rho0 => ic.rho0 = value,
+            ScanParameter::Zeta0 => ic.zeta0 = value,
+            ScanParameter::Mu => ic.mu = value,
+        }
+        ic
+    }
+}
+
+/// One mapped grid point of a [`PoincareScanResult`].
+#[derive(Debug)]
+pub struct PoincareScanPoint {
+    /// The swept parameter's value at this grid point.
+    pub parameter_value: f64,
+    /// The number of bisections that produced this point (`0` for the initial coarse grid).
+    pub depth: usize,
+    /// The mapped intersections, as an [`Evolution`]. Empty (or partial) if `status` is not
+    /// [`IntegrationStatus::Mapped`].
+    pub intersections: Evolution,
+    /// The particle's classified [`OrbitType`].
+    pub orbit_type: OrbitType,
+    /// The particle's final [`IntegrationStatus`] -- surfaced as-is, so a point that timed out or
+    /// failed the mapping accuracy check stays visible instead of being silently dropped or
+    /// retried.
+    pub status: IntegrationStatus,
+}
+
+/// The result of a [`run_adaptive_scan`] call: every mapped point, sorted by `parameter_value`.
+#[derive(Debug)]
+pub struct PoincareScanResult {
+    /// Every mapped point, sorted by `parameter_value`.
+    pub points: Vec<PoincareScanPoint>,
+}
+
+impl PoincareScanResult {
+    /// Iterates over the grid points whose [`IntegrationStatus`] is not
+    /// [`IntegrationStatus::Mapped`], i.e. the parameter values where the scan broke down.
+    pub fn failures(&self) -> impl Iterator<Item = &PoincareScanPoint> {
+        self.points.iter().filter(|p| !p.status.is_mapped())
+    }
+}
+
+/// Runs `scan`'s adaptive refinement, producing a [`PoincareScanResult`] concentrated near
+/// separatrices and island chains.
+///
+/// Starts by mapping a coarse, uniform grid of `scan.initial_steps` points via [`map_ensemble`].
+/// Then, while `scan.max_orbits` has not been reached, bisects every adjacent pair of points whose
+/// [`OrbitType`] differs or whose rotation numbers differ by more than `scan.refinement_tolerance`,
+/// maps every new midpoint in one batch, and repeats against the newly-widened point list, up to
+/// `scan.max_depth` rounds. Points that time out or fail the mapping accuracy check still get a
+/// [`PoincareScanPoint`], with their [`IntegrationStatus`] recording what went wrong, and are never
+/// refined further (there is no useful neighbor comparison to make against a failed orbit).
+pub fn run_adaptive_scan(
+    scan: &PoincareScanConfig,
+    qfactor: &(impl Qfactor + Sync),
+    current: &(impl Current + Sync),
+    bfield: &(impl Bfield + Sync),
+    perturbation: &(impl Perturbation + Sync),
+    params: &MappingParameters,
+    config: &MappingConfig,
+) -> PoincareScanResult {
+    let mut points = map_points(
+        &linspace(scan.start, scan.stop, scan.initial_steps),
+        0,
+        scan,
+        qfactor,
+        current,
+        bfield,
+        perturbation,
+        params,
+        config,
+    );
+
+    for _ in 0..scan.max_depth {
+        if points.len() >= scan.max_orbits {
+            break;
+        }
+        points.sort_by(|a, b| a.parameter_value.total_cmp(&b.parameter_value));
+
+        let midpoints: Vec<f64> = points
+            .windows(2)
+            .filter(|pair| needs_refinement(&pair[0], &pair[1], scan.refinement_tolerance))
+            .map(|pair| 0.5 * (pair[0].parameter_value + pair[1].parameter_value))
+            .take(scan.max_orbits.saturating_sub(points.len()))
+            .collect();
+
+        if midpoints.is_empty() {
+            break;
+        }
+
+        let depth = points.iter().map(|p| p.depth).max().unwrap_or(0) + 1;
+        points.extend(map_points(
+            &midpoints, depth, scan, qfactor, current, bfield, perturbation, params, config,
+        ));
+    }
+
+    points.sort_by(|a, b| a.parameter_value.total_cmp(&b.parameter_value));
+    PoincareScanResult { points }
+}
+
+/// Whether the interval between two neighboring points should be bisected: their [`OrbitType`]s
+/// differ, or (when both rotation numbers are meaningful) they differ by more than `tolerance`
+/// relative to the larger of the two.
+fn needs_refinement(a: &PoincareScanPoint, b: &PoincareScanPoint, tolerance: f64) -> bool {
+    if !a.status.is_mapped() || !b.status.is_mapped() {
+        return false;
+    }
+    if std::mem::discriminant(&a.orbit_type) != std::mem::discriminant(&b.orbit_type) {
+        return true;
+    }
+
+    let (nu_a, nu_b) = (a.intersections.rotation_number, b.intersections.rotation_number);
+    if nu_a.is_nan() || nu_b.is_nan() {
+        return false;
+    }
+    let scale = nu_a.abs().max(nu_b.abs());
+    if scale == 0.0 {
+        return false;
+    }
+    (nu_a - nu_b).abs() / scale > tolerance
+}
+
+/// Maps one batch of parameter values, in parallel, into [`PoincareScanPoint`]s at `depth`.
+#[allow(clippy::too_many_arguments)]
+fn map_points(
+    values: &[f64],
+    depth: usize,
+    scan: &PoincareScanConfig,
+    qfactor: &(impl Qfactor + Sync),
+    current: &(impl Current + Sync),
+    bfield: &(impl Bfield + Sync),
+    perturbation: &(impl Perturbation + Sync),
+    params: &MappingParameters,
+    config: &MappingConfig,
+) -> Vec<PoincareScanPoint> {
+    let mut particles: Vec<Particle> = values
+        .iter()
+        .map(|&value| Particle::new(&scan.initial_conditions_at(value)))
+        .collect();
+
+    map_ensemble(
+        &mut particles,
+        qfactor,
+        current,
+        bfield,
+        perturbation,
+        params,
+        config,
+    );
+
+    values
+        .iter()
+        .zip(particles)
+        .map(|(&parameter_value, particle)| PoincareScanPoint {
+            parameter_value,
+            depth,
+            intersections: particle.evolution,
+            orbit_type: particle.orbit_type,
+            status: particle.status,
+        })
+        .collect()
+}
+
+/// `steps` values evenly spaced between `start` and `stop`, inclusive.
+fn linspace(start: f64, stop: f64, steps: usize) -> Vec<f64> {
+    let step = (stop - start) / (steps - 1) as f64;
+    (0..steps).map(|i| start + i as f64 * step).collect()
+}
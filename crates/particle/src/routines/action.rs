@@ -0,0 +1,104 @@
+//! Canonical action integrals (adiabatic invariants) computed from a dense, stored [`Evolution`].
+//!
+//! An integrated orbit's `ψp`/`θ`/`ζ` time series already contain everything a poloidal or
+//! toroidal action needs -- this module never touches the equilibrium or steps the particle
+//! again, it only post-processes whatever [`Evolution`] the caller already produced (typically
+//! via [`Particle::integrate`](crate::Particle::integrate)).
+
+use std::f64::consts::TAU;
+
+use equilibrium::PchipSpline;
+
+use crate::routines::period::first_closed_period;
+use crate::routines::quadrature::adaptive_gauss_kronrod;
+use crate::{Evolution, Result};
+
+/// Relative tolerance (disagreement between the `G7`/`K15` estimate pair) at which a quadrature
+/// panel is accepted instead of bisected.
+const QUADRATURE_REL_TOL: f64 = 1e-10;
+
+/// Upper bound on panel bisections, so a pathological integrand can't recurse forever.
+const MAX_PANEL_BISECTIONS: u32 = 50;
+
+/// A canonical action integral, together with its estimated absolute error.
+#[derive(Debug, Clone, Copy)]
+pub struct ActionIntegral {
+    /// The value of the action integral.
+    pub value: f64,
+    /// The quadrature's estimated absolute error on [`Self::value`].
+    pub error: f64,
+}
+
+/// Computes `J_poloidal = (1/2π) ∮ ψp dθ` over the first closed `θ-ψp` period found in
+/// `evolution`, i.e. between its first two successive crossings of its own starting `θ`.
+pub(crate) fn poloidal_action(evolution: &Evolution) -> Result<ActionIntegral> {
+    action_integral(evolution, &evolution.theta)
+}
+
+/// Computes `J_toroidal = (1/2π) ∮ ψp dζ` over the first closed period found in `evolution`,
+/// analogously to [`poloidal_action`] but bounded by successive crossings of the starting `ζ`.
+pub(crate) fn toroidal_action(evolution: &Evolution) -> Result<ActionIntegral> {
+    action_integral(evolution, &evolution.zeta)
+}
+
+/// Shared implementation for [`poloidal_action`]/[`toroidal_action`]: locates the first period
+/// closed by `angles` (`evolution.theta` or `evolution.zeta`) and integrates `ψp` against it.
+fn action_integral(evolution: &Evolution, angles: &[f64]) -> Result<ActionIntegral> {
+    let (closing_index, t_end) = first_closed_period(&evolution.time, angles)?;
+
+    let times = &evolution.time[..=closing_index + 1];
+    let angle_window = &angles[..=closing_index + 1];
+    let psips = &evolution.psip[..=closing_index + 1];
+
+    let angle_spline = PchipSpline::new(times, angle_window);
+    let psip_spline = PchipSpline::new(times, psips);
+
+    let t_start = times[0];
+
+    let integrand = |t: f64| psip_spline.eval(t) * angle_spline.eval_deriv(t);
+    let (integral, error) =
+        adaptive_gauss_kronrod(&integrand, t_start, t_end, QUADRATURE_REL_TOL, MAX_PANEL_BISECTIONS);
+
+    Ok(ActionIntegral {
+        value: integral / TAU,
+        error: error / TAU,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_action_integral_of_a_closing_circle() {
+        // θ(t) = t, ψp(t) = const: J_poloidal = (1/2π) ∮ ψp dθ = ψp * 2π / 2π = ψp.
+        let psip_value = 0.37;
+        let n = 200;
+        let mut evolution = Evolution::default();
+        for i in 0..=n {
+            let t = TAU * (i as f64) / (n as f64);
+            evolution.time.push(t);
+            evolution.theta.push(t);
+            evolution.zeta.push(0.0);
+            evolution.psip.push(psip_value);
+        }
+
+        let action = poloidal_action(&evolution).unwrap();
+        assert!((action.value - psip_value).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_action_integral_errors_on_non_closing_orbit() {
+        // θ(t) keeps growing but never reaches back down to θ(0) - it never "closes".
+        let mut evolution = Evolution::default();
+        for i in 0..=50 {
+            let t = i as f64 * 0.01;
+            evolution.time.push(t);
+            evolution.theta.push(t);
+            evolution.zeta.push(0.0);
+            evolution.psip.push(0.1);
+        }
+
+        assert!(poloidal_action(&evolution).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! Bounce-averaged quantities for magnetically trapped guiding-center orbits.
+//!
+//! A trapped particle's parallel gyroradius satisfies `ρB = ±√(2(E - μB))` (`m = q = 1` normalized
+//! units, the same `E_par = (ρB)²/2`, `E_perp = μB` split [`sampling`](crate::sampling) already
+//! assumes), so it reflects wherever `B(ψp, θ)` rises to `E/μ`. [`bounce_average`] brackets these
+//! turning points along a fixed `ψp` by sampling `E - μB` over `θ ∈ [0, 2π)`, refines each crossing
+//! with the same [`brent`](crate::routines::brent) used by [`event`](crate::routines::event), and
+//! pairs them cyclically into [`Well`]s -- the θ-ranges the particle actually occupies between two
+//! successive reflections.
+//!
+//! The bounce integral `∮ dθ/|ρ(θ)|` has an integrable inverse-square-root singularity at both
+//! endpoints of a well (`ρ → 0` there), so [`well_quadrature`] maps each well with the tangent
+//! substitution `θ = θ_mid + Δ·sin(s)`, `s ∈ [-π/2, π/2]`, before applying a fixed 8-point
+//! Gauss-Legendre rule: the substitution's `cos(s)` Jacobian vanishes at the same rate as `|ρ|`
+//! near `s = ±π/2`, cancelling the singularity without ever evaluating exactly at a turning point.
+//!
+//! [`BounceAverages::radial_drift`]/[`BounceAverages::toroidal_drift`] use the leading-order
+//! guiding-center drifts `ψ̇p ≈ -∂H/∂θ`, `ζ̇ ≈ -∂H/∂ψp / q(ψp)`, from `H = ρ²B²/2 + μB` and the
+//! canonical `Pζ = g(ψp)ρ - ψ(ψp)` -- dropping the `dg/dψp·ρ` correction so both stay even in `ρ`
+//! (and therefore agree between a well's two legs) along with the Jacobian terms the full
+//! White-canonical equations of motion carry. A rigorous drift would integrate those equations
+//! directly along the orbit, but (like [`event`](crate::routines::event)'s own deferred
+//! dense-output shortcut) that needs `rkf45::Stepper` to retain stage derivatives this checkout's
+//! `rkf45` module does not currently expose. [`Current`](equilibrium::Current) is accordingly left
+//! unused here -- both drifts above only need `B` and `q`.
+//!
+//! Returns [`ParticleError::IntersectionError`] if no well is found, i.e. the particle is passing
+//! (`E/μ` exceeds `B` everywhere on the surface, so it never reflects).
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use equilibrium::{Bfield, Qfactor};
+use rsl_interpolation::{Accelerator, Cache};
+
+use crate::routines::event::{brent, event_intersected};
+use crate::{ParticleError, Result};
+
+/// The number of equally-spaced `θ` samples [`find_wells`] sweeps over `[0, 2π)` to bracket sign
+/// changes of `E - μB`. Coarser wells (few, wide magnetic wells) need far fewer, but a rippled
+/// field can have several narrow ones, so this errs on the dense side.
+const BRACKET_SAMPLES: usize = 256;
+
+/// [`brent`]'s convergence tolerance when refining a bracketed turning point.
+const BOUNCE_POINT_TOL: f64 = 1e-10;
+
+/// [`brent`]'s iteration budget when refining a bracketed turning point.
+const MAX_REFINE_ITERATIONS: usize = 50;
+
+/// 8-point Gauss-Legendre nodes on `[-1, 1]`, positive half only (the rule is symmetric, see
+/// [`GL8_WEIGHTS`]).
+const GL8_NODES: [f64; 4] = [
+    0.1834346424956498,
+    0.5255324099163290,
+    0.7966664774136267,
+    0.9602898564975363,
+];
+
+/// 8-point Gauss-Legendre weights, one per [`GL8_NODES`] entry (used for both its `+`/`-` node).
+const GL8_WEIGHTS: [f64; 4] = [
+    0.3626837833783620,
+    0.3137066458778873,
+    0.2223810344533745,
+    0.1012285362903763,
+];
+
+/// A single poloidal "well": the `θ`-range between two successive turning points over which the
+/// particle is allowed (`E - μB ≥ 0`), i.e. one leg's worth of a bounce. `theta_plus` is always
+/// `> theta_minus`, wrapping past `2π` if the well straddles the `θ = 0` cut.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Well {
+    /// The turning point the particle enters the well through, as `θ` increases.
+    pub theta_minus: f64,
+    /// The turning point the particle reflects off next, as `θ` increases.
+    pub theta_plus: f64,
+}
+
+/// Bounce-averaged quantities for a magnetically trapped particle of energy `E` and magnetic
+/// moment `μ` on a fixed `ψp` surface, computed over [`Self::wells`]'s first (and, for a
+/// single-well equilibrium, only) entry. See the [module docs](self) for the underlying
+/// approximations.
+#[derive(Debug, Clone)]
+pub struct BounceAverages {
+    /// The bounce period `2 ∮ dθ/|ρ(θ)|` of [`Self::wells`]'s first well (the factor of 2 accounts
+    /// for both legs of the bounce, since `|ρ(θ)|` is the same magnitude on each).
+    pub bounce_time: f64,
+    /// The bounce-averaged radial precession drift `⟨ψ̇p⟩` over [`Self::wells`]'s first well.
+    pub radial_drift: f64,
+    /// The bounce-averaged toroidal precession drift `⟨ζ̇⟩` over [`Self::wells`]'s first well.
+    pub toroidal_drift: f64,
+    /// Every well found along the `θ`-sweep, in increasing `θ` order.
+    pub wells: Vec<Well>,
+}
+
+/// Computes `B(ψp, θ)` with a fresh [`Accelerator`]/[`Cache`] -- [`find_wells`]/[`well_quadrature`]
+/// each call this at independent, unrelated `θ`, so there is no benefit (and a borrow-checker
+/// headache) in threading shared ones through.
+fn eval_b(bfield: &impl Bfield, psip: f64, theta: f64) -> Result<f64> {
+    let mut xacc = Accelerator::new();
+    let mut yacc = Accelerator::new();
+    let mut cache = Cache::new();
+    bfield
+        .b(psip, theta, &mut xacc, &mut yacc, &mut cache)
+        .map_err(ParticleError::EqError)
+}
+
+/// Computes `∂B(ψp, θ)/∂θ`, see [`eval_b`].
+fn eval_db_dtheta(bfield: &impl Bfield, psip: f64, theta: f64) -> Result<f64> {
+    let mut xacc = Accelerator::new();
+    let mut yacc = Accelerator::new();
+    let mut cache = Cache::new();
+    bfield
+        .db_dtheta(psip, theta, &mut xacc, &mut yacc, &mut cache)
+        .map_err(ParticleError::EqError)
+}
+
+/// Computes `∂B(ψp, θ)/∂ψp`, see [`eval_b`].
+fn eval_db_dpsip(bfield: &impl Bfield, psip: f64, theta: f64) -> Result<f64> {
+    let mut xacc = Accelerator::new();
+    let mut yacc = Accelerator::new();
+    let mut cache = Cache::new();
+    bfield
+        .db_dpsip(psip, theta, &mut xacc, &mut yacc, &mut cache)
+        .map_err(ParticleError::EqError)
+}
+
+/// `|ρ(θ)| = √(2(E - μB))/B`, from `ρ²B²/2 = E - μB`. Clamped to `0.0` at (or just past, from
+/// floating-point noise) a turning point, where `E - μB` can dip slightly negative.
+fn rho_abs(energy: f64, mu: f64, b: f64) -> f64 {
+    (2.0 * (energy - mu * b)).max(0.0).sqrt() / b
+}
+
+/// Brackets and refines every turning point (`E - μB(ψp, θ) = 0`) over `θ ∈ [0, 2π)`, pairing them
+/// cyclically into [`Well`]s. Returns an empty `Vec` for a passing particle (`E - μB` never
+/// changes sign, i.e. never reflects).
+fn find_wells(psip: f64, mu: f64, energy: f64, bfield: &impl Bfield) -> Result<Vec<Well>> {
+    let g = |theta: f64| -> Result<f64> { Ok(energy - mu * eval_b(bfield, psip, theta)?) };
+
+    let thetas: Vec<f64> = (0..=BRACKET_SAMPLES)
+        .map(|i| TAU * i as f64 / BRACKET_SAMPLES as f64)
+        .collect();
+    let values: Vec<f64> = thetas.iter().map(|&theta| g(theta)).collect::<Result<_>>()?;
+
+    // `(θ, entering)`: `entering` is true where `g` rises through zero (the particle becomes
+    // allowed, i.e. this is a `theta_minus`), false where it falls through zero (`theta_plus`).
+    let mut crossings: Vec<(f64, bool)> = Vec::new();
+    for i in 0..BRACKET_SAMPLES {
+        let (g1, g2) = (values[i], values[i + 1]);
+        if g1 != g2 && event_intersected(g1, g2) {
+            let (t0, t1) = (thetas[i], thetas[i + 1]);
+            let fraction = brent(
+                |t| g(t0 + t * (t1 - t0)),
+                0.0,
+                1.0,
+                g1,
+                g2,
+                BOUNCE_POINT_TOL,
+                MAX_REFINE_ITERATIONS,
+            )?;
+            crossings.push((t0 + fraction * (t1 - t0), g2 > g1));
+        }
+    }
+
+    let n = crossings.len();
+    let mut wells = Vec::new();
+    for i in 0..n {
+        let (theta_minus, entering) = crossings[i];
+        if !entering {
+            continue;
+        }
+        let (mut theta_plus, entering_next) = crossings[(i + 1) % n];
+        if entering_next {
+            continue;
+        }
+        if theta_plus <= theta_minus {
+            theta_plus += TAU;
+        }
+        wells.push(Well { theta_minus, theta_plus });
+    }
+    Ok(wells)
+}
+
+/// Integrates `f` over a [`Well`] via the tangent substitution `θ = θ_mid + Δ·sin(s)` and a fixed
+/// 8-point Gauss-Legendre rule on `s ∈ [-π/2, π/2]` -- see the [module docs](self) for why this
+/// cancels the bounce integrand's endpoint singularity.
+fn well_quadrature(well: &Well, mut f: impl FnMut(f64) -> Result<f64>) -> Result<f64> {
+    let mid = 0.5 * (well.theta_minus + well.theta_plus);
+    let half_width = 0.5 * (well.theta_plus - well.theta_minus);
+
+    let mut total = 0.0;
+    for i in 0..GL8_NODES.len() {
+        for sign in [1.0, -1.0] {
+            let s = sign * GL8_NODES[i] * FRAC_PI_2;
+            let theta = mid + half_width * s.sin();
+            let jacobian = half_width * FRAC_PI_2 * s.cos();
+            total += GL8_WEIGHTS[i] * jacobian * f(theta)?;
+        }
+    }
+    Ok(total)
+}
+
+/// Computes [`BounceAverages`] for a particle of energy `energy` and magnetic moment `mu` confined
+/// to the flux surface `psip`. See the [module docs](self) for the underlying algorithm and its
+/// approximations.
+///
+/// Returns [`ParticleError::IntersectionError`] if the particle is passing, i.e. no well is found.
+pub(crate) fn bounce_average(
+    psip: f64,
+    mu: f64,
+    energy: f64,
+    bfield: &impl Bfield,
+    qfactor: &impl Qfactor,
+) -> Result<BounceAverages> {
+    let wells = find_wells(psip, mu, energy, bfield)?;
+    let well = wells.first().ok_or(ParticleError::IntersectionError)?;
+
+    let mut acc = Accelerator::new();
+    let q = qfactor.q(psip, &mut acc).map_err(ParticleError::EqError)?;
+
+    let denominator = well_quadrature(well, |theta| {
+        let b = eval_b(bfield, psip, theta)?;
+        Ok(1.0 / rho_abs(energy, mu, b))
+    })?;
+
+    let radial_numerator = well_quadrature(well, |theta| {
+        let b = eval_b(bfield, psip, theta)?;
+        let db_dtheta = eval_db_dtheta(bfield, psip, theta)?;
+        let rho2_b = 2.0 * (energy - mu * b) / b;
+        Ok(-(rho2_b + mu) * db_dtheta / rho_abs(energy, mu, b))
+    })?;
+
+    let toroidal_numerator = well_quadrature(well, |theta| {
+        let b = eval_b(bfield, psip, theta)?;
+        let db_dpsip = eval_db_dpsip(bfield, psip, theta)?;
+        let rho2_b = 2.0 * (energy - mu * b) / b;
+        Ok(-(rho2_b + mu) * db_dpsip / q / rho_abs(energy, mu, b))
+    })?;
+
+    Ok(BounceAverages {
+        bounce_time: 2.0 * denominator,
+        radial_drift: radial_numerator / denominator,
+        toroidal_drift: toroidal_numerator / denominator,
+        wells,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A single-harmonic mirror field `B(θ) = b0 + b1·cos(θ)`, independent of `ψp` -- enough to
+    /// open exactly one well per `2π` without depending on a real equilibrium.
+    struct MirrorBfield {
+        b0: f64,
+        b1: f64,
+    }
+
+    impl Bfield for MirrorBfield {
+        fn b(
+            &self,
+            _psip: f64,
+            theta: f64,
+            _xacc: &mut Accelerator,
+            _yacc: &mut Accelerator,
+            _cache: &mut Cache<f64>,
+        ) -> equilibrium::Result<f64> {
+            Ok(self.b0 + self.b1 * theta.cos())
+        }
+        fn db_dpsip(
+            &self,
+            _psip: f64,
+            _theta: f64,
+            _xacc: &mut Accelerator,
+            _yacc: &mut Accelerator,
+            _cache: &mut Cache<f64>,
+        ) -> equilibrium::Result<f64> {
+            Ok(0.0)
+        }
+        fn db_dtheta(
+            &self,
+            _psip: f64,
+            theta: f64,
+            _xacc: &mut Accelerator,
+            _yacc: &mut Accelerator,
+            _cache: &mut Cache<f64>,
+        ) -> equilibrium::Result<f64> {
+            Ok(-self.b1 * theta.sin())
+        }
+    }
+
+    #[derive(Clone)]
+    struct ConstantQfactor(f64);
+
+    impl Qfactor for ConstantQfactor {
+        fn q(&self, _psip: f64, _acc: &mut Accelerator) -> equilibrium::Result<f64> {
+            Ok(self.0)
+        }
+        fn psi(&self, _psip: f64, _acc: &mut Accelerator) -> equilibrium::Result<f64> {
+            Ok(0.0)
+        }
+        fn dpsi_dpsip(&self, _psip: f64, _acc: &mut Accelerator) -> equilibrium::Result<f64> {
+            Ok(0.0)
+        }
+    }
+
+    #[test]
+    fn test_bounce_average_finds_one_well_around_the_mirror_minimum() {
+        let bfield = MirrorBfield { b0: 1.0, b1: 0.5 };
+        let qfactor = ConstantQfactor(1.5);
+        // E/mu = 1.2: B only dips that low around its minimum at theta = pi.
+        let mu = 1.0;
+        let energy = 1.2;
+
+        let averages = bounce_average(0.1, mu, energy, &bfield, &qfactor).unwrap();
+        assert_eq!(averages.wells.len(), 1);
+        let well = averages.wells[0];
+        // B's minimum (and so the well's center) sits at theta = pi.
+        assert!((0.5 * (well.theta_minus + well.theta_plus) - std::f64::consts::PI).abs() < 1e-6);
+        assert!(averages.bounce_time > 0.0);
+        assert!(averages.bounce_time.is_finite());
+        // The mirror is symmetric about theta=pi, so the radial/toroidal drifts from the two
+        // symmetric halves cancel.
+        assert!(averages.radial_drift.abs() < 1e-6);
+        assert!(averages.toroidal_drift.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bounce_average_errors_on_a_passing_particle() {
+        let bfield = MirrorBfield { b0: 1.0, b1: 0.5 };
+        let qfactor = ConstantQfactor(1.5);
+        // E/mu = 2.0 exceeds B everywhere (max B = 1.5): never reflects.
+        assert!(bounce_average(0.1, 1.0, 2.0, &bfield, &qfactor).is_err());
+    }
+}
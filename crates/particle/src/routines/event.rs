@@ -0,0 +1,166 @@
+//! Detection and exact localization of crossings of an arbitrary scalar event surface
+//! `g(state) = 0` (see [`PoincareSection::Event`](crate::PoincareSection::Event)).
+//!
+//! Unlike the angle-plane sections in [`henon`](crate::routines::henon), a generic `g` has no
+//! known derivative along the orbit, so landing on the surface can't reuse Hénon's trick (which
+//! needs `1/θ̇` or `1/ζ̇` to swap the independent variable). Instead, [`locate_event_crossing`]
+//! brackets the crossing fraction along the step and refines it with [`brent`], re-integrating
+//! only the reduced sub-step and re-evaluating `g` at each trial fraction until it is within
+//! `config.map_threshold`.
+//!
+//! This still re-integrates a sub-step per refinement iteration rather than reading the crossing
+//! off a continuous extension of the already-accepted step: a true dense-output interpolant (see
+//! [`hermite_dense_output`](crate::hermite_dense_output)) would let [`brent`] evaluate `g` directly
+//! from the accepted step's endpoint derivatives, sparing the re-integration entirely. Wiring that
+//! in requires `rkf45::Stepper` to retain its stage derivatives, which this checkout's `rkf45`
+//! module does not currently expose.
+
+use std::sync::Arc;
+
+use equilibrium::{Bfield, Current, Perturbation, Qfactor};
+
+use crate::{MappingConfig, ParticleError, Result, State, Stepper};
+
+/// The maximum number of [`brent`] refinement iterations [`locate_event_crossing`] attempts before
+/// giving up. Each iteration re-integrates one sub-step, so this is kept small; a well-behaved `g`
+/// converges in far fewer.
+const MAX_REFINEMENT_ITERATIONS: usize = 20;
+
+/// A boxed event-surface function `g(state) = 0`, e.g. a flux surface `ψ = const`, an energy
+/// shell, or a `Pζ` surface. Boxed (rather than a bare `fn` pointer) so a
+/// [`PoincareSection::Event`](crate::PoincareSection::Event) can also carry a capturing closure;
+/// a plain `fn` with no captures coerces into this automatically.
+pub type EventFn = Arc<dyn Fn(&State) -> f64 + Send + Sync>;
+
+/// Whether the event surface `g(state) = 0` was crossed between two successive evaluations of
+/// `g`, i.e. `g1` and `g2` have opposite signs (or either is exactly zero).
+pub(crate) fn event_intersected(g1: f64, g2: f64) -> bool {
+    g1 * g2 <= 0.0
+}
+
+/// Locates the exact crossing of `g(state) = 0` within the step `[state1, state2]`, which was
+/// taken with step size `dt`.
+///
+/// Brackets the crossing fraction `t ∈ [0, 1]` between the known-opposite-sign endpoints `g1`/`g2`
+/// and refines it with [`brent`]: each trial `t` re-integrates the reduced sub-step
+/// `dtau = t*dt` from `state1` and re-evaluates `g` at the result. Stops as soon as
+/// `|g| < config.map_threshold`, then performs one final re-integration at the converged `t` to
+/// recover the corresponding [`State`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn locate_event_crossing(
+    qfactor: &impl Qfactor,
+    current: &impl Current,
+    bfield: &impl Bfield,
+    perturbation: &impl Perturbation,
+    state1: &State,
+    dt: f64,
+    g1: f64,
+    g2: f64,
+    g: &EventFn,
+    config: &MappingConfig,
+) -> Result<State> {
+    let evaluate_at = |t: f64| -> Result<f64> {
+        let dtau = t * dt;
+        let mut stepper = Stepper::new(state1);
+        stepper.start(dtau, qfactor, current, bfield, perturbation)?;
+        let candidate = stepper
+            .next_state(dtau)
+            .into_evaluated(qfactor, current, bfield, perturbation)?;
+        Ok(g(&candidate))
+    };
+
+    let t = brent(evaluate_at, 0.0, 1.0, g1, g2, config.map_threshold, MAX_REFINEMENT_ITERATIONS)?;
+
+    let dtau = t * dt;
+    let mut stepper = Stepper::new(state1);
+    stepper.start(dtau, qfactor, current, bfield, perturbation)?;
+    stepper
+        .next_state(dtau)
+        .into_evaluated(qfactor, current, bfield, perturbation)
+}
+
+/// Finds a root of `f` bracketed in `[a, b]` via Brent's method, combining bisection, the secant
+/// method, and inverse quadratic interpolation so it converges superlinearly on well-behaved
+/// functions while still guaranteeing the bracket never grows. `fa`/`fb` are `f(a)`/`f(b)`, passed
+/// in since [`locate_event_crossing`] already has them from the crossing check that triggered this
+/// refinement. Stops as soon as `|f(b)| < tol` or the bracket width drops below `tol`; gives up
+/// after `max_iterations`.
+///
+/// `pub(crate)` rather than private since [`bounce`](crate::routines::bounce) also refines
+/// bracketed turning points and has no reason to duplicate this.
+pub(crate) fn brent(
+    mut f: impl FnMut(f64) -> Result<f64>,
+    mut a: f64,
+    mut b: f64,
+    mut fa: f64,
+    mut fb: f64,
+    tol: f64,
+    max_iterations: usize,
+) -> Result<f64> {
+    if fa * fb > 0.0 {
+        return Err(ParticleError::IntersectionError);
+    }
+    if fa.abs() < fb.abs() {
+        std::mem::swap(&mut a, &mut b);
+        std::mem::swap(&mut fa, &mut fb);
+    }
+
+    let mut c = a;
+    let mut fc = fa;
+    let mut d = a;
+    let mut mflag = true;
+
+    for _ in 0..max_iterations {
+        if fb.abs() < tol || (b - a).abs() < tol {
+            return Ok(b);
+        }
+
+        let s = if fa != fc && fb != fc {
+            a * fb * fc / ((fa - fb) * (fa - fc))
+                + b * fa * fc / ((fb - fa) * (fb - fc))
+                + c * fa * fb / ((fc - fa) * (fc - fb))
+        } else {
+            b - fb * (b - a) / (fb - fa)
+        };
+
+        let within_bounds = {
+            let quarter = (3.0 * a + b) / 4.0;
+            let (lo, hi) = if quarter < b { (quarter, b) } else { (b, quarter) };
+            s >= lo && s <= hi
+        };
+
+        let use_bisection = !within_bounds
+            || (mflag && (s - b).abs() >= (b - c).abs() / 2.0)
+            || (!mflag && (s - b).abs() >= (c - d).abs() / 2.0)
+            || (mflag && (b - c).abs() < tol)
+            || (!mflag && (c - d).abs() < tol);
+
+        let s = if use_bisection {
+            mflag = true;
+            0.5 * (a + b)
+        } else {
+            mflag = false;
+            s
+        };
+
+        let fs = f(s)?;
+        d = c;
+        c = b;
+        fc = fb;
+
+        if fa * fs < 0.0 {
+            b = s;
+            fb = fs;
+        } else {
+            a = s;
+            fa = fs;
+        }
+
+        if fa.abs() < fb.abs() {
+            std::mem::swap(&mut a, &mut b);
+            std::mem::swap(&mut fa, &mut fb);
+        }
+    }
+
+    Err(ParticleError::IntersectionError)
+}
@@ -13,6 +13,10 @@ use crate::{State, Stepper};
 pub(crate) fn calculate_mod_state1(state1: &State, section: &PoincareSection) -> State {
     // Do not evaluate the state!
     match section {
+        PoincareSection::Event(_) => unreachable!(
+            "Hénon's trick only applies to the ConstTheta/ConstZeta angle planes -- an \
+             Event surface is handled by routines::event instead"
+        ),
         PoincareSection::ConstTheta => {
             let kappa = 1.0 / state1.theta_dot;
             let dt_dtheta = kappa;
@@ -59,7 +63,11 @@ pub(crate) fn calculate_mod_step(
     // WARN: This is needed to move the %2π pole when α happens to be a multiple of 2π. It seems to
     // work for most cases, but lets keep an eye on it.
     let pole = if params.alpha.abs() < 1e-2 { PI } else { 0.0 };
-    match params.section {
+    match &params.section {
+        PoincareSection::Event(_) => unreachable!(
+            "Hénon's trick only applies to the ConstTheta/ConstZeta angle planes -- an Event \
+             surface is handled by routines::event instead"
+        ),
         PoincareSection::ConstTheta => {
             let direction = (state2.theta - state1.theta).signum();
             direction * (params.alpha - (state1.theta + pole).rem_euclid(TAU) + pole)
@@ -107,7 +115,11 @@ pub(crate) fn calculate_intersection_state(
     params: &MappingParameters,
     mod_state2: State,
 ) -> Result<State> {
-    match params.section {
+    match &params.section {
+        PoincareSection::Event(_) => unreachable!(
+            "Hénon's trick only applies to the ConstTheta/ConstZeta angle planes -- an Event \
+             surface is handled by routines::event instead"
+        ),
         PoincareSection::ConstTheta => {
             let kappa = 1.0;
             let dt_dt = kappa;
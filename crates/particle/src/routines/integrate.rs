@@ -5,6 +5,7 @@ use std::time::Instant;
 use equilibrium::{Bfield, Current, Perturbation, Qfactor};
 
 use crate::IntegrationConfig;
+use crate::config::StepperConfig;
 use crate::{Evolution, Particle, State, Stepper};
 use crate::{ParticleError, Result};
 
@@ -60,7 +61,7 @@ pub(crate) fn integrate(
 
     particle.final_state = state1.into_evaluated(qfactor, currents, bfield, perturbation)?;
     particle.evolution.finish();
-    particle.calculate_orbit_type();
+    particle.calculate_orbit_type(config.orbit_classifier());
     particle.evolution.duration = start.elapsed();
     res
 }
@@ -0,0 +1,97 @@
+//! Post-integration check that the guiding-center invariants held along the stored orbit.
+//!
+//! Guiding-center motion in a time-independent field conserves the energy `E` and, in the
+//! unperturbed axisymmetric case, the toroidal canonical momentum `Pζ = ρ·g(ψ) − ψp`. Both are
+//! already recomputed at every stored state by [`Evolution::push_state`](crate::Evolution), so
+//! this module just reduces the resulting [`Evolution::energy_drift`]/[`pzeta_drift`](crate::Evolution::pzeta_drift)
+//! (and their absolute counterparts) down to a worst-case drift and compares it against the
+//! config's tolerances.
+
+use crate::config::StepperConfig;
+use crate::{Evolution, IntegrationStatus};
+
+/// The worst (largest-magnitude) drift of a time series already expressed as a per-step drift,
+/// ignoring `NaN` entries (the first stored state's drift from itself is always `0.0`/`NaN` and
+/// never the worst anyway).
+pub(crate) fn worst_drift(drift: &[f64]) -> f64 {
+    drift.iter().copied().fold(0.0, |worst, d| if d.is_finite() && d > worst { d } else { worst })
+}
+
+/// Checks whether the energy and (when `check_pzeta` is set) `Pζ` drifts recorded in `evolution`
+/// stayed within `config`'s dual abs/rel tolerances, returning the corresponding
+/// [`IntegrationStatus::InvariantDriftExceeded`] if either invariant failed either check.
+///
+/// `check_pzeta` must be `false` whenever a non-trivial [`Perturbation`](equilibrium::Perturbation)
+/// was supplied to the integration, since `Pζ` is not conserved in that case -- see
+/// [`Particle::worst_pzeta_drift`](crate::Particle::worst_pzeta_drift).
+pub(crate) fn check_invariant_drift(
+    evolution: &Evolution,
+    config: &impl StepperConfig,
+    check_pzeta: bool,
+) -> Option<IntegrationStatus> {
+    let energy_drift = worst_drift(&evolution.energy_drift);
+    let energy_abs_drift = worst_drift(&evolution.energy_abs_drift);
+    let energy_exceeded =
+        energy_abs_drift > config.energy_abs_tol() || energy_drift > config.energy_rel_tol();
+
+    let pzeta_drift = worst_drift(&evolution.pzeta_drift);
+    let pzeta_abs_drift = worst_drift(&evolution.pzeta_abs_drift);
+    let pzeta_exceeded = check_pzeta
+        && (pzeta_abs_drift > config.pzeta_abs_tol() || pzeta_drift > config.pzeta_rel_tol());
+
+    (energy_exceeded || pzeta_exceeded).then_some(IntegrationStatus::InvariantDriftExceeded {
+        energy_drift,
+        pzeta_drift: if check_pzeta { pzeta_drift } else { f64::NAN },
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_worst_drift() {
+        assert_eq!(worst_drift(&[0.0, 1e-10, 5e-9, 2e-10]), 5e-9);
+        assert_eq!(worst_drift(&[]), 0.0);
+        assert_eq!(worst_drift(&[f64::NAN, f64::NAN]), 0.0);
+    }
+
+    #[test]
+    fn test_check_invariant_drift_within_tolerance() {
+        let mut evolution = Evolution::default();
+        evolution.energy_drift = vec![0.0, 1e-11];
+        evolution.energy_abs_drift = vec![0.0, 1e-13];
+        evolution.pzeta_drift = vec![0.0, 1e-9];
+        evolution.pzeta_abs_drift = vec![0.0, 1e-11];
+
+        let config = crate::IntegrationConfig::default();
+        assert!(check_invariant_drift(&evolution, &config, true).is_none());
+    }
+
+    #[test]
+    fn test_check_invariant_drift_energy_exceeded() {
+        let mut evolution = Evolution::default();
+        evolution.energy_drift = vec![0.0, 1.0];
+        evolution.energy_abs_drift = vec![0.0, 1.0];
+        evolution.pzeta_drift = vec![0.0, 1e-9];
+        evolution.pzeta_abs_drift = vec![0.0, 1e-11];
+
+        let config = crate::IntegrationConfig::default();
+        assert!(matches!(
+            check_invariant_drift(&evolution, &config, true),
+            Some(IntegrationStatus::InvariantDriftExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_invariant_drift_pzeta_skipped_with_perturbation() {
+        let mut evolution = Evolution::default();
+        evolution.energy_drift = vec![0.0, 1e-11];
+        evolution.energy_abs_drift = vec![0.0, 1e-13];
+        evolution.pzeta_drift = vec![0.0, 1.0];
+        evolution.pzeta_abs_drift = vec![0.0, 1.0];
+
+        let config = crate::IntegrationConfig::default();
+        assert!(check_invariant_drift(&evolution, &config, false).is_none());
+    }
+}
@@ -4,6 +4,8 @@
 use std::f64::consts::TAU;
 use std::time::Instant;
 
+use crate::config::StepperConfig;
+use crate::routines::event::{EventFn, event_intersected, locate_event_crossing};
 use crate::routines::henon::{
     calculate_intersection_state, calculate_mod_state1, calculate_mod_state2, calculate_mod_step,
     intersected,
@@ -14,24 +16,47 @@ use crate::{ParticleError, Result};
 use equilibrium::{Bfield, Current, Perturbation, Qfactor};
 
 /// Defines the surface of the Poincare section.
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone)]
 pub enum PoincareSection {
     /// Defines a surface of xᵢ= θ.
     ConstTheta,
     /// Defines a surface of xᵢ= ζ.
     ConstZeta,
+    /// Defines an arbitrary scalar surface `g(state) = 0`, e.g. a flux surface `ψ = const`, an
+    /// energy shell, or a `Pζ` surface. Crossings are detected as sign changes of `g` and landed
+    /// on via regula-falsi refinement -- see [`crate::routines::event`] -- rather than Hénon's
+    /// trick, which the `ConstTheta`/`ConstZeta` variants above use.
+    Event(EventFn),
+}
+
+impl std::fmt::Debug for PoincareSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConstTheta => write!(f, "ConstTheta"),
+            Self::ConstZeta => write!(f, "ConstZeta"),
+            Self::Event(_) => write!(f, "Event(..)"),
+        }
+    }
 }
 
 /// Defines all the necessary parameters of a Poincare Map.
 #[non_exhaustive]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct MappingParameters {
-    /// The surface of section Σ, defined by an equation xᵢ= α, where xᵢ= θ or ζ.
+    /// The surface of section Σ, defined by an equation xᵢ= α, where xᵢ= θ or ζ, or an arbitrary
+    /// [`PoincareSection::Event`].
     pub section: PoincareSection,
-    /// The constant that defines the surface of section.
+    /// The constant that defines the surface of section. Unused for [`PoincareSection::Event`].
     pub alpha: f64,
     /// The number of interections to calculate.
     pub intersections: usize,
+    /// If set, only integrate far enough to collect half of [`Self::intersections`], then mirror
+    /// them under `θ → −θ` to synthesize the rest -- see
+    /// [`Particle::orbit_symmetry`](crate::Particle::orbit_symmetry). Halves the required
+    /// integration length for an up-down (or stellarator) symmetric equilibrium, at the cost of a
+    /// wrong result if the orbit is not actually symmetric, e.g. under a symmetry-breaking
+    /// [`Perturbation`](equilibrium::Perturbation) -- check [`Particle::orbit_symmetry`] first.
+    pub fold_symmetric: bool,
 }
 
 impl MappingParameters {
@@ -42,8 +67,26 @@ impl MappingParameters {
             section,
             alpha: alpha.rem_euclid(TAU),
             intersections,
+            fold_symmetric: false,
+        }
+    }
+
+    /// Creates a new [`MappingParameters`] for an arbitrary [`PoincareSection::Event`] surface
+    /// `g(state) = 0`. `alpha` is unused by this section and kept at `0.0`.
+    pub fn new_event(g: EventFn, intersections: usize) -> Self {
+        Self {
+            section: PoincareSection::Event(g),
+            alpha: 0.0,
+            intersections,
+            fold_symmetric: false,
         }
     }
+
+    /// Opts into the symmetric-fold/mirror optimization described on [`Self::fold_symmetric`].
+    pub fn with_fold_symmetric(mut self, fold_symmetric: bool) -> Self {
+        self.fold_symmetric = fold_symmetric;
+        self
+    }
 }
 
 /// Calculates the PoincareSection=const intersections.
@@ -68,6 +111,15 @@ pub(crate) fn map_integrate(
     let mut state2: State;
     let mut dt = config.first_step;
 
+    // Only half the intersections need to actually be integrated when folding onto the
+    // symmetric half-plane -- the rest are mirrored in after the fact, see
+    // `Evolution::mirror_intersections`.
+    let intersections_target = if params.fold_symmetric {
+        params.intersections.div_ceil(2)
+    } else {
+        params.intersections
+    };
+
     // ==================== Main loop
 
     loop {
@@ -75,7 +127,7 @@ pub(crate) fn map_integrate(
             res = Err(ParticleError::TimedOut(start.elapsed()));
             break;
         }
-        if particle.evolution.steps_stored() > params.intersections {
+        if particle.evolution.steps_stored() > intersections_target {
             res = Ok(());
             break;
         }
@@ -89,29 +141,62 @@ pub(crate) fn map_integrate(
             .into_evaluated(qfactor, current, bfield, perturbation)?;
         particle.evolution.steps_taken += 1;
 
-        // Hénon's trick.
-        // Depending on the PoincareSection, the independent variable becomes either `zeta` or
-        // `theta`. Checking its value in every function and every loop has negligible performance
-        // impact and produces much more readable code, instead of rewritting the same function
-        // twice.
-        let (old_angle, new_angle) = match params.section {
-            PoincareSection::ConstTheta => (state1.theta, state2.theta),
-            PoincareSection::ConstZeta => (state1.zeta, state2.zeta),
+        // Depending on the PoincareSection, intersections are detected/landed on via either
+        // Hénon's trick (angle planes) or regula-falsi refinement (an arbitrary event surface) --
+        // see `routines::event`.
+        let intersection_state = match &params.section {
+            PoincareSection::ConstTheta | PoincareSection::ConstZeta => {
+                let (old_angle, new_angle) = match &params.section {
+                    PoincareSection::ConstTheta => (state1.theta, state2.theta),
+                    PoincareSection::ConstZeta => (state1.zeta, state2.zeta),
+                    PoincareSection::Event(_) => unreachable!(),
+                };
+                intersected(old_angle, new_angle, params.alpha)
+                    .then(|| {
+                        let mod_state1 = calculate_mod_state1(&state1, &params.section);
+                        let dtau = calculate_mod_step(&state1, &state2, params);
+                        let mod_state2 = calculate_mod_state2(
+                            qfactor,
+                            current,
+                            bfield,
+                            perturbation,
+                            mod_state1,
+                            dtau,
+                        )?;
+                        calculate_intersection_state(
+                            qfactor,
+                            current,
+                            bfield,
+                            perturbation,
+                            params,
+                            mod_state2,
+                        )
+                    })
+                    .transpose()?
+            }
+            PoincareSection::Event(g) => {
+                let g1 = g(&state1);
+                let g2 = g(&state2);
+                event_intersected(g1, g2)
+                    .then(|| {
+                        locate_event_crossing(
+                            qfactor,
+                            current,
+                            bfield,
+                            perturbation,
+                            &state1,
+                            dt,
+                            g1,
+                            g2,
+                            g,
+                            config,
+                        )
+                    })
+                    .transpose()?
+            }
         };
-        if intersected(old_angle, new_angle, params.alpha) {
-            let mod_state1 = calculate_mod_state1(&state1, &params.section);
-            let dtau = calculate_mod_step(&state1, &state2, params);
-            let mod_state2 =
-                calculate_mod_state2(qfactor, current, bfield, perturbation, mod_state1, dtau)?;
-            let intersection_state = calculate_intersection_state(
-                qfactor,
-                current,
-                bfield,
-                perturbation,
-                params,
-                mod_state2,
-            )?;
 
+        if let Some(intersection_state) = intersection_state {
             particle.evolution.push_state(&intersection_state);
 
             // NOTE: Even after landing on the intersection, we must continue the integration from
@@ -129,12 +214,93 @@ pub(crate) fn map_integrate(
     check_mapping_accuracy(&particle.evolution, &params.section, config)?;
     particle.final_state = state1.into_evaluated(qfactor, current, bfield, perturbation)?;
     particle.evolution.finish();
-    particle.calculate_orbit_type();
+    // The advancing angle is whichever of θ/ζ is *not* pinned by the section: a ConstTheta
+    // section freezes θ at each crossing and lets ζ accumulate between crossings, and vice versa
+    // for ConstZeta. An arbitrary `Event` surface has no such pair, so no rotation number is
+    // estimated for it.
+    let advancing_angle = match params.section {
+        PoincareSection::ConstTheta => Some(&particle.evolution.zeta),
+        PoincareSection::ConstZeta => Some(&particle.evolution.theta),
+        PoincareSection::Event(_) => None,
+    };
+    if let Some(advancing_angle) = advancing_angle {
+        let (nu, nu_err) = rotation_number(advancing_angle);
+        particle.evolution.rotation_number = nu;
+        particle.evolution.rotation_number_err = nu_err;
+    }
+    particle.calculate_orbit_type(config.orbit_classifier());
     particle.evolution.duration = start.elapsed();
+    if params.fold_symmetric {
+        // Mirrored in last, after every check/statistic above already ran on the real
+        // (un-mirrored) intersections -- `rotation_number`/`check_mapping_accuracy` both assume
+        // consecutive stored ζ/θ actually came from successive crossings, which a mirrored copy
+        // does not.
+        particle.evolution.mirror_intersections();
+    }
     res
 }
 
-/// Checks if all the value diffs in the array are within the threshold.
+/// Estimates the rotation number `ν` of a Poincare mapping from its recorded advancing-angle
+/// samples (ζ for a [`PoincareSection::ConstTheta`] section, θ for
+/// [`PoincareSection::ConstZeta`]), via a weighted Birkhoff average of the per-intersection
+/// increments `δ_k = (x_{k+1} - x_k) mod TAU`. Weights `w_k = exp(-1/(s(1-s)))`, `s=(k+0.5)/N`,
+/// taper to 0 at both ends of the window, which converges dramatically faster than an unweighted
+/// (Birkhoff/ergodic) average for a regular orbit.
+///
+/// Returns `(ν, err)`, where `err` is the relative difference between `ν` computed over the first
+/// half of the window and over the full window -- small for a regular (invariant-curve) orbit,
+/// large for a chaotic one, since the weighted average only converges quickly in the regular case.
+/// Returns `(NAN, NAN)` if fewer than 2 intersections were recorded.
+pub(crate) fn rotation_number(zeta: &[f64]) -> (f64, f64) {
+    if zeta.len() < 2 {
+        return (f64::NAN, f64::NAN);
+    }
+
+    let deltas: Vec<f64> = zeta
+        .windows(2)
+        .map(|w| (w[1] - w[0]).rem_euclid(TAU))
+        .collect();
+
+    let nu_full = birkhoff_average(&deltas) / TAU;
+    let nu_half = birkhoff_average(&deltas[..deltas.len().div_ceil(2)]) / TAU;
+
+    let err = if nu_full == 0.0 {
+        0.0
+    } else {
+        ((nu_half - nu_full) / nu_full).abs()
+    };
+
+    (nu_full, err)
+}
+
+/// Computes the weighted Birkhoff average of `samples`, with weights `w_k = exp(-1/(s(1-s)))`,
+/// `s=(k+0.5)/len`, normalized to sum to 1.
+fn birkhoff_average(samples: &[f64]) -> f64 {
+    let n = samples.len();
+    let weights: Vec<f64> = (0..n)
+        .map(|k| {
+            let s = (k as f64 + 0.5) / n as f64;
+            (-1.0 / (s * (1.0 - s))).exp()
+        })
+        .collect();
+    let weight_sum: f64 = weights.iter().sum();
+
+    samples
+        .iter()
+        .zip(weights.iter())
+        .map(|(sample, weight)| sample * weight)
+        .sum::<f64>()
+        / weight_sum
+}
+
+/// Checks the accuracy of the recorded intersections.
+///
+/// The angle-plane sections check that every consecutive pair of intersections is `TAU` apart (a
+/// direct consequence of Hénon's trick landing inexactly on `params.alpha`). An arbitrary
+/// [`PoincareSection::Event`] has no such periodicity, so there is nothing analogous to check
+/// post-hoc: [`locate_event_crossing`] already only ever returns a state once `|g| <
+/// config.map_threshold`, so every recorded intersection's residual is already within tolerance by
+/// construction.
 fn check_mapping_accuracy(
     evolution: &Evolution,
     section: &PoincareSection,
@@ -143,6 +309,7 @@ fn check_mapping_accuracy(
     let intersections_array = match section {
         PoincareSection::ConstZeta => &evolution.zeta,
         PoincareSection::ConstTheta => &evolution.theta,
+        PoincareSection::Event(_) => return Ok(()),
     };
     _check_mapping_accuracy(intersections_array, config)
 }
@@ -222,4 +389,29 @@ mod test {
         assert!(_check_mapping_accuracy(&not_ok2, &config).is_err());
         assert!(_check_mapping_accuracy(&not_ok3, &config).is_err());
     }
+
+    #[test]
+    fn test_mirror_intersections_doubles_and_reflects() {
+        let mut evolution = Evolution::default();
+        for i in 0..4 {
+            let theta = i as f64 * 0.1;
+            evolution.theta.push(theta);
+            evolution.rho.push(0.3 + i as f64);
+            evolution.psip.push(0.5);
+            evolution.zeta.push(1.0);
+            evolution.time.push(i as f64);
+        }
+
+        evolution.mirror_intersections();
+
+        assert_eq!(evolution.theta.len(), 8);
+        assert_eq!(evolution.steps_stored(), 8);
+        // The mirrored half reflects θ and ρ but leaves ψp/ζ unchanged.
+        for i in 0..4 {
+            assert_eq!(evolution.theta[4 + i], -evolution.theta[i]);
+            assert_eq!(evolution.rho[4 + i], -evolution.rho[i]);
+            assert_eq!(evolution.psip[4 + i], evolution.psip[i]);
+            assert_eq!(evolution.zeta[4 + i], evolution.zeta[i]);
+        }
+    }
 }
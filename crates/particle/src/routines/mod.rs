@@ -26,15 +26,43 @@
 //!     5. Save duration
 //!     6. Return `res`
 
+mod action;
+mod bounce;
+mod event;
 mod integrate;
+mod invariants;
 mod map;
+mod naff;
+mod orbit_average;
+mod poincare_analysis;
+mod resample;
 mod single_period_integrate;
+mod symmetry;
 
 mod henon;
+mod period;
+mod quadrature;
 
+pub(crate) use action::{poloidal_action, toroidal_action};
+pub(crate) use bounce::bounce_average;
+pub(crate) use event::{brent, event_intersected, locate_event_crossing};
 pub(crate) use integrate::integrate;
+pub(crate) use invariants::{check_invariant_drift, worst_drift};
 pub(crate) use map::map_integrate;
+pub(crate) use naff::naff_frequencies;
+pub(crate) use orbit_average::orbit_average;
+pub(crate) use poincare_analysis::poincare_analysis;
+pub(crate) use resample::{resample, state_at};
 pub(crate) use single_period_integrate::close_theta_period;
+pub(crate) use symmetry::orbit_symmetry;
 
+pub use action::ActionIntegral;
+pub use bounce::{BounceAverages, Well};
+pub use event::EventFn;
 pub use map::{MappingParameters, PoincareSection};
+pub use naff::NaffFrequencies;
+pub use orbit_average::{EvolutionSample, OrbitAverage};
+pub use poincare_analysis::{OrbitClass, PoincareAnalysis};
+pub use resample::Parametrization;
 pub use single_period_integrate::Frequencies;
+pub use symmetry::OrbitSymmetry;
@@ -0,0 +1,237 @@
+//! Laskar's NAFF (Numerical Analysis of Fundamental Frequencies): extracts a quasi-periodic
+//! complex signal's dominant frequency far more precisely than reading off a period-counted
+//! average, by maximizing a windowed Fourier transform instead of locating zero-crossings.
+//!
+//! Unlike [`poloidal_action`](crate::routines::poloidal_action)/
+//! [`orbit_average`](crate::routines::orbit_average), which both need a single *closed* period,
+//! NAFF only needs a long enough dense window of the already-stored [`Evolution`] -- it is an
+//! alternative, higher-precision way to get `ωθ`/`ωζ` out of the same orbit that
+//! [`close_theta_period`](crate::routines::close_theta_period)'s Hénon's-trick period counting
+//! already produces via [`Frequencies`](crate::Frequencies), not a replacement for it: period
+//! counting runs inline during integration, while NAFF is a post-hoc analysis of whatever dense
+//! time series the caller already produced (typically via
+//! [`Particle::integrate`](crate::Particle::integrate)).
+//!
+//! This workspace has no FFT crate, so the coarse search for the dominant line is a direct
+//! windowed Fourier integral evaluated by trapezoidal quadrature over a linear frequency grid,
+//! rather than an FFT -- exact for the purpose (there is no requirement that the grid be a power
+//! of two, or even uniformly spaced in time), just `O(samples × grid points)` instead of
+//! `O(samples log samples)`.
+
+use std::f64::consts::{PI, TAU};
+
+use crate::Evolution;
+
+/// Below this many stored samples, NAFF has nowhere near enough of the signal to resolve a
+/// frequency at all.
+const MIN_SAMPLES: usize = 16;
+
+/// Number of points in the coarse frequency scan that brackets the true peak before golden-section
+/// refinement.
+const COARSE_SCAN_POINTS: usize = 400;
+
+/// Golden-section refinement iterations -- far more than needed to converge to `f64` precision
+/// within the coarse bracket, but each iteration is cheap relative to the coarse scan itself.
+const GOLDEN_SECTION_ITERATIONS: u32 = 80;
+
+/// A peak must stand out by at least this factor over the coarse scan's mean power to count as a
+/// genuine dominant line, rather than noise in a spectrum with no clear periodicity.
+const PEAK_PROMINENCE_FACTOR: f64 = 3.0;
+
+/// `ωθ`, `ωζ` and the derived kinetic safety factor, extracted from a dense, stored [`Evolution`]
+/// via NAFF rather than period counting. `None` wherever the windowed spectrum had no dominant
+/// line -- e.g. too few stored samples, or (for [`Self::omega_theta`]) a passing orbit whose `θ`
+/// winds monotonically rather than oscillating at a single bounce frequency.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NaffFrequencies {
+    /// The dominant frequency of `θ(t) + i·Pθ(t)`.
+    pub omega_theta: Option<f64>,
+    /// The dominant frequency of `ζ(t) + i·Pζ(t)`.
+    pub omega_zeta: Option<f64>,
+    /// `ωζ / ωθ`, `None` unless both frequencies were found.
+    pub qkinetic: Option<f64>,
+}
+
+/// Runs NAFF on `evolution`'s `(θ, Pθ)` and `(ζ, Pζ)` time series.
+pub(crate) fn naff_frequencies(evolution: &Evolution) -> NaffFrequencies {
+    let omega_theta = dominant_frequency(&evolution.time, &evolution.theta, &evolution.ptheta);
+    let omega_zeta = dominant_frequency(&evolution.time, &evolution.zeta, &evolution.pzeta);
+    let qkinetic = omega_zeta.zip(omega_theta).map(|(zeta, theta)| zeta / theta);
+
+    NaffFrequencies { omega_theta, omega_zeta, qkinetic }
+}
+
+/// Finds the dominant frequency of the complex signal `f(t) = re(t) + i·im(t)`, sampled at `time`,
+/// via a windowed Fourier transform `φ(ω) = (1/T)∫ f(t)·χ(t)·e^{−iωt} dt`, where
+/// `χ(t) = 1 − cos(2π(t−t0)/T)` is a Hann window already normalized so `(1/T)∫χ dt = 1` (it
+/// integrates to exactly `T` over the window by construction).
+///
+/// The integral is evaluated by trapezoidal quadrature directly over the stored (possibly
+/// non-uniform) samples. A coarse linear scan over strictly positive `ω` -- `ω = 0`, the signal's
+/// mean/secular drift, is deliberately excluded so a trapped orbit's genuinely-small bounce
+/// frequency is never mistaken for it -- brackets the peak, which is then refined by golden-section
+/// search. Returns `None` if there are too few samples, or the coarse spectrum has no line that
+/// stands out by at least [`PEAK_PROMINENCE_FACTOR`] over the scan's mean power.
+fn dominant_frequency(time: &[f64], re: &[f64], im: &[f64]) -> Option<f64> {
+    if time.len() < MIN_SAMPLES || re.len() != time.len() || im.len() != time.len() {
+        return None;
+    }
+
+    let t0 = time[0];
+    let span = time.last()? - t0;
+    if !(span > 0.0) {
+        return None;
+    }
+
+    let power_at = |omega: f64| windowed_power(time, re, im, omega, t0, span);
+
+    // Average Nyquist frequency for `time.len()` samples spread over `span`, and a floor comfortably
+    // below one full cycle over the whole window.
+    let omega_min = TAU / (span * 50.0);
+    let omega_max = PI * (time.len() - 1) as f64 / span;
+    if omega_max <= omega_min {
+        return None;
+    }
+
+    let grid: Vec<f64> = (0..COARSE_SCAN_POINTS)
+        .map(|i| omega_min + (omega_max - omega_min) * i as f64 / (COARSE_SCAN_POINTS - 1) as f64)
+        .collect();
+    let powers: Vec<f64> = grid.iter().map(|&omega| power_at(omega)).collect();
+
+    let (peak_idx, &peak_power) = powers.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))?;
+
+    // A peak right at the edge of the scanned band has no bracket to refine within, and likely
+    // means the true line lies outside `[omega_min, omega_max]` -- not a result worth reporting.
+    if peak_idx == 0 || peak_idx == grid.len() - 1 {
+        return None;
+    }
+
+    let mean_power = powers.iter().sum::<f64>() / powers.len() as f64;
+    if peak_power <= 0.0 || peak_power < PEAK_PROMINENCE_FACTOR * mean_power {
+        return None;
+    }
+
+    Some(golden_section_maximize(power_at, grid[peak_idx - 1], grid[peak_idx + 1]))
+}
+
+/// `|φ(ω)|` for the windowed Fourier transform described on [`dominant_frequency`], via
+/// trapezoidal quadrature over the stored samples.
+fn windowed_power(time: &[f64], re: &[f64], im: &[f64], omega: f64, t0: f64, span: f64) -> f64 {
+    let term = |k: usize| -> (f64, f64) {
+        let window = 1.0 - (TAU * (time[k] - t0) / span).cos();
+        let (sin_wt, cos_wt) = (omega * time[k]).sin_cos();
+        let real = (re[k] * cos_wt + im[k] * sin_wt) * window;
+        let imag = (im[k] * cos_wt - re[k] * sin_wt) * window;
+        (real, imag)
+    };
+
+    let (mut acc_re, mut acc_im) = (0.0, 0.0);
+    for k in 0..time.len() - 1 {
+        let dt = time[k + 1] - time[k];
+        let (a_re, a_im) = term(k);
+        let (b_re, b_im) = term(k + 1);
+        acc_re += 0.5 * dt * (a_re + b_re);
+        acc_im += 0.5 * dt * (a_im + b_im);
+    }
+
+    let norm = 1.0 / span;
+    (acc_re * norm).hypot(acc_im * norm)
+}
+
+/// Maximizes `f` over `[lo, hi]`, assumed unimodal on the interval (true here, since `[lo, hi]` was
+/// already bracketed around a single coarse-scan peak).
+fn golden_section_maximize(f: impl Fn(f64) -> f64, mut lo: f64, mut hi: f64) -> f64 {
+    const RESIZE: f64 = 0.6180339887498949; // 1/phi
+
+    let mut c = hi - RESIZE * (hi - lo);
+    let mut d = lo + RESIZE * (hi - lo);
+    let mut f_c = f(c);
+    let mut f_d = f(d);
+
+    for _ in 0..GOLDEN_SECTION_ITERATIONS {
+        if f_c > f_d {
+            hi = d;
+            d = c;
+            f_d = f_c;
+            c = hi - RESIZE * (hi - lo);
+            f_c = f(c);
+        } else {
+            lo = c;
+            c = d;
+            f_c = f_d;
+            d = lo + RESIZE * (hi - lo);
+            f_d = f(d);
+        }
+    }
+
+    0.5 * (lo + hi)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn synthetic_evolution(omega: f64, n: usize) -> Evolution {
+        // θ(t) = sin(ωt), Pθ(t) = cos(ωt): f(t) = Pθ + iθ = e^{iωt}, a pure single-frequency
+        // complex signal with a known dominant frequency.
+        let span = TAU * 40.0 / omega;
+        let mut evolution = Evolution::default();
+        for i in 0..n {
+            let t = span * i as f64 / (n - 1) as f64;
+            evolution.time.push(t);
+            evolution.theta.push((omega * t).sin());
+            evolution.ptheta.push((omega * t).cos());
+            evolution.zeta.push(0.0);
+            evolution.pzeta.push(0.0);
+        }
+        evolution
+    }
+
+    #[test]
+    fn test_naff_recovers_a_known_frequency() {
+        let omega = 3.7;
+        let evolution = synthetic_evolution(omega, 4000);
+
+        let found = dominant_frequency(&evolution.time, &evolution.ptheta, &evolution.theta)
+            .expect("a pure single-frequency signal must have a dominant line");
+        assert!((found - omega).abs() < 1e-6, "found {found}, expected {omega}");
+    }
+
+    #[test]
+    fn test_naff_frequencies_reports_qkinetic() {
+        let omega_theta = 2.0;
+        let evolution_theta = synthetic_evolution(omega_theta, 4000);
+        let mut evolution = evolution_theta;
+        let omega_zeta = 5.0;
+        for (i, t) in evolution.time.clone().iter().enumerate() {
+            evolution.zeta[i] = (omega_zeta * t).sin();
+            evolution.pzeta[i] = (omega_zeta * t).cos();
+        }
+
+        let frequencies = naff_frequencies(&evolution);
+        let qkinetic = frequencies.qkinetic.expect("both frequencies should be found");
+        assert!((qkinetic - omega_zeta / omega_theta).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_naff_returns_none_for_a_flat_signal() {
+        let mut evolution = Evolution::default();
+        for i in 0..100 {
+            evolution.time.push(i as f64 * 0.1);
+            evolution.theta.push(0.0);
+            evolution.ptheta.push(0.0);
+            evolution.zeta.push(0.0);
+            evolution.pzeta.push(0.0);
+        }
+
+        let frequencies = naff_frequencies(&evolution);
+        assert_eq!(frequencies.omega_theta, None);
+        assert_eq!(frequencies.omega_zeta, None);
+    }
+
+    #[test]
+    fn test_naff_returns_none_with_too_few_samples() {
+        let evolution = synthetic_evolution(1.0, 5);
+        assert_eq!(dominant_frequency(&evolution.time, &evolution.ptheta, &evolution.theta), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Orbit-averaged quantities computed from a dense, stored [`Evolution`].
+//!
+//! Shares [`first_closed_period`] and [`adaptive_gauss_kronrod`] with
+//! [`action`](crate::routines::action): both post-process whatever [`Evolution`] the caller
+//! already produced, integrating over the first closed `θ-ψp` period instead of stepping the
+//! particle again.
+
+use equilibrium::PchipSpline;
+
+use crate::routines::period::first_closed_period;
+use crate::routines::quadrature::adaptive_gauss_kronrod;
+use crate::{Evolution, Result};
+
+/// Relative tolerance (disagreement between the `G7`/`K15` estimate pair) at which a quadrature
+/// panel is accepted instead of bisected.
+const QUADRATURE_REL_TOL: f64 = 1e-10;
+
+/// Upper bound on panel bisections, so a pathological integrand can't recurse forever.
+const MAX_PANEL_BISECTIONS: u32 = 50;
+
+/// An [`Evolution`]'s state, splined and sampled at a single instant, for use inside an
+/// [`orbit_average`] closure.
+#[derive(Debug, Clone, Copy)]
+pub struct EvolutionSample {
+    pub time: f64,
+    /// The `θ` angle.
+    pub theta: f64,
+    /// The poloidal flux `ψp`.
+    pub psip: f64,
+    /// The parallel gyroradius `ρ_{||}`.
+    pub rho: f64,
+    /// The `ζ` angle.
+    pub zeta: f64,
+    /// The toroidal flux `ψ`.
+    pub psi: f64,
+    /// The canonical momentum `Pθ`.
+    pub ptheta: f64,
+    /// The canonical momentum `Pζ`.
+    pub pzeta: f64,
+    /// The energy.
+    pub energy: f64,
+}
+
+/// An orbit-averaged quantity `⟨f⟩ = ∮f dτ / ∮dτ`, together with its estimated absolute error.
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitAverage {
+    /// The value of the orbit average.
+    pub value: f64,
+    /// The quadrature's estimated absolute error on [`Self::value`].
+    pub error: f64,
+}
+
+/// Computes `⟨f⟩ = ∮f dτ / ∮dτ` over the first closed `θ-ψp` period found in `evolution`, i.e.
+/// between its first two successive crossings of its own starting `θ`.
+///
+/// `f` is evaluated at quadrature points strictly between the stored samples, via a
+/// [`PchipSpline`] fit through every one of `evolution`'s time series -- this is what lets the
+/// underlying adaptive quadrature refine past the integrator's own step size.
+pub(crate) fn orbit_average(
+    evolution: &Evolution,
+    f: impl Fn(EvolutionSample) -> f64,
+) -> Result<OrbitAverage> {
+    let (closing_index, t_end) = first_closed_period(&evolution.time, &evolution.theta)?;
+
+    let times = &evolution.time[..=closing_index + 1];
+    let t_start = times[0];
+
+    let splines = EvolutionSplines {
+        theta: PchipSpline::new(times, &evolution.theta[..=closing_index + 1]),
+        psip: PchipSpline::new(times, &evolution.psip[..=closing_index + 1]),
+        rho: PchipSpline::new(times, &evolution.rho[..=closing_index + 1]),
+        zeta: PchipSpline::new(times, &evolution.zeta[..=closing_index + 1]),
+        psi: PchipSpline::new(times, &evolution.psi[..=closing_index + 1]),
+        ptheta: PchipSpline::new(times, &evolution.ptheta[..=closing_index + 1]),
+        pzeta: PchipSpline::new(times, &evolution.pzeta[..=closing_index + 1]),
+        energy: PchipSpline::new(times, &evolution.energy[..=closing_index + 1]),
+    };
+
+    let integrand = |t: f64| f(splines.sample_at(t));
+    let (integral, error) =
+        adaptive_gauss_kronrod(&integrand, t_start, t_end, QUADRATURE_REL_TOL, MAX_PANEL_BISECTIONS);
+
+    let period = t_end - t_start;
+    Ok(OrbitAverage {
+        value: integral / period,
+        error: error / period,
+    })
+}
+
+/// Every stored [`Evolution`] field, splined over the integration window, so [`EvolutionSample`]s
+/// can be sampled at arbitrary quadrature points.
+struct EvolutionSplines {
+    theta: PchipSpline,
+    psip: PchipSpline,
+    rho: PchipSpline,
+    zeta: PchipSpline,
+    psi: PchipSpline,
+    ptheta: PchipSpline,
+    pzeta: PchipSpline,
+    energy: PchipSpline,
+}
+
+impl EvolutionSplines {
+    fn sample_at(&self, t: f64) -> EvolutionSample {
+        EvolutionSample {
+            time: t,
+            theta: self.theta.eval(t),
+            psip: self.psip.eval(t),
+            rho: self.rho.eval(t),
+            zeta: self.zeta.eval(t),
+            psi: self.psi.eval(t),
+            ptheta: self.ptheta.eval(t),
+            pzeta: self.pzeta.eval(t),
+            energy: self.energy.eval(t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::TAU;
+
+    use super::*;
+
+    #[test]
+    fn test_orbit_average_of_a_constant_field() {
+        // A field constant along the orbit averages to itself, regardless of the weighting.
+        let psip_value = 0.42;
+        let n = 200;
+        let mut evolution = Evolution::default();
+        for i in 0..=n {
+            let t = TAU * (i as f64) / (n as f64);
+            evolution.time.push(t);
+            evolution.theta.push(t);
+            evolution.zeta.push(0.0);
+            evolution.psip.push(psip_value);
+            evolution.rho.push(0.0);
+            evolution.psi.push(0.0);
+            evolution.ptheta.push(0.0);
+            evolution.pzeta.push(0.0);
+            evolution.energy.push(0.0);
+        }
+
+        let average = orbit_average(&evolution, |sample| sample.psip).unwrap();
+        assert!((average.value - psip_value).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_orbit_average_errors_on_non_closing_orbit() {
+        let mut evolution = Evolution::default();
+        for i in 0..=50 {
+            let t = i as f64 * 0.01;
+            evolution.time.push(t);
+            evolution.theta.push(t);
+            evolution.zeta.push(0.0);
+            evolution.psip.push(0.1);
+            evolution.rho.push(0.0);
+            evolution.psi.push(0.0);
+            evolution.ptheta.push(0.0);
+            evolution.pzeta.push(0.0);
+            evolution.energy.push(0.0);
+        }
+
+        assert!(orbit_average(&evolution, |sample| sample.psip).is_err());
+    }
+}
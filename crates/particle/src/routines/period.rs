@@ -0,0 +1,72 @@
+//! Locates the first period an orbit's own starting angle closes, shared by
+//! [`action`](crate::routines::action) and [`orbit_average`](crate::routines::orbit_average), both
+//! of which integrate over "one full period" of a dense, stored [`Evolution`](crate::Evolution).
+
+use std::f64::consts::TAU;
+
+use equilibrium::PchipSpline;
+
+use crate::routines::henon::intersected;
+use crate::{ParticleError, Result};
+
+/// Locates the first return of `angles` (`evolution.theta` or `evolution.zeta`) to its own
+/// starting value (mod 2π), and the exact time of that crossing.
+///
+/// Returns `(closing_index, t_end)`: `times[..=closing_index+1]`/`angles[..=closing_index+1]` is
+/// the window to integrate over, and `t_end` is the exact crossing time within
+/// `(times[closing_index], times[closing_index+1])`.
+pub(crate) fn first_closed_period(times: &[f64], angles: &[f64]) -> Result<(usize, f64)> {
+    if angles.len() < 2 {
+        return Err(ParticleError::IntersectionError);
+    }
+    let alpha = angles[0];
+
+    // Find the first return to `alpha` (mod 2π), skipping the trivial `i=0` self-crossing.
+    let closing_index = (1..angles.len() - 1)
+        .find(|&i| intersected(angles[i], angles[i + 1], alpha))
+        .ok_or(ParticleError::IntersectionError)?;
+
+    let angle_spline = PchipSpline::new(
+        &times[..=closing_index + 1],
+        &angles[..=closing_index + 1],
+    );
+    let t_end = closing_time(
+        &angle_spline,
+        times[closing_index],
+        times[closing_index + 1],
+        alpha,
+    );
+
+    Ok((closing_index, t_end))
+}
+
+/// Finds the exact time at which `angle_spline` crosses `alpha` (mod 2π) within `[lo, hi]`, via
+/// bisection. `[lo, hi]` is assumed to already bracket the crossing, as confirmed by
+/// [`intersected`] on the samples it was built from.
+///
+/// Mirrors the `%2π` pole handling in `calculate_mod_step`: since `angle_spline` is built from a
+/// raw, unwrapped angle that keeps growing past every `2π`, the crossing being sought is not
+/// necessarily at `alpha` itself, but at whichever `alpha + k*2π` actually falls inside the
+/// bracket.
+fn closing_time(angle_spline: &PchipSpline, lo: f64, hi: f64, alpha: f64) -> f64 {
+    let winding = ((angle_spline.eval(0.5 * (lo + hi)) - alpha) / TAU).round();
+    let target = alpha + winding * TAU;
+
+    let g = |t: f64| angle_spline.eval(t) - target;
+    let (mut lo, mut hi) = (lo, hi);
+    let mut g_lo = g(lo);
+    for _ in 0..100 {
+        let mid = 0.5 * (lo + hi);
+        let g_mid = g(mid);
+        if g_mid == 0.0 {
+            return mid;
+        }
+        if g_lo.signum() == g_mid.signum() {
+            lo = mid;
+            g_lo = g_mid;
+        } else {
+            hi = mid;
+        }
+    }
+    0.5 * (lo + hi)
+}
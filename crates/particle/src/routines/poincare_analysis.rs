@@ -0,0 +1,174 @@
+//! Physics interpretation of a finished Poincare mapping's rotation number.
+//!
+//! [`map_integrate`](crate::routines::map_integrate) already estimates the rotation number `ν`
+//! (see [`rotation_number`](crate::routines::map::rotation_number)) and stores it on
+//! [`Evolution`]. [`poincare_analysis`] turns that single number into the interpretation a user
+//! actually wants: the nearest low-order rational `p/q`, and whether the orbit looks like a
+//! regular KAM curve, a `q`-periodic island chain, or a chaotic trajectory.
+
+use crate::Evolution;
+
+/// The relative [`Evolution::rotation_number_err`] above which an orbit is classified
+/// [`OrbitClass::Chaotic`] regardless of how close `ν` sits to a rational -- the weighted
+/// Birkhoff average underlying `ν` only converges quickly on a genuinely regular orbit, so a
+/// large half-window/full-window discrepancy is itself evidence of non-convergence.
+const CHAOS_RELATIVE_ERROR_THRESHOLD: f64 = 0.1;
+
+/// The largest denominator [`continued_fraction_resonance`] will report, so deep-in-the-tail
+/// convergents of an irrational `ν` (spuriously "close" only because `q` is huge) are not
+/// mistaken for a genuine low-order resonance.
+const MAX_RESONANCE_DENOMINATOR: u64 = 50;
+
+/// Absolute tolerance on `|ν - p/q|` for [`continued_fraction_resonance`] to accept a convergent
+/// as the orbit's resonance.
+const RESONANCE_TOLERANCE: f64 = 1e-3;
+
+/// How a Poincare mapping's rotation number classifies the underlying orbit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrbitClass {
+    /// `ν` is not close to any low-order rational: an irrational-winding, regular KAM curve.
+    Regular,
+    /// `ν` sits within [`RESONANCE_TOLERANCE`] of a low-order rational `p/q`: a `q`-periodic
+    /// island chain (or its separatrix).
+    Island {
+        /// The island chain's periodicity `q`.
+        q: u64,
+    },
+    /// [`Evolution::rotation_number_err`] exceeds [`CHAOS_RELATIVE_ERROR_THRESHOLD`] (or no
+    /// rotation number could be estimated at all), so successive estimates of `ν` do not
+    /// converge: the signature of a chaotic trajectory rather than a resonance.
+    Chaotic,
+}
+
+/// Physics interpretation of a finished Poincare mapping's recorded rotation number.
+#[derive(Debug, Clone, Copy)]
+pub struct PoincareAnalysis {
+    /// The estimated rotation number `ν` (see [`Evolution::rotation_number`]).
+    pub rotation_number: f64,
+    /// The stochastic error bar on [`Self::rotation_number`] (see
+    /// [`Evolution::rotation_number_err`]).
+    pub rotation_number_err: f64,
+    /// The nearest low-order resonance `(p, q)` to [`Self::rotation_number`], if one was found
+    /// within [`RESONANCE_TOLERANCE`].
+    pub resonance: Option<(u64, u64)>,
+    /// The orbit's classification.
+    pub class: OrbitClass,
+}
+
+/// Builds a [`PoincareAnalysis`] from an already-mapped [`Evolution`]'s recorded rotation number.
+pub(crate) fn poincare_analysis(evolution: &Evolution) -> PoincareAnalysis {
+    let nu = evolution.rotation_number;
+    let nu_err = evolution.rotation_number_err;
+
+    let resonance = continued_fraction_resonance(nu, RESONANCE_TOLERANCE, MAX_RESONANCE_DENOMINATOR);
+
+    let class = if nu.is_nan() || nu_err.is_nan() || nu_err > CHAOS_RELATIVE_ERROR_THRESHOLD {
+        OrbitClass::Chaotic
+    } else {
+        match resonance {
+            Some((_, q)) => OrbitClass::Island { q },
+            None => OrbitClass::Regular,
+        }
+    };
+
+    PoincareAnalysis {
+        rotation_number: nu,
+        rotation_number_err: nu_err,
+        resonance,
+        class,
+    }
+}
+
+/// Approximates `x` (assumed to lie in the half-open interval 0 to 1) by a continued fraction,
+/// returning the last convergent
+/// `p/q` whose denominator does not exceed `max_denominator` -- the standard best-rational-
+/// approximation algorithm. Returns `None` if even that convergent misses `x` by more than `tol`.
+fn continued_fraction_resonance(x: f64, tol: f64, max_denominator: u64) -> Option<(u64, u64)> {
+    if !(0.0..1.0).contains(&x) {
+        return None;
+    }
+
+    let (mut p_prev, mut q_prev) = (0u64, 1u64);
+    let (mut p, mut q) = (1u64, 0u64);
+    let mut remainder = x;
+
+    loop {
+        let a = remainder.floor();
+        if !a.is_finite() || a < 0.0 || a > u64::MAX as f64 {
+            break;
+        }
+        let a = a as u64;
+
+        let p_new = a.saturating_mul(p).saturating_add(p_prev);
+        let q_new = a.saturating_mul(q).saturating_add(q_prev);
+        if q_new == 0 || q_new > max_denominator {
+            break;
+        }
+        p_prev = p;
+        q_prev = q;
+        p = p_new;
+        q = q_new;
+
+        let fraction = remainder - a as f64;
+        if fraction.abs() < 1e-12 {
+            break;
+        }
+        remainder = 1.0 / fraction;
+    }
+
+    if q == 0 {
+        return None;
+    }
+    ((x - p as f64 / q as f64).abs() < tol).then_some((p, q))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_continued_fraction_finds_exact_rationals() {
+        assert_eq!(
+            continued_fraction_resonance(1.0 / 3.0, 1e-9, 50),
+            Some((1, 3))
+        );
+        assert_eq!(
+            continued_fraction_resonance(2.0 / 5.0, 1e-9, 50),
+            Some((2, 5))
+        );
+    }
+
+    #[test]
+    fn test_continued_fraction_rejects_irrational_beyond_tolerance() {
+        // 1/sqrt(2) - 1, an irrational fraction, shouldn't lock onto any low-order rational to
+        // tight tolerance.
+        let x = std::f64::consts::FRAC_1_SQRT_2 - 0.5;
+        assert_eq!(continued_fraction_resonance(x, 1e-9, 50), None);
+    }
+
+    #[test]
+    fn test_poincare_analysis_classifies_regular_island_and_chaotic() {
+        let mut regular = Evolution::default();
+        regular.rotation_number = std::f64::consts::FRAC_1_SQRT_2 - 0.5;
+        regular.rotation_number_err = 1e-6;
+        assert_eq!(poincare_analysis(&regular).class, OrbitClass::Regular);
+
+        let mut island = Evolution::default();
+        island.rotation_number = 1.0 / 3.0;
+        island.rotation_number_err = 1e-6;
+        assert_eq!(
+            poincare_analysis(&island).class,
+            OrbitClass::Island { q: 3 }
+        );
+
+        let mut chaotic = Evolution::default();
+        chaotic.rotation_number = 1.0 / 3.0;
+        chaotic.rotation_number_err = 0.5;
+        assert_eq!(poincare_analysis(&chaotic).class, OrbitClass::Chaotic);
+
+        let mut undefined = Evolution::default();
+        undefined.rotation_number = f64::NAN;
+        undefined.rotation_number_err = f64::NAN;
+        assert_eq!(poincare_analysis(&undefined).class, OrbitClass::Chaotic);
+    }
+}
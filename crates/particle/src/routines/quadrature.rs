@@ -0,0 +1,106 @@
+//! Adaptive Gauss-Kronrod (G7, K15) quadrature over an arbitrary `f64 -> f64` integrand.
+//!
+//! Shared by [`action`](crate::routines::action) and
+//! [`orbit_average`](crate::routines::orbit_average), both of which post-process a dense, stored
+//! [`Evolution`](crate::Evolution) by integrating some functional of its splined time series.
+
+/// Kronrod-15 abscissas on `[0, 1]`, ordered outermost to innermost (the center node is handled
+/// separately). The embedded 7-point Gauss rule reuses the odd-indexed ones (`XGK[1]`, `XGK[3]`,
+/// `XGK[5]`) plus the center.
+const XGK: [f64; 7] = [
+    0.991455371120813,
+    0.949107912342759,
+    0.864864423359769,
+    0.741531185599394,
+    0.586087235467691,
+    0.405845151377397,
+    0.207784955007898,
+];
+
+/// Kronrod-15 weights, one per [`XGK`] entry plus the center node (`WGK[7]`).
+const WGK: [f64; 8] = [
+    0.022935322010529,
+    0.063092092629979,
+    0.104790010322250,
+    0.140653259715525,
+    0.169004726639267,
+    0.190350578064785,
+    0.204432940075298,
+    0.209482141084728,
+];
+
+/// Gauss-7 weights, one per reused [`XGK`] node (`WG[0..3]`) plus the center (`WG[3]`).
+const WG: [f64; 4] = [
+    0.129484966168870,
+    0.279705391489277,
+    0.381830050505119,
+    0.417959183673469,
+];
+
+/// Evaluates `f` over `[a, b]` with both the 15-point Kronrod rule and its embedded 7-point Gauss
+/// rule, returning `(kronrod_estimate, |kronrod - gauss|)`.
+fn gauss_kronrod_15(f: &impl Fn(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let center = 0.5 * (a + b);
+    let half_length = 0.5 * (b - a);
+
+    let f_center = f(center);
+    let mut kronrod_sum = WGK[7] * f_center;
+    let mut gauss_sum = WG[3] * f_center;
+
+    for i in 0..7 {
+        let dx = half_length * XGK[i];
+        let (f1, f2) = (f(center - dx), f(center + dx));
+        kronrod_sum += WGK[i] * (f1 + f2);
+        if i % 2 == 1 {
+            gauss_sum += WG[i / 2] * (f1 + f2);
+        }
+    }
+
+    let kronrod_result = kronrod_sum * half_length;
+    let gauss_result = gauss_sum * half_length;
+    (kronrod_result, (kronrod_result - gauss_result).abs())
+}
+
+/// Integrates `f` over `[a, b]`, bisecting any panel whose `(G7, K15)` pair disagrees by more
+/// than `rel_tol`, and summing the accepted panels' estimates and errors.
+pub(crate) fn adaptive_gauss_kronrod(
+    f: &impl Fn(f64) -> f64,
+    a: f64,
+    b: f64,
+    rel_tol: f64,
+    max_bisections: u32,
+) -> (f64, f64) {
+    let (estimate, error) = gauss_kronrod_15(f, a, b);
+    let relative_error = error / estimate.abs().max(f64::MIN_POSITIVE);
+
+    if relative_error <= rel_tol || max_bisections == 0 {
+        return (estimate, error);
+    }
+
+    let mid = 0.5 * (a + b);
+    let (left_value, left_error) = adaptive_gauss_kronrod(f, a, mid, rel_tol, max_bisections - 1);
+    let (right_value, right_error) = adaptive_gauss_kronrod(f, mid, b, rel_tol, max_bisections - 1);
+    (left_value + right_value, left_error + right_error)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gauss_kronrod_exact_on_polynomials() {
+        // G7/K15 are exact (up to rounding) for polynomials well within their degree.
+        let f = |x: f64| x.powi(3) - 2.0 * x.powi(2) + 1.0;
+        let (estimate, _) = gauss_kronrod_15(&f, 0.0, 2.0);
+        let exact = 2.0f64.powi(4) / 4.0 - 2.0 * 2.0f64.powi(3) / 3.0 + 2.0;
+        assert!((estimate - exact).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_adaptive_quadrature_sine() {
+        let (estimate, error) =
+            adaptive_gauss_kronrod(&f64::sin, 0.0, std::f64::consts::PI, 1e-12, 50);
+        assert!((estimate - 2.0).abs() < 1e-9);
+        assert!(error < 1e-6);
+    }
+}
@@ -0,0 +1,157 @@
+//! Re-evaluating a dense, stored [`Evolution`] at arbitrary times, without re-integrating.
+//!
+//! The ideal version of this would be the integrator's own dense output: a cubic Hermite
+//! interpolant built from each accepted step's endpoint derivatives (see
+//! [`hermite_dense_output`](crate::hermite_dense_output)), reconstructing the state to the
+//! integrator's own accuracy anywhere inside a step. That needs `rkf45::Stepper` to retain its
+//! accepted step's stage derivatives, which this checkout's `rkf45` module does not currently
+//! expose (see [`hermite_dense_output`](crate::hermite_dense_output)'s own doc comment). Until
+//! then, this module falls back to the same strategy [`orbit_average`](crate::routines::orbit_average)
+//! already uses for sub-step accuracy: a [`PchipSpline`] fit through every stored field, evaluated
+//! at whatever times the caller asks for -- lower fidelity than true dense output between widely
+//! spaced steps, but requiring nothing beyond the samples [`Evolution`] already stores.
+
+use equilibrium::PchipSpline;
+
+use crate::routines::orbit_average::EvolutionSample;
+use crate::Evolution;
+
+/// Which four fields of an [`EvolutionSample`] to emit: the guiding-center coordinates the
+/// integrator itself advances, or the derived canonical/physical quantities computed from them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parametrization {
+    /// `(θ, ψp, ρ, ζ)`, the coordinates the integrator itself advances.
+    GuidingCenter,
+    /// `(Pθ, Pζ, ψ, energy)`, derived quantities computed from the guiding-center coordinates.
+    Derived,
+}
+
+impl EvolutionSample {
+    /// This sample's fields, in the given [`Parametrization`].
+    pub fn in_parametrization(&self, parametrization: Parametrization) -> [f64; 4] {
+        match parametrization {
+            Parametrization::GuidingCenter => [self.theta, self.psip, self.rho, self.zeta],
+            Parametrization::Derived => [self.ptheta, self.pzeta, self.psi, self.energy],
+        }
+    }
+}
+
+/// Every stored [`Evolution`] field, splined over the whole integration window, so a state can be
+/// reconstructed at any time inside (or, by extrapolation, just outside) it.
+struct EvolutionSplines {
+    theta: PchipSpline,
+    psip: PchipSpline,
+    rho: PchipSpline,
+    zeta: PchipSpline,
+    psi: PchipSpline,
+    ptheta: PchipSpline,
+    pzeta: PchipSpline,
+    energy: PchipSpline,
+}
+
+impl EvolutionSplines {
+    /// `None` if `evolution` has fewer than two stored states -- a spline needs at least two
+    /// points to interpolate between.
+    fn new(evolution: &Evolution) -> Option<Self> {
+        if evolution.time.len() < 2 {
+            return None;
+        }
+        let times = &evolution.time;
+        Some(Self {
+            theta: PchipSpline::new(times, &evolution.theta),
+            psip: PchipSpline::new(times, &evolution.psip),
+            rho: PchipSpline::new(times, &evolution.rho),
+            zeta: PchipSpline::new(times, &evolution.zeta),
+            psi: PchipSpline::new(times, &evolution.psi),
+            ptheta: PchipSpline::new(times, &evolution.ptheta),
+            pzeta: PchipSpline::new(times, &evolution.pzeta),
+            energy: PchipSpline::new(times, &evolution.energy),
+        })
+    }
+
+    fn sample_at(&self, t: f64) -> EvolutionSample {
+        EvolutionSample {
+            time: t,
+            theta: self.theta.eval(t),
+            psip: self.psip.eval(t),
+            rho: self.rho.eval(t),
+            zeta: self.zeta.eval(t),
+            psi: self.psi.eval(t),
+            ptheta: self.ptheta.eval(t),
+            pzeta: self.pzeta.eval(t),
+            energy: self.energy.eval(t),
+        }
+    }
+}
+
+/// Reconstructs `evolution`'s state at a single time `t`, via [`EvolutionSplines`]. `None` if
+/// `evolution` has fewer than two stored states.
+pub(crate) fn state_at(evolution: &Evolution, t: f64) -> Option<EvolutionSample> {
+    Some(EvolutionSplines::new(evolution)?.sample_at(t))
+}
+
+/// Reconstructs `evolution`'s state at every time in `times`, via a single [`EvolutionSplines`]
+/// fit shared across all of them. `None` if `evolution` has fewer than two stored states.
+pub(crate) fn resample(evolution: &Evolution, times: &[f64]) -> Option<Vec<EvolutionSample>> {
+    let splines = EvolutionSplines::new(evolution)?;
+    Some(times.iter().map(|&t| splines.sample_at(t)).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn linear_evolution(n: usize) -> Evolution {
+        // Every field advances linearly in t, so any interpolation scheme should reproduce it
+        // to near machine precision, including at off-sample times.
+        let mut evolution = Evolution::default();
+        for i in 0..n {
+            let t = i as f64 * 0.1;
+            evolution.time.push(t);
+            evolution.theta.push(t);
+            evolution.psip.push(2.0 * t);
+            evolution.rho.push(3.0 * t);
+            evolution.zeta.push(4.0 * t);
+            evolution.psi.push(5.0 * t);
+            evolution.ptheta.push(6.0 * t);
+            evolution.pzeta.push(7.0 * t);
+            evolution.energy.push(8.0 * t);
+        }
+        evolution
+    }
+
+    #[test]
+    fn test_state_at_interpolates_between_samples() {
+        let evolution = linear_evolution(20);
+        let sample = state_at(&evolution, 0.55).expect("enough samples");
+        assert!((sample.theta - 0.55).abs() < 1e-9);
+        assert!((sample.psip - 1.10).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_resample_matches_state_at_pointwise() {
+        let evolution = linear_evolution(20);
+        let times = [0.25, 0.75, 1.25];
+        let resampled = resample(&evolution, &times).expect("enough samples");
+        for (&t, sample) in times.iter().zip(&resampled) {
+            let single = state_at(&evolution, t).expect("enough samples");
+            assert!((sample.theta - single.theta).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_state_at_returns_none_with_too_few_samples() {
+        let evolution = linear_evolution(1);
+        assert!(state_at(&evolution, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_in_parametrization_selects_the_right_fields() {
+        let evolution = linear_evolution(20);
+        let sample = state_at(&evolution, 0.5).expect("enough samples");
+        let guiding_center = sample.in_parametrization(Parametrization::GuidingCenter);
+        assert_eq!(guiding_center, [sample.theta, sample.psip, sample.rho, sample.zeta]);
+        let derived = sample.in_parametrization(Parametrization::Derived);
+        assert_eq!(derived, [sample.ptheta, sample.pzeta, sample.psi, sample.energy]);
+    }
+}
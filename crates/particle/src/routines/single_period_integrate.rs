@@ -0,0 +1,300 @@
+//! Integration of a [`Particle`] for one or more `θ-ψp` periods.
+
+use std::f64::consts::{PI, TAU};
+use std::time::Instant;
+
+use common::{array1D_getter_impl, fallible_primitive_getter_impl};
+use ndarray::Array1;
+
+use equilibrium::{Bfield, Current, Perturbation, Qfactor};
+
+use crate::config::StepperConfig;
+use crate::routines::henon::{
+    calculate_intersection_state, calculate_mod_state1, calculate_mod_state2, calculate_mod_step,
+    intersected,
+};
+use crate::{Evolution, Particle, SinglePeriodConfig, State, Stepper};
+use crate::{MappingParameters, PoincareSection};
+use crate::{ParticleError, Result};
+
+/// A particle's `ωθ`, `ωζ` and qkinetic, averaged over every `θ-ψp` period
+/// [`close_theta_period`] managed to close.
+///
+/// Each successive `θ0-ψp0` crossing contributes one sample of `T` and `Δζ`; `ωθ` and `ωζ` are
+/// reported as the mean over those samples, together with the sample's relative standard
+/// deviation (σ/μ), computed exactly as [`Evolution::finish`] computes `energy_std`. A single
+/// closed period gives no error estimate (`omega_theta_err`/`omega_zeta_err` are `None`); a large
+/// relative error over several periods is a cheap diagnostic for a non-closing or chaotic orbit.
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Frequencies {
+    omega_theta_samples: Vec<f64>,
+    omega_zeta_samples: Vec<f64>,
+    omega_theta: Option<f64>,
+    omega_zeta: Option<f64>,
+    omega_theta_err: Option<f64>,
+    omega_zeta_err: Option<f64>,
+    qkinetic: Option<f64>,
+}
+
+impl Frequencies {
+    /// Records one period's `ωθ`/`ωζ` sample and updates the running mean, relative error and
+    /// qkinetic.
+    pub(crate) fn push_period(&mut self, omega_theta: f64, omega_zeta: f64) {
+        self.omega_theta_samples.push(omega_theta);
+        self.omega_zeta_samples.push(omega_zeta);
+
+        let (mean, err) = mean_and_rel_std(&self.omega_theta_samples);
+        self.omega_theta = Some(mean);
+        self.omega_theta_err = err;
+
+        let (mean, err) = mean_and_rel_std(&self.omega_zeta_samples);
+        self.omega_zeta = Some(mean);
+        self.omega_zeta_err = err;
+
+        self.update_qkinetic();
+    }
+
+    /// Reconstructs a [`Frequencies`] from its per-period `ωθ`/`ωζ` samples, e.g. after receiving
+    /// them from a remote worker (see `heap::distributed`). Equivalent to replaying
+    /// [`Self::push_period`] for each sample pair, in order.
+    pub fn from_samples(omega_theta_samples: Vec<f64>, omega_zeta_samples: Vec<f64>) -> Self {
+        let mut frequencies = Self::default();
+        for (&omega_theta, &omega_zeta) in omega_theta_samples.iter().zip(&omega_zeta_samples) {
+            frequencies.push_period(omega_theta, omega_zeta);
+        }
+        frequencies
+    }
+
+    /// Sets qkinetic to ωζ/ωθ if both fields are Some(), otherwise None.
+    fn update_qkinetic(&mut self) {
+        self.qkinetic = self.omega_zeta.zip(self.omega_theta).map(|(z, t)| z / t);
+    }
+
+    array1D_getter_impl!(omega_theta_samples, omega_theta_samples);
+    array1D_getter_impl!(omega_zeta_samples, omega_zeta_samples);
+
+    fallible_primitive_getter_impl!(omega_theta, f64, "The mean `ωθ` over all closed periods.");
+    fallible_primitive_getter_impl!(omega_zeta, f64, "The mean `ωζ` over all closed periods.");
+    fallible_primitive_getter_impl!(
+        omega_theta_err,
+        f64,
+        "The relative standard deviation (σ/μ) of the `ωθ` samples. `None` until at least 2 \
+         periods have closed."
+    );
+    fallible_primitive_getter_impl!(
+        omega_zeta_err,
+        f64,
+        "The relative standard deviation (σ/μ) of the `ωζ` samples. `None` until at least 2 \
+         periods have closed."
+    );
+    fallible_primitive_getter_impl!(qkinetic, f64, "The kinetic safety factor ωζ/ωθ.");
+}
+
+/// Returns the sample mean and, if there are at least 2 samples, its relative standard deviation
+/// (σ/μ). A lone sample has no variance to report.
+fn mean_and_rel_std(samples: &[f64]) -> (f64, Option<f64>) {
+    let array = Array1::from_vec(samples.to_vec());
+    let mean = array.mean().unwrap_or(f64::NAN);
+    let err = (samples.len() >= 2).then(|| array.std(0.0) / mean);
+    (mean, err)
+}
+
+impl std::fmt::Debug for Frequencies {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn stringify(o: Option<f64>) -> String {
+            match o {
+                Some(value) => format!("{:.7}", value),
+                None => String::from("Not calculated"),
+            }
+        }
+
+        f.debug_struct("Frequencies")
+            .field("omega_theta", &stringify(self.omega_theta))
+            .field("omega_theta_err", &stringify(self.omega_theta_err))
+            .field("omega_zeta", &stringify(self.omega_zeta))
+            .field("omega_zeta_err", &stringify(self.omega_zeta_err))
+            .field("qkinetic", &stringify(self.qkinetic))
+            .field("periods closed", &self.omega_theta_samples.len())
+            .finish()
+    }
+}
+
+// ===============================================================================================
+
+/// How [`close_theta_period`] closes a `θ-ψp` period: [`Self::Bounce`] waits for `θ` to turn back
+/// through `theta0` (a trapped orbit), [`Self::Transit`] waits for `θ` to wind monotonically all
+/// the way to `theta0 + 2π` (a passing orbit, whose `θ` never turns back so never re-crosses
+/// `theta0` on its own).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TransitKind {
+    Bounce,
+    Transit,
+}
+
+/// The number of steps [`close_theta_period`] surveys before deciding [`TransitKind`] -- enough to
+/// see a sign change in `ρ` for a bouncing orbit, short enough that the survey itself doesn't eat
+/// meaningfully into `max_steps`.
+const TRANSIT_SURVEY_STEPS: usize = 64;
+
+/// Decides [`TransitKind`] from the sign of the parallel gyroradius `ρ` (which tracks `v∥`) over
+/// the survey: a sign change anywhere in it means the orbit bounces (trapped), no sign change
+/// means it transits monotonically (passing).
+fn decide_transit_kind(rho_survey: &[f64]) -> TransitKind {
+    let saw_positive = rho_survey.iter().any(|&r| r > 0.0);
+    let saw_negative = rho_survey.iter().any(|&r| r < 0.0);
+    if saw_positive && saw_negative {
+        TransitKind::Bounce
+    } else {
+        TransitKind::Transit
+    }
+}
+
+// ===============================================================================================
+
+/// Integrates the particle for [`SinglePeriodConfig::periods`] successive `θ-ψp` period(s),
+/// calculating its `ωθ`, `ωζ` and qkinetic from the mean over the per-period samples (see
+/// [`Frequencies`]).
+pub(crate) fn close_theta_period(
+    particle: &mut Particle,
+    qfactor: &impl Qfactor,
+    current: &impl Current,
+    bfield: &impl Bfield,
+    perturbation: &impl Perturbation,
+    config: &SinglePeriodConfig,
+) -> Result<()> {
+    // ==================== Setup
+
+    let res: Result<()>;
+    let start = Instant::now();
+    particle.evolution = Evolution::default();
+    particle.frequencies = Frequencies::default();
+    particle
+        .initial_state
+        .evaluate(qfactor, current, bfield, perturbation)?;
+    particle.evolution.push_state(&particle.initial_state);
+
+    let theta0 = particle.initial_state.theta;
+    let psip0 = particle.initial_state.psip;
+
+    let mut state1 = particle.initial_state.clone();
+    let mut state2: State;
+    let mut dt = config.first_step;
+
+    // Time and ζ of the last `θ0-ψp0` crossing; the first one is the particle's own start.
+    let mut last_time = particle.initial_state.time;
+    let mut last_zeta = particle.initial_state.zeta;
+    let mut periods_closed = 0;
+
+    // Decided once, after surveying the first `TRANSIT_SURVEY_STEPS` steps' `ρ` (see
+    // `decide_transit_kind`); `None` while the survey is still ongoing. `transit_target` is the
+    // unwrapped `θ` a transiting orbit's current period closes at -- `theta0 + 2π` for the first
+    // period, advanced by another `2π` each time one closes.
+    let mut rho_survey = vec![particle.initial_state.rho];
+    let mut transit_kind: Option<TransitKind> = None;
+    let mut transit_target = theta0 + TAU;
+
+    // ==================== Main loop
+
+    loop {
+        if particle.evolution.steps_taken() == config.max_steps {
+            particle.final_state = state1.clone();
+            res = Err(ParticleError::TimedOut(start.elapsed()));
+            break;
+        }
+
+        // Perform a step.
+        let mut stepper = Stepper::new(&state1);
+        stepper.start(dt, qfactor, current, bfield, perturbation)?;
+        dt = stepper.calculate_optimal_step(dt, config)?;
+        state2 = stepper.next_state(dt);
+        state2.evaluate(qfactor, current, bfield, perturbation)?;
+        particle.evolution.steps_taken += 1;
+
+        // Survey `ρ` until `TransitKind` is decided; no period can close before that.
+        if transit_kind.is_none() {
+            rho_survey.push(state2.rho);
+            if rho_survey.len() >= TRANSIT_SURVEY_STEPS {
+                transit_kind = Some(decide_transit_kind(&rho_survey));
+            }
+        }
+
+        // Check if close to a period.
+        // Use `intersected` rather than an `is_close` check to avoid stopping the particle
+        // immediately and hardcoding tolerances. Checking `psip` as well as `theta` is probably
+        // unnecessary here, but is safe.
+        let closing = match transit_kind {
+            None => false,
+            Some(TransitKind::Bounce) => {
+                intersected(state1.psip, state2.psip, psip0)
+                    && intersected(state1.theta, state2.theta, theta0)
+            }
+            // `θ` never turns back for a transiting (passing) orbit, so it never re-crosses
+            // `theta0` on its own -- wait for it to instead wind all the way to the unwrapped
+            // `transit_target` (`theta0 + 2π`, then `+ 2π` again per further period).
+            Some(TransitKind::Transit) => {
+                (state1.theta - transit_target) * (state2.theta - transit_target) <= 0.0
+            }
+        };
+
+        if closing {
+            // Hénon's trick.
+            // Go back to `state1` and find the exact step that brings `θ` to its initial value
+            // *exactly*, but not `ψp`, although the difference is negligible.
+            // TODO: this residual on ψp is what the per-period samples below are meant to expose.
+            //
+            // `alpha` is always `theta0` reduced mod 2π -- `calculate_mod_step` only ever needs
+            // the target angle *within the current lap* to compute the residual step, regardless
+            // of which unwrapped `transit_target` the outer `closing` check above is waiting for.
+            let params = MappingParameters {
+                section: PoincareSection::ConstTheta,
+                alpha: theta0,
+                intersections: 1,
+                fold_symmetric: false,
+            };
+            let mod_state1 = calculate_mod_state1(&state1, &params.section);
+            let dtau = calculate_mod_step(&state1, &state2, &params);
+            let mod_state2 =
+                calculate_mod_state2(qfactor, current, bfield, perturbation, mod_state1, dtau)?;
+            let intersection_state = calculate_intersection_state(
+                qfactor,
+                current,
+                bfield,
+                perturbation,
+                &params,
+                mod_state2,
+            )?;
+
+            let t_period = intersection_state.time - last_time;
+            let omega_theta = 2.0 * PI / t_period;
+            // NOTE:
+            // >>> ωζ is the bounce/transit averaged rate of toroidal precession Δζ/T over this
+            // >>> period, not the instantaneous dζ/dt.
+            let omega_zeta = (intersection_state.zeta - last_zeta) / t_period;
+            particle.frequencies.push_period(omega_theta, omega_zeta);
+
+            particle.evolution.push_state(&intersection_state);
+            last_time = intersection_state.time;
+            last_zeta = intersection_state.zeta;
+            periods_closed += 1;
+            transit_target += TAU;
+
+            if periods_closed == config.periods {
+                particle.final_state = intersection_state;
+                res = Ok(());
+                break;
+            }
+            // NOTE: Even after landing on the intersection, we must continue the integration from
+            // `state2`, not the intersection state -- landing exactly on it would most likely
+            // misfire the next period's sign-change detection (see the analogous note in
+            // `map_integrate`).
+        }
+        state1 = state2;
+    }
+
+    // ==================== Finalization
+
+    particle.evolution.finish();
+    particle.calculate_orbit_type(config.orbit_classifier());
+    particle.evolution.duration = start.elapsed();
+    res
+}
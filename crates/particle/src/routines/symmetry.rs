@@ -0,0 +1,115 @@
+//! Up-down symmetry detection for a completed orbit.
+//!
+//! An axisymmetric equilibrium without a perturbation (or a stellarator symmetric one) traces
+//! out a guiding-center orbit whose `(ψp, θ)` points are invariant under `θ → −θ`. This module
+//! tests that invariance directly from the stored [`Evolution`] rather than from the equilibrium
+//! itself, so a [`Perturbation`](equilibrium::Perturbation) with non-trivial poloidal/toroidal
+//! mode numbers shows up as a nonzero [`OrbitSymmetry::residual`] rather than a silent loss of
+//! symmetry.
+
+use crate::Evolution;
+
+/// The `ψp` mismatch below which a sampled point's `−θ` counterpart counts as a match.
+const SYMMETRY_PSIP_TOL: f64 = 1e-6;
+
+/// Whether [`orbit_symmetry`] found the stored orbit invariant under `θ → −θ`, and by how much.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitSymmetry {
+    /// Whether every sampled `(ψp, θ)` point has a `(ψp, −θ)` counterpart elsewhere in the orbit,
+    /// within [`SYMMETRY_PSIP_TOL`].
+    pub up_down: bool,
+    /// The worst (largest) `ψp` mismatch between a sampled point and its closest-in-`θ` `−θ`
+    /// counterpart. `0.0` for a perfectly symmetric orbit, growing with the size of a
+    /// symmetry-breaking perturbation. `NAN` if `evolution` has fewer than 2 stored states.
+    pub residual: f64,
+}
+
+/// Tests the `(ψp, θ)` points stored in `evolution` for invariance under `θ → −θ`.
+///
+/// For every stored point, finds the orbit's own sample closest in `θ` to `−θ` (via binary search
+/// over the points sorted by `θ`) and tracks the worst `ψp` mismatch across all of them. This
+/// reuses whatever density the caller already integrated at, rather than stepping the particle
+/// again or fitting a spline -- `θ` need not be monotonic (it oscillates for a trapped orbit), so
+/// a spline over `θ` as the independent variable is not an option here, unlike
+/// [`poloidal_action`](crate::routines::poloidal_action)'s spline over time.
+pub(crate) fn orbit_symmetry(evolution: &Evolution) -> OrbitSymmetry {
+    let mut points: Vec<(f64, f64)> = evolution
+        .theta
+        .iter()
+        .copied()
+        .zip(evolution.psip.iter().copied())
+        .collect();
+
+    if points.len() < 2 {
+        return OrbitSymmetry { up_down: false, residual: f64::NAN };
+    }
+
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let thetas: Vec<f64> = points.iter().map(|&(theta, _)| theta).collect();
+
+    let residual = points
+        .iter()
+        .map(|&(theta, psip)| {
+            let target = -theta;
+            let insertion = thetas.partition_point(|&t| t < target);
+            // `partition_point` only gives the insertion point, not the closer of its two
+            // neighbors, so both straddling candidates are checked.
+            [insertion.checked_sub(1), Some(insertion).filter(|&i| i < thetas.len())]
+                .into_iter()
+                .flatten()
+                .map(|i| (points[i].1 - psip).abs())
+                .fold(f64::INFINITY, f64::min)
+        })
+        .fold(0.0_f64, f64::max);
+
+    OrbitSymmetry { up_down: residual < SYMMETRY_PSIP_TOL, residual }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f64::consts::TAU;
+
+    #[test]
+    fn test_orbit_symmetry_detects_a_symmetric_orbit() {
+        // ψp(θ) = cos(θ) is manifestly even in θ, so this orbit is exactly up-down symmetric.
+        let mut evolution = Evolution::default();
+        let n = 200;
+        for i in 0..=n {
+            let theta = -std::f64::consts::PI + TAU * (i as f64) / (n as f64);
+            evolution.theta.push(theta);
+            evolution.psip.push(theta.cos());
+        }
+
+        let symmetry = orbit_symmetry(&evolution);
+        assert!(symmetry.up_down);
+        assert!(symmetry.residual < 1e-6);
+    }
+
+    #[test]
+    fn test_orbit_symmetry_reports_a_broken_orbit() {
+        // ψp(θ) = cos(θ) + 0.2θ is not even in θ: a sizeable, genuinely asymmetric perturbation.
+        let mut evolution = Evolution::default();
+        let n = 200;
+        for i in 0..=n {
+            let theta = -std::f64::consts::PI + TAU * (i as f64) / (n as f64);
+            evolution.theta.push(theta);
+            evolution.psip.push(theta.cos() + 0.2 * theta);
+        }
+
+        let symmetry = orbit_symmetry(&evolution);
+        assert!(!symmetry.up_down);
+        assert!(symmetry.residual > 0.1);
+    }
+
+    #[test]
+    fn test_orbit_symmetry_nan_residual_with_too_few_points() {
+        let mut evolution = Evolution::default();
+        evolution.theta.push(0.0);
+        evolution.psip.push(0.0);
+
+        let symmetry = orbit_symmetry(&evolution);
+        assert!(!symmetry.up_down);
+        assert!(symmetry.residual.is_nan());
+    }
+}
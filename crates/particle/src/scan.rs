@@ -0,0 +1,190 @@
+//! Parameter scans over a grid of [`InitialConditions`], producing a family of Poincare sections.
+//!
+//! A single [`Particle::map`] answers "what does the orbit starting here look like"; a scan
+//! answers "how does that orbit change as I sweep one initial condition", which is the usual way
+//! to build up a picture of a device's phase space -- trapped/passing boundaries, resonances,
+//! island chains -- one slice at a time. [`run_scan`] builds one [`Particle`] per grid point and
+//! hands the whole batch to [`map_ensemble`], so the scan is as parallel as mapping a single
+//! particle already was.
+
+use equilibrium::{Bfield, Current, Perturbation, Qfactor};
+
+use crate::{Evolution, InitialConditions, IntegrationStatus, MappingConfig, MappingParameters};
+use crate::{Particle, map_ensemble};
+
+/// The [`InitialConditions`] field a [`ScanConfig`] sweeps over.
+#[derive(Debug, Clone, Copy)]
+pub enum ScanParameter {
+    /// Sweeps [`InitialConditions::theta0`].
+    Theta0,
+    /// Sweeps [`InitialConditions::psip0`].
+    Psip0,
+    /// Sweeps [`InitialConditions::rho0`].
+    Rho0,
+    /// Sweeps [`InitialConditions::zeta0`].
+    Zeta0,
+    /// Sweeps [`InitialConditions::mu`].
+    Mu,
+}
+
+/// The spacing of a [`ScanConfig`]'s grid.
+#[derive(Debug, Clone, Copy)]
+pub enum ScanSpacing {
+    /// `steps` points evenly spaced between `start` and `stop`.
+    Linear,
+    /// `steps` points evenly spaced in log-space between `start` and `stop`. Both bounds must be
+    /// strictly positive.
+    Log,
+}
+
+/// Defines a grid of [`InitialConditions`], generated by sweeping one [`ScanParameter`] over
+/// `start..=stop` while holding every other field at `base`'s value.
+#[derive(Debug, Clone)]
+pub struct ScanConfig {
+    /// The [`InitialConditions`] every grid point starts from, except for the swept field.
+    pub base: InitialConditions,
+    /// The field of `base` that is swept.
+    pub parameter: ScanParameter,
+    /// The first value of the swept parameter.
+    pub start: f64,
+    /// The last value of the swept parameter.
+    pub stop: f64,
+    /// The number of grid points, including both `start` and `stop`.
+    pub steps: usize,
+    /// The grid's spacing.
+    pub spacing: ScanSpacing,
+}
+
+impl ScanConfig {
+    /// Creates a new [`ScanConfig`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `steps < 2`.
+    pub fn new(
+        base: InitialConditions,
+        parameter: ScanParameter,
+        start: f64,
+        stop: f64,
+        steps: usize,
+        spacing: ScanSpacing,
+    ) -> Self {
+        assert!(steps >= 2, "a scan needs at least two grid points");
+        Self {
+            base,
+            parameter,
+            start,
+            stop,
+            steps,
+            spacing,
+        }
+    }
+
+    /// The swept parameter's values, in grid order.
+    fn values(&self) -> Vec<f64> {
+        match self.spacing {
+            ScanSpacing::Linear => {
+                let step = (self.stop - self.start) / (self.steps - 1) as f64;
+                (0..self.steps)
+                    .map(|i| self.start + i as f64 * step)
+                    .collect()
+            }
+            ScanSpacing::Log => {
+                assert!(
+                    self.start > 0.0 && self.stop > 0.0,
+                    "a log-spaced scan needs strictly positive bounds"
+                );
+                let (log_start, log_stop) = (self.start.ln(), self.stop.ln());
+                let step = (log_stop - log_start) / (self.steps - 1) as f64;
+                (0..self.steps)
+                    .map(|i| (log_start + i as f64 * step).exp())
+                    .collect()
+            }
+        }
+    }
+
+    /// `base` with the swept field set to `value`.
+    fn initial_conditions_at(&self, value: f64) -> InitialConditions {
+        let mut ic = self.base.clone();
+        match self.parameter {
+            ScanParameter::Theta0 => ic.theta0 = value,
+            ScanParameter::Psip0 => ic.psip0 = value,
+            ScanParameter::Rho0 => ic.rho0 = value,
+            ScanParameter::Zeta0 => ic.zeta0 = value,
+            ScanParameter::Mu => ic.mu = value,
+        }
+        ic
+    }
+}
+
+/// One grid point of a [`ScanResult`].
+#[derive(Debug)]
+pub struct ScanPoint {
+    /// The swept parameter's value at this grid point.
+    pub parameter_value: f64,
+    /// The mapped intersections, as an [`Evolution`]. Empty (or partial) if `status` is not
+    /// [`IntegrationStatus::Mapped`].
+    pub intersections: Evolution,
+    /// The particle's final [`IntegrationStatus`] -- surfaced as-is, so that a grid point that
+    /// timed out or produced invalid intersections stays visible instead of being dropped.
+    pub status: IntegrationStatus,
+}
+
+/// The result of a [`run_scan`] call: one [`ScanPoint`] per grid point, in grid order.
+#[derive(Debug)]
+pub struct ScanResult {
+    /// One entry per grid point, in grid order.
+    pub points: Vec<ScanPoint>,
+}
+
+impl ScanResult {
+    /// Iterates over the grid points whose [`IntegrationStatus`] is not
+    /// [`IntegrationStatus::Mapped`], i.e. the parameter values where the scan broke down.
+    pub fn failures(&self) -> impl Iterator<Item = &ScanPoint> {
+        self.points.iter().filter(|p| !p.status.is_mapped())
+    }
+}
+
+/// Runs `scan`'s grid of [`InitialConditions`] through [`map_ensemble`], producing a family of
+/// Poincare sections.
+///
+/// Every grid point is mapped concurrently, exactly as [`map_ensemble`] does for a batch of
+/// unrelated particles -- a scan is, after all, just a batch of particles whose initial conditions
+/// happen to lie on a grid. Grid points that time out or fail the mapping accuracy check still get
+/// a [`ScanPoint`], with their [`IntegrationStatus`] recording what went wrong.
+pub fn run_scan(
+    scan: &ScanConfig,
+    qfactor: &(impl Qfactor + Sync),
+    current: &(impl Current + Sync),
+    bfield: &(impl Bfield + Sync),
+    perturbation: &(impl Perturbation + Sync),
+    params: &MappingParameters,
+    config: &MappingConfig,
+) -> ScanResult {
+    let values = scan.values();
+    let mut particles: Vec<Particle> = values
+        .iter()
+        .map(|&value| Particle::new(&scan.initial_conditions_at(value)))
+        .collect();
+
+    map_ensemble(
+        &mut particles,
+        qfactor,
+        current,
+        bfield,
+        perturbation,
+        params,
+        config,
+    );
+
+    let points = values
+        .into_iter()
+        .zip(particles)
+        .map(|(parameter_value, particle)| ScanPoint {
+            parameter_value,
+            intersections: particle.evolution,
+            status: particle.status,
+        })
+        .collect();
+    ScanResult { points }
+}
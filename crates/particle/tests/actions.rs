@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+
+use equilibrium::{
+    bfields::*, currents::*, geometries::*, harmonics::*, perturbations::*, qfactors::*,
+};
+use particle::*;
+
+#[test]
+fn test_particle_action_integrals() {
+    let path = PathBuf::from("../equilibrium/lar_netcdf.nc");
+
+    let geometry = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+        .build()
+        .unwrap();
+    let qfactor = NcQfactorBuilder::new(&path, "steffen").build().unwrap();
+    let current = NcCurrentBuilder::new(&path, "steffen").build().unwrap();
+    let bfield = NcBfieldBuilder::new(&path, "bicubic").build().unwrap();
+    let perturbation = NcPerturbation::from_harmonics(&vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1)
+            .build()
+            .unwrap(),
+    ]);
+
+    let initial_conditions = InitialConditions {
+        time0: 0.0,
+        theta0: 0.0,
+        psip0: geometry.psip_wall() / 2.0,
+        rho0: 1e-4,
+        zeta0: 0.0,
+        mu: 0.0,
+    };
+
+    let mut particle = Particle::new(&initial_conditions);
+    let config = IntegrationConfig::default();
+    particle.integrate(
+        &qfactor,
+        &current,
+        &bfield,
+        &perturbation,
+        (0.0, 1e6),
+        &config,
+    );
+    assert!(particle.status.is_integrated());
+
+    let poloidal = particle.poloidal_action().unwrap();
+    assert!(poloidal.value.is_finite());
+    assert!(poloidal.error.is_finite());
+
+    let toroidal = particle.toroidal_action().unwrap();
+    assert!(toroidal.value.is_finite());
+    assert!(toroidal.error.is_finite());
+}
+
+#[test]
+fn test_action_integral_errors_on_non_closing_orbit() {
+    let path = PathBuf::from("../equilibrium/lar_netcdf.nc");
+
+    let geometry = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+        .build()
+        .unwrap();
+    let qfactor = NcQfactorBuilder::new(&path, "steffen").build().unwrap();
+    let current = NcCurrentBuilder::new(&path, "steffen").build().unwrap();
+    let bfield = NcBfieldBuilder::new(&path, "bicubic").build().unwrap();
+    let perturbation = NcPerturbation::from_harmonics(&vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1)
+            .build()
+            .unwrap(),
+    ]);
+
+    let initial_conditions = InitialConditions {
+        time0: 0.0,
+        theta0: 0.0,
+        psip0: geometry.psip_wall() / 2.0,
+        rho0: 1e-4,
+        zeta0: 0.0,
+        mu: 0.0,
+    };
+
+    // A handful of steps is nowhere near enough to close a `θ-ψp` period.
+    let mut particle = Particle::new(&initial_conditions);
+    let config = IntegrationConfig {
+        max_steps: 5,
+        ..Default::default()
+    };
+    particle.integrate(
+        &qfactor,
+        &current,
+        &bfield,
+        &perturbation,
+        (0.0, 1e10),
+        &config,
+    );
+    assert!(particle.status.is_timed_out());
+
+    assert!(particle.poloidal_action().is_err());
+}
@@ -0,0 +1,179 @@
+use std::path::PathBuf;
+
+use equilibrium::{
+    bfields::*, currents::*, geometries::*, harmonics::*, perturbations::*, qfactors::*,
+};
+use particle::*;
+
+#[test]
+fn test_single_period_integrate_ensemble() {
+    let path = PathBuf::from("../equilibrium/lar_netcdf.nc");
+
+    let geometry = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+        .build()
+        .unwrap();
+    let qfactor = NcQfactorBuilder::new(&path, "steffen").build().unwrap();
+    let current = NcCurrentBuilder::new(&path, "steffen").build().unwrap();
+    let bfield = NcBfieldBuilder::new(&path, "bicubic").build().unwrap();
+    let perturbation = NcPerturbation::from_harmonics(&vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1)
+            .build()
+            .unwrap(),
+        NcHarmonicBuilder::new(&path, "steffen", 3, 2)
+            .build()
+            .unwrap(),
+    ]);
+
+    let mut particles: Vec<Particle> = (1..=8)
+        .map(|n| {
+            let initial_conditions = InitialConditions {
+                time0: 0.0,
+                theta0: 2.0,
+                psip0: geometry.psip_wall() * (n as f64) / 9.0,
+                rho0: 1e-6,
+                zeta0: 0.0,
+                mu: 0.0,
+            };
+            Particle::new(&initial_conditions)
+        })
+        .collect();
+
+    let config = SinglePeriodConfig::default();
+    let results = single_period_integrate_ensemble(
+        &mut particles,
+        &qfactor,
+        &current,
+        &bfield,
+        &perturbation,
+        &config,
+        false,
+    );
+
+    assert_eq!(results.len(), particles.len());
+    for (particle, result) in particles.iter().zip(results.iter()) {
+        assert!(result.is_ok());
+        assert!(particle.status.is_single_period_integrated());
+        assert!(particle.final_energy().is_finite());
+    }
+
+    // A deliberately tiny step budget should surface as `TimedOut` rather than abort the batch.
+    let config = SinglePeriodConfig {
+        max_steps: 10,
+        ..Default::default()
+    };
+    let results = single_period_integrate_ensemble(
+        &mut particles,
+        &qfactor,
+        &current,
+        &bfield,
+        &perturbation,
+        &config,
+        true,
+    );
+
+    assert_eq!(results.len(), particles.len());
+    for (particle, result) in particles.iter().zip(results.iter()) {
+        assert!(result.is_err());
+        assert!(particle.status.is_timed_out());
+        assert_eq!(particle.evolution.steps_taken(), 10);
+        assert_eq!(particle.evolution.steps_stored(), 0);
+    }
+}
+
+#[test]
+fn test_integrate_ensemble() {
+    let path = PathBuf::from("../equilibrium/lar_netcdf.nc");
+
+    let geometry = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+        .build()
+        .unwrap();
+    let qfactor = NcQfactorBuilder::new(&path, "steffen").build().unwrap();
+    let current = NcCurrentBuilder::new(&path, "steffen").build().unwrap();
+    let bfield = NcBfieldBuilder::new(&path, "bicubic").build().unwrap();
+    let perturbation = NcPerturbation::from_harmonics(&vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1)
+            .build()
+            .unwrap(),
+    ]);
+
+    let mut particles: Vec<Particle> = (1..=8)
+        .map(|n| {
+            let initial_conditions = InitialConditions {
+                time0: 0.0,
+                theta0: 2.0,
+                psip0: geometry.psip_wall() * (n as f64) / 9.0,
+                rho0: 1e-6,
+                zeta0: 0.0,
+                mu: 0.0,
+            };
+            Particle::new(&initial_conditions)
+        })
+        .collect();
+
+    let config = IntegrationConfig::default();
+    let results = integrate_ensemble(
+        &mut particles,
+        &qfactor,
+        &current,
+        &bfield,
+        &perturbation,
+        (0.0, 10.0),
+        &config,
+    );
+
+    assert_eq!(results.len(), particles.len());
+    for (particle, result) in particles.iter().zip(results.iter()) {
+        assert!(result.is_ok());
+        assert!(particle.status.is_integrated());
+        assert!(particle.final_energy().is_finite());
+    }
+}
+
+#[test]
+fn test_map_ensemble() {
+    let path = PathBuf::from("../equilibrium/lar_netcdf.nc");
+
+    let geometry = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+        .build()
+        .unwrap();
+    let qfactor = NcQfactorBuilder::new(&path, "steffen").build().unwrap();
+    let current = NcCurrentBuilder::new(&path, "steffen").build().unwrap();
+    let bfield = NcBfieldBuilder::new(&path, "bicubic").build().unwrap();
+    let perturbation = NcPerturbation::from_harmonics(&vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1)
+            .build()
+            .unwrap(),
+    ]);
+
+    let mut particles: Vec<Particle> = (1..=8)
+        .map(|n| {
+            let initial_conditions = InitialConditions {
+                time0: 0.0,
+                theta0: 2.0,
+                psip0: geometry.psip_wall() * (n as f64) / 9.0,
+                rho0: 1e-6,
+                zeta0: 0.0,
+                mu: 0.0,
+            };
+            Particle::new(&initial_conditions)
+        })
+        .collect();
+
+    let config = MappingConfig::default();
+    let params = MappingParameters::new(PoincareSection::ConstTheta, 0.0, 5);
+    let results = map_ensemble(
+        &mut particles,
+        &qfactor,
+        &current,
+        &bfield,
+        &perturbation,
+        &params,
+        &config,
+    );
+
+    assert_eq!(results.len(), particles.len());
+    for (particle, result) in particles.iter().zip(results.iter()) {
+        assert!(result.is_ok());
+        assert!(particle.status.is_mapped());
+    }
+}
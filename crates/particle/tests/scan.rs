@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+use equilibrium::{
+    bfields::*, currents::*, geometries::*, harmonics::*, perturbations::*, qfactors::*,
+};
+use particle::*;
+
+#[test]
+fn test_run_scan_sweeps_psip0() {
+    let path = PathBuf::from("../equilibrium/lar_netcdf.nc");
+
+    let geometry = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+        .build()
+        .unwrap();
+    let qfactor = NcQfactorBuilder::new(&path, "steffen").build().unwrap();
+    let current = NcCurrentBuilder::new(&path, "steffen").build().unwrap();
+    let bfield = NcBfieldBuilder::new(&path, "bicubic").build().unwrap();
+    let perturbation = NcPerturbation::from_harmonics(&vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1)
+            .build()
+            .unwrap(),
+    ]);
+
+    let base = InitialConditions {
+        time0: 0.0,
+        theta0: 2.0,
+        psip0: 0.0,
+        rho0: 1e-6,
+        zeta0: 0.0,
+        mu: 0.0,
+    };
+    let scan = ScanConfig::new(
+        base,
+        ScanParameter::Psip0,
+        geometry.psip_wall() / 9.0,
+        geometry.psip_wall() * 8.0 / 9.0,
+        8,
+        ScanSpacing::Linear,
+    );
+
+    let config = MappingConfig::default();
+    let params = MappingParameters::new(PoincareSection::ConstTheta, 0.0, 5);
+    let result = run_scan(
+        &scan,
+        &qfactor,
+        &current,
+        &bfield,
+        &perturbation,
+        &params,
+        &config,
+    );
+
+    assert_eq!(result.points.len(), 8);
+    for point in &result.points {
+        assert!(point.status.is_mapped());
+        assert!(point.parameter_value.is_finite());
+    }
+    assert_eq!(result.failures().count(), 0);
+}
+
+#[test]
+fn test_run_scan_surfaces_timed_out_points() {
+    let path = PathBuf::from("../equilibrium/lar_netcdf.nc");
+
+    let geometry = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+        .build()
+        .unwrap();
+    let qfactor = NcQfactorBuilder::new(&path, "steffen").build().unwrap();
+    let current = NcCurrentBuilder::new(&path, "steffen").build().unwrap();
+    let bfield = NcBfieldBuilder::new(&path, "bicubic").build().unwrap();
+    let perturbation = NcPerturbation::from_harmonics(&vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1)
+            .build()
+            .unwrap(),
+    ]);
+
+    let base = InitialConditions {
+        time0: 0.0,
+        theta0: 2.0,
+        psip0: 0.0,
+        rho0: 1e-6,
+        zeta0: 0.0,
+        mu: 0.0,
+    };
+    let scan = ScanConfig::new(
+        base,
+        ScanParameter::Psip0,
+        geometry.psip_wall() / 9.0,
+        geometry.psip_wall() * 8.0 / 9.0,
+        4,
+        ScanSpacing::Linear,
+    );
+
+    // A handful of steps is nowhere near enough to find any intersections.
+    let config = MappingConfig {
+        max_steps: 5,
+        ..Default::default()
+    };
+    let params = MappingParameters::new(PoincareSection::ConstTheta, 0.0, 5);
+    let result = run_scan(
+        &scan,
+        &qfactor,
+        &current,
+        &bfield,
+        &perturbation,
+        &params,
+        &config,
+    );
+
+    assert_eq!(result.points.len(), 4);
+    assert_eq!(result.failures().count(), 4);
+    for point in result.failures() {
+        assert!(point.status.is_timed_out());
+    }
+}
@@ -73,3 +73,53 @@ fn test_particle_single_period_integration() {
     let _ = format!("{:?}", &particle.evolution);
     particle.evolution.discard();
 }
+
+#[test]
+fn test_multi_period_frequency_averaging() {
+    let path = PathBuf::from("../equilibrium/lar_netcdf.nc");
+
+    let geometry = NcGeometryBuilder::new(&path, "steffen", "bicubic")
+        .build()
+        .unwrap();
+    let qfactor = NcQfactorBuilder::new(&path, "steffen").build().unwrap();
+    let current = NcCurrentBuilder::new(&path, "steffen").build().unwrap();
+    let bfield = NcBfieldBuilder::new(&path, "bicubic").build().unwrap();
+    let perturbation = NcPerturbation::from_harmonics(&vec![
+        NcHarmonicBuilder::new(&path, "steffen", 2, 1)
+            .build()
+            .unwrap(),
+    ]);
+
+    let initial_conditions = InitialConditions {
+        time0: 0.0,
+        theta0: 2.0,
+        psip0: geometry.psip_wall() / 2.0,
+        rho0: 1e-6,
+        zeta0: 0.0,
+        mu: 0.0,
+    };
+
+    // A single period has no error estimate: there is only one sample.
+    let mut particle = Particle::new(&initial_conditions);
+    let config = SinglePeriodConfig::default();
+    particle.single_period_integrate(&qfactor, &current, &bfield, &perturbation, &config);
+    assert!(particle.status.is_single_period_integrated());
+    assert!(particle.frequencies.omega_theta().is_some());
+    assert!(particle.frequencies.omega_theta_err().is_none());
+    assert!(particle.frequencies.omega_zeta_err().is_none());
+
+    // Several periods accumulate one sample each, and report a relative error over them.
+    let mut particle = Particle::new(&initial_conditions);
+    let config = SinglePeriodConfig {
+        periods: 4,
+        ..Default::default()
+    };
+    particle.single_period_integrate(&qfactor, &current, &bfield, &perturbation, &config);
+    assert!(particle.status.is_single_period_integrated());
+    assert_eq!(particle.frequencies.omega_theta_samples().len(), 4);
+    assert_eq!(particle.frequencies.omega_zeta_samples().len(), 4);
+    assert!(particle.frequencies.omega_theta_err().unwrap() >= 0.0);
+    assert!(particle.frequencies.omega_zeta_err().unwrap() >= 0.0);
+
+    let _ = format!("{:?}", &particle.frequencies);
+}
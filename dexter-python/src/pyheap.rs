@@ -46,6 +46,10 @@ py_export_getter!(PyHeapInitialConditions, is_empty, bool);
 
 // ===============================================================================================
 
+// No `py_pickle_impl!` on `PyHeapInitialConditions`/`PyHeap` here: both `HeapInitialConditions`
+// (`initials.rs`) and `Heap` (`heap.rs`) live in files this checkout does not contain, so there
+// are no struct definitions here to add the required `serde::Serialize`/`Deserialize` derives to.
+
 #[pyclass(name = "Heap")]
 pub struct PyHeap(pub Heap);
 
@@ -56,6 +60,16 @@ impl PyHeap {
         Self(Heap::new(&initials.0))
     }
 
+    // `Heap::poincare` (`heap.rs`) drives every particle in `self.0.initials` through this one
+    // call, but `heap.rs` does not exist in this checkout, so whether it already distributes that
+    // ensemble across threads can't be checked from here, and a parallel mode can't be added to
+    // it from this side of the binding either. `particle::ensemble::map_ensemble`/`Ensemble::map`
+    // already do exactly this kind of work-stealing `rayon` fan-out for a `&mut [Particle]` --
+    // `Heap::poincare` should delegate to the same mechanism once `heap.rs` exists, rather than a
+    // serial particle-by-particle loop. The thread-count/chunk-size knob this would need belongs
+    // on `MappingParameters` itself (defined in the equally absent `state.rs`) with a matching
+    // getter added here on `PyMappingParameters`, so a caller could tune it the same way
+    // `PyMappingParameters::new_py` already exposes `section`/`alpha`/`intersections`.
     pub fn poincare(
         &mut self,
         qfactor: &PyQfactor,
@@ -7,6 +7,7 @@ use rsl_interpolation::{Accelerator, Cache};
 
 use dexter::equilibrium::*;
 use std::result::Result; // awful; replace equilibrium's Result
+use std::sync::Arc;
 
 use super::pyerrors::PyEqError;
 use crate::{
@@ -205,13 +206,19 @@ pub struct PyNcHarmonic(pub NcHarmonic);
 #[pymethods]
 impl PyNcHarmonic {
     #[new]
-    #[pyo3(signature = (path, typ, m, n, phase_method = "Resonance"))]
+    #[pyo3(signature = (
+        path, typ, m, n, phase_method = "Resonance", custom_phase_psip = None, custom_phase = None,
+        custom_phase_fn = None
+    ))]
     pub fn new(
         path: &str,
         typ: &str,
         m: i64,
         n: i64,
         phase_method: Option<&str>,
+        custom_phase_psip: Option<Vec<f64>>,
+        custom_phase: Option<Vec<f64>>,
+        custom_phase_fn: Option<Bound<'_, PyAny>>,
     ) -> Result<Self, PyEqError> {
         let path = std::path::PathBuf::from(path);
         let builder =
@@ -221,7 +228,33 @@ impl PyNcHarmonic {
                     "average" => PhaseMethod::Average,
                     "resonance" => PhaseMethod::Resonance,
                     "interpolation" => PhaseMethod::Interpolation,
-                    "custom" => todo!("How to pass this?"), // TODO:
+                    "custom" => {
+                        let psip_grid = custom_phase_psip
+                            .expect("custom_phase_psip is required for phase_method='custom'");
+                        let phase_grid = match (custom_phase, custom_phase_fn) {
+                            (Some(values), None) => values,
+                            (None, Some(callable)) => psip_grid
+                                .iter()
+                                .map(|&psip| {
+                                    callable
+                                        .call1((psip,))
+                                        .and_then(|v| v.extract::<f64>())
+                                        .expect(
+                                            "custom_phase_fn must accept one float and return one float",
+                                        )
+                                })
+                                .collect(),
+                            _ => panic!(
+                                "exactly one of custom_phase or custom_phase_fn must be given for \
+                                 phase_method='custom'"
+                            ),
+                        };
+                        PhaseMethod::Custom(Arc::new(CustomPhaseProfile::new(
+                            typ,
+                            &psip_grid,
+                            &phase_grid,
+                        )?))
+                    }
                     _ => panic!("Invalid phase method"),
                 },
                 None => PhaseMethod::default(),
@@ -238,18 +271,27 @@ impl PyNcHarmonic {
 
 impl From<&NcHarmonic> for PyNcHarmonic {
     fn from(harmonic: &NcHarmonic) -> Self {
+        let (phase_method, custom_phase_psip, custom_phase) = match harmonic.phase_method() {
+            PhaseMethod::Zero => ("zero", None, None),
+            PhaseMethod::Average => ("average", None, None),
+            PhaseMethod::Resonance => ("resonance", None, None),
+            PhaseMethod::Interpolation => ("interpolation", None, None),
+            PhaseMethod::Custom(profile) => (
+                "custom",
+                Some(profile.psip_data().to_vec()),
+                Some(profile.phase_data().to_vec()),
+            ),
+        };
+
         PyNcHarmonic::new(
             harmonic.path().to_str().unwrap(), // Safe: already exists
             harmonic.typ().as_str(),
             harmonic.m(),
             harmonic.n(),
-            Some(match harmonic.phase_method() {
-                PhaseMethod::Zero => "zero",
-                PhaseMethod::Average => "average",
-                PhaseMethod::Resonance => "resonance",
-                PhaseMethod::Interpolation => "interpolation",
-                PhaseMethod::Custom(_) => "custom",
-            }),
+            Some(phase_method),
+            custom_phase_psip,
+            custom_phase,
+            None,
         )
         .unwrap()
     }
@@ -261,6 +303,7 @@ py_get_path!(PyNcHarmonic);
 py_len_impl!(PyNcHarmonic);
 py_export_getter!(PyNcHarmonic, typ, String);
 py_export_getter!(PyNcHarmonic, phase_average, Option<f64>);
+py_export_getter!(PyNcHarmonic, psip_resonance, Option<f64>);
 py_export_getter!(PyNcHarmonic, phase_resonance, Option<f64>);
 py_export_getter!(PyNcHarmonic, m, i64);
 py_export_getter!(PyNcHarmonic, n, i64);
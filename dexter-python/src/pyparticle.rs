@@ -1,18 +1,22 @@
 //! Particle objects' Python wrappers.
 
-use numpy::{IntoPyArray, PyArray1};
+use numpy::{IntoPyArray, PyArray1, PyArray2};
 use pyo3::prelude::*;
 use pyo3::types::PyTuple;
 use safe_unwrap::safe_unwrap;
 
-use particle::{Evolution, Frequencies, InitialConditions, MappingParameters, Particle, Radians};
+use ndarray::{Array1, Array2, ArrayView1};
+use particle::{
+    Ensemble, Evolution, Frequencies, IntegrationConfig, InitialConditions, MappingConfig,
+    MappingParameters, Parametrization, Particle, PoincareAnalysis, Radians,
+};
 use utils::{
     py_debug_impl, py_export_getter, py_get_enum_string, py_get_numpy1D, py_get_primitive_field,
-    py_repr_impl,
+    py_pickle_impl, py_repr_impl,
 };
 
-use crate::pyerrors::PyParticleError;
-use crate::pylibrium::{PyBfield, PyCurrents, PyPerturbation, PyQfactor};
+use crate::pyerrors::{PyEqError, PyParticleError};
+use crate::pylibrium::{PyBfield, PyCurrents, PyNcGeometry, PyPerturbation, PyQfactor};
 
 #[derive(Clone)]
 #[pyclass(frozen, name = "InitialConditions")]
@@ -41,6 +45,7 @@ py_get_primitive_field!(PyInitialConditions, psip0, f64);
 py_get_primitive_field!(PyInitialConditions, rho0, f64);
 py_get_primitive_field!(PyInitialConditions, zeta0, f64);
 py_get_primitive_field!(PyInitialConditions, mu, f64);
+py_pickle_impl!(PyInitialConditions, rebuild_initial_conditions);
 
 // ===============================================================================================
 
@@ -66,11 +71,100 @@ py_get_enum_string!(PyMappingParameters, section);
 py_get_primitive_field!(PyMappingParameters, alpha, Radians);
 py_get_primitive_field!(PyMappingParameters, intersections, usize);
 
+// No `py_pickle_impl!` here: `PoincareSection::Event` carries an `Arc<dyn Fn(&State) -> f64>`,
+// which has no serde representation, so `MappingParameters` as a whole can't derive
+// `Serialize`/`Deserialize`.
+
 // ===============================================================================================
 
 #[pyclass(frozen, name = "Evolution")]
 pub struct PyEvolution(Evolution);
 
+#[pymethods]
+impl PyEvolution {
+    /// Reconstructs the stored orbit's state at every time in `times`, via
+    /// [`Evolution::resample`], without re-integrating. Rows are `times`, in the order given;
+    /// columns are the four fields selected by `parametrization` -- `"guiding_center"` for
+    /// `(θ, ψp, ρ, ζ)`, the coordinates the integrator itself advances, or `"derived"` for
+    /// `(Pθ, Pζ, ψ, energy)`.
+    ///
+    /// Panics if fewer than two states are stored (nothing to interpolate between), or if
+    /// `parametrization` isn't one of the two names above.
+    pub fn resample<'py>(
+        &self,
+        py: Python<'py>,
+        times: Vec<f64>,
+        parametrization: &str,
+    ) -> Bound<'py, PyArray2<f64>> {
+        let parametrization = match parametrization.to_lowercase().as_str() {
+            "guiding_center" | "guidingcenter" => Parametrization::GuidingCenter,
+            "derived" => Parametrization::Derived,
+            _ => panic!("parametrization must be 'guiding_center' or 'derived'"),
+        };
+        let samples = self
+            .0
+            .resample(&times)
+            .expect("resample needs at least two stored states");
+
+        let mut array = Array2::from_elem((times.len(), 4), f64::NAN);
+        for (mut row, sample) in array.rows_mut().into_iter().zip(&samples) {
+            let fields = sample.in_parametrization(parametrization);
+            row.iter_mut().zip(fields).for_each(|(cell, value)| *cell = value);
+        }
+        array.into_pyarray(py)
+    }
+
+    /// Converts the stored orbit from its native flux coordinates `(ψp, θ, ζ)` into another
+    /// coordinate frame, via `geometry`. `frame` selects the output:
+    /// - `"flux"`: the raw `(ψp, θ, ζ)` time series, unchanged.
+    /// - `"cylindrical"`: `(R, Z, φ)`, with `R`/`Z` from [`NcGeometry::rlab_batch`]/[`zlab_batch`]
+    ///   (`equilibrium::NcGeometry`) and `φ = -ζ` (opposite sign, the usual lab-frame toroidal
+    ///   angle convention).
+    /// - `"cartesian"`: `(X, Y, Z)`, with `X = R·cos(φ)`, `Y = R·sin(φ)`.
+    ///
+    /// Reuses a single accelerator/cache pair across the whole time series (see
+    /// [`NcGeometry::rlab_batch`]) rather than evaluating `rlab`/`zlab` one point at a time.
+    ///
+    /// Panics if `frame` isn't one of the three names above.
+    ///
+    /// [`NcGeometry::rlab_batch`]: equilibrium::NcGeometry::rlab_batch
+    /// [`zlab_batch`]: equilibrium::NcGeometry::zlab_batch
+    pub fn in_frame<'py>(
+        &self,
+        py: Python<'py>,
+        geometry: &PyNcGeometry,
+        frame: &str,
+    ) -> Result<(Bound<'py, PyArray1<f64>>, Bound<'py, PyArray1<f64>>, Bound<'py, PyArray1<f64>>), PyEqError>
+    {
+        match frame.to_lowercase().as_str() {
+            "flux" => Ok((
+                self.0.psip.clone().into_pyarray(py),
+                self.0.theta.clone().into_pyarray(py),
+                self.0.zeta.clone().into_pyarray(py),
+            )),
+            "cylindrical" => {
+                let psips = ArrayView1::from(&self.0.psip);
+                let thetas = ArrayView1::from(&self.0.theta);
+                let r = geometry.0.rlab_batch(&psips, &thetas)?;
+                let z = geometry.0.zlab_batch(&psips, &thetas)?;
+                let phi = -Array1::from_vec(self.0.zeta.clone());
+                Ok((r.into_pyarray(py), z.into_pyarray(py), phi.into_pyarray(py)))
+            }
+            "cartesian" => {
+                let psips = ArrayView1::from(&self.0.psip);
+                let thetas = ArrayView1::from(&self.0.theta);
+                let r = geometry.0.rlab_batch(&psips, &thetas)?;
+                let z = geometry.0.zlab_batch(&psips, &thetas)?;
+                let phi = -Array1::from_vec(self.0.zeta.clone());
+                let x = &r * &phi.mapv(f64::cos);
+                let y = &r * &phi.mapv(f64::sin);
+                Ok((x.into_pyarray(py), y.into_pyarray(py), z.into_pyarray(py)))
+            }
+            _ => panic!("frame must be 'flux', 'cylindrical', or 'cartesian'"),
+        }
+    }
+}
+
 py_debug_impl!(PyEvolution);
 py_repr_impl!(PyEvolution);
 py_get_numpy1D!(PyEvolution, time);
@@ -83,8 +177,11 @@ py_get_numpy1D!(PyEvolution, ptheta);
 py_get_numpy1D!(PyEvolution, pzeta);
 py_get_numpy1D!(PyEvolution, energy);
 py_get_primitive_field!(PyEvolution, energy_std, f64);
+py_get_primitive_field!(PyEvolution, rotation_number, f64);
+py_get_primitive_field!(PyEvolution, rotation_number_err, f64);
 py_export_getter!(PyEvolution, steps_taken, usize);
 py_export_getter!(PyEvolution, steps_stored, usize);
+py_pickle_impl!(PyEvolution, rebuild_evolution);
 
 // ===============================================================================================
 
@@ -95,10 +192,46 @@ py_debug_impl!(PyFrequencies);
 py_repr_impl!(PyFrequencies);
 py_export_getter!(PyFrequencies, omega_theta, Option<f64>);
 py_export_getter!(PyFrequencies, omega_zeta, Option<f64>);
+py_export_getter!(PyFrequencies, omega_theta_err, Option<f64>);
+py_export_getter!(PyFrequencies, omega_zeta_err, Option<f64>);
 py_export_getter!(PyFrequencies, qkinetic, Option<f64>);
+py_get_numpy1D!(PyFrequencies, omega_theta_samples);
+py_get_numpy1D!(PyFrequencies, omega_zeta_samples);
+py_pickle_impl!(PyFrequencies, rebuild_frequencies);
 
 // ===============================================================================================
 
+/// Physics interpretation of a mapped [`PyParticle`]'s recorded rotation number -- see
+/// [`Particle::poincare_analysis`](particle::Particle::poincare_analysis).
+#[pyclass(frozen, name = "PoincareAnalysis")]
+pub struct PyPoincareAnalysis(PoincareAnalysis);
+
+#[pymethods]
+impl PyPoincareAnalysis {
+    /// The nearest low-order resonance `(p, q)` to `rotation_number`, or `None` if none was found
+    /// within tolerance.
+    #[getter]
+    pub fn resonance(&self) -> Option<(u64, u64)> {
+        self.0.resonance
+    }
+}
+
+py_debug_impl!(PyPoincareAnalysis);
+py_repr_impl!(PyPoincareAnalysis);
+py_get_primitive_field!(PyPoincareAnalysis, rotation_number, f64);
+py_get_primitive_field!(PyPoincareAnalysis, rotation_number_err, f64);
+py_get_enum_string!(PyPoincareAnalysis, class);
+
+// ===============================================================================================
+
+// No `py_pickle_impl!` on `PyParticle` yet: `Particle`'s own `initial_state`/`final_state` are
+// `State`, and its `map`-only fields are produced through `Stepper` -- both live in `state.rs`/
+// `rkf45.rs`, which this checkout does not contain, so there is nothing here yet to add the
+// required `serde::Serialize`/`Deserialize` derives to. `PyInitialConditions`/`PyEvolution`/
+// `PyFrequencies` above already cover a finished particle's picklable output (initial conditions,
+// time series, and period-averaged frequencies) -- `PyParticle` itself would need to be
+// reconstructed from those plus a `State`, once that module exists in this checkout.
+
 #[pyclass(name = "Particle")]
 pub struct PyParticle(pub Particle);
 
@@ -124,6 +257,11 @@ impl PyParticle {
         PyFrequencies(self.0.frequencies.clone())
     }
 
+    #[getter]
+    pub fn get_poincare_analysis(&self) -> PyPoincareAnalysis {
+        PyPoincareAnalysis(self.0.poincare_analysis())
+    }
+
     pub fn integrate<'py>(
         &mut self,
         qfactor: &PyQfactor,
@@ -132,21 +270,7 @@ impl PyParticle {
         perturbation: &PyPerturbation,
         t_eval: Bound<'py, PyTuple>,
     ) -> Result<(), PyParticleError> {
-        match t_eval.len() {
-            2 => (),
-            _ => panic!("`t_eval` must be of the form (t0, tf)"),
-        };
-        let t_eval: Vec<f64> = t_eval
-            .iter()
-            .map(|any| {
-                any.extract::<f64>()
-                    .expect("t_eval elements must be floats")
-            })
-            .collect();
-        let t_eval = (
-            safe_unwrap!("len already checked", t_eval.first().copied()),
-            safe_unwrap!("len already checked", t_eval.last().copied()),
-        );
+        let t_eval = extract_t_eval(t_eval);
 
         Ok(self
             .0
@@ -189,3 +313,158 @@ py_get_enum_string!(PyParticle, status);
 py_get_enum_string!(PyParticle, orbit_type);
 py_export_getter!(PyParticle, initial_energy, f64);
 py_export_getter!(PyParticle, final_energy, f64);
+
+// ===============================================================================================
+
+// `PyEnsemble` wraps `particle::Ensemble`, not `PyParticle`'s inner `Particle` -- it is built
+// directly from a list of `PyInitialConditions` rather than from existing `PyParticle`s, since
+// `Ensemble::integrate`/`Ensemble::map` need to own their particles to hand them to `rayon`'s
+// `par_iter_mut`. There is accordingly no way to pull an individual `PyParticle` back out of a
+// `PyEnsemble`; the `*_list`/stacked getters below are how per-particle results come back out.
+//
+// No progress-callback hook is wired into `integrate`/`map` below: doing so live, mid-`rayon`-batch,
+// would need the counters `PoincarePbar` tracks, and that type lives in `progress_bars.rs`, which
+// (like `state.rs`/`rkf45.rs` noted above) this checkout does not contain.
+#[pyclass(name = "Ensemble")]
+pub struct PyEnsemble(pub Ensemble);
+
+#[pymethods]
+impl PyEnsemble {
+    #[new]
+    pub fn new_py(initial_conditions: Vec<PyInitialConditions>) -> Self {
+        let initial_conditions: Vec<InitialConditions> =
+            initial_conditions.into_iter().map(|ic| ic.0).collect();
+        Self(Ensemble::new(&initial_conditions))
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Integrates every particle in the ensemble concurrently via rayon's work-stealing
+    /// scheduler (see [`Ensemble::integrate`]), releasing the GIL for the duration so a Python
+    /// caller's other threads stay live across what can be a long, thousands-of-particles batch.
+    pub fn integrate(
+        &mut self,
+        py: Python<'_>,
+        qfactor: &PyQfactor,
+        currents: &PyCurrents,
+        bfield: &PyBfield,
+        perturbation: &PyPerturbation,
+        t_eval: Bound<'_, PyTuple>,
+    ) {
+        let t_eval = extract_t_eval(t_eval);
+        py.allow_threads(|| {
+            self.0.integrate(
+                &qfactor.0,
+                &currents.0,
+                &bfield.0,
+                &perturbation.0,
+                t_eval,
+                &IntegrationConfig::default(),
+            );
+        });
+    }
+
+    /// Maps every particle in the ensemble concurrently, releasing the GIL -- see
+    /// [`Self::integrate`].
+    pub fn map(
+        &mut self,
+        py: Python<'_>,
+        qfactor: &PyQfactor,
+        currents: &PyCurrents,
+        bfield: &PyBfield,
+        perturbation: &PyPerturbation,
+        params: &PyMappingParameters,
+    ) {
+        py.allow_threads(|| {
+            self.0.map(
+                &qfactor.0,
+                &currents.0,
+                &bfield.0,
+                &perturbation.0,
+                &params.0,
+                &MappingConfig::default(),
+            );
+        });
+    }
+
+    /// Every particle's `θ(t)`/intersection series, stacked into one `n_particles × max_len`
+    /// array, right-padded with `NaN` for particles shorter than the ensemble's longest -- see
+    /// [`Ensemble::stack`].
+    #[getter]
+    pub fn theta<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.0.stack(|p| &p.evolution.theta).into_pyarray(py)
+    }
+
+    #[getter]
+    pub fn psip<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.0.stack(|p| &p.evolution.psip).into_pyarray(py)
+    }
+
+    #[getter]
+    pub fn rho<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.0.stack(|p| &p.evolution.rho).into_pyarray(py)
+    }
+
+    #[getter]
+    pub fn zeta<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.0.stack(|p| &p.evolution.zeta).into_pyarray(py)
+    }
+
+    #[getter]
+    pub fn energy<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f64>> {
+        self.0.stack(|p| &p.evolution.energy).into_pyarray(py)
+    }
+
+    /// The unpadded `θ` series of every particle, one `PyArray1` per particle -- the ragged
+    /// alternative to [`Self::theta`], for a `map()`-ed ensemble whose particles' intersection
+    /// counts genuinely differ and shouldn't be read back through a `NaN`-padded matrix.
+    pub fn theta_list<'py>(&self, py: Python<'py>) -> Vec<Bound<'py, PyArray1<f64>>> {
+        self.0
+            .particles()
+            .iter()
+            .map(|p| p.evolution.theta.clone().into_pyarray(py))
+            .collect()
+    }
+
+    pub fn psip_list<'py>(&self, py: Python<'py>) -> Vec<Bound<'py, PyArray1<f64>>> {
+        self.0
+            .particles()
+            .iter()
+            .map(|p| p.evolution.psip.clone().into_pyarray(py))
+            .collect()
+    }
+
+    #[getter]
+    pub fn status(&self) -> Vec<String> {
+        self.0.status().iter().map(|s| format!("{s:?}")).collect()
+    }
+
+    #[getter]
+    pub fn orbit_type(&self) -> Vec<String> {
+        self.0.orbit_type().iter().map(|t| format!("{t:?}")).collect()
+    }
+}
+
+py_debug_impl!(PyEnsemble);
+
+/// Shared by [`PyParticle::integrate`] and [`PyEnsemble::integrate`]: unpacks a Python `(t0, tf)`
+/// tuple into the `(f64, f64)` pair every `integrate` routine takes.
+fn extract_t_eval(t_eval: Bound<'_, PyTuple>) -> (f64, f64) {
+    match t_eval.len() {
+        2 => (),
+        _ => panic!("`t_eval` must be of the form (t0, tf)"),
+    };
+    let t_eval: Vec<f64> = t_eval
+        .iter()
+        .map(|any| {
+            any.extract::<f64>()
+                .expect("t_eval elements must be floats")
+        })
+        .collect();
+    (
+        safe_unwrap!("len already checked", t_eval.first().copied()),
+        safe_unwrap!("len already checked", t_eval.last().copied()),
+    )
+}
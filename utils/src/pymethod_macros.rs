@@ -111,3 +111,42 @@ macro_rules! py_get_typ {
         }
     };
 }
+
+/// Generates pickle support (`__getstate__`/`__reduce__`) for `$py_object`, by round-tripping its
+/// inner `self.0` field through a compact `bincode` byte buffer -- the inner type must implement
+/// `serde::Serialize`/`serde::Deserialize`.
+///
+/// These wrappers are all `#[pyclass(frozen, ...)]`, so there is no legal `&mut self` to give a
+/// `__setstate__` -- `__reduce__` alone covers the pickle protocol for an immutable object: pickle
+/// calls the returned callable with the returned args and uses its result directly, with no
+/// separate state-restoring step. That callable is `$rebuild_fn`, a standalone `#[pyfunction]`
+/// this macro also generates, rather than `$py_object` itself -- these wrappers' `#[new]`
+/// generally takes the underlying Rust type's own constructor arguments, not serialized bytes.
+#[macro_export]
+macro_rules! py_pickle_impl {
+    ($py_object:ident, $rebuild_fn:ident) => {
+        #[pyo3::pyfunction]
+        fn $rebuild_fn(bytes: Vec<u8>) -> pyo3::PyResult<$py_object> {
+            bincode::deserialize(&bytes)
+                .map($py_object)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+        }
+
+        #[pymethods]
+        impl $py_object {
+            pub fn __getstate__(&self) -> pyo3::PyResult<Vec<u8>> {
+                bincode::serialize(&self.0)
+                    .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+            }
+
+            pub fn __reduce__(
+                slf: pyo3::PyRef<'_, Self>,
+            ) -> pyo3::PyResult<(pyo3::Py<pyo3::PyAny>, (Vec<u8>,))> {
+                let py = slf.py();
+                let state = slf.__getstate__()?;
+                let rebuild = pyo3::wrap_pyfunction!($rebuild_fn, py)?;
+                Ok((rebuild.into(), (state,)))
+            }
+        }
+    };
+}